@@ -1,9 +1,9 @@
 use std::error::Error;
 
-use azure_core::http::StatusCode;
+use azure_core::http::Etag;
 use azure_data_cosmos::{
     models::{PatchDocument, PatchOperation},
-    CosmosClient, PartitionKey,
+    CosmosClient, CosmosError, ItemOptions, PartitionKey,
 };
 use clap::Args;
 
@@ -27,6 +27,14 @@ pub struct PatchCommand {
     /// A JSON patch operation to apply to the item, can be specified multiple times. See https://learn.microsoft.com/en-us/azure/cosmos-db/partial-document-update
     #[arg(long, short)]
     operation: Vec<String>,
+
+    /// An optional filter predicate the item must match for the patch to be applied, for example "FROM c WHERE c.active = true".
+    #[arg(long)]
+    condition: Option<String>,
+
+    /// An optional ETag the item must still have for the patch to be applied, as returned by a previous read.
+    #[arg(long)]
+    if_match: Option<String>,
 }
 
 impl PatchCommand {
@@ -40,19 +48,32 @@ impl PatchCommand {
             .iter()
             .map(|op| serde_json::from_str(op).expect("Invalid JSON patch operation"))
             .collect();
-        let patch = PatchDocument { operations };
+        let patch = PatchDocument {
+            operations,
+            condition: self.condition.map(Into::into),
+        };
+
+        let options = ItemOptions {
+            precondition: self.if_match.map(|etag| Etag::from(etag).into()),
+            ..Default::default()
+        };
 
         let response = container_client
-            .patch_item(pk, &self.item_id, patch, None)
+            .patch_item(pk, &self.item_id, patch, Some(options))
             .await;
         match response {
-            Err(e) if e.http_status() == Some(StatusCode::NotFound) => println!("Item not found!"),
             Ok(r) => {
                 let item: serde_json::Value = r.into_json_body().await?;
                 println!("Patched item:");
                 println!("{:#?}", item);
             }
-            Err(e) => return Err(e.into()),
+            Err(e) => match CosmosError::from_error(&e) {
+                Some(error) if error.is_not_found() => println!("Item not found!"),
+                Some(error) if error.is_precondition_failed() => {
+                    println!("Item did not match the condition or ETag, patch was not applied.")
+                }
+                _ => return Err(e.into()),
+            },
         };
         Ok(())
     }
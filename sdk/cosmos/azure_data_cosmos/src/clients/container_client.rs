@@ -3,16 +3,40 @@
 
 use crate::{
     constants,
-    models::{ContainerProperties, PatchDocument, ThroughputProperties},
+    models::{
+        ConflictProperties, ContainerProperties, ItemReference, PartitionKeyDefinition,
+        PatchDocument, ReadManyFailure, ReadManyResults, SearchResults, StoredProcedureProperties,
+        ThroughputProperties, TriggerProperties, UserDefinedFunctionProperties,
+    },
     options::{QueryOptions, ReadContainerOptions},
+    partition_key_range_cache::PartitionKeyRange,
     pipeline::CosmosPipeline,
     resource_context::{ResourceLink, ResourceType},
-    DeleteContainerOptions, FeedPager, ItemOptions, PartitionKey, Query, QueryPartitionStrategy,
-    ReplaceContainerOptions, ThroughputOptions,
+    search, ConflictOptions, CosmosDiagnostics, CosmosError, DeleteContainerOptions,
+    ExecuteStoredProcedureOptions, FeedPager, FeedRange, ItemOptions, PartitionKey, Query,
+    QueryConflictsOptions, QueryPartitionStrategy, QueryScriptsOptions, ReadFeedRangesOptions,
+    ReadManyOptions, ReplaceContainerOptions, ScriptOptions, SessionToken, ThroughputOptions,
+    ThroughputPoller,
 };
 
-use azure_core::http::{headers, request::Request, response::Response, Method};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use azure_core::http::{headers, request::Request, response::Response, Method, StatusCode};
+use futures::{StreamExt, TryStreamExt};
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+/// The default number of concurrent requests sent by [`ContainerClient::read_many_items()`].
+const DEFAULT_READ_MANY_CONCURRENCY: usize = 10;
+
+/// The maximum number of IDs looked up by a single query in [`ContainerClient::read_many_items()`].
+const READ_MANY_QUERY_BATCH_SIZE: usize = 256;
+
+/// The maximum number of times [`ContainerClient::read_many_items()`] groups items by partition key range, when the ranges are split while the items are read.
+const MAX_READ_MANY_RESOLUTIONS: u32 = 3;
 
 /// A client for working with a specific container in a Cosmos DB account.
 ///
@@ -20,6 +44,10 @@ use serde::{de::DeserializeOwned, Serialize};
 pub struct ContainerClient {
     link: ResourceLink,
     items_link: ResourceLink,
+    sprocs_link: ResourceLink,
+    triggers_link: ResourceLink,
+    udfs_link: ResourceLink,
+    conflicts_link: ResourceLink,
     pipeline: CosmosPipeline,
 }
 
@@ -33,10 +61,18 @@ impl ContainerClient {
             .feed(ResourceType::Containers)
             .item(container_id);
         let items_link = link.feed(ResourceType::Items);
+        let sprocs_link = link.feed(ResourceType::StoredProcedures);
+        let triggers_link = link.feed(ResourceType::Triggers);
+        let udfs_link = link.feed(ResourceType::UserDefinedFunctions);
+        let conflicts_link = link.feed(ResourceType::Conflicts);
 
         Self {
             link,
             items_link,
+            sprocs_link,
+            triggers_link,
+            udfs_link,
+            conflicts_link,
             pipeline,
         }
     }
@@ -163,6 +199,66 @@ impl ContainerClient {
             .await
     }
 
+    /// Replaces the container throughput properties, returning a [`ThroughputPoller`] which tracks the change until the service has applied it.
+    ///
+    /// Increasing the throughput beyond what the current physical partitions can serve requires the service to split partitions, which can take several hours.
+    /// The new throughput must use the same mode (manual or autoscale) as the current one, use [`migrate_throughput()`](Self::migrate_throughput()) to switch modes.
+    ///
+    /// # Arguments
+    /// * `throughput` - The new throughput properties to set.
+    /// * `options` - Optional parameters for the request.
+    pub async fn begin_replace_throughput(
+        &self,
+        throughput: ThroughputProperties,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        self.begin_throughput_update(throughput, false, options)
+            .await
+    }
+
+    /// Migrates the container between manual and autoscale throughput, returning a [`ThroughputPoller`] which tracks the migration until the service has completed it.
+    ///
+    /// Pass [`ThroughputProperties::autoscale()`] to migrate a container using manual throughput to autoscale, or [`ThroughputProperties::manual()`] to migrate it to manual throughput.
+    /// If the container already uses the requested mode, the throughput is replaced, as with [`begin_replace_throughput()`](Self::begin_replace_throughput()).
+    ///
+    /// # Arguments
+    /// * `throughput` - The new throughput properties to set.
+    /// * `options` - Optional parameters for the request.
+    pub async fn migrate_throughput(
+        &self,
+        throughput: ThroughputProperties,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        self.begin_throughput_update(throughput, true, options)
+            .await
+    }
+
+    async fn begin_throughput_update(
+        &self,
+        throughput: ThroughputProperties,
+        migrate: bool,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        let options = options.unwrap_or_default();
+
+        // We need to get the RID for the container.
+        let properties = self.read(None).await?.into_body().await?;
+        let resource_id = properties
+            .system_properties
+            .resource_id
+            .expect("service should always return a '_rid' for a container");
+
+        self.pipeline
+            .begin_throughput_offer_update(
+                options.method_options.context,
+                &resource_id,
+                throughput,
+                migrate,
+                options.polling_interval,
+            )
+            .await
+    }
+
     /// Deletes this container.
     ///
     #[doc = include_str!("../../docs/control-plane-warning.md")]
@@ -260,6 +356,7 @@ impl ContainerClient {
             req.insert_header(headers::PREFER, constants::PREFER_MINIMAL);
         }
         req.insert_headers(&partition_key.into())?;
+        add_trigger_headers(&mut req, &options);
         req.set_json(&item)?;
         self.pipeline
             .send(
@@ -336,6 +433,48 @@ impl ContainerClient {
     ///     .await?;
     /// # }
     /// ```
+    ///
+    /// # Optimistic Concurrency
+    ///
+    /// To make sure the item hasn't been changed by another writer since you read it, set [`ItemOptions::precondition`] to [`Precondition::IfMatch`](crate::Precondition::IfMatch),
+    /// using the ETag returned when the item was read. If the item has changed, the replace fails with a `412 Precondition Failed` status:
+    ///
+    /// ```rust,no_run
+    /// use azure_data_cosmos::{models::SystemProperties, CosmosError, ItemOptions};
+    /// use serde::{Deserialize, Serialize};
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// #[derive(Debug, Deserialize, Serialize)]
+    /// pub struct Product {
+    ///     id: String,
+    ///     category_id: String,
+    ///     product_name: String,
+    ///     #[serde(flatten)]
+    ///     system_properties: SystemProperties,
+    /// }
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// let mut product: Product = container_client
+    ///     .read_item("category1", "product1", None)
+    ///     .await?
+    ///     .into_json_body()
+    ///     .await?;
+    /// product.product_name = "Updated Product".to_string();
+    /// let options = ItemOptions {
+    ///     precondition: product
+    ///         .system_properties
+    ///         .etag
+    ///         .clone()
+    ///         .map(Into::into),
+    ///     ..Default::default()
+    /// };
+    /// match container_client.replace_item("category1", "product1", product, Some(options)).await {
+    ///     Err(e) if CosmosError::from_error(&e).is_some_and(|e| e.is_precondition_failed()) => {
+    ///         println!("the product was modified concurrently, re-read it and try again");
+    ///     }
+    ///     result => { result?; }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn replace_item<T: Serialize>(
         &self,
         partition_key: impl Into<PartitionKey>,
@@ -351,6 +490,8 @@ impl ContainerClient {
             req.insert_header(headers::PREFER, constants::PREFER_MINIMAL);
         }
         req.insert_headers(&partition_key.into())?;
+        req.add_optional_header(&options.precondition);
+        add_trigger_headers(&mut req, &options);
         req.set_json(&item)?;
         self.pipeline
             .send(options.method_options.context, &mut req, link)
@@ -440,6 +581,8 @@ impl ContainerClient {
         }
         req.insert_header(constants::IS_UPSERT, "true");
         req.insert_headers(&partition_key.into())?;
+        req.add_optional_header(&options.precondition);
+        add_trigger_headers(&mut req, &options);
         req.set_json(&item)?;
         self.pipeline
             .send(
@@ -494,6 +637,9 @@ impl ContainerClient {
         let url = self.pipeline.url(&link);
         let mut req = Request::new(url, Method::Get);
         req.insert_headers(&partition_key.into())?;
+        req.add_optional_header(&options.precondition);
+        req.add_optional_header(&options.session_token);
+        req.add_optional_header(&options.consistency_level);
         self.pipeline
             .send(options.method_options.context, &mut req, link)
             .await
@@ -530,6 +676,8 @@ impl ContainerClient {
         let url = self.pipeline.url(&link);
         let mut req = Request::new(url, Method::Delete);
         req.insert_headers(&partition_key.into())?;
+        req.add_optional_header(&options.precondition);
+        add_trigger_headers(&mut req, &options);
         self.pipeline
             .send(options.method_options.context, &mut req, link)
             .await
@@ -606,6 +754,8 @@ impl ContainerClient {
             req.insert_header(headers::PREFER, constants::PREFER_MINIMAL);
         }
         req.insert_headers(&partition_key.into())?;
+        req.add_optional_header(&options.precondition);
+        add_trigger_headers(&mut req, &options);
         req.set_json(&patch)?;
 
         self.pipeline
@@ -613,19 +763,328 @@ impl ContainerClient {
             .await
     }
 
-    /// Executes a single-partition query against items in the container.
+    /// Reads many items from the container, given their IDs and partition keys.
+    ///
+    /// This is much more efficient than calling [`read_item()`](ContainerClient::read_item()) for each item.
+    /// The items are grouped by the physical partition (partition key range) they are stored in,
+    /// and each group is read with a single point read (for a single item) or queries targeting the partition (for several items).
+    /// The requests are sent concurrently, up to [`ReadManyOptions::max_concurrency`].
+    /// If a partition is split while the items are read, its items are grouped again using the new partitions.
+    ///
+    /// Failures don't cause the whole operation to fail: each requested item is reported as found, missing, or failed in the returned [`ReadManyResults`].
+    /// An error is only returned if the container's properties or partition key ranges can't be read.
+    ///
+    /// # Arguments
+    /// * `items` - The items to read, as [`ItemReference`]s or `(id, partition_key)` tuples.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// #[derive(serde::Deserialize)]
+    /// struct Product {
+    ///     id: String,
+    ///     category_id: String,
+    /// }
+    /// let results = container_client
+    ///     .read_many_items::<Product>(
+    ///         [("product1", "category1"), ("product2", "category1"), ("product3", "category2")],
+    ///         None,
+    ///     )
+    ///     .await?;
+    /// println!("Found {} items, for {} RUs", results.items.len(), results.request_charge);
+    /// for missing in results.missing {
+    ///     println!("Item {} doesn't exist", missing.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_many_items<T: DeserializeOwned + Send>(
+        &self,
+        items: impl IntoIterator<Item = impl Into<ItemReference>>,
+        options: Option<ReadManyOptions<'_>>,
+    ) -> azure_core::Result<ReadManyResults<T>> {
+        let options = options.unwrap_or_default();
+
+        // Remove duplicates. PartitionKey doesn't implement Hash, so we use its JSON representation in the key.
+        let mut seen = HashSet::new();
+        let mut pending = Vec::new();
+        for item in items {
+            let item: ItemReference = item.into();
+            if seen.insert((item.id.clone(), serde_json::to_string(&item.partition_key)?)) {
+                pending.push(item);
+            }
+        }
+
+        let definition = self
+            .read(Some(ReadContainerOptions {
+                method_options: options.method_options.clone(),
+            }))
+            .await?
+            .into_body()
+            .await?
+            .partition_key;
+        let concurrency = options
+            .max_concurrency
+            .unwrap_or(DEFAULT_READ_MANY_CONCURRENCY)
+            .max(1);
+
+        let mut results = ReadManyResults::default();
+        let mut resolutions = 0;
+        while !pending.is_empty() {
+            let ranges = self
+                .pipeline
+                .partition_key_ranges(options.method_options.context.clone(), &self.link, false)
+                .await?;
+            let batches = read_many_batches(
+                std::mem::take(&mut pending),
+                &definition,
+                &ranges,
+                &mut results,
+            );
+            resolutions += 1;
+
+            let mut batch_results = futures::stream::iter(batches)
+                .map(|(range_id, items)| {
+                    self.read_many_batch::<T>(&definition, range_id, items, &options)
+                })
+                .buffer_unordered(concurrency);
+            while let Some(mut batch) = batch_results.next().await {
+                // When a partition key range was split, the cached ranges have been invalidated: its items are read again from the new ranges.
+                if resolutions < MAX_READ_MANY_RESOLUTIONS {
+                    batch.failures.retain(|failure| {
+                        let gone = CosmosError::from_error(&failure.error)
+                            .is_some_and(|e| e.is_partition_key_range_gone());
+                        if gone {
+                            pending.push(failure.item.clone());
+                        }
+                        !gone
+                    });
+                }
+                results.items.extend(batch.items);
+                results.missing.extend(batch.missing);
+                results.failures.extend(batch.failures);
+                results.request_charge += batch.request_charge;
+            }
+        }
+        Ok(results)
+    }
+
+    /// Reads a batch of items stored in the same partition key range, recording any failure against each item in the batch.
+    async fn read_many_batch<T: DeserializeOwned>(
+        &self,
+        definition: &PartitionKeyDefinition,
+        range_id: String,
+        items: Vec<ItemReference>,
+        options: &ReadManyOptions<'_>,
+    ) -> ReadManyResults<T> {
+        let mut results = ReadManyResults::default();
+        let found = match self
+            .read_many_values(
+                definition,
+                &range_id,
+                &items,
+                options,
+                &mut results.request_charge,
+            )
+            .await
+        {
+            Ok(found) => found,
+            Err(error) => {
+                let error = Arc::new(error);
+                results.failures = items
+                    .into_iter()
+                    .map(|item| ReadManyFailure {
+                        item,
+                        error: error.clone(),
+                    })
+                    .collect();
+                return results;
+            }
+        };
+
+        // Items are identified by their ID and partition key, as the same ID can be used in several logical partitions.
+        // Partition keys are compared by their effective partition key, as the service may encode the values differently than the references (such as `1.0` for `1`).
+        let mut remaining: HashMap<(String, String), ItemReference> = HashMap::new();
+        for item in items {
+            // The effective partition keys of the batch were already computed to group the items by range.
+            let epk = item
+                .partition_key
+                .effective_partition_key(definition)
+                .unwrap_or_default();
+            remaining.insert((item.id.clone(), epk), item);
+        }
+        for value in found {
+            let Some(id) = value.get("id").and_then(|id| id.as_str()).map(String::from) else {
+                continue;
+            };
+            let Some(epk) = item_effective_partition_key(&value, definition) else {
+                continue;
+            };
+            let Some(item) = remaining.remove(&(id, epk)) else {
+                continue;
+            };
+            match serde_json::from_value(value) {
+                Ok(value) => results.items.push(value),
+                Err(e) => results.failures.push(ReadManyFailure {
+                    item,
+                    error: Arc::new(e.into()),
+                }),
+            }
+        }
+        results.missing = remaining.into_values().collect();
+        results
+    }
+
+    /// Reads the raw JSON of the items in a batch, using a point read for a single item, or a query targeting their partition key range for several items.
+    async fn read_many_values(
+        &self,
+        definition: &PartitionKeyDefinition,
+        range_id: &str,
+        items: &[ItemReference],
+        options: &ReadManyOptions<'_>,
+        request_charge: &mut f64,
+    ) -> azure_core::Result<Vec<Value>> {
+        if let [item] = items {
+            let item_options = ItemOptions {
+                method_options: options.method_options.clone(),
+                session_token: options.session_token.clone(),
+                consistency_level: options.consistency_level,
+                ..Default::default()
+            };
+            return match self
+                .read_item(item.partition_key.clone(), &item.id, Some(item_options))
+                .await
+            {
+                Ok(response) => {
+                    *request_charge +=
+                        CosmosDiagnostics::from_headers(response.headers()).request_charge;
+                    Ok(vec![response.into_json_body().await?])
+                }
+                // Only a plain `404 Not Found` means the item doesn't exist.
+                // With a sub-status (such as `READ_SESSION_NOT_AVAILABLE`), the item may exist, and the read failed.
+                Err(e)
+                    if CosmosError::from_error(&e).is_some_and(|e| {
+                        e.status() == StatusCode::NotFound
+                            && matches!(e.substatus(), None | Some(0))
+                    }) =>
+                {
+                    Ok(Vec::new())
+                }
+                Err(e) => Err(e),
+            };
+        }
+
+        // Each item is matched by its ID and partition key values, as `[id, pk1, pk2, ...]`.
+        let mut key = String::from("[c.id");
+        for path in &definition.paths {
+            key.push_str(", c");
+            for property in path.trim_start_matches('/').split('/') {
+                key.push('[');
+                key.push_str(&serde_json::to_string(property)?);
+                key.push(']');
+            }
+        }
+        key.push(']');
+        let keys = items
+            .iter()
+            .map(|item| {
+                let mut key = vec![Value::String(item.id.clone())];
+                if let Value::Array(values) = serde_json::to_value(&item.partition_key)? {
+                    key.extend(values);
+                }
+                Ok(Value::Array(key))
+            })
+            .collect::<azure_core::Result<Vec<_>>>()?;
+        let query = Query::from(format!(
+            "SELECT * FROM c WHERE ARRAY_CONTAINS(@items, {})",
+            key
+        ))
+        .with_parameter("@items", keys)?;
+
+        let url = self.pipeline.url(&self.items_link);
+        let mut base_request = Request::new(url, Method::Post);
+        base_request.add_optional_header(&options.session_token);
+        base_request.add_optional_header(&options.consistency_level);
+        base_request.insert_header(constants::ENABLE_CROSS_PARTITION_QUERY, "True");
+        base_request.insert_header(constants::PARTITION_KEY_RANGE_ID, range_id.to_string());
+        let mut pages = self.pipeline.send_query_request::<Value>(
+            options.method_options.context.clone(),
+            query,
+            base_request,
+            self.items_link.clone(),
+        )?;
+        let mut values = Vec::new();
+        while let Some(page) = pages.try_next().await? {
+            *request_charge += page.diagnostics().request_charge;
+            values.extend(page.into_items());
+        }
+        Ok(values)
+    }
+
+    /// Gets the latest session token observed by this client for the container, if any.
+    ///
+    /// The returned token covers every partition key range of the container this client has interacted with.
+    /// It can be stored (for example, in a cookie) and provided to another client instance using [`ItemOptions::session_token`],
+    /// [`QueryOptions::session_token`] or [`ContainerClient::set_session_token()`], to guarantee that reads made by that client observe writes made by this one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// use azure_data_cosmos::ItemOptions;
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// # let other_container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// container_client
+    ///     .upsert_item("partition1", serde_json::json!({"id": "item1"}), None)
+    ///     .await?;
+    ///
+    /// // Read the item from another client, making sure the write is visible.
+    /// let options = ItemOptions {
+    ///     session_token: container_client.session_token(),
+    ///     ..Default::default()
+    /// };
+    /// let item: serde_json::Value = other_container_client
+    ///     .read_item("partition1", "item1", Some(options))
+    ///     .await?
+    ///     .into_json_body()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn session_token(&self) -> Option<SessionToken> {
+        self.pipeline.sessions.get(&self.link.path())
+    }
+
+    /// Replaces the session tokens tracked by this client for the container.
+    ///
+    /// Subsequent reads and queries that don't specify a session token in their options will use the provided token,
+    /// until the client observes a more recent session token from the service.
+    ///
+    /// # Arguments
+    /// * `session_token` - The session token to use, typically obtained from [`ContainerClient::session_token()`] on another client.
+    pub fn set_session_token(&self, session_token: impl Into<SessionToken>) {
+        self.pipeline
+            .sessions
+            .set(&self.link.path(), &session_token.into());
+    }
+
+    /// Executes a query against items in the container, within a single partition or a [`FeedRange`].
     ///
     /// The resulting document will be deserialized into the type provided as `T`.
     /// If you want to deserialize the document to a direct representation of the JSON returned, use [`serde_json::Value`] as the target type.
     ///
     /// We recommend using ["turbofish" syntax](https://doc.rust-lang.org/book/appendix-02-operators.html#:~:text=turbofish) (`query_items::<SomeTargetType>(...)`) to specify the target type, as it makes type inference easier.
     ///
-    /// **NOTE:** Currently, the Azure Cosmos DB SDK for Rust only supports single-partition querying. Cross-partition queries may be supported in the future.
+    /// **NOTE:** Currently, the Azure Cosmos DB SDK for Rust only supports querying a single partition, or the partitions within a [`FeedRange`] (see [`QueryPartitionStrategy::FeedRange`]).
+    /// Other cross-partition queries may be supported in the future.
     ///
     /// # Arguments
     ///
     /// * `query` - The query to execute.
-    /// * `partition_key_strategy` - The partition key to scope the query on.
+    /// * `partition_key_strategy` - The partition key or [`FeedRange`] to scope the query on.
     /// * `options` - Optional parameters for the request.
     ///
     /// # Examples
@@ -675,14 +1134,1658 @@ impl ContainerClient {
         let options = options.unwrap_or_default();
         let url = self.pipeline.url(&self.items_link);
         let mut base_request = Request::new(url, Method::Post);
-        let QueryPartitionStrategy::SinglePartition(partition_key) = partition_key.into();
-        base_request.insert_headers(&partition_key)?;
+        base_request.add_optional_header(&options.session_token);
+        base_request.add_optional_header(&options.consistency_level);
+        if options.populate_query_metrics {
+            base_request.insert_header(constants::POPULATE_QUERY_METRICS, "True");
+        }
+        if options.populate_index_metrics {
+            base_request.insert_header(constants::POPULATE_INDEX_METRICS, "True");
+        }
 
-        self.pipeline.send_query_request(
+        match partition_key.into() {
+            QueryPartitionStrategy::SinglePartition(partition_key) => {
+                base_request.insert_headers(&partition_key)?;
+                self.pipeline.send_query_request(
+                    options.method_options.context,
+                    query.into(),
+                    base_request,
+                    self.items_link.clone(),
+                )
+            }
+            QueryPartitionStrategy::FeedRange(feed_range) => {
+                self.pipeline.send_feed_range_query_request(
+                    options.method_options.context,
+                    query.into(),
+                    base_request,
+                    self.items_link.clone(),
+                    self.link.clone(),
+                    feed_range,
+                )
+            }
+        }
+    }
+
+    /// Executes a vector, full-text or hybrid search query across all the partitions of the container, returning the top items and their scores.
+    ///
+    /// The query must rank items by a score, using `ORDER BY VectorDistance(...)` for a vector search, or `ORDER BY RANK FullTextScore(...)` or `ORDER BY RANK RRF(...)` for full-text and hybrid searches,
+    /// and should limit the number of results with `TOP` or `OFFSET ... LIMIT`.
+    /// The query is executed following the query plan provided by the service: the top items of each partition are read, then merged into a single ranking.
+    /// Hybrid searches combine the ranks of each item in each component search using Reciprocal Rank Fusion.
+    ///
+    /// The container's [`VectorEmbeddingPolicy`](crate::models::VectorEmbeddingPolicy) and indexing policy must be configured for the fields used in the search.
+    ///
+    /// # Arguments
+    /// * `query` - The search query to execute.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// use azure_data_cosmos::Query;
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// #[derive(serde::Deserialize)]
+    /// struct Chunk {
+    ///     id: String,
+    ///     text: String,
+    /// }
+    /// let embedding: Vec<f32> = vec![0.1, 0.2, 0.3];
+    /// let query = Query::from(
+    ///     "SELECT TOP 5 c.id, c.text FROM c ORDER BY VectorDistance(c.embedding, @embedding)",
+    /// )
+    /// .with_parameter("@embedding", embedding)?;
+    /// let results = container_client.search_items::<Chunk>(query, None).await?;
+    /// for result in results.items {
+    ///     println!("{} ({}): {}", result.item.id, result.score, result.item.text);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn search_items<T: DeserializeOwned + Send>(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryOptions<'_>>,
+    ) -> azure_core::Result<SearchResults<T>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.items_link);
+        let mut base_request = Request::new(url, Method::Post);
+        base_request.add_optional_header(&options.session_token);
+        base_request.add_optional_header(&options.consistency_level);
+
+        search::execute_search(
+            &self.pipeline,
             options.method_options.context,
+            &self.link,
+            &self.items_link,
             query.into(),
             base_request,
-            self.items_link.clone(),
         )
+        .await
+    }
+
+    /// Reads the feed ranges of the container, one for each of its physical partitions.
+    ///
+    /// The feed ranges cover every effective partition key, without overlapping, so they can be used to split the work of reading the whole container between several workers.
+    /// Each worker can then query the items within its feed range using [`QueryPartitionStrategy::FeedRange`].
+    /// The feed ranges are read from the partition key ranges cached by the client, which are refreshed when the service reports that a partition was split.
+    /// A feed range remains valid after its partition is split: queries within it read from each of the new partitions.
+    ///
+    /// # Arguments
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// let feed_ranges = container_client.read_feed_ranges(None).await?;
+    /// for feed_range in feed_ranges {
+    ///     // Hand each feed range to a different worker, which can run:
+    ///     let pager = container_client.query_items::<serde_json::Value>("SELECT * FROM c", feed_range, None)?;
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn read_feed_ranges(
+        &self,
+        options: Option<ReadFeedRangesOptions<'_>>,
+    ) -> azure_core::Result<Vec<FeedRange>> {
+        let options = options.unwrap_or_default();
+        let ranges = self
+            .pipeline
+            .partition_key_ranges(options.method_options.context, &self.link, false)
+            .await?;
+        Ok(ranges.iter().map(|range| range.feed_range()).collect())
+    }
+
+    /// Creates a new stored procedure in the container.
+    ///
+    /// # Arguments
+    /// * `properties` - A [`StoredProcedureProperties`] describing the new stored procedure.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// use azure_data_cosmos::models::StoredProcedureProperties;
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// let body = r#"function greet(name) {
+    ///     getContext().getResponse().setBody("Hello, " + name);
+    /// }"#;
+    /// container_client
+    ///     .create_stored_procedure(StoredProcedureProperties::new("greet", body), None)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_stored_procedure(
+        &self,
+        properties: StoredProcedureProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<StoredProcedureProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.sprocs_link);
+        let mut req = Request::new(url, Method::Post);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(
+                options.method_options.context,
+                &mut req,
+                self.sprocs_link.clone(),
+            )
+            .await
+    }
+
+    /// Reads the properties of a stored procedure.
+    ///
+    /// # Arguments
+    /// * `stored_procedure_id` - The id of the stored procedure to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_stored_procedure(
+        &self,
+        stored_procedure_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<StoredProcedureProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.sprocs_link.item(stored_procedure_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Replaces an existing stored procedure.
+    ///
+    /// # Arguments
+    /// * `properties` - The new [`StoredProcedureProperties`]. The [`id`](StoredProcedureProperties::id) identifies the stored procedure to replace.
+    /// * `options` - Optional parameters for the request.
+    pub async fn replace_stored_procedure(
+        &self,
+        properties: StoredProcedureProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<StoredProcedureProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.sprocs_link.item(&properties.id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Put);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a stored procedure.
+    ///
+    /// # Arguments
+    /// * `stored_procedure_id` - The id of the stored procedure to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_stored_procedure(
+        &self,
+        stored_procedure_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.sprocs_link.item(stored_procedure_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against the stored procedures in the container.
+    ///
+    /// # Arguments
+    /// * `query` - The query to execute.
+    /// * `options` - Optional parameters for the request.
+    pub fn query_stored_procedures(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryScriptsOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<StoredProcedureProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.sprocs_link);
+        let base_request = Request::new(url, Method::Post);
+
+        self.pipeline.send_query_request(
+            options.method_options.context,
+            query.into(),
+            base_request,
+            self.sprocs_link.clone(),
+        )
+    }
+
+    /// Executes a stored procedure.
+    ///
+    /// Stored procedures run transactionally, within the logical partition identified by `partition_key`.
+    /// The value passed to `getContext().getResponse().setBody()` by the stored procedure is returned as the response body,
+    /// and can be deserialized using [`Response::into_json_body`].
+    /// When [`ExecuteStoredProcedureOptions::enable_script_logging`] is set, the output of `console.log` is available using [`ScriptLog::from_headers`](crate::models::ScriptLog::from_headers).
+    ///
+    /// # Arguments
+    /// * `partition_key` - The partition key the stored procedure runs against.
+    /// * `stored_procedure_id` - The id of the stored procedure to execute.
+    /// * `parameters` - The parameters to pass to the stored procedure. This must serialize to a JSON array, such as a tuple, a [`Vec`] or a [`serde_json::json!`] array.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// let greeting: String = container_client
+    ///     .execute_stored_procedure("partition1", "greet", ("World",), None)
+    ///     .await?
+    ///     .into_json_body()
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_stored_procedure<T: Serialize>(
+        &self,
+        partition_key: impl Into<PartitionKey>,
+        stored_procedure_id: &str,
+        parameters: T,
+        options: Option<ExecuteStoredProcedureOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.sprocs_link.item(stored_procedure_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Post);
+        req.insert_headers(&partition_key.into())?;
+        if options.enable_script_logging {
+            req.insert_header(constants::SCRIPT_ENABLE_LOGGING, "true");
+        }
+        req.set_json(&parameters)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Creates a new trigger in the container.
+    ///
+    /// # Arguments
+    /// * `properties` - A [`TriggerProperties`] describing the new trigger.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// Triggers only run when they are requested using [`ItemOptions::pre_triggers`] or [`ItemOptions::post_triggers`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// use azure_data_cosmos::{
+    ///     models::{TriggerOperation, TriggerProperties, TriggerType},
+    ///     ItemOptions,
+    /// };
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// let body = r#"function addTimestamp() {
+    ///     var request = getContext().getRequest();
+    ///     var item = request.getBody();
+    ///     item.createdAt = new Date().toISOString();
+    ///     request.setBody(item);
+    /// }"#;
+    /// let trigger = TriggerProperties::new("addTimestamp", body, TriggerType::Pre, TriggerOperation::Create);
+    /// container_client.create_trigger(trigger, None).await?;
+    ///
+    /// let options = ItemOptions {
+    ///     pre_triggers: vec!["addTimestamp".to_string()],
+    ///     ..Default::default()
+    /// };
+    /// container_client
+    ///     .create_item("partition1", serde_json::json!({"id": "item1"}), Some(options))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_trigger(
+        &self,
+        properties: TriggerProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<TriggerProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.triggers_link);
+        let mut req = Request::new(url, Method::Post);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(
+                options.method_options.context,
+                &mut req,
+                self.triggers_link.clone(),
+            )
+            .await
+    }
+
+    /// Reads the properties of a trigger.
+    ///
+    /// # Arguments
+    /// * `trigger_id` - The id of the trigger to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_trigger(
+        &self,
+        trigger_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<TriggerProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.triggers_link.item(trigger_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Replaces an existing trigger.
+    ///
+    /// # Arguments
+    /// * `properties` - The new [`TriggerProperties`]. The [`id`](TriggerProperties::id) identifies the trigger to replace.
+    /// * `options` - Optional parameters for the request.
+    pub async fn replace_trigger(
+        &self,
+        properties: TriggerProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<TriggerProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.triggers_link.item(&properties.id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Put);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a trigger.
+    ///
+    /// # Arguments
+    /// * `trigger_id` - The id of the trigger to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_trigger(
+        &self,
+        trigger_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.triggers_link.item(trigger_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against the triggers in the container.
+    ///
+    /// # Arguments
+    /// * `query` - The query to execute.
+    /// * `options` - Optional parameters for the request.
+    pub fn query_triggers(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryScriptsOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<TriggerProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.triggers_link);
+        let base_request = Request::new(url, Method::Post);
+
+        self.pipeline.send_query_request(
+            options.method_options.context,
+            query.into(),
+            base_request,
+            self.triggers_link.clone(),
+        )
+    }
+
+    /// Creates a new user-defined function in the container.
+    ///
+    /// # Arguments
+    /// * `properties` - A [`UserDefinedFunctionProperties`] describing the new user-defined function.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// User-defined functions can be called from queries using the `udf.` prefix, for example `SELECT udf.tax(c.price) FROM c`.
+    pub async fn create_user_defined_function(
+        &self,
+        properties: UserDefinedFunctionProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<UserDefinedFunctionProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.udfs_link);
+        let mut req = Request::new(url, Method::Post);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(
+                options.method_options.context,
+                &mut req,
+                self.udfs_link.clone(),
+            )
+            .await
+    }
+
+    /// Reads the properties of a user-defined function.
+    ///
+    /// # Arguments
+    /// * `user_defined_function_id` - The id of the user-defined function to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_user_defined_function(
+        &self,
+        user_defined_function_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<UserDefinedFunctionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.udfs_link.item(user_defined_function_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Replaces an existing user-defined function.
+    ///
+    /// # Arguments
+    /// * `properties` - The new [`UserDefinedFunctionProperties`]. The [`id`](UserDefinedFunctionProperties::id) identifies the user-defined function to replace.
+    /// * `options` - Optional parameters for the request.
+    pub async fn replace_user_defined_function(
+        &self,
+        properties: UserDefinedFunctionProperties,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response<UserDefinedFunctionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.udfs_link.item(&properties.id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Put);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a user-defined function.
+    ///
+    /// # Arguments
+    /// * `user_defined_function_id` - The id of the user-defined function to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_user_defined_function(
+        &self,
+        user_defined_function_id: &str,
+        options: Option<ScriptOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.udfs_link.item(user_defined_function_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against the user-defined functions in the container.
+    ///
+    /// # Arguments
+    /// * `query` - The query to execute.
+    /// * `options` - Optional parameters for the request.
+    pub fn query_user_defined_functions(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryScriptsOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<UserDefinedFunctionProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.udfs_link);
+        let base_request = Request::new(url, Method::Post);
+
+        self.pipeline.send_query_request(
+            options.method_options.context,
+            query.into(),
+            base_request,
+            self.udfs_link.clone(),
+        )
+    }
+
+    /// Reads a conflict from the conflicts feed of the container.
+    ///
+    /// Conflicts are only recorded in accounts with multiple write regions, see [`ConflictProperties`] for more information.
+    ///
+    /// # Arguments
+    /// * `partition_key` - The partition key of the conflicting item.
+    /// * `conflict_id` - The id of the conflict to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_conflict(
+        &self,
+        partition_key: impl Into<PartitionKey>,
+        conflict_id: &str,
+        options: Option<ConflictOptions<'_>>,
+    ) -> azure_core::Result<Response<ConflictProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.conflicts_link.item(conflict_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        req.insert_headers(&partition_key.into())?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a conflict from the conflicts feed of the container, once it has been resolved.
+    ///
+    /// Deleting a conflict doesn't modify the conflicting item.
+    ///
+    /// # Arguments
+    /// * `partition_key` - The partition key of the conflicting item.
+    /// * `conflict_id` - The id of the conflict to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_conflict(
+        &self,
+        partition_key: impl Into<PartitionKey>,
+        conflict_id: &str,
+        options: Option<ConflictOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.conflicts_link.item(conflict_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        req.insert_headers(&partition_key.into())?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against the conflicts feed of the container.
+    ///
+    /// Use a [`FeedRange`] (such as [`FeedRange::full()`]) as the partition strategy to read the conflicts of every partition.
+    ///
+    /// # Arguments
+    /// * `query` - The query to execute.
+    /// * `partition_key` - The partition key or [`FeedRange`] to scope the query on.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// A background job can merge each conflicting version into the current item, then delete the conflict:
+    ///
+    /// ```rust,no_run
+    /// use azure_data_cosmos::{models::ConflictOperationType, FeedRange};
+    /// use futures::TryStreamExt;
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+    /// #[derive(serde::Deserialize)]
+    /// struct Order {
+    ///     id: String,
+    ///     customer_id: String,
+    /// }
+    /// let mut conflicts = container_client.query_conflicts("SELECT * FROM c", FeedRange::full(), None)?;
+    /// while let Some(page) = conflicts.try_next().await? {
+    ///     for conflict in page.into_items() {
+    ///         if conflict.operation_type != ConflictOperationType::Delete {
+    ///             if let Some(order) = conflict.content::<Order>()? {
+    ///                 // Merge `order` into the current version of the item...
+    ///                 container_client
+    ///                     .delete_conflict(order.customer_id, &conflict.id, None)
+    ///                     .await?;
+    ///             }
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_conflicts(
+        &self,
+        query: impl Into<Query>,
+        partition_key: impl Into<QueryPartitionStrategy>,
+        options: Option<QueryConflictsOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<ConflictProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.conflicts_link);
+        let mut base_request = Request::new(url, Method::Post);
+
+        match partition_key.into() {
+            QueryPartitionStrategy::SinglePartition(partition_key) => {
+                base_request.insert_headers(&partition_key)?;
+                self.pipeline.send_query_request(
+                    options.method_options.context,
+                    query.into(),
+                    base_request,
+                    self.conflicts_link.clone(),
+                )
+            }
+            QueryPartitionStrategy::FeedRange(feed_range) => {
+                self.pipeline.send_feed_range_query_request(
+                    options.method_options.context,
+                    query.into(),
+                    base_request,
+                    self.conflicts_link.clone(),
+                    self.link.clone(),
+                    feed_range,
+                )
+            }
+        }
+    }
+}
+
+/// Groups items by the partition key range containing their effective partition key, in batches of up to [`READ_MANY_QUERY_BATCH_SIZE`] items.
+///
+/// Items whose partition key can't be hashed are recorded as failures.
+fn read_many_batches<T>(
+    items: Vec<ItemReference>,
+    definition: &PartitionKeyDefinition,
+    ranges: &[PartitionKeyRange],
+    results: &mut ReadManyResults<T>,
+) -> Vec<(String, Vec<ItemReference>)> {
+    let mut groups: Vec<(String, Vec<ItemReference>)> = Vec::new();
+    for item in items {
+        let range = item
+            .partition_key
+            .effective_partition_key(definition)
+            .and_then(|epk| {
+                ranges
+                    .iter()
+                    .find(|range| range.feed_range().contains(&epk))
+                    .ok_or_else(|| {
+                        azure_core::Error::message(
+                            azure_core::error::ErrorKind::DataConversion,
+                            format!(
+                                "no partition key range contains the effective partition key {}",
+                                epk
+                            ),
+                        )
+                    })
+            });
+        match range {
+            Ok(range) => match groups.iter_mut().find(|(id, _)| *id == range.id) {
+                Some((_, items)) => items.push(item),
+                None => groups.push((range.id.clone(), vec![item])),
+            },
+            Err(error) => results.failures.push(ReadManyFailure {
+                item,
+                error: Arc::new(error),
+            }),
+        }
+    }
+    groups
+        .into_iter()
+        .flat_map(|(range_id, items)| {
+            items
+                .chunks(READ_MANY_QUERY_BATCH_SIZE)
+                .map(|batch| (range_id.clone(), batch.to_vec()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Computes the effective partition key of an item from its partition key values.
+///
+/// Returns `None` if the values aren't valid partition key values, such as objects or arrays.
+fn item_effective_partition_key(
+    item: &Value,
+    definition: &PartitionKeyDefinition,
+) -> Option<String> {
+    serde_json::from_value::<PartitionKey>(Value::Array(partition_key_values(item, definition)))
+        .ok()?
+        .effective_partition_key(definition)
+        .ok()
+}
+
+/// Extracts the partition key values of an item, following the paths of the container's partition key definition.
+///
+/// Missing values are returned as `null`.
+fn partition_key_values(item: &Value, definition: &PartitionKeyDefinition) -> Vec<Value> {
+    definition
+        .paths
+        .iter()
+        .map(|path| {
+            path.trim_start_matches('/')
+                .split('/')
+                .try_fold(item, |value, property| value.get(property))
+                .cloned()
+                .unwrap_or(Value::Null)
+        })
+        .collect()
+}
+
+fn add_trigger_headers(req: &mut Request, options: &ItemOptions<'_>) {
+    if !options.pre_triggers.is_empty() {
+        req.insert_header(
+            constants::PRE_TRIGGER_INCLUDE,
+            options.pre_triggers.join(","),
+        );
+    }
+    if !options.post_triggers.is_empty() {
+        req.insert_header(
+            constants::POST_TRIGGER_INCLUDE,
+            options.post_triggers.join(","),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use azure_core::http::poller::PollerStatus;
+    use azure_core::{
+        credentials::{AccessToken, TokenCredential},
+        http::{
+            headers::Headers, request::Body, ClientOptions, HttpClient, Method, Response,
+            StatusCode, TransportOptions,
+        },
+        Bytes,
+    };
+    use azure_core_test::http::MockHttpClient;
+    use futures::{FutureExt, TryStreamExt};
+    use serde::Deserialize;
+    use time::OffsetDateTime;
+
+    use crate::{
+        clients::ContainerClient,
+        constants,
+        models::{
+            ConflictOperationType, ConflictProperties, ItemReference, PartitionKeyDefinition,
+            ThroughputProperties,
+        },
+        CosmosClient, CosmosClientOptions, CosmosError, FeedRange, PartitionKey, Query,
+        ThroughputOptions,
+    };
+
+    #[derive(Debug)]
+    struct TestTokenCredential;
+
+    #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+    impl TokenCredential for TestTokenCredential {
+        async fn get_token(&self, _scopes: &[&str]) -> azure_core::Result<AccessToken> {
+            Ok(AccessToken::new(
+                "test_token",
+                OffsetDateTime::now_utc().saturating_add(time::Duration::minutes(5)),
+            ))
+        }
+    }
+
+    /// Creates a client for the container "c" in the database "db", which sends its requests to `transport`.
+    fn test_client(transport: Arc<dyn HttpClient>) -> azure_core::Result<ContainerClient> {
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(transport)),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        Ok(client.database_client("db").container_client("c"))
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: String,
+        value: u32,
+    }
+
+    /// A partition key range of the mock container: its ID, and the effective partition keys it covers.
+    type MockRange = (&'static str, &'static str, &'static str);
+
+    /// A request sent to the mock container: its method, path and the partition key range it targets.
+    type MockRequest = (Method, String, Option<String>);
+
+    /// Serves a container partitioned on `/pk`, holding items "a", "b" and "c" (in partition "p1"), "a" (in partition "p4") and "d" and "x" (in partitions "p2" and "p3").
+    /// Item "c" has an invalid shape, point reads in partition "broken" fail, and point reads in partition "session" fail with `404 Not Found` and the sub-status 1002.
+    ///
+    /// The container's partition key ranges are the last entry of `ranges`, but each read of the partition key ranges returns the next entry, simulating stale routing information.
+    /// Queries targeting a range which isn't in the last entry fail with `410 Gone`.
+    fn partitioned_transport(
+        requests: Arc<Mutex<Vec<MockRequest>>>,
+        ranges: Vec<Vec<MockRange>>,
+    ) -> Arc<dyn HttpClient> {
+        let ranges = Arc::new(Mutex::new((ranges, 0)));
+        Arc::new(MockHttpClient::new(move |req| {
+            let requests = requests.clone();
+            let ranges = ranges.clone();
+            async move {
+                let header = |name| req.headers().get_optional_string(&name);
+                let range_id = header(constants::PARTITION_KEY_RANGE_ID);
+                let path = req.url().path().to_string();
+                requests
+                    .lock()
+                    .unwrap()
+                    .push((*req.method(), path.clone(), range_id.clone()));
+                let definition: PartitionKeyDefinition = serde_json::from_value(
+                    serde_json::json!({"paths": ["/pk"], "kind": "Hash", "version": 2}),
+                )
+                .unwrap();
+                let json = |status, headers, body: serde_json::Value| {
+                    Ok(Response::from_bytes(
+                        status,
+                        headers,
+                        serde_json::to_vec(&body).unwrap(),
+                    ))
+                };
+
+                // The ranges returned by this read, and the ranges the container actually has.
+                let (returned, current) = {
+                    let mut ranges = ranges.lock().unwrap();
+                    let (all, reads) = &mut *ranges;
+                    if path == "/dbs/db/colls/c/pkranges" {
+                        *reads = (*reads + 1).min(all.len());
+                    }
+                    (
+                        all[reads.saturating_sub(1)].clone(),
+                        all.last().unwrap().clone(),
+                    )
+                };
+                match path.as_str() {
+                    "/dbs/db/colls/c" => {
+                        return json(
+                            StatusCode::Ok,
+                            Headers::new(),
+                            serde_json::json!({"id": "c", "partitionKey": definition}),
+                        );
+                    }
+                    "/dbs/db/colls/c/pkranges" => {
+                        let ranges: Vec<_> = returned
+                            .iter()
+                            .map(|(id, min, max)| {
+                                serde_json::json!({"id": id, "minInclusive": min, "maxExclusive": max})
+                            })
+                            .collect();
+                        return json(
+                            StatusCode::Ok,
+                            Headers::new(),
+                            serde_json::json!({ "PartitionKeyRanges": ranges }),
+                        );
+                    }
+                    _ => {}
+                }
+
+                let mut headers = Headers::new();
+                headers.insert(constants::REQUEST_CHARGE, "1.5");
+                let items = vec![
+                    serde_json::json!({"id": "a", "pk": "p1", "value": 1}),
+                    serde_json::json!({"id": "b", "pk": "p1", "value": 2}),
+                    serde_json::json!({"id": "c", "pk": "p1", "value": "not a number"}),
+                    serde_json::json!({"id": "a", "pk": "p4", "value": 7}),
+                    serde_json::json!({"id": "d", "pk": "p2", "value": 4}),
+                    serde_json::json!({"id": "x", "pk": "p3", "value": 9}),
+                ];
+                match req.method() {
+                    Method::Get => {
+                        let partition_key = header(constants::PARTITION_KEY).unwrap();
+                        match partition_key.as_str() {
+                            r#"["broken"]"# => {
+                                return json(StatusCode::BadRequest, headers, serde_json::json!({}))
+                            }
+                            r#"["session"]"# => {
+                                headers.insert(constants::SUB_STATUS, "1002");
+                                return json(StatusCode::NotFound, headers, serde_json::json!({}));
+                            }
+                            _ => {}
+                        }
+                        let partition_key: serde_json::Value =
+                            serde_json::from_str(&partition_key).unwrap();
+                        let id = path.rsplit('/').next().unwrap();
+                        match items.into_iter().find(|v| {
+                            v["id"] == id && serde_json::json!([v["pk"].clone()]) == partition_key
+                        }) {
+                            Some(item) => json(StatusCode::Ok, headers, item),
+                            None => json(StatusCode::NotFound, headers, serde_json::json!({})),
+                        }
+                    }
+                    _ => {
+                        let range_id = range_id.expect("queries target a partition key range");
+                        let Some((_, min, max)) =
+                            current.iter().find(|(id, _, _)| *id == range_id)
+                        else {
+                            headers.insert(constants::SUB_STATUS, "1002");
+                            return json(StatusCode::Gone, headers, serde_json::json!({}));
+                        };
+                        let range = FeedRange::new(*min, *max);
+                        let Body::Bytes(body) = req.body() else {
+                            panic!("expected a query body");
+                        };
+                        let query: serde_json::Value = serde_json::from_slice(body).unwrap();
+                        let keys = query["parameters"][0]["value"].as_array().unwrap();
+                        let documents: Vec<_> = items
+                            .into_iter()
+                            .filter(|v| {
+                                let epk = PartitionKey::from(v["pk"].as_str().unwrap().to_string())
+                                    .effective_partition_key(&definition)
+                                    .unwrap();
+                                range.contains(&epk)
+                                    && keys.contains(&serde_json::json!([v["id"], v["pk"]]))
+                            })
+                            .collect();
+                        json(
+                            StatusCode::Ok,
+                            headers,
+                            serde_json::json!({ "Documents": documents }),
+                        )
+                    }
+                }
+            }
+            .boxed()
+        }))
+    }
+
+    #[tokio::test]
+    pub async fn read_many_items() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(partitioned_transport(
+            requests.clone(),
+            vec![vec![
+                ("0", "", "20"),
+                ("1", "20", "38"),
+                ("2", "38", "3B"),
+                ("3", "3B", "FF"),
+            ]],
+        ))?;
+
+        let mut results = container_client
+            .read_many_items::<Item>(
+                vec![
+                    ItemReference::new("a", "p1"),
+                    ItemReference::new("b", "p1"),
+                    ItemReference::new("c", "p1"),
+                    ItemReference::new("missing", "p1"),
+                    ItemReference::new("a", "p1"),
+                    ItemReference::new("a", "p4"),
+                    ItemReference::new("d", "p2"),
+                    ItemReference::new("e", "broken"),
+                    ItemReference::new("s", "session"),
+                ],
+                None,
+            )
+            .await?;
+
+        results.items.sort_by_key(|i| i.value);
+        assert_eq!(
+            vec![("a", 1), ("b", 2), ("d", 4), ("a", 7)],
+            results
+                .items
+                .iter()
+                .map(|i| (i.id.as_str(), i.value))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["missing"],
+            results.missing.iter().map(|i| &i.id).collect::<Vec<_>>()
+        );
+        // "s" may exist: the replica hadn't caught up with the session when it was read.
+        let mut failed: Vec<_> = results.failures.iter().map(|f| &f.item.id).collect();
+        failed.sort();
+        assert_eq!(vec!["c", "e", "s"], failed);
+
+        // One query for the items in range "0" (partitions "p1" and "p4"), and point reads for the other ranges.
+        let requests = requests.lock().unwrap();
+        let queries: Vec<_> = requests
+            .iter()
+            .filter(|(method, _, _)| *method == Method::Post)
+            .map(|(_, path, range_id)| (path.as_str(), range_id.as_deref()))
+            .collect();
+        assert_eq!(vec![("/dbs/db/colls/c/docs", Some("0"))], queries);
+        for id in ["d", "e", "s"] {
+            let path = format!("/dbs/db/colls/c/docs/{}", id);
+            assert!(requests
+                .iter()
+                .any(|(method, p, _)| *method == Method::Get && *p == path));
+        }
+        assert_eq!(3.0, results.request_charge);
+        Ok(())
+    }
+
+    #[test]
+    pub fn matches_items_by_effective_partition_key() {
+        let definition = PartitionKeyDefinition::from("/pk");
+        let epk = |item| super::item_effective_partition_key(&item, &definition);
+        let reference = |pk: PartitionKey| pk.effective_partition_key(&definition).ok();
+
+        assert_eq!(
+            reference(PartitionKey::from(1)),
+            epk(serde_json::json!({"id": "a", "pk": 1.0}))
+        );
+        assert_eq!(
+            reference(PartitionKey::from("1")),
+            epk(serde_json::json!({"id": "a", "pk": "1"}))
+        );
+        assert_ne!(
+            reference(PartitionKey::from(1)),
+            epk(serde_json::json!({"id": "a", "pk": "1"}))
+        );
+        // Missing values are matched as `null`.
+        assert_eq!(
+            reference(PartitionKey::from(())),
+            epk(serde_json::json!({"id": "a"}))
+        );
+        assert_eq!(
+            None,
+            epk(serde_json::json!({"id": "a", "pk": {"nested": 1}}))
+        );
+    }
+
+    #[tokio::test]
+    pub async fn read_many_items_after_split() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(partitioned_transport(
+            requests.clone(),
+            vec![
+                vec![("0", "", "FF")],
+                vec![("1", "", "20"), ("2", "20", "FF")],
+            ],
+        ))?;
+
+        let mut results = container_client
+            .read_many_items::<Item>(
+                vec![
+                    ItemReference::new("a", "p1"),
+                    ItemReference::new("b", "p1"),
+                    ItemReference::new("d", "p2"),
+                    ItemReference::new("x", "p3"),
+                ],
+                None,
+            )
+            .await?;
+
+        results.items.sort_by_key(|i| i.value);
+        assert_eq!(
+            vec!["a", "b", "d", "x"],
+            results.items.iter().map(|i| &i.id).collect::<Vec<_>>()
+        );
+        assert!(results.missing.is_empty());
+        assert!(results.failures.is_empty());
+
+        // Range "0" was split, so its items were grouped again using the new ranges.
+        let requests = requests.lock().unwrap();
+        let mut queries: Vec<_> = requests
+            .iter()
+            .filter(|(method, _, _)| *method == Method::Post)
+            .filter_map(|(_, _, range_id)| range_id.as_deref())
+            .collect();
+        queries[1..].sort();
+        assert_eq!(vec!["0", "1", "2"], queries);
+        assert_eq!(
+            2,
+            requests
+                .iter()
+                .filter(|(_, path, _)| path == "/dbs/db/colls/c/pkranges")
+                .count()
+        );
+        Ok(())
+    }
+
+    /// Serves a container with two partition key ranges, where the first range returns its results in two pages.
+    fn feed_range_transport(requests: Arc<Mutex<Vec<Vec<Option<String>>>>>) -> Arc<dyn HttpClient> {
+        Arc::new(MockHttpClient::new(move |req| {
+            let requests = requests.clone();
+            async move {
+                if req.url().path() == "/dbs/db/colls/c/pkranges" {
+                    let body = serde_json::json!({ "PartitionKeyRanges": [
+                        { "id": "1", "minInclusive": "80", "maxExclusive": "FF" },
+                        { "id": "0", "minInclusive": "", "maxExclusive": "80" },
+                    ]});
+                    return Ok(Response::from_bytes(
+                        StatusCode::Ok,
+                        Headers::new(),
+                        serde_json::to_vec(&body).unwrap(),
+                    ));
+                }
+
+                let header = |name| req.headers().get_optional_string(&name);
+                let range_id = header(constants::PARTITION_KEY_RANGE_ID).unwrap();
+                let continuation = header(constants::CONTINUATION);
+                requests.lock().unwrap().push(vec![
+                    Some(range_id.clone()),
+                    header(constants::START_EPK),
+                    header(constants::END_EPK),
+                    continuation.clone(),
+                ]);
+                let mut headers = Headers::new();
+                let id = match (range_id.as_str(), continuation) {
+                    ("0", None) => {
+                        headers.insert(constants::CONTINUATION, "next");
+                        "a"
+                    }
+                    ("0", Some(_)) => "b",
+                    _ => "c",
+                };
+                let body = serde_json::json!({ "Documents": [{ "id": id, "value": 1 }] });
+                Ok(Response::from_bytes(
+                    StatusCode::Ok,
+                    headers,
+                    serde_json::to_vec(&body).unwrap(),
+                ))
+            }
+            .boxed()
+        }))
+    }
+
+    #[tokio::test]
+    pub async fn query_feed_range() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(feed_range_transport(requests.clone()))?;
+
+        let feed_ranges = container_client.read_feed_ranges(None).await?;
+        assert_eq!(
+            vec![FeedRange::new("", "80"), FeedRange::new("80", "FF")],
+            feed_ranges
+        );
+
+        let items: Vec<Item> = container_client
+            .query_items::<Item>("SELECT * FROM c", FeedRange::new("40", "FF"), None)?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flat_map(|page| page.into_items())
+            .collect();
+        assert_eq!(
+            vec!["a", "b", "c"],
+            items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>()
+        );
+
+        // The first range is only partially covered, so the query is limited to the requested effective partition keys.
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            vec![
+                vec![some("0"), some("40"), some("80"), None],
+                vec![some("0"), some("40"), some("80"), some("next")],
+                vec![some("1"), None, None, None],
+            ],
+            *requests.lock().unwrap()
+        );
+        Ok(())
+    }
+
+    /// Serves a container whose only partition key range "0" is split into ranges "1" and "2" after the first page of a query.
+    fn split_transport(requests: Arc<Mutex<Vec<Vec<Option<String>>>>>) -> Arc<dyn HttpClient> {
+        let split = Arc::new(Mutex::new(false));
+        Arc::new(MockHttpClient::new(move |req| {
+            let requests = requests.clone();
+            let split = split.clone();
+            async move {
+                let header = |name| req.headers().get_optional_string(&name);
+                let json = |status, headers, body: serde_json::Value| {
+                    Ok(Response::from_bytes(
+                        status,
+                        headers,
+                        serde_json::to_vec(&body).unwrap(),
+                    ))
+                };
+                if req.url().path() == "/dbs/db/colls/c/pkranges" {
+                    requests.lock().unwrap().push(vec![None]);
+                    let ranges = match *split.lock().unwrap() {
+                        false => serde_json::json!([
+                            { "id": "0", "minInclusive": "", "maxExclusive": "FF" },
+                        ]),
+                        true => serde_json::json!([
+                            { "id": "1", "minInclusive": "", "maxExclusive": "80" },
+                            { "id": "2", "minInclusive": "80", "maxExclusive": "FF" },
+                        ]),
+                    };
+                    return json(
+                        StatusCode::Ok,
+                        Headers::new(),
+                        serde_json::json!({ "PartitionKeyRanges": ranges }),
+                    );
+                }
+
+                let range_id = header(constants::PARTITION_KEY_RANGE_ID).unwrap();
+                let continuation = header(constants::CONTINUATION);
+                requests.lock().unwrap().push(vec![
+                    Some(range_id.clone()),
+                    header(constants::START_EPK),
+                    header(constants::END_EPK),
+                    continuation.clone(),
+                ]);
+                let mut headers = Headers::new();
+                let id = match (range_id.as_str(), continuation) {
+                    ("0", None) => {
+                        *split.lock().unwrap() = true;
+                        headers.insert(constants::CONTINUATION, "next");
+                        "a"
+                    }
+                    ("0", Some(_)) => {
+                        headers.insert(constants::SUB_STATUS, "1002");
+                        return json(StatusCode::Gone, headers, serde_json::json!({}));
+                    }
+                    ("1", _) => "b",
+                    _ => "c",
+                };
+                json(
+                    StatusCode::Ok,
+                    headers,
+                    serde_json::json!({ "Documents": [{ "id": id, "value": 1 }] }),
+                )
+            }
+            .boxed()
+        }))
+    }
+
+    #[tokio::test]
+    pub async fn query_feed_range_after_split() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(split_transport(requests.clone()))?;
+
+        let feed_ranges = container_client.read_feed_ranges(None).await?;
+        assert_eq!(vec![FeedRange::full()], feed_ranges);
+
+        let items: Vec<Item> = container_client
+            .query_items::<Item>("SELECT * FROM c", feed_ranges[0].clone(), None)?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flat_map(|page| page.into_items())
+            .collect();
+        assert_eq!(
+            vec!["a", "b", "c"],
+            items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>()
+        );
+
+        // The ranges are only read again once the service reports the split, and the query continues from each of the new ranges.
+        let feed_ranges = container_client.read_feed_ranges(None).await?;
+        assert_eq!(
+            vec![FeedRange::new("", "80"), FeedRange::new("80", "FF")],
+            feed_ranges
+        );
+        let some = |s: &str| Some(s.to_string());
+        assert_eq!(
+            vec![
+                vec![None],
+                vec![some("0"), None, None, None],
+                vec![some("0"), None, None, some("next")],
+                vec![None],
+                vec![some("1"), None, None, some("next")],
+                vec![some("2"), None, None, some("next")],
+            ],
+            *requests.lock().unwrap()
+        );
+        Ok(())
+    }
+
+    /// Serves a vector search over a container with two partition key ranges.
+    fn search_transport() -> Arc<dyn HttpClient> {
+        Arc::new(MockHttpClient::new(move |req| {
+            async move {
+                let mut headers = Headers::new();
+                headers.insert(constants::REQUEST_CHARGE, "1");
+                let body = if req.url().path() == "/dbs/db/colls/c/pkranges" {
+                    serde_json::json!({ "PartitionKeyRanges": [
+                        { "id": "0", "minInclusive": "", "maxExclusive": "80" },
+                        { "id": "1", "minInclusive": "80", "maxExclusive": "FF" },
+                    ]})
+                } else if req
+                    .headers()
+                    .get_optional_str(&constants::IS_QUERY_PLAN_REQUEST)
+                    .is_some()
+                {
+                    serde_json::json!({
+                        "queryInfo": {
+                            "top": 3,
+                            "orderBy": ["Descending"],
+                            "rewrittenQuery": "SELECT TOP 3 c._rid, [{\"item\": VectorDistance(c.embedding, @embedding)}] AS orderByItems, c AS payload FROM c WHERE ({documentdb-formattableorderbyquery-filter}) ORDER BY VectorDistance(c.embedding, @embedding) DESC",
+                        },
+                        "queryRanges": [{ "min": "", "max": "FF", "isMinInclusive": true, "isMaxInclusive": false }],
+                    })
+                } else {
+                    let Body::Bytes(body) = req.body() else {
+                        panic!("expected a query body");
+                    };
+                    let query: serde_json::Value = serde_json::from_slice(body).unwrap();
+                    assert!(query["query"].as_str().unwrap().contains("WHERE (true)"));
+                    assert_eq!("@embedding", query["parameters"][0]["name"]);
+                    let row = |id: &str, score: f64| {
+                        serde_json::json!({
+                            "_rid": id,
+                            "orderByItems": [{ "item": score }],
+                            "payload": { "id": id, "value": 1 },
+                        })
+                    };
+                    let range_id = req
+                        .headers()
+                        .get_optional_string(&constants::PARTITION_KEY_RANGE_ID)
+                        .unwrap();
+                    match range_id.as_str() {
+                        "0" => serde_json::json!({ "Documents": [row("a", 0.9), row("b", 0.5), row("c", 0.1)] }),
+                        _ => serde_json::json!({ "Documents": [row("d", 0.8), row("e", 0.7)] }),
+                    }
+                };
+                Ok(Response::from_bytes(
+                    StatusCode::Ok,
+                    headers,
+                    serde_json::to_vec(&body).unwrap(),
+                ))
+            }
+            .boxed()
+        }))
+    }
+
+    #[tokio::test]
+    pub async fn search_items_merges_partitions() -> Result<(), Box<dyn std::error::Error>> {
+        let container_client = test_client(search_transport())?;
+
+        let query =
+            Query::from("SELECT TOP 3 * FROM c ORDER BY VectorDistance(c.embedding, @embedding)")
+                .with_parameter("@embedding", [0.1, 0.2])?;
+        let results = container_client.search_items::<Item>(query, None).await?;
+        assert_eq!(
+            vec![("a", 0.9), ("d", 0.8), ("e", 0.7)],
+            results
+                .items
+                .iter()
+                .map(|i| (i.item.id.as_str(), i.score))
+                .collect::<Vec<_>>()
+        );
+        // One query plan request and one query per partition key range.
+        assert_eq!(3.0, results.request_charge);
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn conflicts() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let transport = {
+            let requests = requests.clone();
+            Arc::new(MockHttpClient::new(move |req| {
+                let requests = requests.clone();
+                async move {
+                    requests.lock().unwrap().push((
+                        *req.method(),
+                        req.url().path().to_string(),
+                        req.headers()
+                            .get_optional_string(&constants::PARTITION_KEY)
+                            .unwrap_or_default(),
+                    ));
+                    let conflict = serde_json::json!({
+                        "id": "conflict1",
+                        "resourceType": "document",
+                        "operationType": "create",
+                        "resourceId": "AbCdAA==",
+                        "content": r#"{"id":"item1","value":3}"#,
+                    });
+                    let body = match req.method() {
+                        Method::Get => serde_json::to_vec(&conflict).unwrap(),
+                        Method::Post => {
+                            serde_json::to_vec(&serde_json::json!({ "Conflicts": [conflict] }))
+                                .unwrap()
+                        }
+                        _ => Vec::new(),
+                    };
+                    Ok(Response::from_bytes(StatusCode::Ok, Headers::new(), body))
+                }
+                .boxed()
+            })) as Arc<dyn HttpClient>
+        };
+        let container_client = test_client(transport)?;
+
+        let conflict = container_client
+            .read_conflict("p1", "conflict1", None)
+            .await?
+            .into_body()
+            .await?;
+        assert_eq!(ConflictOperationType::Create, conflict.operation_type);
+        assert_eq!(
+            Some(Item {
+                id: "item1".to_string(),
+                value: 3
+            }),
+            conflict.content()?
+        );
+
+        let conflicts: Vec<ConflictProperties> = container_client
+            .query_conflicts("SELECT * FROM c", "p1", None)?
+            .try_collect::<Vec<_>>()
+            .await?
+            .into_iter()
+            .flat_map(|page| page.into_items())
+            .collect();
+        assert_eq!(vec![conflict], conflicts);
+
+        container_client
+            .delete_conflict("p1", "conflict1", None)
+            .await?;
+
+        let conflict_path = "/dbs/db/colls/c/conflicts/conflict1".to_string();
+        let partition_key = r#"["p1"]"#.to_string();
+        assert_eq!(
+            vec![
+                (Method::Get, conflict_path.clone(), partition_key.clone()),
+                (
+                    Method::Post,
+                    "/dbs/db/colls/c/conflicts".to_string(),
+                    partition_key.clone()
+                ),
+                (Method::Delete, conflict_path, partition_key),
+            ],
+            *requests.lock().unwrap()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn migrate_throughput() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let transport = {
+            let requests = requests.clone();
+            Arc::new(MockHttpClient::new(move |req| {
+                let requests = requests.clone();
+                async move {
+                    let path = req.url().path().to_string();
+                    let offer_reads = {
+                        let mut requests = requests.lock().unwrap();
+                        requests.push((*req.method(), path.clone()));
+                        requests
+                            .iter()
+                            .filter(|(m, p)| *m == Method::Get && p == "/offers/offer1")
+                            .count()
+                    };
+                    let offer = |content: serde_json::Value| {
+                        serde_json::json!({
+                            "id": "offer1",
+                            "resource": "dbs/AbCd==/colls/AbCdEF==/",
+                            "offerResourceId": "AbCdEF==",
+                            "offerType": "Invalid",
+                            "offerVersion": "V2",
+                            "content": content,
+                        })
+                    };
+                    let manual = offer(serde_json::json!({
+                        "offerThroughput": 400,
+                        "offerMinimumThroughputParameters": { "maxThroughputEverProvisioned": 400 },
+                    }));
+                    let autoscale = offer(serde_json::json!({
+                        "offerAutopilotSettings": { "maxThroughput": 4000 },
+                    }));
+                    let mut headers = Headers::new();
+                    let (status, body) = match (*req.method(), path.as_str()) {
+                        (Method::Get, "/dbs/db/colls/c") => (
+                            StatusCode::Ok,
+                            serde_json::json!({ "id": "c", "_rid": "AbCdEF==", "partitionKey": { "paths": ["/id"], "kind": "Hash" } }),
+                        ),
+                        (Method::Post, "/offers") => {
+                            (StatusCode::Ok, serde_json::json!({ "Offers": [manual] }))
+                        }
+                        (Method::Post, "/offers/offer1/_offerMigration") => {
+                            headers.insert(constants::OFFER_REPLACE_PENDING, "true");
+                            return Ok(Response::from_bytes(
+                                StatusCode::Accepted,
+                                headers,
+                                Bytes::new(),
+                            ));
+                        }
+                        // The first read after the migration still reports it as pending.
+                        (Method::Get, "/offers/offer1") if offer_reads <= 2 => {
+                            (StatusCode::Ok, manual)
+                        }
+                        (Method::Get, "/offers/offer1") if offer_reads == 3 => {
+                            headers.insert(constants::OFFER_REPLACE_PENDING, "true");
+                            (StatusCode::Ok, manual)
+                        }
+                        (Method::Get, "/offers/offer1") => {
+                            headers.insert(constants::MIN_THROUGHPUT, "1000");
+                            (StatusCode::Ok, autoscale)
+                        }
+                        _ => (StatusCode::BadRequest, serde_json::Value::Null),
+                    };
+                    Ok(Response::from_bytes(
+                        status,
+                        headers,
+                        serde_json::to_vec(&body).unwrap(),
+                    ))
+                }
+                .boxed()
+            })) as Arc<dyn HttpClient>
+        };
+        let container_client = test_client(transport)?;
+
+        let current = container_client
+            .read_throughput(None)
+            .await?
+            .unwrap()
+            .into_body()
+            .await?;
+        assert!(!current.is_autoscale());
+        assert_eq!(Some(400), current.maximum_throughput_ever_provisioned());
+
+        let poller = container_client
+            .migrate_throughput(
+                ThroughputProperties::autoscale(4000, None),
+                Some(ThroughputOptions {
+                    polling_interval: Some(std::time::Duration::from_millis(1)),
+                    ..Default::default()
+                }),
+            )
+            .await?;
+        assert!(matches!(poller.status(), PollerStatus::InProgress));
+        let throughput = poller.wait().await?;
+        assert!(!throughput.is_replace_pending());
+        assert_eq!(Some(4000), throughput.autoscale_maximum());
+        assert_eq!(Some(1000), throughput.minimum_throughput());
+
+        let requests = requests.lock().unwrap();
+        let migrations = requests
+            .iter()
+            .filter(|(_, p)| p == "/offers/offer1/_offerMigration")
+            .count();
+        let offer_reads = requests
+            .iter()
+            .filter(|(m, p)| *m == Method::Get && p == "/offers/offer1")
+            .count();
+        assert_eq!(1, migrations);
+        // The offer is read by `read_throughput()` and before the migration, then polled twice.
+        assert_eq!(4, offer_reads);
+        Ok(())
+    }
+
+    /// Serves a container with a manual throughput offer, which is replaced asynchronously when `pending` is set.
+    /// Reads of the offer fail once it was replaced.
+    fn replace_throughput_transport(
+        requests: Arc<Mutex<Vec<(Method, String)>>>,
+        pending: bool,
+    ) -> Arc<dyn HttpClient> {
+        Arc::new(MockHttpClient::new(move |req| {
+            let requests = requests.clone();
+            async move {
+                let path = req.url().path().to_string();
+                let replaced = {
+                    let mut requests = requests.lock().unwrap();
+                    requests.push((*req.method(), path.clone()));
+                    requests.iter().any(|(m, _)| *m == Method::Put)
+                };
+                let offer = serde_json::json!({
+                    "id": "offer1",
+                    "resource": "dbs/AbCd==/colls/AbCdEF==/",
+                    "offerResourceId": "AbCdEF==",
+                    "offerType": "Invalid",
+                    "offerVersion": "V2",
+                    "content": { "offerThroughput": 400 },
+                });
+                let mut headers = Headers::new();
+                let (status, body) = match (*req.method(), path.as_str()) {
+                    (Method::Get, "/dbs/db/colls/c") => (
+                        StatusCode::Ok,
+                        serde_json::json!({ "id": "c", "_rid": "AbCdEF==", "partitionKey": { "paths": ["/id"], "kind": "Hash" } }),
+                    ),
+                    (Method::Post, "/offers") => {
+                        (StatusCode::Ok, serde_json::json!({ "Offers": [offer] }))
+                    }
+                    (Method::Get, "/offers/offer1") if !replaced => (StatusCode::Ok, offer),
+                    (Method::Put, "/offers/offer1") => {
+                        if pending {
+                            headers.insert(constants::OFFER_REPLACE_PENDING, "true");
+                        }
+                        (StatusCode::Ok, offer)
+                    }
+                    _ => (StatusCode::BadRequest, serde_json::Value::Null),
+                };
+                Ok(Response::from_bytes(
+                    status,
+                    headers,
+                    serde_json::to_vec(&body).unwrap(),
+                ))
+            }
+            .boxed()
+        }))
+    }
+
+    #[tokio::test]
+    pub async fn poll_completed_throughput_change() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(replace_throughput_transport(requests.clone(), false))?;
+
+        let mut poller = container_client
+            .begin_replace_throughput(ThroughputProperties::manual(800), None)
+            .await?;
+        assert!(matches!(poller.status(), PollerStatus::Succeeded));
+
+        // The change is already applied, so polling doesn't read the offer again.
+        let sent = requests.lock().unwrap().len();
+        assert!(matches!(poller.poll().await?, PollerStatus::Succeeded));
+        assert!(!poller.current().is_replace_pending());
+        assert_eq!(sent, requests.lock().unwrap().len());
+        poller.wait().await?;
+        assert_eq!(sent, requests.lock().unwrap().len());
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn poll_throughput_change_error() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let container_client = test_client(replace_throughput_transport(requests.clone(), true))?;
+
+        let mut poller = container_client
+            .begin_replace_throughput(ThroughputProperties::manual(800), None)
+            .await?;
+        assert!(matches!(poller.status(), PollerStatus::InProgress));
+
+        let error = poller.poll().await.unwrap_err();
+        assert_eq!(
+            Some(StatusCode::BadRequest),
+            CosmosError::from_error(&error).map(|e| e.status())
+        );
+        // The failed read doesn't change the status of the poller.
+        assert!(matches!(poller.status(), PollerStatus::InProgress));
+        assert!(poller.current().is_replace_pending());
+        Ok(())
     }
 }
@@ -15,6 +15,8 @@ use azure_core::{
 use serde::Serialize;
 use std::sync::Arc;
 
+#[cfg(feature = "key_auth")]
+use crate::ResourceTokenResolver;
 #[cfg(feature = "key_auth")]
 use azure_core::credentials::Secret;
 
@@ -54,7 +56,7 @@ impl CosmosClient {
             pipeline: CosmosPipeline::new(
                 endpoint.parse()?,
                 AuthorizationPolicy::from_token_credential(credential),
-                options.client_options,
+                options,
             ),
         })
     }
@@ -87,7 +89,76 @@ impl CosmosClient {
             pipeline: CosmosPipeline::new(
                 endpoint.parse()?,
                 AuthorizationPolicy::from_shared_key(key),
-                options.client_options,
+                options,
+            ),
+        })
+    }
+
+    /// Creates a new CosmosClient, using a resource token.
+    ///
+    /// Resource tokens grant access to specific resources, and are obtained from a [permission](crate::models::PermissionProperties) by a client using key authentication,
+    /// typically in a back-end service which hands them out to client applications.
+    /// All requests made by the client use the provided token, so the client can only access the resources covered by the token's permission.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Cosmos DB account, for example `https://myaccount.documents.azure.com/`.
+    /// * `resource_token` - The resource token to use when authenticating, as found in [`PermissionProperties::token`](crate::models::PermissionProperties::token).
+    /// * `options` - Optional configuration for the client.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use azure_data_cosmos::CosmosClient;
+    /// use azure_core::credentials::Secret;
+    ///
+    /// let client = CosmosClient::with_resource_token("https://myaccount.documents.azure.com/", Secret::from("type=resource&ver=1&sig=..."), None).unwrap();
+    /// ```
+    #[cfg(feature = "key_auth")]
+    pub fn with_resource_token(
+        endpoint: &str,
+        resource_token: Secret,
+        options: Option<CosmosClientOptions>,
+    ) -> azure_core::Result<Self> {
+        Self::with_resource_token_resolver(endpoint, Arc::new(resource_token), options)
+    }
+
+    /// Creates a new CosmosClient, using resource tokens provided by a [`ResourceTokenResolver`] for each request.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Cosmos DB account, for example `https://myaccount.documents.azure.com/`.
+    /// * `resolver` - A [`ResourceTokenResolver`] which provides the resource token to use for each resource.
+    /// * `options` - Optional configuration for the client.
+    ///
+    /// # Examples
+    ///
+    /// A [`HashMap`](std::collections::HashMap) can be used to provide a token for each resource:
+    ///
+    /// ```rust,no_run
+    /// use std::{collections::HashMap, sync::Arc};
+    /// use azure_data_cosmos::CosmosClient;
+    /// use azure_core::credentials::Secret;
+    ///
+    /// let tokens = HashMap::from([
+    ///     ("dbs/MyDatabase/colls/Orders".to_string(), Secret::from("type=resource&ver=1&sig=...")),
+    ///     ("dbs/MyDatabase/colls/Products".to_string(), Secret::from("type=resource&ver=1&sig=...")),
+    /// ]);
+    /// let client = CosmosClient::with_resource_token_resolver("https://myaccount.documents.azure.com/", Arc::new(tokens), None).unwrap();
+    /// ```
+    #[cfg(feature = "key_auth")]
+    pub fn with_resource_token_resolver(
+        endpoint: &str,
+        resolver: Arc<dyn ResourceTokenResolver>,
+        options: Option<CosmosClientOptions>,
+    ) -> azure_core::Result<Self> {
+        let options = options.unwrap_or_default();
+        Ok(Self {
+            databases_link: ResourceLink::root(ResourceType::Databases),
+            pipeline: CosmosPipeline::new(
+                endpoint.parse()?,
+                AuthorizationPolicy::from_resource_tokens(resolver),
+                options,
             ),
         })
     }
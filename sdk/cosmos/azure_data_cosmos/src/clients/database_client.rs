@@ -3,12 +3,17 @@
 
 use crate::{
     clients::ContainerClient,
-    models::{ContainerProperties, DatabaseProperties, ThroughputProperties},
+    constants,
+    models::{
+        ContainerProperties, DatabaseProperties, PermissionProperties, ThroughputProperties,
+        UserProperties,
+    },
     options::ReadDatabaseOptions,
     pipeline::CosmosPipeline,
     resource_context::{ResourceLink, ResourceType},
-    CreateContainerOptions, DeleteDatabaseOptions, FeedPager, Query, QueryContainersOptions,
-    ThroughputOptions,
+    CreateContainerOptions, DeleteDatabaseOptions, FeedPager, PermissionOptions, Query,
+    QueryContainersOptions, QueryPermissionsOptions, QueryUsersOptions, ThroughputOptions,
+    ThroughputPoller, UserOptions,
 };
 
 use azure_core::http::{request::Request, response::Response, Method};
@@ -19,6 +24,7 @@ use azure_core::http::{request::Request, response::Response, Method};
 pub struct DatabaseClient {
     link: ResourceLink,
     containers_link: ResourceLink,
+    users_link: ResourceLink,
     database_id: String,
     pipeline: CosmosPipeline,
 }
@@ -28,10 +34,12 @@ impl DatabaseClient {
         let database_id = database_id.to_string();
         let link = ResourceLink::root(ResourceType::Databases).item(&database_id);
         let containers_link = link.feed(ResourceType::Containers);
+        let users_link = link.feed(ResourceType::Users);
 
         Self {
             link,
             containers_link,
+            users_link,
             database_id,
             pipeline,
         }
@@ -212,4 +220,320 @@ impl DatabaseClient {
             .replace_throughput_offer(options.method_options.context, &resource_id, throughput)
             .await
     }
+
+    /// Replaces the database throughput properties, returning a [`ThroughputPoller`] which tracks the change until the service has applied it.
+    ///
+    /// Increasing the throughput beyond what the current physical partitions can serve requires the service to split partitions, which can take several hours.
+    /// The new throughput must use the same mode (manual or autoscale) as the current one, use [`migrate_throughput()`](Self::migrate_throughput()) to switch modes.
+    ///
+    /// # Arguments
+    /// * `throughput` - The new throughput properties to set.
+    /// * `options` - Optional parameters for the request.
+    pub async fn begin_replace_throughput(
+        &self,
+        throughput: ThroughputProperties,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        self.begin_throughput_update(throughput, false, options)
+            .await
+    }
+
+    /// Migrates the database between manual and autoscale throughput, returning a [`ThroughputPoller`] which tracks the migration until the service has completed it.
+    ///
+    /// Pass [`ThroughputProperties::autoscale()`] to migrate a database using manual throughput to autoscale, or [`ThroughputProperties::manual()`] to migrate it to manual throughput.
+    /// If the database already uses the requested mode, the throughput is replaced, as with [`begin_replace_throughput()`](Self::begin_replace_throughput()).
+    ///
+    /// # Arguments
+    /// * `throughput` - The new throughput properties to set.
+    /// * `options` - Optional parameters for the request.
+    pub async fn migrate_throughput(
+        &self,
+        throughput: ThroughputProperties,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        self.begin_throughput_update(throughput, true, options)
+            .await
+    }
+
+    async fn begin_throughput_update(
+        &self,
+        throughput: ThroughputProperties,
+        migrate: bool,
+        options: Option<ThroughputOptions<'_>>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        let options = options.unwrap_or_default();
+
+        // We need to get the RID for the database.
+        let properties = self.read(None).await?.into_body().await?;
+        let resource_id = properties
+            .system_properties
+            .resource_id
+            .expect("service should always return a '_rid' for a database");
+
+        self.pipeline
+            .begin_throughput_offer_update(
+                options.method_options.context,
+                &resource_id,
+                throughput,
+                migrate,
+                options.polling_interval,
+            )
+            .await
+    }
+
+    /// Creates a new user in the database.
+    ///
+    /// # Arguments
+    /// * `properties` - A [`UserProperties`] describing the new user.
+    /// * `options` - Optional parameters for the request.
+    pub async fn create_user(
+        &self,
+        properties: UserProperties,
+        options: Option<UserOptions<'_>>,
+    ) -> azure_core::Result<Response<UserProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.users_link);
+        let mut req = Request::new(url, Method::Post);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(
+                options.method_options.context,
+                &mut req,
+                self.users_link.clone(),
+            )
+            .await
+    }
+
+    /// Reads the properties of a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_user(
+        &self,
+        user_id: &str,
+        options: Option<UserOptions<'_>>,
+    ) -> azure_core::Result<Response<UserProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.users_link.item(user_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Replaces an existing user. This can be used to rename the user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user to replace.
+    /// * `properties` - The new [`UserProperties`] for the user.
+    /// * `options` - Optional parameters for the request.
+    pub async fn replace_user(
+        &self,
+        user_id: &str,
+        properties: UserProperties,
+        options: Option<UserOptions<'_>>,
+    ) -> azure_core::Result<Response<UserProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.users_link.item(user_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Put);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a user, and all of its permissions.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_user(
+        &self,
+        user_id: &str,
+        options: Option<UserOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.users_link.item(user_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against users in the database.
+    ///
+    /// # Arguments
+    /// * `query` - The query to execute.
+    /// * `options` - Optional parameters for the request.
+    pub fn query_users(
+        &self,
+        query: impl Into<Query>,
+        options: Option<QueryUsersOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<UserProperties>> {
+        let options = options.unwrap_or_default();
+        let url = self.pipeline.url(&self.users_link);
+        let base_request = Request::new(url, Method::Post);
+
+        self.pipeline.send_query_request(
+            options.method_options.context,
+            query.into(),
+            base_request,
+            self.users_link.clone(),
+        )
+    }
+
+    /// Creates a new permission for a user.
+    ///
+    /// The response contains the resource token for the permission, in [`PermissionProperties::token`].
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user to grant the permission to.
+    /// * `properties` - A [`PermissionProperties`] describing the new permission.
+    /// * `options` - Optional parameters for the request.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+    /// use azure_data_cosmos::models::{PermissionMode, PermissionProperties};
+    /// # let database_client: azure_data_cosmos::clients::DatabaseClient = panic!("this is a non-running example");
+    /// let permission = PermissionProperties::new("orders", PermissionMode::All, "dbs/MyDatabase/colls/Orders")
+    ///     .with_resource_partition_key("customer1");
+    /// let permission = database_client
+    ///     .create_permission("customer1", permission, None)
+    ///     .await?
+    ///     .into_body()
+    ///     .await?;
+    /// let resource_token = permission.token.expect("the service always returns a resource token");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_permission(
+        &self,
+        user_id: &str,
+        properties: PermissionProperties,
+        options: Option<PermissionOptions<'_>>,
+    ) -> azure_core::Result<Response<PermissionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.permissions_link(user_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Post);
+        add_token_expiry_header(&mut req, &options);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Reads a permission of a user, including a new resource token.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user the permission belongs to.
+    /// * `permission_id` - The id of the permission to read.
+    /// * `options` - Optional parameters for the request.
+    pub async fn read_permission(
+        &self,
+        user_id: &str,
+        permission_id: &str,
+        options: Option<PermissionOptions<'_>>,
+    ) -> azure_core::Result<Response<PermissionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.permissions_link(user_id).item(permission_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Get);
+        add_token_expiry_header(&mut req, &options);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Replaces an existing permission of a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user the permission belongs to.
+    /// * `properties` - The new [`PermissionProperties`]. The [`id`](PermissionProperties::id) identifies the permission to replace.
+    /// * `options` - Optional parameters for the request.
+    pub async fn replace_permission(
+        &self,
+        user_id: &str,
+        properties: PermissionProperties,
+        options: Option<PermissionOptions<'_>>,
+    ) -> azure_core::Result<Response<PermissionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.permissions_link(user_id).item(&properties.id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Put);
+        add_token_expiry_header(&mut req, &options);
+        req.set_json(&properties)?;
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Deletes a permission of a user.
+    ///
+    /// Resource tokens already issued for the permission remain valid until they expire.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user the permission belongs to.
+    /// * `permission_id` - The id of the permission to delete.
+    /// * `options` - Optional parameters for the request.
+    pub async fn delete_permission(
+        &self,
+        user_id: &str,
+        permission_id: &str,
+        options: Option<PermissionOptions<'_>>,
+    ) -> azure_core::Result<Response> {
+        let options = options.unwrap_or_default();
+        let link = self.permissions_link(user_id).item(permission_id);
+        let url = self.pipeline.url(&link);
+        let mut req = Request::new(url, Method::Delete);
+        self.pipeline
+            .send(options.method_options.context, &mut req, link)
+            .await
+    }
+
+    /// Executes a query against the permissions of a user.
+    ///
+    /// # Arguments
+    /// * `user_id` - The id of the user whose permissions are queried.
+    /// * `query` - The query to execute.
+    /// * `options` - Optional parameters for the request.
+    pub fn query_permissions(
+        &self,
+        user_id: &str,
+        query: impl Into<Query>,
+        options: Option<QueryPermissionsOptions<'_>>,
+    ) -> azure_core::Result<FeedPager<PermissionProperties>> {
+        let options = options.unwrap_or_default();
+        let link = self.permissions_link(user_id);
+        let url = self.pipeline.url(&link);
+        let base_request = Request::new(url, Method::Post);
+
+        self.pipeline.send_query_request(
+            options.method_options.context,
+            query.into(),
+            base_request,
+            link,
+        )
+    }
+
+    fn permissions_link(&self, user_id: &str) -> ResourceLink {
+        self.users_link
+            .item(user_id)
+            .feed(ResourceType::Permissions)
+    }
+}
+
+fn add_token_expiry_header(req: &mut Request, options: &PermissionOptions<'_>) {
+    if let Some(expiry) = options.resource_token_expiry {
+        req.insert_header(
+            constants::RESOURCE_TOKEN_EXPIRY,
+            expiry.as_secs().to_string(),
+        );
+    }
 }
@@ -14,13 +14,64 @@ use azure_core::http::{
 pub const QUERY: HeaderName = HeaderName::from_static("x-ms-documentdb-query");
 pub const PARTITION_KEY: HeaderName = HeaderName::from_static("x-ms-documentdb-partitionkey");
 pub const CONTINUATION: HeaderName = HeaderName::from_static("x-ms-continuation");
+pub const ENABLE_CROSS_PARTITION_QUERY: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-query-enablecrosspartition");
+pub const PARTITION_KEY_RANGE_ID: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-partitionkeyrangeid");
+pub const IS_QUERY_PLAN_REQUEST: HeaderName =
+    HeaderName::from_static("x-ms-cosmos-is-query-plan-request");
+pub const SUPPORTED_QUERY_FEATURES: HeaderName =
+    HeaderName::from_static("x-ms-cosmos-supported-query-features");
+pub const QUERY_VERSION: HeaderName = HeaderName::from_static("x-ms-cosmos-query-version");
+pub const START_EPK: HeaderName = HeaderName::from_static("x-ms-start-epk");
+pub const END_EPK: HeaderName = HeaderName::from_static("x-ms-end-epk");
 pub const INDEX_METRICS: HeaderName = HeaderName::from_static("x-ms-cosmos-index-utilization");
 pub const QUERY_METRICS: HeaderName = HeaderName::from_static("x-ms-documentdb-query-metrics");
 pub const IS_UPSERT: HeaderName = HeaderName::from_static("x-ms-documentdb-is-upsert");
+pub const SUB_STATUS: HeaderName = HeaderName::from_static("x-ms-substatus");
+pub const RETRY_AFTER_MS: HeaderName = HeaderName::from_static("x-ms-retry-after-ms");
+pub const PRE_TRIGGER_INCLUDE: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-pre-trigger-include");
+pub const POST_TRIGGER_INCLUDE: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-post-trigger-include");
+pub const SCRIPT_ENABLE_LOGGING: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-script-enable-logging");
+pub const SCRIPT_LOG_RESULTS: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-script-log-results");
+pub const RESOURCE_TOKEN_EXPIRY: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-expiry-seconds");
+pub const REQUEST_CHARGE: HeaderName = HeaderName::from_static("x-ms-request-charge");
+pub const ACTIVITY_ID: HeaderName = HeaderName::from_static("x-ms-activity-id");
+pub const REQUEST_DURATION: HeaderName = HeaderName::from_static("x-ms-request-duration-ms");
+pub const POPULATE_QUERY_METRICS: HeaderName =
+    HeaderName::from_static("x-ms-documentdb-populatequerymetrics");
+pub const POPULATE_INDEX_METRICS: HeaderName =
+    HeaderName::from_static("x-ms-cosmos-populateindexmetrics");
+pub const CONSISTENCY_LEVEL: HeaderName = HeaderName::from_static("x-ms-consistency-level");
 pub const OFFER_THROUGHPUT: HeaderName = HeaderName::from_static("x-ms-offer-throughput");
 pub const OFFER_AUTOPILOT_SETTINGS: HeaderName =
     HeaderName::from_static("x-ms-cosmos-offer-autopilot-settings");
+/// Set to `true` on offer responses while a throughput change is still being applied by the service.
+pub const OFFER_REPLACE_PENDING: HeaderName = HeaderName::from_static("x-ms-offer-replace-pending");
+pub const MIN_THROUGHPUT: HeaderName = HeaderName::from_static("x-ms-cosmos-min-throughput");
 
 pub const QUERY_CONTENT_TYPE: ContentType = ContentType::from_static("application/query+json");
 
 pub(crate) const PREFER_MINIMAL: HeaderValue = HeaderValue::from_static("return=minimal");
+
+/// Sub-status codes returned by the service in the `x-ms-substatus` header, which refine the meaning of the HTTP status code.
+///
+/// See [`CosmosError::substatus()`](crate::CosmosError::substatus()).
+pub mod substatus {
+    /// With `410 Gone`, the partition key range targeted by the request was split or merged.
+    pub const PARTITION_KEY_RANGE_GONE: u32 = 1002;
+
+    /// With `404 Not Found`, the replica serving the read hasn't caught up with the session token of the request yet.
+    pub const READ_SESSION_NOT_AVAILABLE: u32 = 1002;
+
+    /// With `410 Gone`, the partition key range targeted by the request is being split.
+    pub const COMPLETING_SPLIT: u32 = 1007;
+
+    /// With `410 Gone`, the partition key range targeted by the request is being moved.
+    pub const COMPLETING_PARTITION_MIGRATION: u32 = 1008;
+}
@@ -0,0 +1,467 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Types describing the cost and performance of Cosmos DB operations.
+
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use azure_core::{base64, http::headers::Headers};
+use serde::Deserialize;
+
+use crate::{constants, SessionToken};
+
+/// Diagnostics describing a Cosmos DB operation.
+///
+/// Use a [`DiagnosticsRecorder`] to get the diagnostics of any operation, or [`FeedPage::diagnostics()`](crate::FeedPage::diagnostics())
+/// and [`FeedPage::total_diagnostics()`](crate::FeedPage::total_diagnostics()) for queries.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CosmosDiagnostics {
+    /// The number of request units (RUs) consumed by the operation.
+    pub request_charge: f64,
+
+    /// The activity ID of the operation, which identifies it when contacting Azure support.
+    pub activity_id: Option<String>,
+
+    /// The session token returned by the service.
+    pub session_token: Option<SessionToken>,
+
+    /// The time the service spent processing the operation, excluding network latency.
+    pub server_duration: Option<Duration>,
+
+    /// The number of times the client retried the operation (for example, because it was throttled).
+    pub retry_count: u32,
+
+    /// Detailed query execution metrics, returned when [`QueryOptions::populate_query_metrics`](crate::QueryOptions::populate_query_metrics) is set.
+    pub query_metrics: Option<QueryMetrics>,
+
+    /// Index utilization metrics, returned when [`QueryOptions::populate_index_metrics`](crate::QueryOptions::populate_index_metrics) is set.
+    pub index_metrics: Option<IndexMetrics>,
+}
+
+impl CosmosDiagnostics {
+    /// Gets the diagnostics from the headers of a Cosmos DB response.
+    ///
+    /// Values that are missing or can't be parsed are left empty.
+    /// The retry count isn't returned by the service, so it's always 0: use a [`DiagnosticsRecorder`] to get it.
+    pub fn from_headers(headers: &Headers) -> Self {
+        let parse = |name| {
+            headers
+                .get_optional_str(name)
+                .and_then(|v| v.trim().parse::<f64>().ok())
+        };
+        Self {
+            request_charge: parse(&constants::REQUEST_CHARGE).unwrap_or_default(),
+            activity_id: headers.get_optional_string(&constants::ACTIVITY_ID),
+            session_token: SessionToken::from_headers(headers),
+            server_duration: parse(&constants::REQUEST_DURATION)
+                .and_then(|ms| Duration::try_from_secs_f64(ms / 1000.0).ok()),
+            retry_count: 0,
+            query_metrics: headers
+                .get_optional_str(&constants::QUERY_METRICS)
+                .map(QueryMetrics::parse),
+            index_metrics: headers
+                .get_optional_str(&constants::INDEX_METRICS)
+                .and_then(IndexMetrics::parse),
+        }
+    }
+
+    /// Adds the diagnostics of another operation (such as the next page of a query) to these diagnostics.
+    ///
+    /// Request charges, durations, retry counts and query metrics are summed.
+    /// The activity ID and session token are replaced by the most recent values, and the first index metrics returned are kept.
+    pub fn merge(&mut self, other: &CosmosDiagnostics) {
+        self.request_charge += other.request_charge;
+        self.retry_count += other.retry_count;
+        self.server_duration = match (self.server_duration, other.server_duration) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        };
+        if other.activity_id.is_some() {
+            self.activity_id.clone_from(&other.activity_id);
+        }
+        if other.session_token.is_some() {
+            self.session_token.clone_from(&other.session_token);
+        }
+        match (&mut self.query_metrics, &other.query_metrics) {
+            (Some(metrics), Some(other)) => metrics.merge(other),
+            (metrics @ None, Some(other)) => *metrics = Some(other.clone()),
+            _ => {}
+        }
+        if self.index_metrics.is_none() {
+            self.index_metrics.clone_from(&other.index_metrics);
+        }
+    }
+}
+
+/// Records the diagnostics of the operations sent with a [`Context`](azure_core::http::Context) containing it.
+///
+/// Add a recorder to the context of an operation's options to get its diagnostics (including its retry count), whether it succeeds or fails:
+///
+/// ```rust,no_run
+/// use azure_core::http::{ClientMethodOptions, Context};
+/// use azure_data_cosmos::{DiagnosticsRecorder, ItemOptions};
+///
+/// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+/// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+/// let recorder = DiagnosticsRecorder::default();
+/// let options = ItemOptions {
+///     method_options: ClientMethodOptions {
+///         context: Context::new().with_value(recorder.clone()),
+///     },
+///     ..Default::default()
+/// };
+/// let result = container_client.read_item("partition1", "item1", Some(options)).await;
+/// if let Some(diagnostics) = recorder.diagnostics() {
+///     println!("request charge: {} RUs, {} retries", diagnostics.request_charge, diagnostics.retry_count);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The recorder keeps the diagnostics of the last operation sent with it.
+/// Operations returning a [`FeedPager`](crate::FeedPager) don't use the recorder: use [`FeedPage::diagnostics()`](crate::FeedPage::diagnostics()) instead.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticsRecorder(Arc<Mutex<Option<CosmosDiagnostics>>>);
+
+impl DiagnosticsRecorder {
+    /// Gets the diagnostics of the last operation sent with this recorder, or `None` if no operation has completed yet.
+    pub fn diagnostics(&self) -> Option<CosmosDiagnostics> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    pub(crate) fn record(&self, diagnostics: CosmosDiagnostics) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(diagnostics);
+    }
+}
+
+/// Query execution metrics, as returned by the service in the `x-ms-documentdb-query-metrics` header.
+///
+/// See <https://learn.microsoft.com/azure/cosmos-db/nosql/query-metrics> for more information on each metric.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct QueryMetrics {
+    pub total_execution_time: Duration,
+    pub query_compile_time: Duration,
+    pub logical_plan_build_time: Duration,
+    pub physical_plan_build_time: Duration,
+    pub query_optimization_time: Duration,
+    pub vm_execution_time: Duration,
+    pub index_lookup_time: Duration,
+    pub document_load_time: Duration,
+    pub system_function_execution_time: Duration,
+    pub user_function_execution_time: Duration,
+    pub document_write_time: Duration,
+    pub retrieved_document_count: u64,
+    pub retrieved_document_size: u64,
+    pub output_document_count: u64,
+    pub output_document_size: u64,
+
+    /// The ratio of retrieved documents that matched the query filter using the index, between 0 and 1.
+    pub index_hit_ratio: f64,
+}
+
+impl QueryMetrics {
+    /// Parses query metrics from the value of the `x-ms-documentdb-query-metrics` header.
+    ///
+    /// The value is a `;`-separated list of `name=value` pairs. Unknown or malformed metrics are ignored.
+    pub fn parse(value: &str) -> Self {
+        let mut metrics = QueryMetrics::default();
+        for (name, value) in value
+            .split(';')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+        {
+            let Ok(number) = value.parse::<f64>() else {
+                continue;
+            };
+            let millis = || Duration::try_from_secs_f64(number / 1000.0).unwrap_or_default();
+            let count = || number as u64;
+            match name {
+                "totalExecutionTimeInMs" => metrics.total_execution_time = millis(),
+                "queryCompileTimeInMs" => metrics.query_compile_time = millis(),
+                "queryLogicalPlanBuildTimeInMs" => metrics.logical_plan_build_time = millis(),
+                "queryPhysicalPlanBuildTimeInMs" => metrics.physical_plan_build_time = millis(),
+                "queryOptimizationTimeInMs" => metrics.query_optimization_time = millis(),
+                "VMExecutionTimeInMs" => metrics.vm_execution_time = millis(),
+                "indexLookupTimeInMs" => metrics.index_lookup_time = millis(),
+                "documentLoadTimeInMs" => metrics.document_load_time = millis(),
+                "systemFunctionExecuteTimeInMs" => {
+                    metrics.system_function_execution_time = millis()
+                }
+                "userFunctionExecuteTimeInMs" => metrics.user_function_execution_time = millis(),
+                "writeOutputTimeInMs" => metrics.document_write_time = millis(),
+                "retrievedDocumentCount" => metrics.retrieved_document_count = count(),
+                "retrievedDocumentSize" => metrics.retrieved_document_size = count(),
+                "outputDocumentCount" => metrics.output_document_count = count(),
+                "outputDocumentSize" => metrics.output_document_size = count(),
+                "indexUtilizationRatio" => metrics.index_hit_ratio = number,
+                _ => {}
+            }
+        }
+        metrics
+    }
+
+    /// Adds the metrics of another page of results to these metrics.
+    ///
+    /// Times and counts are summed. The index hit ratio is averaged, weighted by the number of retrieved documents.
+    pub fn merge(&mut self, other: &QueryMetrics) {
+        let retrieved = self.retrieved_document_count + other.retrieved_document_count;
+        if retrieved > 0 {
+            self.index_hit_ratio = (self.index_hit_ratio * self.retrieved_document_count as f64
+                + other.index_hit_ratio * other.retrieved_document_count as f64)
+                / retrieved as f64;
+        }
+        self.total_execution_time += other.total_execution_time;
+        self.query_compile_time += other.query_compile_time;
+        self.logical_plan_build_time += other.logical_plan_build_time;
+        self.physical_plan_build_time += other.physical_plan_build_time;
+        self.query_optimization_time += other.query_optimization_time;
+        self.vm_execution_time += other.vm_execution_time;
+        self.index_lookup_time += other.index_lookup_time;
+        self.document_load_time += other.document_load_time;
+        self.system_function_execution_time += other.system_function_execution_time;
+        self.user_function_execution_time += other.user_function_execution_time;
+        self.document_write_time += other.document_write_time;
+        self.retrieved_document_count = retrieved;
+        self.retrieved_document_size += other.retrieved_document_size;
+        self.output_document_count += other.output_document_count;
+        self.output_document_size += other.output_document_size;
+    }
+}
+
+/// Index utilization metrics, as returned by the service in the `x-ms-cosmos-index-utilization` header.
+///
+/// These describe the indexes used by a query, and the indexes that could be added to improve its performance.
+/// See <https://learn.microsoft.com/azure/cosmos-db/nosql/index-metrics> for more information.
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct IndexMetrics {
+    /// Single-path indexes used by the query.
+    #[serde(default)]
+    pub utilized_single_indexes: Vec<SingleIndexMetrics>,
+
+    /// Single-path indexes that could improve the query, if they were added to the indexing policy.
+    #[serde(default)]
+    pub potential_single_indexes: Vec<SingleIndexMetrics>,
+
+    /// Composite indexes used by the query.
+    #[serde(default)]
+    pub utilized_composite_indexes: Vec<CompositeIndexMetrics>,
+
+    /// Composite indexes that could improve the query, if they were added to the indexing policy.
+    #[serde(default)]
+    pub potential_composite_indexes: Vec<CompositeIndexMetrics>,
+}
+
+impl IndexMetrics {
+    /// Parses index metrics from the value of the `x-ms-cosmos-index-utilization` header, which is base64-encoded JSON.
+    ///
+    /// Returns `None` if the value is malformed.
+    pub fn parse(value: &str) -> Option<Self> {
+        let json = base64::decode(value.trim()).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+}
+
+/// Describes a single-path index, in [`IndexMetrics`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct SingleIndexMetrics {
+    /// The query filter expression the index applies to.
+    #[serde(default)]
+    pub filter_expression: String,
+
+    /// The indexed path, for example `/name/?`.
+    #[serde(default)]
+    pub index_spec: String,
+
+    /// The estimated impact of the index on the query (for example, `High` or `Low`).
+    #[serde(default)]
+    pub index_impact_score: Option<String>,
+}
+
+/// Describes a composite index, in [`IndexMetrics`].
+#[non_exhaustive]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct CompositeIndexMetrics {
+    /// The indexed paths and their sort order, for example `["/name ASC", "/age DESC"]`.
+    #[serde(default)]
+    pub index_specs: Vec<String>,
+
+    /// The estimated impact of the index on the query (for example, `High` or `Low`).
+    #[serde(default)]
+    pub index_impact_score: Option<String>,
+}
+
+/// Thresholds above which operations are logged, to help find slow or expensive operations.
+///
+/// Operations exceeding any of the thresholds are logged at the `WARN` level (using [`tracing`](https://docs.rs/tracing)), including their diagnostics.
+/// Set [`CosmosClientOptions::diagnostics_thresholds`](crate::CosmosClientOptions::diagnostics_thresholds) to enable logging.
+#[derive(Clone, Debug, Default)]
+pub struct DiagnosticsThresholds {
+    /// Operations taking longer than this, including retries, are logged.
+    pub latency: Option<Duration>,
+
+    /// Operations consuming more request units (RUs) than this are logged.
+    pub request_charge: Option<f64>,
+}
+
+impl DiagnosticsThresholds {
+    /// Determines whether an operation exceeds any of the thresholds.
+    pub(crate) fn is_exceeded(&self, elapsed: Duration, diagnostics: &CosmosDiagnostics) -> bool {
+        self.latency.is_some_and(|latency| elapsed > latency)
+            || self
+                .request_charge
+                .is_some_and(|charge| diagnostics.request_charge > charge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use azure_core::{base64, http::headers::Headers};
+
+    use crate::{
+        constants, CosmosDiagnostics, DiagnosticsThresholds, IndexMetrics, QueryMetrics,
+        SessionToken,
+    };
+
+    const QUERY_METRICS: &str = "totalExecutionTimeInMs=33.67;queryCompileTimeInMs=0.06;queryLogicalPlanBuildTimeInMs=0.02;queryPhysicalPlanBuildTimeInMs=0.10;queryOptimizationTimeInMs=0.00;VMExecutionTimeInMs=32.56;indexLookupTimeInMs=0.36;documentLoadTimeInMs=9.58;systemFunctionExecuteTimeInMs=0.00;userFunctionExecuteTimeInMs=0.00;retrievedDocumentCount=2000;retrievedDocumentSize=1125600;outputDocumentCount=2000;outputDocumentSize=1125600;writeOutputTimeInMs=18.10;indexUtilizationRatio=1.00";
+
+    #[test]
+    pub fn parse_query_metrics() {
+        let metrics = QueryMetrics::parse(QUERY_METRICS);
+        assert_eq!(Duration::from_micros(33670), metrics.total_execution_time);
+        assert_eq!(Duration::from_micros(32560), metrics.vm_execution_time);
+        assert_eq!(Duration::from_micros(18100), metrics.document_write_time);
+        assert_eq!(2000, metrics.retrieved_document_count);
+        assert_eq!(1125600, metrics.output_document_size);
+        assert_eq!(1.0, metrics.index_hit_ratio);
+    }
+
+    #[test]
+    pub fn parse_query_metrics_ignores_malformed_values() {
+        let metrics = QueryMetrics::parse(
+            "retrievedDocumentCount=abc;nonsense;unknownMetric=4;outputDocumentCount=3",
+        );
+        assert_eq!(0, metrics.retrieved_document_count);
+        assert_eq!(3, metrics.output_document_count);
+    }
+
+    #[test]
+    pub fn parse_index_metrics() {
+        let json = r#"{"UtilizedSingleIndexes":[{"FilterExpression":"","IndexSpec":"/name/?","FilterPreciseSet":true,"IndexPreciseSet":true,"IndexImpactScore":"High"}],"PotentialSingleIndexes":[],"UtilizedCompositeIndexes":[],"PotentialCompositeIndexes":[{"IndexSpecs":["/name ASC","/age ASC"],"IndexPreciseSet":false,"IndexImpactScore":"High"}]}"#;
+        let metrics = IndexMetrics::parse(&base64::encode(json)).unwrap();
+        assert_eq!(1, metrics.utilized_single_indexes.len());
+        assert_eq!("/name/?", metrics.utilized_single_indexes[0].index_spec);
+        assert_eq!(
+            vec!["/name ASC", "/age ASC"],
+            metrics.potential_composite_indexes[0].index_specs
+        );
+        assert_eq!(None, IndexMetrics::parse("not base64!"));
+    }
+
+    #[test]
+    pub fn from_headers() {
+        let mut headers = Headers::new();
+        headers.insert(constants::REQUEST_CHARGE, "2.86");
+        headers.insert(constants::ACTIVITY_ID, "a1b2c3");
+        headers.insert(azure_core::http::headers::SESSION_TOKEN, "0:-1#12");
+        headers.insert(constants::REQUEST_DURATION, "1.5");
+        headers.insert(constants::QUERY_METRICS, QUERY_METRICS);
+
+        let diagnostics = CosmosDiagnostics::from_headers(&headers);
+        assert_eq!(2.86, diagnostics.request_charge);
+        assert_eq!(Some("a1b2c3"), diagnostics.activity_id.as_deref());
+        assert_eq!(
+            Some(SessionToken::from("0:-1#12")),
+            diagnostics.session_token
+        );
+        assert_eq!(
+            Some(Duration::from_micros(1500)),
+            diagnostics.server_duration
+        );
+        assert_eq!(0, diagnostics.retry_count);
+        assert_eq!(
+            2000,
+            diagnostics.query_metrics.unwrap().retrieved_document_count
+        );
+        assert_eq!(None, diagnostics.index_metrics);
+    }
+
+    #[test]
+    pub fn from_empty_headers() {
+        assert_eq!(
+            CosmosDiagnostics::default(),
+            CosmosDiagnostics::from_headers(&Headers::new())
+        );
+    }
+
+    #[test]
+    pub fn merge() {
+        let mut headers = Headers::new();
+        headers.insert(constants::REQUEST_CHARGE, "2.5");
+        headers.insert(constants::ACTIVITY_ID, "first");
+        headers.insert(constants::REQUEST_DURATION, "1");
+        headers.insert(
+            constants::QUERY_METRICS,
+            "retrievedDocumentCount=10;indexUtilizationRatio=1.0",
+        );
+        let mut total = CosmosDiagnostics::default();
+        total.merge(&CosmosDiagnostics::from_headers(&headers));
+
+        headers.insert(constants::REQUEST_CHARGE, "3");
+        headers.insert(constants::ACTIVITY_ID, "second");
+        headers.insert(
+            constants::QUERY_METRICS,
+            "retrievedDocumentCount=30;indexUtilizationRatio=0.0",
+        );
+        total.merge(&CosmosDiagnostics {
+            retry_count: 1,
+            ..CosmosDiagnostics::from_headers(&headers)
+        });
+
+        assert_eq!(5.5, total.request_charge);
+        assert_eq!(Some("second"), total.activity_id.as_deref());
+        assert_eq!(Some(Duration::from_millis(2)), total.server_duration);
+        assert_eq!(1, total.retry_count);
+        let metrics = total.query_metrics.unwrap();
+        assert_eq!(40, metrics.retrieved_document_count);
+        assert_eq!(0.25, metrics.index_hit_ratio);
+    }
+
+    #[test]
+    pub fn thresholds() {
+        let diagnostics = CosmosDiagnostics {
+            request_charge: 10.0,
+            ..Default::default()
+        };
+        let thresholds = DiagnosticsThresholds {
+            latency: Some(Duration::from_millis(100)),
+            request_charge: Some(50.0),
+        };
+        assert!(!thresholds.is_exceeded(Duration::from_millis(50), &diagnostics));
+        assert!(thresholds.is_exceeded(Duration::from_millis(150), &diagnostics));
+        assert!(thresholds.is_exceeded(
+            Duration::from_millis(50),
+            &CosmosDiagnostics {
+                request_charge: 51.0,
+                ..Default::default()
+            }
+        ));
+        assert!(
+            !DiagnosticsThresholds::default().is_exceeded(Duration::from_secs(3600), &diagnostics)
+        );
+    }
+}
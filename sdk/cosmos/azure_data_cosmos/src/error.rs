@@ -0,0 +1,286 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use std::{collections::HashMap, fmt, time::Duration};
+
+use azure_core::{
+    error::{ErrorKind, HttpError},
+    http::StatusCode,
+};
+
+use crate::constants;
+
+/// Describes an error returned by the Cosmos DB service.
+///
+/// The methods of the clients return [`azure_core::Error`]s. Use [`CosmosError::from_error()`] to get the Cosmos DB details of an error returned by the service,
+/// such as the sub-status code, the activity ID to share with support, or the delay requested by the service before retrying:
+///
+/// ```rust,no_run
+/// use azure_data_cosmos::{constants::substatus, CosmosError};
+/// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+/// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+/// match container_client.read_item("partition1", "item1", None).await {
+///     Ok(response) => println!("{:?}", response.into_json_body::<serde_json::Value>().await?),
+///     Err(e) => match CosmosError::from_error(&e) {
+///         Some(error) if error.is_not_found() => println!("item not found"),
+///         Some(error) if error.substatus() == Some(substatus::READ_SESSION_NOT_AVAILABLE) => println!("session not available"),
+///         Some(error) => println!("request {:?} failed: {}", error.activity_id(), error),
+///         None => return Err(e.into()),
+///     },
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// A `CosmosError` can be converted back into an [`azure_core::Error`], with the [`ErrorKind::HttpResponse`] kind, and from which [`CosmosError::from_error()`] returns the same details.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CosmosError {
+    status: StatusCode,
+    substatus: Option<u32>,
+    activity_id: Option<String>,
+    request_charge: Option<f64>,
+    retry_after: Option<Duration>,
+    code: Option<String>,
+    message: Option<String>,
+}
+
+impl CosmosError {
+    /// Gets the Cosmos DB details of an error returned by the service, if `error` (or one of its sources) is an HTTP error.
+    ///
+    /// This searches the entire ["source" chain](https://doc.rust-lang.org/std/error/trait.Error.html#method.source) for either a `CosmosError`,
+    /// or an [`HttpError`] from which the details are read.
+    pub fn from_error(error: &azure_core::Error) -> Option<Self> {
+        let mut source = error.get_ref()? as &dyn std::error::Error;
+        loop {
+            if let Some(e) = source.downcast_ref::<Self>() {
+                return Some(e.clone());
+            }
+            if let Some(e) = source.downcast_ref::<HttpError>() {
+                return Some(Self::from_http_error(e));
+            }
+            source = source.source()?;
+        }
+    }
+
+    fn from_http_error(error: &HttpError) -> Self {
+        let headers = error.headers();
+        let header = |name: &azure_core::http::headers::HeaderName| headers.get(name.as_str());
+        Self {
+            status: error.status(),
+            substatus: header(&constants::SUB_STATUS).and_then(|s| s.parse().ok()),
+            activity_id: header(&constants::ACTIVITY_ID).cloned(),
+            request_charge: header(&constants::REQUEST_CHARGE).and_then(|s| s.parse().ok()),
+            retry_after: retry_after(headers),
+            code: error.error_code().map(ToOwned::to_owned),
+            message: error.error_message().map(ToOwned::to_owned),
+        }
+    }
+
+    /// Gets the HTTP status code of the error.
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Gets the sub-status code of the error, returned by the service in the `x-ms-substatus` header.
+    ///
+    /// See [`constants::substatus`] for the sub-status codes handled by this library.
+    pub fn substatus(&self) -> Option<u32> {
+        self.substatus
+    }
+
+    /// Gets the activity ID of the failed request, returned by the service in the `x-ms-activity-id` header.
+    ///
+    /// Include it when contacting support about the error.
+    pub fn activity_id(&self) -> Option<&str> {
+        self.activity_id.as_deref()
+    }
+
+    /// Gets the request charge of the failed request, in request units.
+    pub fn request_charge(&self) -> Option<f64> {
+        self.request_charge
+    }
+
+    /// Gets the delay the service requested before retrying the request, for throttled requests.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after
+    }
+
+    /// Gets the error code from the body of the response, such as `NotFound` or `Conflict`.
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// Gets the error message from the body of the response.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns `true` if the resource doesn't exist (`404 Not Found`).
+    ///
+    /// A `404 Not Found` with the [`READ_SESSION_NOT_AVAILABLE`](constants::substatus::READ_SESSION_NOT_AVAILABLE) sub-status is not considered as such,
+    /// as the resource may exist but the replica hasn't caught up with the session token yet.
+    pub fn is_not_found(&self) -> bool {
+        self.status == StatusCode::NotFound
+            && self.substatus != Some(constants::substatus::READ_SESSION_NOT_AVAILABLE)
+    }
+
+    /// Returns `true` if a resource with the same ID, or the same unique key, already exists (`409 Conflict`).
+    pub fn is_conflict(&self) -> bool {
+        self.status == StatusCode::Conflict
+    }
+
+    /// Returns `true` if the ETag of the resource doesn't match the `If-Match` precondition (`412 Precondition Failed`),
+    /// or if the item doesn't match the condition of a patch.
+    pub fn is_precondition_failed(&self) -> bool {
+        self.status == StatusCode::PreconditionFailed
+    }
+
+    /// Returns `true` if the ETag of the resource matches the `If-None-Match` precondition of a read (`304 Not Modified`).
+    pub fn is_not_modified(&self) -> bool {
+        self.status == StatusCode::NotModified
+    }
+
+    /// Returns `true` if the request was throttled because the provisioned throughput was exceeded (`429 Too Many Requests`).
+    pub fn is_throttled(&self) -> bool {
+        self.status == StatusCode::TooManyRequests
+    }
+
+    /// Returns `true` if the partition key range targeted by the request was split, merged or moved (`410 Gone` with a partition sub-status).
+    ///
+    /// The partition key ranges of the container must be read again to target the new ranges.
+    pub(crate) fn is_partition_key_range_gone(&self) -> bool {
+        self.status == StatusCode::Gone
+            && matches!(
+                self.substatus,
+                Some(
+                    constants::substatus::PARTITION_KEY_RANGE_GONE
+                        | constants::substatus::COMPLETING_SPLIT
+                        | constants::substatus::COMPLETING_PARTITION_MIGRATION
+                )
+            )
+    }
+}
+
+impl fmt::Display for CosmosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", u16::from(self.status))?;
+        if let Some(substatus) = self.substatus {
+            write!(f, "/{}", substatus)?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, " ({})", code)?;
+        }
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CosmosError {}
+
+impl From<CosmosError> for azure_core::Error {
+    fn from(error: CosmosError) -> Self {
+        let kind = ErrorKind::http_response(error.status, error.code.clone());
+        let message = error.to_string();
+        azure_core::Error::full(kind, error, message)
+    }
+}
+
+/// Reads the delay before retrying from the `x-ms-retry-after-ms` header, or the standard `retry-after-ms` and `retry-after` headers.
+fn retry_after(headers: &HashMap<String, String>) -> Option<Duration> {
+    let millis = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|s| s.parse::<f64>().ok())
+            .filter(|ms| ms.is_finite() && *ms >= 0.0)
+            .map(|ms| Duration::from_secs_f64(ms / 1000.0))
+    };
+    millis(constants::RETRY_AFTER_MS.as_str())
+        .or_else(|| millis("retry-after-ms"))
+        .or_else(|| {
+            headers
+                .get("retry-after")
+                .and_then(|s| s.parse::<u64>().ok())
+                .map(Duration::from_secs)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use azure_core::{
+        error::{ErrorKind, HttpError},
+        http::{headers::Headers, response::Response, StatusCode},
+        Bytes,
+    };
+
+    use super::CosmosError;
+    use crate::constants;
+
+    async fn service_error(
+        status: StatusCode,
+        substatus: Option<&'static str>,
+    ) -> azure_core::Error {
+        let mut headers = Headers::new();
+        if let Some(substatus) = substatus {
+            headers.insert(constants::SUB_STATUS, substatus);
+        }
+        headers.insert(constants::ACTIVITY_ID, "a0b1c2d3");
+        headers.insert(constants::REQUEST_CHARGE, "1.24");
+        headers.insert(constants::RETRY_AFTER_MS, "250");
+        let response: Response<()> = Response::from_bytes(
+            status,
+            headers,
+            Bytes::from_static(br#"{"code":"NotFound","message":"Entity with the specified id does not exist in the system."}"#),
+        );
+        let http_error = HttpError::new(response).await;
+        azure_core::Error::full(
+            ErrorKind::http_response(status, http_error.error_code().map(ToOwned::to_owned)),
+            http_error,
+            "request failed",
+        )
+    }
+
+    #[tokio::test]
+    pub async fn reads_service_error_details() {
+        let error = CosmosError::from_error(&service_error(StatusCode::NotFound, Some("0")).await)
+            .expect("an HTTP error");
+        assert_eq!(StatusCode::NotFound, error.status());
+        assert_eq!(Some(0), error.substatus());
+        assert_eq!(Some("a0b1c2d3"), error.activity_id());
+        assert_eq!(Some(1.24), error.request_charge());
+        assert_eq!(Some(Duration::from_millis(250)), error.retry_after());
+        assert_eq!(Some("NotFound"), error.code());
+        assert_eq!(
+            Some("Entity with the specified id does not exist in the system."),
+            error.message()
+        );
+        assert!(error.is_not_found());
+        assert!(!error.is_conflict());
+        assert!(!error.is_precondition_failed());
+        assert!(!error.is_throttled());
+
+        let error =
+            CosmosError::from_error(&service_error(StatusCode::NotFound, Some("1002")).await)
+                .unwrap();
+        assert!(!error.is_not_found());
+
+        let error = azure_core::Error::message(ErrorKind::Io, "connection reset");
+        assert!(CosmosError::from_error(&error).is_none());
+    }
+
+    #[tokio::test]
+    pub async fn converts_losslessly_into_azure_core_error() {
+        let error =
+            CosmosError::from_error(&service_error(StatusCode::Conflict, Some("0")).await).unwrap();
+        let converted: azure_core::Error = error.clone().into();
+        assert_eq!(Some(StatusCode::Conflict), converted.http_status());
+        assert!(matches!(
+            converted.kind(),
+            ErrorKind::HttpResponse { error_code: Some(code), .. } if code == "NotFound"
+        ));
+        assert_eq!(Some(error), CosmosError::from_error(&converted));
+    }
+}
@@ -0,0 +1,820 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! An in-process fake of the Cosmos DB gateway, to run tests deterministically without an account or the emulator.
+//!
+//! [`FakeCosmosGateway`] implements [`HttpClient`], and serves the requests of a [`CosmosClient`] from memory.
+//! It supports:
+//!
+//! * Creating, reading, replacing, deleting and listing databases and containers.
+//! * Creating, upserting, reading, replacing, patching and deleting items, using the partition key of the container.
+//! * Queries, within a single partition or across all the partitions of a container, supporting `SELECT` (`*`, `VALUE` or a list of properties), `TOP`,
+//!   `WHERE`, `ORDER BY`, `OFFSET ... LIMIT`, parameters, the common operators and functions, and the `COUNT`, `SUM`, `MIN`, `MAX` and `AVG` aggregates.
+//! * ETags, with `If-Match` and `If-None-Match` preconditions, and conditional patches.
+//! * Stored procedures, triggers, user-defined functions, users and permissions, as stored resources (scripts are never executed).
+//!
+//! Each container has a single partition key range, and every response is returned in a single page.
+//! Throughput, indexing policies, unique keys and time-to-live are not simulated, and queries requiring a query plan (such as [`ContainerClient::search_items`](crate::clients::ContainerClient::search_items()))
+//! are rejected with `400 Bad Request`.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use azure_data_cosmos::{fake::FakeCosmosGateway, models::ContainerProperties};
+//! use serde_json::json;
+//!
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! let gateway = FakeCosmosGateway::new();
+//! let client = gateway.client();
+//! client.create_database("SampleDB", None).await?;
+//! let database = client.database_client("SampleDB");
+//! database
+//!     .create_container(
+//!         ContainerProperties {
+//!             id: "Products".into(),
+//!             partition_key: "/category".into(),
+//!             ..Default::default()
+//!         },
+//!         None,
+//!     )
+//!     .await?;
+//!
+//! let container = database.container_client("Products");
+//! container
+//!     .create_item("gear", json!({"id": "1", "category": "gear", "price": 10}), None)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! # tokio::runtime::Runtime::new().unwrap().block_on(doc()).unwrap();
+//! ```
+
+mod patch;
+mod sql;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+use azure_core::{
+    credentials::{AccessToken, TokenCredential},
+    date::OffsetDateTime,
+    http::{
+        headers::{self, HeaderName, Headers},
+        request::{Body, Request},
+        response::Response,
+        HttpClient, Method, StatusCode, TransportOptions, Url,
+    },
+    Bytes,
+};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use crate::{
+    constants, models::PatchDocument, utils::url_decode, CosmosClient, CosmosClientOptions,
+};
+
+use sql::SelectQuery;
+
+/// The endpoint of the fake account.
+const ENDPOINT: &str = "https://fake-cosmos.localhost/";
+
+/// The request charge returned for every operation.
+const REQUEST_CHARGE: &str = "1";
+
+/// An in-process fake of the Cosmos DB gateway, which serves the requests of a [`CosmosClient`] from memory.
+///
+/// Clones of a gateway share the same data. See the [module documentation](crate::fake) for the supported features.
+#[derive(Clone, Debug)]
+pub struct FakeCosmosGateway {
+    endpoint: Url,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for FakeCosmosGateway {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FakeCosmosGateway {
+    /// Creates a new gateway, with no databases.
+    pub fn new() -> Self {
+        Self {
+            endpoint: ENDPOINT.parse().expect("the fake endpoint is a valid URL"),
+            state: Default::default(),
+        }
+    }
+
+    /// Gets the endpoint of the fake account.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets client options sending all the requests to this gateway.
+    ///
+    /// Use them to create a [`CosmosClient`] with your own credential, or to change other options.
+    pub fn client_options(&self) -> CosmosClientOptions {
+        let mut options = CosmosClientOptions::default();
+        options.client_options.transport = Some(TransportOptions::new(Arc::new(self.clone())));
+        options
+    }
+
+    /// Creates a [`CosmosClient`] sending all the requests to this gateway.
+    pub fn client(&self) -> CosmosClient {
+        CosmosClient::new(
+            self.endpoint.as_str(),
+            Arc::new(FakeCredential),
+            Some(self.client_options()),
+        )
+        .expect("the fake endpoint is a valid URL")
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl HttpClient for FakeCosmosGateway {
+    async fn execute_request(&self, request: &Request) -> azure_core::Result<Response> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.sequence += 1;
+        let activity_id = format!("00000000-0000-0000-0000-{:012x}", state.sequence);
+
+        let mut headers = Headers::new();
+        headers.insert(constants::ACTIVITY_ID, activity_id);
+        headers.insert(constants::REQUEST_CHARGE, REQUEST_CHARGE);
+        let (status, body) = match state.handle(request, &self.endpoint) {
+            Ok(reply) => {
+                if let Some(etag) = reply.etag {
+                    headers.insert(headers::ETAG, etag);
+                }
+                if reply.session {
+                    headers.insert(headers::SESSION_TOKEN, format!("0:-1#{}", state.lsn));
+                }
+                let minimal = request
+                    .headers()
+                    .get_optional_str(&headers::PREFER)
+                    .is_some_and(|p| p == constants::PREFER_MINIMAL.as_str());
+                let body = match reply.body {
+                    Some(body) if !(minimal && reply.is_write) => {
+                        headers.insert(headers::CONTENT_TYPE, "application/json");
+                        serde_json::to_vec(&body)?.into()
+                    }
+                    _ => Bytes::new(),
+                };
+                (reply.status, body)
+            }
+            Err(fault) => {
+                headers.insert(constants::SUB_STATUS, fault.substatus.to_string());
+                headers.insert(headers::CONTENT_TYPE, "application/json");
+                let body = json!({ "code": fault.code(), "message": fault.message });
+                (fault.status, serde_json::to_vec(&body)?.into())
+            }
+        };
+        Ok(Response::from_bytes(status, headers, body))
+    }
+}
+
+/// A credential returning a fixed token, which the gateway ignores.
+#[derive(Debug)]
+struct FakeCredential;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl TokenCredential for FakeCredential {
+    async fn get_token(&self, _scopes: &[&str]) -> azure_core::Result<AccessToken> {
+        Ok(AccessToken::new(
+            "fake_token",
+            OffsetDateTime::now_utc() + Duration::from_secs(3600),
+        ))
+    }
+}
+
+/// The data of the fake account.
+#[derive(Debug, Default)]
+struct State {
+    /// Maps the path of each feed (for example, `dbs/MyDatabase/colls`) to its resources, in creation order.
+    feeds: BTreeMap<String, Vec<Resource>>,
+
+    /// Incremented for each request, and used to generate activity IDs.
+    sequence: u64,
+
+    /// Incremented for each write, and used to generate ETags, resource IDs and session tokens.
+    lsn: u64,
+}
+
+#[derive(Debug)]
+struct Resource {
+    /// The partition key values of an item, as sent in the `x-ms-documentdb-partitionkey` header.
+    partition_key: Option<Value>,
+    body: Value,
+}
+
+impl Resource {
+    fn id(&self) -> &str {
+        self.body["id"].as_str().unwrap_or_default()
+    }
+
+    fn etag(&self) -> Option<&str> {
+        self.body["_etag"].as_str()
+    }
+
+    fn rid(&self) -> Option<String> {
+        self.body["_rid"].as_str().map(ToOwned::to_owned)
+    }
+}
+
+/// A successful response.
+struct Reply {
+    status: StatusCode,
+    body: Option<Value>,
+    etag: Option<String>,
+    /// Whether the response includes a session token, as responses to item operations do.
+    session: bool,
+    /// Whether the request wrote a resource, in which case `Prefer: return=minimal` omits the body.
+    is_write: bool,
+}
+
+impl Reply {
+    fn new(status: StatusCode, body: Value) -> Self {
+        Self {
+            status,
+            etag: body["_etag"].as_str().map(ToOwned::to_owned),
+            body: Some(body),
+            session: false,
+            is_write: false,
+        }
+    }
+}
+
+/// A failed response.
+struct Fault {
+    status: StatusCode,
+    substatus: u32,
+    message: String,
+}
+
+impl Fault {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            substatus: 0,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BadRequest, message)
+    }
+
+    fn not_found() -> Self {
+        Self::new(
+            StatusCode::NotFound,
+            "Entity with the specified id does not exist in the system.",
+        )
+    }
+
+    fn code(&self) -> &'static str {
+        match self.status {
+            StatusCode::BadRequest => "BadRequest",
+            StatusCode::NotFound => "NotFound",
+            StatusCode::Conflict => "Conflict",
+            StatusCode::Gone => "Gone",
+            StatusCode::PreconditionFailed => "PreconditionFailed",
+            _ => "InternalServerError",
+        }
+    }
+}
+
+/// The body of a query request.
+#[derive(Deserialize)]
+struct QueryBody {
+    query: String,
+    #[serde(default)]
+    parameters: Vec<QueryParameter>,
+}
+
+#[derive(Deserialize)]
+struct QueryParameter {
+    name: String,
+    value: Value,
+}
+
+/// The property under which a feed returns its resources, for each resource type.
+fn feed_property(resource_type: &str) -> Option<&'static str> {
+    Some(match resource_type {
+        "dbs" => "Databases",
+        "colls" => "DocumentCollections",
+        "docs" => "Documents",
+        "sprocs" => "StoredProcedures",
+        "triggers" => "Triggers",
+        "udfs" => "UserDefinedFunctions",
+        "users" => "Users",
+        "permissions" => "Permissions",
+        "conflicts" => "Conflicts",
+        "offers" => "Offers",
+        "pkranges" => "PartitionKeyRanges",
+        _ => return None,
+    })
+}
+
+impl State {
+    fn handle(&mut self, request: &Request, endpoint: &Url) -> Result<Reply, Fault> {
+        let segments: Vec<String> = request
+            .url()
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|s| !s.is_empty())
+            .map(url_decode)
+            .collect();
+
+        if segments.is_empty() {
+            return match request.method() {
+                Method::Get => Ok(Reply::new(StatusCode::Ok, account(endpoint))),
+                _ => Err(Fault::bad_request("unsupported operation on the account")),
+            };
+        }
+
+        // Each parent of the target must exist.
+        for end in (2..segments.len()).step_by(2) {
+            let (feed, id) = (segments[..end - 1].join("/"), &segments[end - 1]);
+            if self.find(&feed, None, id).is_none() {
+                return Err(Fault::not_found());
+            }
+        }
+
+        if segments.len() % 2 == 1 {
+            let resource_type = segments.last().expect("segments is not empty").as_str();
+            if feed_property(resource_type).is_none() {
+                return Err(Fault::bad_request(format!(
+                    "unsupported resource type '{}'",
+                    resource_type
+                )));
+            }
+            let feed = segments.join("/");
+            match request.method() {
+                Method::Get if resource_type == "pkranges" => Ok(Reply::new(
+                    StatusCode::Ok,
+                    feed_body(
+                        "pkranges",
+                        vec![json!({
+                            "id": "0",
+                            "minInclusive": "",
+                            "maxExclusive": "FF",
+                            "parents": [],
+                        })],
+                    ),
+                )),
+                Method::Get => self.read_feed(request, &segments, &feed),
+                Method::Post if is_query(request) => self.query(request, &segments, &feed),
+                Method::Post => self.create(request, &segments, &feed),
+                _ => Err(Fault::bad_request("unsupported operation on a feed")),
+            }
+        } else {
+            let feed = segments[..segments.len() - 1].join("/");
+            let id = segments.last().expect("segments is not empty");
+            match request.method() {
+                Method::Get => self.read(request, &segments, &feed, id),
+                Method::Put => self.replace(request, &segments, &feed, id),
+                Method::Patch => self.patch(request, &segments, &feed, id),
+                Method::Delete => self.delete(request, &segments, &feed, id),
+                _ => Err(Fault::bad_request(
+                    "unsupported operation, the fake gateway doesn't execute stored procedures",
+                )),
+            }
+        }
+    }
+
+    /// Finds a resource, in the partition when provided.
+    fn find(&self, feed: &str, partition_key: Option<&Value>, id: &str) -> Option<usize> {
+        self.feeds.get(feed)?.iter().position(|r| {
+            r.id() == id && (partition_key.is_none() || r.partition_key.as_ref() == partition_key)
+        })
+    }
+
+    /// Gets the partition key paths of the container owning an item feed.
+    fn partition_key_paths(&self, segments: &[String]) -> Result<Vec<String>, Fault> {
+        let container_feed = segments[..3].join("/");
+        let index = self
+            .find(&container_feed, None, &segments[3])
+            .ok_or_else(Fault::not_found)?;
+        let container = &self.feeds[&container_feed][index].body;
+        Ok(container["partitionKey"]["paths"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|p| p.as_str().map(ToOwned::to_owned))
+            .collect())
+    }
+
+    /// Gets the partition key of an item request, checking that it matches the item when one is provided.
+    fn partition_key(
+        &self,
+        request: &Request,
+        segments: &[String],
+        item: Option<&Value>,
+    ) -> Result<Option<Value>, Fault> {
+        if !is_item_feed(segments) {
+            return Ok(None);
+        }
+        let header = request
+            .headers()
+            .get_optional_str(&constants::PARTITION_KEY)
+            .ok_or_else(|| {
+                Fault::bad_request("the partition key must be provided for item operations")
+            })?;
+        let partition_key: Value = serde_json::from_str(header)
+            .map_err(|_| Fault::bad_request("the partition key header is not valid JSON"))?;
+        if let Some(item) = item {
+            let paths = self.partition_key_paths(segments)?;
+            let extracted: Vec<Value> = paths
+                .iter()
+                .map(|path| {
+                    path.trim_start_matches('/')
+                        .split('/')
+                        .try_fold(item, |value, property| value.get(property))
+                        .cloned()
+                        // An item without the partition key property has the "undefined" partition key, sent as `{}`.
+                        .unwrap_or_else(|| Value::Object(Map::new()))
+                })
+                .collect();
+            if !partition_key_equals(&partition_key, &extracted) {
+                return Err(Fault::bad_request(
+                    "PartitionKey extracted from document doesn't match the one specified in the header.",
+                ));
+            }
+        }
+        Ok(Some(partition_key))
+    }
+
+    fn read_feed(
+        &self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+    ) -> Result<Reply, Fault> {
+        let partition_key = if is_item_feed(segments)
+            && request
+                .headers()
+                .get_optional_str(&constants::PARTITION_KEY)
+                .is_some()
+        {
+            self.partition_key(request, segments, None)?
+        } else {
+            None
+        };
+        let resources = self
+            .resources(feed, partition_key.as_ref())
+            .cloned()
+            .collect();
+        Ok(Reply::new(
+            StatusCode::Ok,
+            feed_body(segments.last().expect("segments is not empty"), resources),
+        ))
+    }
+
+    fn resources<'a>(
+        &'a self,
+        feed: &str,
+        partition_key: Option<&'a Value>,
+    ) -> impl Iterator<Item = &'a Value> {
+        self.feeds
+            .get(feed)
+            .into_iter()
+            .flatten()
+            .filter(move |r| partition_key.is_none() || r.partition_key.as_ref() == partition_key)
+            .map(|r| &r.body)
+    }
+
+    fn query(&self, request: &Request, segments: &[String], feed: &str) -> Result<Reply, Fault> {
+        let header = |name: &HeaderName| request.headers().get_optional_str(name);
+        if header(&constants::IS_QUERY_PLAN_REQUEST).is_some() {
+            return Err(Fault::bad_request(
+                "the fake gateway doesn't support queries requiring a query plan",
+            ));
+        }
+        if let Some(range_id) = header(&constants::PARTITION_KEY_RANGE_ID) {
+            if range_id != "0" {
+                let mut fault = Fault::new(StatusCode::Gone, "the partition key range is gone");
+                fault.substatus = constants::substatus::PARTITION_KEY_RANGE_GONE;
+                return Err(fault);
+            }
+        }
+
+        let partition_key = if is_item_feed(segments) && header(&constants::PARTITION_KEY).is_some()
+        {
+            self.partition_key(request, segments, None)?
+        } else if is_item_feed(segments)
+            && header(&constants::ENABLE_CROSS_PARTITION_QUERY).is_none()
+            && header(&constants::PARTITION_KEY_RANGE_ID).is_none()
+        {
+            return Err(Fault::bad_request(
+                "cross partition query is required but disabled",
+            ));
+        } else {
+            None
+        };
+
+        let body: QueryBody = serde_json::from_slice(&body(request))
+            .map_err(|e| Fault::bad_request(format!("invalid query body: {}", e)))?;
+        let parameters: HashMap<String, Value> = body
+            .parameters
+            .into_iter()
+            .map(|p| (p.name, p.value))
+            .collect();
+        let results = SelectQuery::parse(&body.query)
+            .and_then(|q| q.execute(self.resources(feed, partition_key.as_ref()), &parameters))
+            .map_err(|e| Fault::bad_request(format!("invalid query: {}", e)))?;
+
+        let mut reply = Reply::new(
+            StatusCode::Ok,
+            feed_body(segments.last().expect("segments is not empty"), results),
+        );
+        reply.session = is_item_feed(segments);
+        Ok(reply)
+    }
+
+    fn create(
+        &mut self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+    ) -> Result<Reply, Fault> {
+        let body: Value = serde_json::from_slice(&body(request))
+            .map_err(|e| Fault::bad_request(format!("invalid resource body: {}", e)))?;
+        let Some(id) = body
+            .get("id")
+            .and_then(Value::as_str)
+            .map(ToOwned::to_owned)
+        else {
+            return Err(Fault::bad_request(
+                "the resource must have a string 'id' property",
+            ));
+        };
+        if id.is_empty() || id.contains(['/', '\\', '?', '#']) {
+            return Err(Fault::bad_request(format!("'{}' is not a valid id", id)));
+        }
+        let partition_key = self.partition_key(request, segments, Some(&body))?;
+        let upsert = request
+            .headers()
+            .get_optional_str(&constants::IS_UPSERT)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+
+        let (status, stored) = match self.find(feed, partition_key.as_ref(), &id) {
+            Some(_) if !upsert => {
+                return Err(Fault::new(
+                    StatusCode::Conflict,
+                    "Entity with the specified id already exists in the system.",
+                ))
+            }
+            Some(index) => {
+                self.check_if_match(request, feed, index)?;
+                let rid = self.feeds[feed][index].rid();
+                let stored = self.stamp(body, &format!("{}/{}", feed, id), rid);
+                self.feeds.get_mut(feed).expect("the feed exists")[index].body = stored.clone();
+                (StatusCode::Ok, stored)
+            }
+            None => {
+                let stored = self.stamp(body, &format!("{}/{}", feed, id), None);
+                self.feeds
+                    .entry(feed.to_string())
+                    .or_default()
+                    .push(Resource {
+                        partition_key,
+                        body: stored.clone(),
+                    });
+                (StatusCode::Created, stored)
+            }
+        };
+        Ok(self.write_reply(status, stored, segments))
+    }
+
+    fn read(
+        &self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+        id: &str,
+    ) -> Result<Reply, Fault> {
+        let item_segments = &segments[..segments.len() - 1];
+        let partition_key = self.partition_key(request, item_segments, None)?;
+        let index = self
+            .find(feed, partition_key.as_ref(), id)
+            .ok_or_else(Fault::not_found)?;
+        let resource = &self.feeds[feed][index];
+        let mut reply = Reply::new(StatusCode::Ok, resource.body.clone());
+        reply.session = is_item_feed(item_segments);
+        if let (Some(etag), Some(if_none_match)) = (
+            resource.etag(),
+            request.headers().get_optional_str(&headers::IF_NONE_MATCH),
+        ) {
+            if if_none_match == "*" || if_none_match == etag {
+                reply.status = StatusCode::NotModified;
+                reply.body = None;
+            }
+        }
+        Ok(reply)
+    }
+
+    fn replace(
+        &mut self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+        id: &str,
+    ) -> Result<Reply, Fault> {
+        let body: Value = serde_json::from_slice(&body(request))
+            .map_err(|e| Fault::bad_request(format!("invalid resource body: {}", e)))?;
+        if body.get("id").and_then(Value::as_str) != Some(id) {
+            return Err(Fault::bad_request(
+                "the id of the resource must match the id in the URL",
+            ));
+        }
+        let item_segments = &segments[..segments.len() - 1];
+        let partition_key = self.partition_key(request, item_segments, Some(&body))?;
+        let index = self.find_for_write(request, feed, partition_key.as_ref(), id)?;
+        let rid = self.feeds[feed][index].rid();
+        let stored = self.stamp(body, &segments.join("/"), rid);
+        self.feeds.get_mut(feed).expect("the feed exists")[index].body = stored.clone();
+        Ok(self.write_reply(StatusCode::Ok, stored, item_segments))
+    }
+
+    fn patch(
+        &mut self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+        id: &str,
+    ) -> Result<Reply, Fault> {
+        let item_segments = &segments[..segments.len() - 1];
+        if !is_item_feed(item_segments) {
+            return Err(Fault::bad_request("only items can be patched"));
+        }
+        let patch: PatchDocument = serde_json::from_slice(&body(request))
+            .map_err(|e| Fault::bad_request(format!("invalid patch document: {}", e)))?;
+        let partition_key = self.partition_key(request, item_segments, None)?;
+        let index = self.find_for_write(request, feed, partition_key.as_ref(), id)?;
+        let current = &self.feeds[feed][index].body;
+
+        if let Some(condition) = &patch.condition {
+            let matches = sql::Condition::parse(condition)
+                .and_then(|c| c.matches(current))
+                .map_err(|e| Fault::bad_request(format!("invalid patch condition: {}", e)))?;
+            if !matches {
+                return Err(Fault::new(
+                    StatusCode::PreconditionFailed,
+                    "the item doesn't match the patch condition",
+                ));
+            }
+        }
+        let patched = patch::apply(current, &patch.operations).map_err(Fault::bad_request)?;
+        if patched.get("id").and_then(Value::as_str) != Some(id) {
+            return Err(Fault::bad_request("the id of an item cannot be patched"));
+        }
+        // Patching the partition key would move the item to another partition, which the service rejects.
+        self.partition_key(request, item_segments, Some(&patched))?;
+
+        let rid = self.feeds[feed][index].rid();
+        let stored = self.stamp(patched, &segments.join("/"), rid);
+        self.feeds.get_mut(feed).expect("the feed exists")[index].body = stored.clone();
+        Ok(self.write_reply(StatusCode::Ok, stored, item_segments))
+    }
+
+    fn delete(
+        &mut self,
+        request: &Request,
+        segments: &[String],
+        feed: &str,
+        id: &str,
+    ) -> Result<Reply, Fault> {
+        let item_segments = &segments[..segments.len() - 1];
+        let partition_key = self.partition_key(request, item_segments, None)?;
+        let index = self.find_for_write(request, feed, partition_key.as_ref(), id)?;
+        self.feeds
+            .get_mut(feed)
+            .expect("the feed exists")
+            .remove(index);
+
+        // Deleting a resource deletes all of its children.
+        let prefix = format!("{}/", segments.join("/"));
+        self.feeds.retain(|path, _| !path.starts_with(&prefix));
+        self.lsn += 1;
+
+        Ok(Reply {
+            status: StatusCode::NoContent,
+            body: None,
+            etag: None,
+            session: is_item_feed(item_segments),
+            is_write: true,
+        })
+    }
+
+    /// Finds the resource targeted by a write, checking its `If-Match` precondition.
+    fn find_for_write(
+        &self,
+        request: &Request,
+        feed: &str,
+        partition_key: Option<&Value>,
+        id: &str,
+    ) -> Result<usize, Fault> {
+        let index = self
+            .find(feed, partition_key, id)
+            .ok_or_else(Fault::not_found)?;
+        self.check_if_match(request, feed, index)?;
+        Ok(index)
+    }
+
+    /// Checks the `If-Match` precondition of a write against the current ETag of a resource.
+    fn check_if_match(&self, request: &Request, feed: &str, index: usize) -> Result<(), Fault> {
+        if let Some(if_match) = request.headers().get_optional_str(&headers::IF_MATCH) {
+            let etag = self.feeds[feed][index].etag();
+            if if_match != "*" && Some(if_match) != etag {
+                return Err(Fault::new(
+                    StatusCode::PreconditionFailed,
+                    "Operation cannot be performed because one of the specified precondition is not met.",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the system properties of a resource being written, keeping the resource ID of a resource being replaced.
+    fn stamp(&mut self, mut body: Value, path: &str, rid: Option<String>) -> Value {
+        self.lsn += 1;
+        if let Value::Object(object) = &mut body {
+            let rid = rid.unwrap_or_else(|| format!("{:016x}", self.lsn));
+            object.insert("_rid".to_string(), Value::String(rid));
+            object.insert("_self".to_string(), Value::String(format!("{}/", path)));
+            object.insert(
+                "_etag".to_string(),
+                Value::String(format!("\"00000000-0000-0000-0000-{:012x}\"", self.lsn)),
+            );
+            object.insert(
+                "_ts".to_string(),
+                Value::from(OffsetDateTime::now_utc().unix_timestamp()),
+            );
+        }
+        body
+    }
+
+    fn write_reply(&self, status: StatusCode, body: Value, segments: &[String]) -> Reply {
+        let mut reply = Reply::new(status, body);
+        reply.session = is_item_feed(segments);
+        reply.is_write = true;
+        reply
+    }
+}
+
+/// Returns `true` if the segments of a feed path designate the items of a container (`dbs/{db}/colls/{container}/docs`).
+fn is_item_feed(segments: &[String]) -> bool {
+    segments.len() == 5 && segments[4] == "docs"
+}
+
+/// Returns `true` if the request is a query, rather than a create.
+fn is_query(request: &Request) -> bool {
+    request
+        .headers()
+        .get_optional_str(&constants::QUERY)
+        .is_some_and(|v| v.eq_ignore_ascii_case("true"))
+}
+
+fn body(request: &Request) -> Bytes {
+    match request.body() {
+        Body::Bytes(bytes) => bytes.clone(),
+        #[allow(unreachable_patterns)]
+        _ => Bytes::new(),
+    }
+}
+
+fn feed_body(resource_type: &str, resources: Vec<Value>) -> Value {
+    let property = feed_property(resource_type).expect("the resource type was validated");
+    let count = resources.len();
+    json!({
+        "_rid": "",
+        property: resources,
+        "_count": count,
+    })
+}
+
+fn account(endpoint: &Url) -> Value {
+    let location = json!([{ "name": "Local", "databaseAccountEndpoint": endpoint.as_str() }]);
+    json!({
+        "id": "fake-cosmos",
+        "writableLocations": location,
+        "readableLocations": location,
+        "enableMultipleWriteLocations": false,
+    })
+}
+
+/// Compares partition key values, treating numbers as equal when they have the same value.
+fn partition_key_equals(header: &Value, extracted: &[Value]) -> bool {
+    let Some(values) = header.as_array() else {
+        return false;
+    };
+    values.len() == extracted.len()
+        && values.iter().zip(extracted).all(|(a, b)| match (a, b) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+            _ => a == b,
+        })
+}
@@ -0,0 +1,187 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Applies [`PatchOperation`]s to documents stored by the fake gateway.
+
+use serde_json::Value;
+
+use crate::models::PatchOperation;
+
+/// Applies the operations to a copy of the document, returning a message describing the problem if any operation is invalid.
+///
+/// The operations are applied atomically: the document is only changed if all the operations succeed.
+pub(crate) fn apply(document: &Value, operations: &[PatchOperation]) -> Result<Value, String> {
+    let mut patched = document.clone();
+    for operation in operations {
+        match operation {
+            PatchOperation::Add { path, value } => insert(&mut patched, path, value.clone(), true)?,
+            PatchOperation::Set { path, value } => {
+                insert(&mut patched, path, value.clone(), false)?
+            }
+            PatchOperation::Replace { path, value } => {
+                *get_mut(&mut patched, path)? = value.clone();
+            }
+            PatchOperation::Remove { path } => {
+                remove(&mut patched, path)?;
+            }
+            PatchOperation::Increment { path, value } => {
+                let target = get_mut(&mut patched, path)?;
+                *target = match (target.as_i64(), value.as_i64()) {
+                    (Some(current), Some(increment)) => current
+                        .checked_add(increment)
+                        .map(Value::from)
+                        .ok_or_else(|| format!("incrementing '{}' overflows", path))?,
+                    _ => {
+                        let current = target
+                            .as_f64()
+                            .ok_or_else(|| format!("'{}' is not a number", path))?;
+                        let sum = current + value.as_f64().unwrap_or_default();
+                        serde_json::Number::from_f64(sum)
+                            .map(Value::Number)
+                            .ok_or_else(|| format!("incrementing '{}' overflows", path))?
+                    }
+                };
+            }
+            PatchOperation::Move { from, to } => {
+                let value = remove(&mut patched, from)?;
+                insert(&mut patched, to, value, false)?;
+            }
+        }
+    }
+    Ok(patched)
+}
+
+/// Splits a JSON pointer into its parent pointer segments and last segment.
+fn split(path: &str) -> Result<(Vec<String>, String), String> {
+    let Some(pointer) = path.strip_prefix('/') else {
+        return Err(format!("'{}' is not a valid path", path));
+    };
+    let mut segments: Vec<String> = pointer
+        .split('/')
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect();
+    let last = segments.pop().unwrap_or_default();
+    if last.is_empty() {
+        return Err(format!("'{}' is not a valid path", path));
+    }
+    Ok((segments, last))
+}
+
+fn parent_mut<'a>(document: &'a mut Value, path: &str) -> Result<(&'a mut Value, String), String> {
+    let (parents, last) = split(path)?;
+    let mut current = document;
+    for segment in &parents {
+        current = match current {
+            Value::Object(object) => object.get_mut(segment),
+            Value::Array(array) => segment.parse::<usize>().ok().and_then(|i| array.get_mut(i)),
+            _ => None,
+        }
+        .ok_or_else(|| format!("the parent of '{}' doesn't exist", path))?;
+    }
+    Ok((current, last))
+}
+
+fn get_mut<'a>(document: &'a mut Value, path: &str) -> Result<&'a mut Value, String> {
+    let (parent, last) = parent_mut(document, path)?;
+    match parent {
+        Value::Object(object) => object.get_mut(&last),
+        Value::Array(array) => last.parse::<usize>().ok().and_then(|i| array.get_mut(i)),
+        _ => None,
+    }
+    .ok_or_else(|| format!("'{}' doesn't exist", path))
+}
+
+/// Adds or sets a value. When `insert_into_arrays` is `true`, array indexes insert the value, otherwise they replace the existing element.
+fn insert(
+    document: &mut Value,
+    path: &str,
+    value: Value,
+    insert_into_arrays: bool,
+) -> Result<(), String> {
+    let (parent, last) = parent_mut(document, path)?;
+    match parent {
+        Value::Object(object) => {
+            object.insert(last, value);
+        }
+        Value::Array(array) if last == "-" => array.push(value),
+        Value::Array(array) => {
+            let index = last
+                .parse::<usize>()
+                .ok()
+                .filter(|i| *i <= array.len())
+                .ok_or_else(|| format!("'{}' is not a valid array index", path))?;
+            if insert_into_arrays || index == array.len() {
+                array.insert(index, value);
+            } else {
+                array[index] = value;
+            }
+        }
+        _ => {
+            return Err(format!(
+                "the parent of '{}' is not an object or array",
+                path
+            ))
+        }
+    }
+    Ok(())
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, String> {
+    let (parent, last) = parent_mut(document, path)?;
+    match parent {
+        Value::Object(object) => object.remove(&last),
+        Value::Array(array) => last
+            .parse::<usize>()
+            .ok()
+            .filter(|i| *i < array.len())
+            .map(|i| array.remove(i)),
+        _ => None,
+    }
+    .ok_or_else(|| format!("'{}' doesn't exist", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::apply;
+    use crate::models::PatchDocument;
+
+    #[test]
+    pub fn applies_operations() {
+        let document = json!({"id": "1", "count": 1, "tags": ["a", "c"], "nested": {"old": true}});
+        let patch = PatchDocument::default()
+            .with_add("/tags/1", "b")
+            .unwrap()
+            .with_set("/tags/-", "d")
+            .unwrap()
+            .with_increment("/count", 2)
+            .unwrap()
+            .with_replace("/nested/old", false)
+            .unwrap()
+            .with_move("/nested/old", "/moved")
+            .unwrap()
+            .with_remove("/nested")
+            .unwrap();
+        assert_eq!(
+            json!({"id": "1", "count": 3, "tags": ["a", "b", "c", "d"], "moved": false}),
+            apply(&document, &patch.operations).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn rejects_invalid_operations() {
+        let document = json!({"id": "1", "name": "widget"});
+        for patch in [
+            PatchDocument::default()
+                .with_replace("/missing", 1)
+                .unwrap(),
+            PatchDocument::default().with_remove("/missing").unwrap(),
+            PatchDocument::default().with_increment("/name", 1).unwrap(),
+            PatchDocument::default().with_set("/a/b", 1).unwrap(),
+            PatchDocument::default().with_set("relative", 1).unwrap(),
+        ] {
+            assert!(apply(&document, &patch.operations).is_err());
+        }
+    }
+}
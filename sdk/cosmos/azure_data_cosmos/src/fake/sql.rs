@@ -0,0 +1,1166 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! A small interpreter for the subset of the Cosmos DB SQL dialect supported by the fake gateway.
+//!
+//! Values are represented as `Option<Value>`, where `None` is `undefined` (for example, a missing property).
+//! As in Cosmos DB, comparing values of different types, or applying an operator to values of the wrong type, produces `undefined`,
+//! and a `WHERE` clause only keeps the documents for which the filter is `true`.
+
+use std::{cmp::Ordering, collections::HashMap};
+
+use serde_json::{Map, Number, Value};
+
+/// The result of parsing or evaluating a query, with a message describing the problem on error.
+pub(crate) type SqlResult<T> = Result<T, String>;
+
+/// A parsed `SELECT` query.
+#[derive(Debug)]
+pub(crate) struct SelectQuery {
+    top: Option<usize>,
+    selection: Selection,
+    alias: String,
+    filter: Option<Expr>,
+    order_by: Vec<(Expr, bool)>,
+    offset_limit: Option<(usize, usize)>,
+}
+
+/// A parsed `FROM <alias> WHERE <predicate>` condition, as used by conditional patch operations.
+#[derive(Debug)]
+pub(crate) struct Condition {
+    alias: String,
+    predicate: Expr,
+}
+
+#[derive(Debug)]
+enum Selection {
+    All,
+    Value(Expr),
+    Fields(Vec<(Expr, String)>),
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(Option<Value>),
+    Parameter(String),
+    Identifier(String),
+    Array(Vec<Expr>),
+    Property(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Negate(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    In(Box<Expr>, Vec<Expr>),
+    Between(Box<Expr>, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Modulo,
+    Concat,
+}
+
+const AGGREGATES: &[&str] = &["COUNT", "SUM", "MIN", "MAX", "AVG"];
+
+const KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "ORDER", "BY", "AND", "OR", "NOT", "TOP", "VALUE", "OFFSET",
+    "LIMIT", "AS", "IN", "ASC", "DESC", "JOIN", "DISTINCT", "BETWEEN", "GROUP",
+];
+
+impl SelectQuery {
+    /// Parses the text of a query.
+    pub fn parse(text: &str) -> SqlResult<Self> {
+        let mut parser = Parser::new(text)?;
+        let query = parser.select_query()?;
+        parser.end()?;
+        Ok(query)
+    }
+
+    /// Runs the query against the provided documents.
+    pub fn execute<'a>(
+        &self,
+        documents: impl IntoIterator<Item = &'a Value>,
+        parameters: &HashMap<String, Value>,
+    ) -> SqlResult<Vec<Value>> {
+        let scope = Scope {
+            alias: &self.alias,
+            parameters,
+        };
+
+        let mut rows = Vec::new();
+        for document in documents {
+            let keep = match &self.filter {
+                Some(filter) => scope.evaluate(filter, document)? == Some(Value::Bool(true)),
+                None => true,
+            };
+            if keep {
+                rows.push(document);
+            }
+        }
+
+        if self.is_aggregate() {
+            return Ok(vec![self.aggregate(&scope, &rows)?]);
+        }
+
+        if !self.order_by.is_empty() {
+            let mut keyed = Vec::with_capacity(rows.len());
+            for row in rows {
+                let keys = self
+                    .order_by
+                    .iter()
+                    .map(|(expr, _)| scope.evaluate(expr, row))
+                    .collect::<SqlResult<Vec<_>>>()?;
+                keyed.push((keys, row));
+            }
+            keyed.sort_by(|(a, _), (b, _)| {
+                for ((left, right), (_, descending)) in a.iter().zip(b).zip(&self.order_by) {
+                    let ordering = compare_for_ordering(left, right);
+                    if ordering != Ordering::Equal {
+                        return if *descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
+                        };
+                    }
+                }
+                Ordering::Equal
+            });
+            rows = keyed.into_iter().map(|(_, row)| row).collect();
+        }
+
+        let (offset, limit) = self.offset_limit.unwrap_or((0, usize::MAX));
+        let limit = limit.min(self.top.unwrap_or(usize::MAX));
+
+        let mut results = Vec::new();
+        for row in rows.into_iter().skip(offset).take(limit) {
+            match &self.selection {
+                Selection::All => results.push(row.clone()),
+                Selection::Value(expr) => {
+                    if let Some(value) = scope.evaluate(expr, row)? {
+                        results.push(value);
+                    }
+                }
+                Selection::Fields(fields) => {
+                    let mut object = Map::new();
+                    for (expr, name) in fields {
+                        if let Some(value) = scope.evaluate(expr, row)? {
+                            object.insert(name.clone(), value);
+                        }
+                    }
+                    results.push(Value::Object(object));
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    fn is_aggregate(&self) -> bool {
+        match &self.selection {
+            Selection::All => false,
+            Selection::Value(expr) => is_aggregate_call(expr),
+            Selection::Fields(fields) => fields.iter().any(|(expr, _)| is_aggregate_call(expr)),
+        }
+    }
+
+    fn aggregate(&self, scope: &Scope, rows: &[&Value]) -> SqlResult<Value> {
+        match &self.selection {
+            Selection::Value(expr) => Ok(scope.aggregate(expr, rows)?.unwrap_or(Value::Null)),
+            Selection::Fields(fields) => {
+                let mut object = Map::new();
+                for (expr, name) in fields {
+                    if let Some(value) = scope.aggregate(expr, rows)? {
+                        object.insert(name.clone(), value);
+                    }
+                }
+                Ok(Value::Object(object))
+            }
+            Selection::All => unreachable!("SELECT * is never an aggregate"),
+        }
+    }
+}
+
+impl Condition {
+    /// Parses a condition of the form `FROM <alias> WHERE <predicate>`.
+    pub fn parse(text: &str) -> SqlResult<Self> {
+        let mut parser = Parser::new(text)?;
+        parser.expect_keyword("FROM")?;
+        let alias = parser.container_alias()?;
+        parser.expect_keyword("WHERE")?;
+        let predicate = parser.expression()?;
+        parser.end()?;
+        Ok(Self { alias, predicate })
+    }
+
+    /// Returns `true` if the document matches the condition.
+    pub fn matches(&self, document: &Value) -> SqlResult<bool> {
+        let parameters = HashMap::new();
+        let scope = Scope {
+            alias: &self.alias,
+            parameters: &parameters,
+        };
+        Ok(scope.evaluate(&self.predicate, document)? == Some(Value::Bool(true)))
+    }
+}
+
+fn is_aggregate_call(expr: &Expr) -> bool {
+    matches!(expr, Expr::Call(name, _) if AGGREGATES.contains(&name.as_str()))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Parameter(String),
+    String(String),
+    Number(Number),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "!=", "<>", "<=", ">=", "||", "(", ")", "[", "]", ",", ".", "*", "+", "-", "/", "%", "=", "<",
+    ">",
+];
+
+fn tokenize(text: &str) -> SqlResult<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = text.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' || c == '@' {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            let word: String = chars[start..i].iter().collect();
+            tokens.push(if c == '@' {
+                Token::Parameter(word)
+            } else {
+                Token::Identifier(word)
+            });
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || chars[i] == '.'
+                    || chars[i] == 'e'
+                    || chars[i] == 'E'
+                    || ((chars[i] == '-' || chars[i] == '+') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let literal: String = chars[start..i].iter().collect();
+            let number = serde_json::from_str::<Number>(&literal)
+                .map_err(|_| format!("invalid number literal '{}'", literal))?;
+            tokens.push(Token::Number(number));
+        } else if c == '\'' || c == '"' {
+            i += 1;
+            let mut value = String::new();
+            loop {
+                let Some(&next) = chars.get(i) else {
+                    return Err("unterminated string literal".to_string());
+                };
+                i += 1;
+                match next {
+                    _ if next == c => break,
+                    '\\' => {
+                        let escaped = chars.get(i).copied().ok_or("unterminated string literal")?;
+                        i += 1;
+                        match escaped {
+                            'n' => value.push('\n'),
+                            'r' => value.push('\r'),
+                            't' => value.push('\t'),
+                            'b' => value.push('\x08'),
+                            'f' => value.push('\x0c'),
+                            'u' => {
+                                let hex: String = chars.iter().skip(i).take(4).collect();
+                                let code = u32::from_str_radix(&hex, 16)
+                                    .ok()
+                                    .and_then(char::from_u32)
+                                    .ok_or_else(|| {
+                                        format!("invalid escape sequence '\\u{}'", hex)
+                                    })?;
+                                value.push(code);
+                                i += 4;
+                            }
+                            other => value.push(other),
+                        }
+                    }
+                    _ => value.push(next),
+                }
+            }
+            tokens.push(Token::String(value));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or_else(|| format!("unexpected character '{}'", c))?;
+            tokens.push(Token::Symbol(symbol));
+            i += symbol.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> SqlResult<Self> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> SqlResult<Token> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or("unexpected end of query")?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn end(&self) -> SqlResult<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> SqlResult<()> {
+        if self.eat_keyword(keyword) {
+            Ok(())
+        } else {
+            Err(format!("expected {}", keyword))
+        }
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> SqlResult<()> {
+        if self.eat_symbol(symbol) {
+            Ok(())
+        } else {
+            Err(format!("expected '{}'", symbol))
+        }
+    }
+
+    fn identifier(&mut self) -> SqlResult<String> {
+        match self.next()? {
+            Token::Identifier(word) if !is_keyword(&word) => Ok(word),
+            token => Err(format!("expected an identifier, found {:?}", token)),
+        }
+    }
+
+    fn integer(&mut self) -> SqlResult<usize> {
+        match self.next()? {
+            Token::Number(n) => n
+                .as_u64()
+                .and_then(|n| usize::try_from(n).ok())
+                .ok_or_else(|| format!("expected a non-negative integer, found {}", n)),
+            token => Err(format!(
+                "expected a non-negative integer, found {:?}",
+                token
+            )),
+        }
+    }
+
+    fn select_query(&mut self) -> SqlResult<SelectQuery> {
+        self.expect_keyword("SELECT")?;
+        if self.is_keyword("DISTINCT") {
+            return Err("DISTINCT is not supported".to_string());
+        }
+        let top = if self.eat_keyword("TOP") {
+            Some(self.integer()?)
+        } else {
+            None
+        };
+
+        let selection = if self.eat_symbol("*") {
+            Selection::All
+        } else if self.eat_keyword("VALUE") {
+            Selection::Value(self.expression()?)
+        } else {
+            let mut fields = Vec::new();
+            loop {
+                let expr = self.expression()?;
+                let name = if self.eat_keyword("AS") {
+                    self.identifier()?
+                } else {
+                    match &expr {
+                        Expr::Property(_, name) => match name.as_ref() {
+                            Expr::Literal(Some(Value::String(name))) => name.clone(),
+                            _ => format!("${}", fields.len() + 1),
+                        },
+                        _ => format!("${}", fields.len() + 1),
+                    }
+                };
+                fields.push((expr, name));
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+            Selection::Fields(fields)
+        };
+
+        self.expect_keyword("FROM")?;
+        let alias = self.container_alias()?;
+        if self.is_keyword("JOIN") {
+            return Err("JOIN is not supported".to_string());
+        }
+
+        let filter = if self.eat_keyword("WHERE") {
+            Some(self.expression()?)
+        } else {
+            None
+        };
+        if self.is_keyword("GROUP") {
+            return Err("GROUP BY is not supported".to_string());
+        }
+
+        let mut order_by = Vec::new();
+        if self.eat_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            loop {
+                let expr = self.expression()?;
+                let descending = if self.eat_keyword("DESC") {
+                    true
+                } else {
+                    self.eat_keyword("ASC");
+                    false
+                };
+                order_by.push((expr, descending));
+                if !self.eat_symbol(",") {
+                    break;
+                }
+            }
+        }
+
+        let offset_limit = if self.eat_keyword("OFFSET") {
+            let offset = self.integer()?;
+            self.expect_keyword("LIMIT")?;
+            Some((offset, self.integer()?))
+        } else {
+            None
+        };
+
+        Ok(SelectQuery {
+            top,
+            selection,
+            alias,
+            filter,
+            order_by,
+            offset_limit,
+        })
+    }
+
+    /// Parses `<container> [[AS] <alias>]`, returning the name used to refer to the documents.
+    fn container_alias(&mut self) -> SqlResult<String> {
+        let container = self.identifier()?;
+        if self.eat_keyword("AS") {
+            return self.identifier();
+        }
+        match self.peek() {
+            Some(Token::Identifier(word)) if !is_keyword(word) => self.identifier(),
+            _ => Ok(container),
+        }
+    }
+
+    fn expression(&mut self) -> SqlResult<Expr> {
+        let mut left = self.and()?;
+        while self.eat_keyword("OR") {
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> SqlResult<Expr> {
+        let mut left = self.not()?;
+        while self.eat_keyword("AND") {
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(self.not()?));
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> SqlResult<Expr> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> SqlResult<Expr> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BinaryOp::Eq,
+            Some(Token::Symbol("!=" | "<>")) => BinaryOp::Ne,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::Le,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::Ge,
+            _ => {
+                let negated = self.eat_keyword("NOT");
+                let expr = if self.eat_keyword("IN") {
+                    self.expect_symbol("(")?;
+                    let list = self.list(")")?;
+                    Expr::In(Box::new(left), list)
+                } else if self.eat_keyword("BETWEEN") {
+                    let low = self.additive()?;
+                    self.expect_keyword("AND")?;
+                    let high = self.additive()?;
+                    Expr::Between(Box::new(left), Box::new(low), Box::new(high))
+                } else if negated {
+                    return Err("expected IN or BETWEEN after NOT".to_string());
+                } else {
+                    return Ok(left);
+                };
+                return Ok(if negated {
+                    Expr::Not(Box::new(expr))
+                } else {
+                    expr
+                });
+            }
+        };
+        self.position += 1;
+        let right = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> SqlResult<Expr> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Subtract,
+                Some(Token::Symbol("||")) => BinaryOp::Concat,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.multiplicative()?));
+        }
+    }
+
+    fn multiplicative(&mut self) -> SqlResult<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("*")) => BinaryOp::Multiply,
+                Some(Token::Symbol("/")) => BinaryOp::Divide,
+                Some(Token::Symbol("%")) => BinaryOp::Modulo,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+    }
+
+    fn unary(&mut self) -> SqlResult<Expr> {
+        if self.eat_symbol("-") {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        let mut expr = self.primary()?;
+        loop {
+            if self.eat_symbol(".") {
+                let name = self.identifier()?;
+                expr = Expr::Property(
+                    Box::new(expr),
+                    Box::new(Expr::Literal(Some(Value::String(name)))),
+                );
+            } else if self.eat_symbol("[") {
+                let index = self.expression()?;
+                self.expect_symbol("]")?;
+                expr = Expr::Property(Box::new(expr), Box::new(index));
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn primary(&mut self) -> SqlResult<Expr> {
+        match self.next()? {
+            Token::Number(n) => Ok(Expr::Literal(Some(Value::Number(n)))),
+            Token::String(s) => Ok(Expr::Literal(Some(Value::String(s)))),
+            Token::Parameter(name) => Ok(Expr::Parameter(name)),
+            Token::Symbol("(") => {
+                let expr = self.expression()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Token::Symbol("[") => Ok(Expr::Array(self.list("]")?)),
+            Token::Identifier(word) => match word.to_ascii_uppercase().as_str() {
+                "TRUE" => Ok(Expr::Literal(Some(Value::Bool(true)))),
+                "FALSE" => Ok(Expr::Literal(Some(Value::Bool(false)))),
+                "NULL" => Ok(Expr::Literal(Some(Value::Null))),
+                "UNDEFINED" => Ok(Expr::Literal(None)),
+                _ if self.eat_symbol("(") => {
+                    let arguments = self.list(")")?;
+                    Ok(Expr::Call(word.to_ascii_uppercase(), arguments))
+                }
+                _ if is_keyword(&word) => Err(format!("unexpected keyword {}", word)),
+                _ => Ok(Expr::Identifier(word)),
+            },
+            token => Err(format!("unexpected {:?}", token)),
+        }
+    }
+
+    /// Parses a comma-separated list of expressions, up to and including the `close` symbol.
+    fn list(&mut self, close: &str) -> SqlResult<Vec<Expr>> {
+        let mut items = Vec::new();
+        if self.eat_symbol(close) {
+            return Ok(items);
+        }
+        loop {
+            items.push(self.expression()?);
+            if self.eat_symbol(close) {
+                return Ok(items);
+            }
+            self.expect_symbol(",")?;
+        }
+    }
+}
+
+fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+struct Scope<'a> {
+    alias: &'a str,
+    parameters: &'a HashMap<String, Value>,
+}
+
+impl Scope<'_> {
+    fn evaluate(&self, expr: &Expr, document: &Value) -> SqlResult<Option<Value>> {
+        Ok(match expr {
+            Expr::Literal(value) => value.clone(),
+            Expr::Parameter(name) => Some(
+                self.parameters
+                    .get(name)
+                    .cloned()
+                    .ok_or_else(|| format!("parameter {} is not defined", name))?,
+            ),
+            Expr::Identifier(name) if name == self.alias => Some(document.clone()),
+            Expr::Identifier(name) => {
+                return Err(format!("identifier '{}' could not be resolved", name))
+            }
+            Expr::Array(items) => {
+                let mut values = Vec::with_capacity(items.len());
+                for item in items {
+                    if let Some(value) = self.evaluate(item, document)? {
+                        values.push(value);
+                    }
+                }
+                Some(Value::Array(values))
+            }
+            Expr::Property(target, key) => {
+                let target = self.evaluate(target, document)?;
+                let key = self.evaluate(key, document)?;
+                match (target, key) {
+                    (Some(Value::Object(mut object)), Some(Value::String(key))) => {
+                        object.remove(&key)
+                    }
+                    (Some(Value::Array(mut array)), Some(Value::Number(index))) => index
+                        .as_u64()
+                        .and_then(|i| usize::try_from(i).ok())
+                        .filter(|i| *i < array.len())
+                        .map(|i| array.swap_remove(i)),
+                    _ => None,
+                }
+            }
+            Expr::Not(operand) => match self.evaluate(operand, document)? {
+                Some(Value::Bool(b)) => Some(Value::Bool(!b)),
+                _ => None,
+            },
+            Expr::Negate(operand) => match self.evaluate(operand, document)? {
+                Some(Value::Number(n)) => number(-n.as_f64().unwrap_or_default()),
+                _ => None,
+            },
+            Expr::Binary(BinaryOp::And, left, right) => {
+                let left = self.evaluate(left, document)?;
+                let right = self.evaluate(right, document)?;
+                match (left, right) {
+                    (Some(Value::Bool(false)), _) | (_, Some(Value::Bool(false))) => {
+                        Some(Value::Bool(false))
+                    }
+                    (Some(Value::Bool(true)), Some(Value::Bool(true))) => Some(Value::Bool(true)),
+                    _ => None,
+                }
+            }
+            Expr::Binary(BinaryOp::Or, left, right) => {
+                let left = self.evaluate(left, document)?;
+                let right = self.evaluate(right, document)?;
+                match (left, right) {
+                    (Some(Value::Bool(true)), _) | (_, Some(Value::Bool(true))) => {
+                        Some(Value::Bool(true))
+                    }
+                    (Some(Value::Bool(false)), Some(Value::Bool(false))) => {
+                        Some(Value::Bool(false))
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                let left = self.evaluate(left, document)?;
+                let right = self.evaluate(right, document)?;
+                binary(*op, left, right)
+            }
+            Expr::In(value, list) => {
+                let value = self.evaluate(value, document)?;
+                let mut found = false;
+                for item in list {
+                    let item = self.evaluate(item, document)?;
+                    if binary(BinaryOp::Eq, value.clone(), item) == Some(Value::Bool(true)) {
+                        found = true;
+                        break;
+                    }
+                }
+                value.map(|_| Value::Bool(found))
+            }
+            Expr::Between(value, low, high) => {
+                let value = self.evaluate(value, document)?;
+                let low = binary(BinaryOp::Ge, value.clone(), self.evaluate(low, document)?);
+                let high = binary(BinaryOp::Le, value, self.evaluate(high, document)?);
+                match (low, high) {
+                    (Some(Value::Bool(low)), Some(Value::Bool(high))) => {
+                        Some(Value::Bool(low && high))
+                    }
+                    _ => None,
+                }
+            }
+            Expr::Call(name, _) if AGGREGATES.contains(&name.as_str()) => {
+                return Err(format!(
+                    "the aggregate function {} is only supported in the SELECT clause",
+                    name
+                ))
+            }
+            Expr::Call(name, arguments) => {
+                let arguments = arguments
+                    .iter()
+                    .map(|a| self.evaluate(a, document))
+                    .collect::<SqlResult<Vec<_>>>()?;
+                call(name, arguments)?
+            }
+        })
+    }
+
+    /// Evaluates an aggregate function call over all the rows.
+    fn aggregate(&self, expr: &Expr, rows: &[&Value]) -> SqlResult<Option<Value>> {
+        let Expr::Call(name, arguments) = expr else {
+            return Err("cannot mix aggregate and non-aggregate expressions in SELECT".to_string());
+        };
+        let [argument] = arguments.as_slice() else {
+            return Err(format!("{} expects a single argument", name));
+        };
+        let mut values = Vec::new();
+        for row in rows {
+            if let Some(value) = self.evaluate(argument, row)? {
+                values.push(value);
+            }
+        }
+        let numbers = || values.iter().filter_map(Value::as_f64);
+        Ok(match name.as_str() {
+            "COUNT" => Some(Value::from(values.len())),
+            "SUM" => number(numbers().sum()),
+            "AVG" if values.is_empty() => None,
+            "AVG" => number(numbers().sum::<f64>() / values.len() as f64),
+            "MIN" => values
+                .into_iter()
+                .map(Some)
+                .min_by(compare_for_ordering)
+                .flatten(),
+            "MAX" => values
+                .into_iter()
+                .map(Some)
+                .max_by(compare_for_ordering)
+                .flatten(),
+            _ => return Err(format!("{} is not an aggregate function", name)),
+        })
+    }
+}
+
+/// Converts the result of an arithmetic operation into a JSON number, preferring integers when the value is integral.
+fn number(value: f64) -> Option<Value> {
+    if value.fract() == 0.0 && value.abs() < (i64::MAX as f64) {
+        Some(Value::from(value as i64))
+    } else {
+        Number::from_f64(value).map(Value::Number)
+    }
+}
+
+fn binary(op: BinaryOp, left: Option<Value>, right: Option<Value>) -> Option<Value> {
+    let (left, right) = (left?, right?);
+    match op {
+        BinaryOp::Eq | BinaryOp::Ne => {
+            let equal = match (&left, &right) {
+                (Value::Number(a), Value::Number(b)) => a.as_f64() == b.as_f64(),
+                (Value::Null, Value::Null) => true,
+                (Value::Bool(a), Value::Bool(b)) => a == b,
+                (Value::String(a), Value::String(b)) => a == b,
+                (Value::Array(_), Value::Array(_)) | (Value::Object(_), Value::Object(_)) => {
+                    left == right
+                }
+                _ => return None,
+            };
+            Some(Value::Bool(equal == (op == BinaryOp::Eq)))
+        }
+        BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => {
+            let ordering = match (&left, &right) {
+                (Value::Number(a), Value::Number(b)) => a.as_f64()?.partial_cmp(&b.as_f64()?)?,
+                (Value::String(a), Value::String(b)) => a.cmp(b),
+                (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+                _ => return None,
+            };
+            Some(Value::Bool(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::Le => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            }))
+        }
+        BinaryOp::Concat => match (left, right) {
+            (Value::String(a), Value::String(b)) => Some(Value::String(a + &b)),
+            _ => None,
+        },
+        BinaryOp::Add
+        | BinaryOp::Subtract
+        | BinaryOp::Multiply
+        | BinaryOp::Divide
+        | BinaryOp::Modulo => {
+            let (a, b) = (left.as_f64()?, right.as_f64()?);
+            number(match op {
+                BinaryOp::Add => a + b,
+                BinaryOp::Subtract => a - b,
+                BinaryOp::Multiply => a * b,
+                BinaryOp::Divide => a / b,
+                _ => a % b,
+            })
+        }
+        BinaryOp::And | BinaryOp::Or => unreachable!("logical operators are evaluated lazily"),
+    }
+}
+
+fn call(name: &str, arguments: Vec<Option<Value>>) -> SqlResult<Option<Value>> {
+    let argument = |i: usize| arguments.get(i).cloned().flatten();
+    let string = |i: usize| match argument(i) {
+        Some(Value::String(s)) => Some(s),
+        _ => None,
+    };
+    let flag = |i: usize| matches!(argument(i), Some(Value::Bool(true)));
+    let expect = |min: usize, max: usize| {
+        if arguments.len() < min || arguments.len() > max {
+            Err(format!(
+                "{} expects between {} and {} arguments",
+                name, min, max
+            ))
+        } else {
+            Ok(())
+        }
+    };
+
+    Ok(match name {
+        "IS_DEFINED" | "IS_NULL" | "IS_STRING" | "IS_NUMBER" | "IS_BOOL" | "IS_ARRAY"
+        | "IS_OBJECT" | "IS_PRIMITIVE" => {
+            expect(1, 1)?;
+            let value = argument(0);
+            Some(Value::Bool(match name {
+                "IS_DEFINED" => value.is_some(),
+                "IS_NULL" => value == Some(Value::Null),
+                "IS_STRING" => matches!(value, Some(Value::String(_))),
+                "IS_NUMBER" => matches!(value, Some(Value::Number(_))),
+                "IS_BOOL" => matches!(value, Some(Value::Bool(_))),
+                "IS_ARRAY" => matches!(value, Some(Value::Array(_))),
+                "IS_OBJECT" => matches!(value, Some(Value::Object(_))),
+                _ => matches!(
+                    value,
+                    Some(Value::Null | Value::Bool(_) | Value::Number(_) | Value::String(_))
+                ),
+            }))
+        }
+        "ARRAY_CONTAINS" => {
+            expect(2, 3)?;
+            let partial = flag(2);
+            match (argument(0), argument(1)) {
+                (Some(Value::Array(items)), Some(needle)) => {
+                    Some(Value::Bool(items.iter().any(|item| {
+                        if partial {
+                            is_subset(&needle, item)
+                        } else {
+                            binary(BinaryOp::Eq, Some(item.clone()), Some(needle.clone()))
+                                == Some(Value::Bool(true))
+                        }
+                    })))
+                }
+                _ => None,
+            }
+        }
+        "ARRAY_LENGTH" => {
+            expect(1, 1)?;
+            match argument(0) {
+                Some(Value::Array(items)) => Some(Value::from(items.len())),
+                _ => None,
+            }
+        }
+        "LENGTH" => {
+            expect(1, 1)?;
+            string(0).map(|s| Value::from(s.chars().count()))
+        }
+        "LOWER" | "UPPER" => {
+            expect(1, 1)?;
+            string(0).map(|s| {
+                Value::String(if name == "LOWER" {
+                    s.to_lowercase()
+                } else {
+                    s.to_uppercase()
+                })
+            })
+        }
+        "CONTAINS" | "STARTSWITH" | "ENDSWITH" => {
+            expect(2, 3)?;
+            match (string(0), string(1)) {
+                (Some(mut value), Some(mut pattern)) => {
+                    if flag(2) {
+                        value = value.to_lowercase();
+                        pattern = pattern.to_lowercase();
+                    }
+                    Some(Value::Bool(match name {
+                        "CONTAINS" => value.contains(&pattern),
+                        "STARTSWITH" => value.starts_with(&pattern),
+                        _ => value.ends_with(&pattern),
+                    }))
+                }
+                _ => None,
+            }
+        }
+        "CONCAT" => {
+            let mut result = String::new();
+            for i in 0..arguments.len() {
+                result.push_str(&string(i).ok_or("CONCAT expects string arguments")?);
+            }
+            Some(Value::String(result))
+        }
+        "SUBSTRING" => {
+            expect(3, 3)?;
+            let start = argument(1).and_then(|v| v.as_u64());
+            let length = argument(2).and_then(|v| v.as_u64());
+            match (string(0), start, length) {
+                (Some(s), Some(start), Some(length)) => Some(Value::String(
+                    s.chars()
+                        .skip(start as usize)
+                        .take(length as usize)
+                        .collect(),
+                )),
+                _ => None,
+            }
+        }
+        "ABS" | "FLOOR" | "CEILING" | "ROUND" => {
+            expect(1, 1)?;
+            argument(0).and_then(|v| v.as_f64()).and_then(|n| {
+                number(match name {
+                    "ABS" => n.abs(),
+                    "FLOOR" => n.floor(),
+                    "CEILING" => n.ceil(),
+                    _ => n.round(),
+                })
+            })
+        }
+        _ => return Err(format!("the function {} is not supported", name)),
+    })
+}
+
+/// Returns `true` if every property of `subset` has the same value in `value`, as used by partial `ARRAY_CONTAINS` matches.
+fn is_subset(subset: &Value, value: &Value) -> bool {
+    match (subset, value) {
+        (Value::Object(subset), Value::Object(value)) => subset
+            .iter()
+            .all(|(k, v)| value.get(k).is_some_and(|other| is_subset(v, other))),
+        _ => subset == value,
+    }
+}
+
+/// Orders values as Cosmos DB does in `ORDER BY`: undefined, null, booleans, numbers, strings, arrays and then objects.
+fn compare_for_ordering(left: &Option<Value>, right: &Option<Value>) -> Ordering {
+    fn rank(value: &Option<Value>) -> u8 {
+        match value {
+            None => 0,
+            Some(Value::Null) => 1,
+            Some(Value::Bool(_)) => 2,
+            Some(Value::Number(_)) => 3,
+            Some(Value::String(_)) => 4,
+            Some(Value::Array(_)) => 5,
+            Some(Value::Object(_)) => 6,
+        }
+    }
+    match (left, right) {
+        (Some(Value::Bool(a)), Some(Value::Bool(b))) => a.cmp(b),
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a
+            .as_f64()
+            .partial_cmp(&b.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Some(Value::String(a)), Some(Value::String(b))) => a.cmp(b),
+        _ => rank(left).cmp(&rank(right)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::{json, Value};
+
+    use super::{Condition, SelectQuery};
+
+    fn documents() -> Vec<Value> {
+        vec![
+            json!({"id": "1", "name": "Widget", "price": 10.5, "tags": ["a", "b"], "address": {"city": "Oslo"}}),
+            json!({"id": "2", "name": "gadget", "price": 3, "tags": ["b"], "discontinued": true}),
+            json!({"id": "3", "name": "Gizmo", "price": 7, "tags": [], "address": {"city": "Lima"}}),
+            json!({"id": "4", "name": "Doohickey", "price": "unknown"}),
+        ]
+    }
+
+    fn run(query: &str, parameters: Value) -> Vec<Value> {
+        let parameters: HashMap<String, Value> = serde_json::from_value(parameters).unwrap();
+        SelectQuery::parse(query)
+            .unwrap()
+            .execute(&documents(), &parameters)
+            .unwrap()
+    }
+
+    #[test]
+    pub fn filters_and_projects() {
+        assert_eq!(
+            vec![json!("1"), json!("3")],
+            run(
+                "SELECT VALUE c.id FROM c WHERE c.price > @min AND NOT IS_DEFINED(c.discontinued)",
+                json!({"@min": 5})
+            )
+        );
+        assert_eq!(
+            vec![
+                json!({"id": "1", "city": "Oslo"}),
+                json!({"id": "3", "city": "Lima"})
+            ],
+            run(
+                r#"SELECT r.id, r["address"].city FROM root r WHERE ARRAY_LENGTH(r.tags) >= 0 AND IS_DEFINED(r.address)"#,
+                json!({})
+            )
+        );
+        assert_eq!(
+            vec![json!("2")],
+            run(
+                "select value c.id from c where startswith(lower(c.name), 'ga') or c.id in ('9')",
+                json!({})
+            )
+        );
+        assert_eq!(
+            vec![json!("1"), json!("2")],
+            run(
+                "SELECT VALUE c.id FROM c WHERE ARRAY_CONTAINS(c.tags, @tag)",
+                json!({"@tag": "b"})
+            )
+        );
+    }
+
+    #[test]
+    pub fn orders_and_pages() {
+        assert_eq!(
+            vec![json!("4"), json!("1"), json!("3")],
+            run(
+                "SELECT VALUE c.id FROM c ORDER BY c.price DESC OFFSET 0 LIMIT 3",
+                json!({})
+            )
+        );
+        assert_eq!(
+            vec![json!("Gizmo")],
+            run(
+                "SELECT TOP 1 VALUE c.name FROM c WHERE IS_NUMBER(c.price) ORDER BY c.price ASC OFFSET 1 LIMIT 5",
+                json!({})
+            )
+        );
+    }
+
+    #[test]
+    pub fn aggregates() {
+        assert_eq!(
+            vec![json!(4)],
+            run("SELECT VALUE COUNT(1) FROM c", json!({}))
+        );
+        assert_eq!(
+            vec![json!({"total": 20.5, "cheapest": 3})],
+            run(
+                "SELECT SUM(c.price) AS total, MIN(c.price) AS cheapest FROM c WHERE IS_NUMBER(c.price)",
+                json!({})
+            )
+        );
+    }
+
+    #[test]
+    pub fn rejects_unsupported_queries() {
+        for query in [
+            "SELECT DISTINCT c.id FROM c",
+            "SELECT * FROM c JOIN t IN c.tags",
+            "SELECT * FROM c WHERE",
+            "SELECT * FROM c WHERE FOO(c.id)",
+            "SELECT * FROM c WHERE c.id = 'unterminated",
+        ] {
+            let result =
+                SelectQuery::parse(query).and_then(|q| q.execute(&documents(), &HashMap::new()));
+            assert!(result.is_err(), "{}", query);
+        }
+        let result = SelectQuery::parse("SELECT * FROM c WHERE d.id = '1'")
+            .unwrap()
+            .execute(&documents(), &HashMap::new());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    pub fn conditions() {
+        let condition = Condition::parse("from c where c.price BETWEEN 5 AND 11").unwrap();
+        let matches: Vec<_> = documents()
+            .iter()
+            .map(|d| condition.matches(d).unwrap())
+            .collect();
+        assert_eq!(vec![true, false, true, false], matches);
+    }
+}
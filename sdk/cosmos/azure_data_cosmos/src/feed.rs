@@ -1,7 +1,9 @@
+use std::sync::{Mutex, PoisonError};
+
 use azure_core::http::{headers::Headers, PageStream, PagerResult, Response};
 use serde::{de::DeserializeOwned, Deserialize};
 
-use crate::constants;
+use crate::{constants, CosmosDiagnostics};
 
 /// Represents a single page of results from a Cosmos DB feed.
 ///
@@ -21,6 +23,12 @@ pub struct FeedPage<T> {
     /// Response headers from the server for this page of results.
     /// In a cross-partition query, these headers may be missing on some pages.
     headers: Headers,
+
+    /// The diagnostics of this page.
+    diagnostics: CosmosDiagnostics,
+
+    /// The diagnostics of this page and all the previous pages of the feed.
+    total_diagnostics: CosmosDiagnostics,
 }
 
 impl<T> FeedPage<T> {
@@ -50,6 +58,35 @@ impl<T> FeedPage<T> {
     pub fn headers(&self) -> &Headers {
         &self.headers
     }
+
+    /// Gets the diagnostics (such as the request charge) of this page of results.
+    pub fn diagnostics(&self) -> CosmosDiagnostics {
+        self.diagnostics.clone()
+    }
+
+    /// Gets the diagnostics of this page of results and all the previous pages returned by the same [`FeedPager`], aggregated using [`CosmosDiagnostics::merge`].
+    ///
+    /// The total diagnostics of the last page describe the cost of the entire query.
+    pub fn total_diagnostics(&self) -> &CosmosDiagnostics {
+        &self.total_diagnostics
+    }
+
+    /// Replaces the continuation token of this page, for feeds which track their progress across several requests.
+    pub(crate) fn set_continuation(&mut self, continuation: Option<String>) {
+        self.continuation = continuation;
+    }
+
+    /// Replaces the diagnostics of this page with the diagnostics recorded while it was read, which include the retry count.
+    pub(crate) fn set_diagnostics(&mut self, diagnostics: CosmosDiagnostics) {
+        self.diagnostics = diagnostics;
+    }
+
+    /// Adds the diagnostics of this page to the running totals of its feed, and records the new totals on this page.
+    pub(crate) fn accumulate_diagnostics(&mut self, totals: &Mutex<CosmosDiagnostics>) {
+        let mut totals = totals.lock().unwrap_or_else(PoisonError::into_inner);
+        totals.merge(&self.diagnostics);
+        self.total_diagnostics = totals.clone();
+    }
 }
 
 impl<T> From<FeedPage<T>> for PagerResult<FeedPage<T>, String> {
@@ -71,6 +108,13 @@ struct FeedBody<T> {
     #[serde(alias = "DocumentCollections")]
     #[serde(alias = "Databases")]
     #[serde(alias = "Offers")]
+    #[serde(alias = "StoredProcedures")]
+    #[serde(alias = "Triggers")]
+    #[serde(alias = "UserDefinedFunctions")]
+    #[serde(alias = "Users")]
+    #[serde(alias = "Permissions")]
+    #[serde(alias = "PartitionKeyRanges")]
+    #[serde(alias = "Conflicts")]
     items: Vec<T>,
 }
 
@@ -80,10 +124,13 @@ impl<T: DeserializeOwned> FeedPage<T> {
         let continuation = headers.get_optional_string(&constants::CONTINUATION);
         let body: FeedBody<T> = response.into_json_body::<FeedBody<T>>().await?;
 
+        let diagnostics = CosmosDiagnostics::from_headers(&headers);
         Ok(Self {
             items: body.items,
             continuation,
             headers,
+            total_diagnostics: diagnostics.clone(),
+            diagnostics,
         })
     }
 }
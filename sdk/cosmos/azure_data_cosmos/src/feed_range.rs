@@ -0,0 +1,153 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use std::{fmt, str::FromStr};
+
+use azure_core::error::{Error, ErrorKind};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The lowest effective partition key, the start of the first partition key range of every container.
+pub(crate) const MIN_EFFECTIVE_PARTITION_KEY: &str = "";
+
+/// An effective partition key greater than every hashed partition key, the end of the last partition key range of every container.
+pub(crate) const MAX_EFFECTIVE_PARTITION_KEY: &str = "FF";
+
+/// A range of effective partition keys within a container.
+///
+/// Each item in a container is assigned an effective partition key, by hashing its partition key (see [`PartitionKey::effective_partition_key()`](crate::PartitionKey::effective_partition_key())).
+/// Feed ranges are used to split the work of reading a container between several workers:
+/// [`ContainerClient::read_feed_ranges()`](crate::clients::ContainerClient::read_feed_ranges()) returns one feed range for each physical partition of the container,
+/// and [`QueryPartitionStrategy::FeedRange`](crate::QueryPartitionStrategy::FeedRange) scopes a query to the items within a single feed range.
+///
+/// Feed ranges can be serialized (using serde, or [`Display`](std::fmt::Display) and [`FromStr`]) to hand them to other processes.
+/// The serialized form is the same one used by the other Cosmos DB SDKs.
+///
+/// ```rust
+/// # use azure_data_cosmos::FeedRange;
+/// let range = FeedRange::new("", "05C1DFFFFFFFFC");
+/// let serialized = range.to_string();
+/// assert_eq!(r#"{"Range":{"min":"","max":"05C1DFFFFFFFFC"}}"#, serialized);
+/// assert_eq!(range, serialized.parse().unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FeedRange {
+    min_inclusive: String,
+    max_exclusive: String,
+}
+
+impl FeedRange {
+    /// Creates a new [`FeedRange`] covering the effective partition keys from `min_inclusive` up to, but not including, `max_exclusive`.
+    pub fn new(min_inclusive: impl Into<String>, max_exclusive: impl Into<String>) -> Self {
+        Self {
+            min_inclusive: min_inclusive.into(),
+            max_exclusive: max_exclusive.into(),
+        }
+    }
+
+    /// Creates a [`FeedRange`] covering every effective partition key, and so every item in a container.
+    pub fn full() -> Self {
+        Self::new(MIN_EFFECTIVE_PARTITION_KEY, MAX_EFFECTIVE_PARTITION_KEY)
+    }
+
+    /// Gets the lowest effective partition key in the range.
+    pub fn min_inclusive(&self) -> &str {
+        &self.min_inclusive
+    }
+
+    /// Gets the effective partition key immediately after the end of the range.
+    pub fn max_exclusive(&self) -> &str {
+        &self.max_exclusive
+    }
+
+    /// Returns `true` if the range contains the provided effective partition key.
+    pub fn contains(&self, effective_partition_key: &str) -> bool {
+        self.min_inclusive.as_str() <= effective_partition_key
+            && effective_partition_key < self.max_exclusive.as_str()
+    }
+
+    /// Returns `true` if the range shares at least one effective partition key with `other`.
+    pub fn overlaps(&self, other: &FeedRange) -> bool {
+        self.min_inclusive < other.max_exclusive && other.min_inclusive < self.max_exclusive
+    }
+}
+
+/// The serialized form of a [`FeedRange`], which wraps the range in a `Range` object.
+#[derive(Deserialize, Serialize)]
+struct SerializedFeedRange {
+    #[serde(rename = "Range")]
+    range: SerializedRange,
+}
+
+#[derive(Deserialize, Serialize)]
+struct SerializedRange {
+    min: String,
+    max: String,
+}
+
+impl Serialize for FeedRange {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SerializedFeedRange {
+            range: SerializedRange {
+                min: self.min_inclusive.clone(),
+                max: self.max_exclusive.clone(),
+            },
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FeedRange {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let serialized = SerializedFeedRange::deserialize(deserializer)?;
+        Ok(FeedRange::new(serialized.range.min, serialized.range.max))
+    }
+}
+
+impl fmt::Display for FeedRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl FromStr for FeedRange {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(|e| {
+            Error::full(
+                ErrorKind::DataConversion,
+                e,
+                format!("invalid feed range: {}", s),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FeedRange;
+
+    #[test]
+    pub fn deserialize_with_inclusivity() {
+        let range: FeedRange = serde_json::from_str(
+            r#"{"Range":{"min":"05C1DFFFFFFFFC","max":"FF","isMinInclusive":true,"isMaxInclusive":false}}"#,
+        )
+        .unwrap();
+        assert_eq!(FeedRange::new("05C1DFFFFFFFFC", "FF"), range);
+        assert!("not a feed range".parse::<FeedRange>().is_err());
+    }
+
+    #[test]
+    pub fn contains_and_overlaps() {
+        let first = FeedRange::new("", "3F");
+        let second = FeedRange::new("3F", "FF");
+        assert!(first.contains(""));
+        assert!(first.contains("22E342F38A486A088463DFF7838A5963"));
+        assert!(!first.contains("3F"));
+        assert!(second.contains("3F"));
+        assert!(!first.overlaps(&second));
+        assert!(FeedRange::full().overlaps(&first));
+        assert!(FeedRange::new("20", "40").overlaps(&second));
+    }
+}
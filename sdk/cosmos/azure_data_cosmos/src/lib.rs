@@ -12,12 +12,25 @@
 
 pub mod clients;
 pub mod constants;
+mod diagnostics;
+mod error;
+#[cfg(feature = "fake")]
+pub mod fake;
 mod feed;
+mod feed_range;
+mod location_cache;
 mod options;
 mod partition_key;
+mod partition_key_range_cache;
 pub(crate) mod pipeline;
 mod query;
+pub mod query_builder;
 pub(crate) mod resource_context;
+#[cfg(feature = "key_auth")]
+mod resource_token;
+mod search;
+mod session;
+mod throughput_poller;
 pub(crate) mod utils;
 
 pub mod models;
@@ -25,8 +38,15 @@ pub mod models;
 #[doc(inline)]
 pub use clients::CosmosClient;
 
+pub use diagnostics::*;
+pub use error::CosmosError;
 pub use options::*;
 pub use partition_key::*;
 pub use query::*;
+#[cfg(feature = "key_auth")]
+pub use resource_token::ResourceTokenResolver;
+pub use session::SessionToken;
 
 pub use feed::{FeedPage, FeedPager};
+pub use feed_range::FeedRange;
+pub use throughput_poller::ThroughputPoller;
@@ -0,0 +1,388 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Tracks the regional endpoints of a Cosmos DB account and their availability.
+
+use std::{
+    collections::HashMap,
+    sync::{PoisonError, RwLock},
+    time::Duration,
+};
+
+use azure_core::{
+    date::OffsetDateTime,
+    http::{response::Model, Url},
+};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+/// How long an endpoint is considered unavailable after a failure, before we try it again.
+const UNAVAILABLE_ENDPOINT_EXPIRATION: Duration = Duration::from_secs(5 * 60);
+
+/// The default interval between refreshes of the account topology.
+pub(crate) const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// The properties of a Cosmos DB database account, as returned by a `GET` on the account endpoint.
+///
+/// We only deserialize the properties needed to route requests.
+#[derive(Model, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AccountProperties {
+    #[serde(default)]
+    pub writable_locations: Vec<AccountRegion>,
+
+    #[serde(default)]
+    pub readable_locations: Vec<AccountRegion>,
+
+    #[serde(default)]
+    pub enable_multiple_write_locations: bool,
+}
+
+/// A region in which a Cosmos DB account is available.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub(crate) struct AccountRegion {
+    pub name: String,
+
+    #[serde(rename = "databaseAccountEndpoint")]
+    pub endpoint: String,
+}
+
+#[derive(Debug, Default)]
+struct LocationState {
+    /// Regional endpoints accepting writes, in the order we should try them.
+    write_endpoints: Vec<Url>,
+
+    /// Regional endpoints accepting reads, in the order we should try them.
+    read_endpoints: Vec<Url>,
+
+    /// Endpoints that failed recently, and when they failed.
+    unavailable_endpoints: HashMap<Url, OffsetDateTime>,
+
+    /// When the account topology was last refreshed, if it ever was.
+    last_refresh: Option<OffsetDateTime>,
+
+    /// Set when a failure indicates the topology may have changed (for example, after a failover of the write region).
+    refresh_requested: bool,
+}
+
+/// Maintains the list of regional endpoints for an account, ordered by the user's preferred regions, and tracks which of them are unavailable.
+#[derive(Debug)]
+pub(crate) struct LocationCache {
+    default_endpoint: Url,
+    preferred_regions: Vec<String>,
+    refresh_interval: Duration,
+    state: RwLock<LocationState>,
+}
+
+/// Normalizes a region name so that "West US 2", "westus2" and "WestUS2" are all considered equal.
+fn normalize_region(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn elapsed_since(time: OffsetDateTime) -> Duration {
+    (OffsetDateTime::now_utc() - time)
+        .try_into()
+        .unwrap_or_default()
+}
+
+impl LocationCache {
+    pub fn new(
+        default_endpoint: Url,
+        preferred_regions: &[String],
+        refresh_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            default_endpoint,
+            preferred_regions: preferred_regions
+                .iter()
+                .map(|r| normalize_region(r))
+                .collect(),
+            refresh_interval: refresh_interval.unwrap_or(DEFAULT_REFRESH_INTERVAL),
+            state: RwLock::new(LocationState::default()),
+        }
+    }
+
+    /// Indicates if endpoint discovery is enabled.
+    ///
+    /// Discovery is only enabled when the user has specified preferred regions.
+    /// Otherwise, all requests are sent to the default endpoint.
+    pub fn is_enabled(&self) -> bool {
+        !self.preferred_regions.is_empty()
+    }
+
+    /// Checks if the account topology should be refreshed and, if so, marks the refresh as started.
+    ///
+    /// Returns `true` if the caller should refresh the topology.
+    /// Concurrent callers will only see `true` once per refresh interval.
+    pub fn begin_refresh(&self) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        let due = match state.last_refresh {
+            None => true,
+            Some(t) => state.refresh_requested || elapsed_since(t) >= self.refresh_interval,
+        };
+        if due {
+            state.last_refresh = Some(OffsetDateTime::now_utc());
+            state.refresh_requested = false;
+        }
+        due
+    }
+
+    /// Updates the endpoints using the provided account properties.
+    pub fn update(&self, account: &AccountProperties) {
+        let write_regions = if account.enable_multiple_write_locations {
+            self.order_by_preference(&account.writable_locations)
+        } else {
+            // With a single write region, there's no choice to make. The first writable location is the current write region.
+            Self::parse_endpoints(account.writable_locations.iter())
+        };
+        let read_regions = self.order_by_preference(&account.readable_locations);
+        debug!(
+            ?write_regions,
+            ?read_regions,
+            "refreshed Cosmos DB account endpoints"
+        );
+
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        state.write_endpoints = write_regions;
+        state.read_endpoints = read_regions;
+    }
+
+    fn order_by_preference(&self, regions: &[AccountRegion]) -> Vec<Url> {
+        let by_name: HashMap<String, &AccountRegion> = regions
+            .iter()
+            .map(|r| (normalize_region(&r.name), r))
+            .collect();
+        Self::parse_endpoints(
+            self.preferred_regions
+                .iter()
+                .filter_map(|name| by_name.get(name).copied()),
+        )
+    }
+
+    fn parse_endpoints<'a>(regions: impl Iterator<Item = &'a AccountRegion>) -> Vec<Url> {
+        regions
+            .filter_map(|r| match Url::parse(&r.endpoint) {
+                Ok(url) => Some(url),
+                Err(e) => {
+                    warn!(region = %r.name, "ignoring invalid regional endpoint: {}", e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Gets the endpoints to try for a request, in order.
+    ///
+    /// Available endpoints come first, in order of preference, followed by endpoints that failed recently, and finally the default endpoint.
+    pub fn endpoints(&self, is_write: bool) -> Vec<Url> {
+        let state = self.state.read().unwrap_or_else(PoisonError::into_inner);
+        let candidates = if is_write {
+            &state.write_endpoints
+        } else {
+            &state.read_endpoints
+        };
+
+        let is_unavailable = |url: &Url| {
+            state
+                .unavailable_endpoints
+                .get(url)
+                .is_some_and(|t| elapsed_since(*t) < UNAVAILABLE_ENDPOINT_EXPIRATION)
+        };
+        let (mut endpoints, unavailable): (Vec<Url>, Vec<Url>) =
+            candidates.iter().cloned().partition(|u| !is_unavailable(u));
+        endpoints.extend(unavailable);
+        if !endpoints.contains(&self.default_endpoint) {
+            endpoints.push(self.default_endpoint.clone());
+        }
+        endpoints
+    }
+
+    /// Marks an endpoint as unavailable, moving it to the end of the list of endpoints for a while.
+    ///
+    /// This also requests a refresh of the topology, since the failure may indicate a regional failover.
+    pub fn mark_unavailable(&self, endpoint: &Url) {
+        let mut state = self.state.write().unwrap_or_else(PoisonError::into_inner);
+        state
+            .unavailable_endpoints
+            .insert(endpoint.clone(), OffsetDateTime::now_utc());
+        state.refresh_requested = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use azure_core::http::Url;
+
+    use super::{AccountProperties, AccountRegion, LocationCache};
+
+    fn region(name: &str, endpoint: &str) -> AccountRegion {
+        AccountRegion {
+            name: name.to_string(),
+            endpoint: endpoint.to_string(),
+        }
+    }
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    fn account(multiple_write_locations: bool) -> AccountProperties {
+        AccountProperties {
+            writable_locations: vec![
+                region("East US", "https://account-eastus.documents.azure.com:443/"),
+                region(
+                    "West US 2",
+                    "https://account-westus2.documents.azure.com:443/",
+                ),
+            ],
+            readable_locations: vec![
+                region("East US", "https://account-eastus.documents.azure.com:443/"),
+                region(
+                    "West US 2",
+                    "https://account-westus2.documents.azure.com:443/",
+                ),
+                region(
+                    "North Europe",
+                    "https://account-northeurope.documents.azure.com:443/",
+                ),
+            ],
+            enable_multiple_write_locations: multiple_write_locations,
+        }
+    }
+
+    fn cache(preferred_regions: &[&str]) -> LocationCache {
+        let preferred_regions: Vec<String> =
+            preferred_regions.iter().map(|s| s.to_string()).collect();
+        LocationCache::new(
+            url("https://account.documents.azure.com/"),
+            &preferred_regions,
+            None,
+        )
+    }
+
+    #[test]
+    pub fn deserialize_account_properties() {
+        let json = r#"{
+            "id": "account",
+            "writableLocations": [{"name": "East US", "databaseAccountEndpoint": "https://account-eastus.documents.azure.com:443/"}],
+            "readableLocations": [{"name": "East US", "databaseAccountEndpoint": "https://account-eastus.documents.azure.com:443/"}],
+            "enableMultipleWriteLocations": true,
+            "userConsistencyPolicy": {"defaultConsistencyLevel": "Session"}
+        }"#;
+        let account: AccountProperties = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            vec![region(
+                "East US",
+                "https://account-eastus.documents.azure.com:443/"
+            )],
+            account.writable_locations
+        );
+        assert!(account.enable_multiple_write_locations);
+    }
+
+    #[test]
+    pub fn default_endpoint_before_refresh() {
+        let cache = cache(&["West US 2"]);
+        assert_eq!(
+            vec![url("https://account.documents.azure.com/")],
+            cache.endpoints(false)
+        );
+        assert_eq!(
+            vec![url("https://account.documents.azure.com/")],
+            cache.endpoints(true)
+        );
+    }
+
+    #[test]
+    pub fn reads_use_preferred_regions() {
+        let cache = cache(&["northeurope", "West US 2"]);
+        cache.update(&account(false));
+        assert_eq!(
+            vec![
+                url("https://account-northeurope.documents.azure.com/"),
+                url("https://account-westus2.documents.azure.com/"),
+                url("https://account.documents.azure.com/"),
+            ],
+            cache.endpoints(false)
+        );
+    }
+
+    #[test]
+    pub fn single_write_region_ignores_preferred_regions() {
+        let cache = cache(&["West US 2"]);
+        cache.update(&account(false));
+        assert_eq!(
+            vec![
+                url("https://account-eastus.documents.azure.com/"),
+                url("https://account-westus2.documents.azure.com/"),
+                url("https://account.documents.azure.com/"),
+            ],
+            cache.endpoints(true)
+        );
+    }
+
+    #[test]
+    pub fn multiple_write_regions_use_preferred_regions() {
+        let cache = cache(&["West US 2", "East US"]);
+        cache.update(&account(true));
+        assert_eq!(
+            vec![
+                url("https://account-westus2.documents.azure.com/"),
+                url("https://account-eastus.documents.azure.com/"),
+                url("https://account.documents.azure.com/"),
+            ],
+            cache.endpoints(true)
+        );
+    }
+
+    #[test]
+    pub fn unavailable_endpoints_move_to_the_end() {
+        let cache = cache(&["West US 2", "North Europe"]);
+        cache.update(&account(false));
+        cache.mark_unavailable(&url("https://account-westus2.documents.azure.com/"));
+        assert_eq!(
+            vec![
+                url("https://account-northeurope.documents.azure.com/"),
+                url("https://account-westus2.documents.azure.com/"),
+                url("https://account.documents.azure.com/"),
+            ],
+            cache.endpoints(false)
+        );
+    }
+
+    #[test]
+    pub fn refresh_is_disabled_without_preferred_regions() {
+        let cache = cache(&[]);
+        assert!(!cache.is_enabled());
+        assert!(!cache.begin_refresh());
+    }
+
+    #[test]
+    pub fn refresh_once_per_interval() {
+        let cache = cache(&["West US 2"]);
+        assert!(cache.begin_refresh());
+        assert!(!cache.begin_refresh());
+
+        // A failure requests an early refresh.
+        cache.mark_unavailable(&url("https://account-westus2.documents.azure.com/"));
+        assert!(cache.begin_refresh());
+        assert!(!cache.begin_refresh());
+
+        let cache = LocationCache::new(
+            url("https://account.documents.azure.com/"),
+            &["West US 2".to_string()],
+            Some(Duration::ZERO),
+        );
+        assert!(cache.begin_refresh());
+        assert!(cache.begin_refresh());
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use azure_core::{error::ErrorKind, http::response::Model};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::models::SystemProperties;
+
+/// Properties of a conflict in a container of an account with multiple write regions.
+///
+/// When the same item is written concurrently in several regions, and the container's [`ConflictResolutionPolicy`](crate::models::ConflictResolutionPolicy)
+/// uses [`ConflictResolutionMode::Custom`](crate::models::ConflictResolutionMode::Custom) without a resolution procedure (or the procedure fails),
+/// the losing writes are recorded in the conflicts feed of the container. They remain there until they are deleted,
+/// using [`ContainerClient::delete_conflict()`](crate::clients::ContainerClient::delete_conflict()), usually after merging them into the item.
+///
+/// Returned by [`ContainerClient::read_conflict()`](crate::clients::ContainerClient::read_conflict()) and [`ContainerClient::query_conflicts()`](crate::clients::ContainerClient::query_conflicts()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictProperties {
+    /// The ID of the conflict.
+    pub id: String,
+
+    /// The type of the conflicting resource, `document` for items.
+    #[serde(default)]
+    pub resource_type: String,
+
+    /// The operation which caused the conflict.
+    pub operation_type: ConflictOperationType,
+
+    /// The resource ID (`_rid`) of the conflicting resource.
+    #[serde(default)]
+    pub resource_id: String,
+
+    /// The logical sequence number of the conflicting write.
+    #[serde(default)]
+    #[serde(rename = "conflict_lsn")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflict_lsn: Option<i64>,
+
+    /// The conflicting version of the resource, as a JSON string.
+    ///
+    /// Use [`ConflictProperties::content()`] to deserialize it.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+
+    /// A [`SystemProperties`] object containing common system properties for the conflict.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl ConflictProperties {
+    /// Deserializes the conflicting version of the resource (usually an item), if the conflict includes it.
+    pub fn content<T: DeserializeOwned>(&self) -> azure_core::Result<Option<T>> {
+        self.content
+            .as_deref()
+            .map(|content| {
+                serde_json::from_str(content).map_err(|e| {
+                    azure_core::Error::full(
+                        ErrorKind::DataConversion,
+                        e,
+                        format!("failed to deserialize the content of conflict {}", self.id),
+                    )
+                })
+            })
+            .transpose()
+    }
+}
+
+/// The operation which caused a conflict.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictOperationType {
+    /// The resource was created concurrently in several regions.
+    #[default]
+    Create,
+
+    /// The resource was replaced (or upserted) concurrently in several regions.
+    Replace,
+
+    /// The resource was deleted in one region while being modified in another one.
+    Delete,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use crate::models::{ConflictOperationType, ConflictProperties};
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: String,
+        value: u32,
+    }
+
+    #[test]
+    pub fn deserialize_conflict() {
+        let conflict: ConflictProperties = serde_json::from_str(
+            r#"{
+                "id": "conflict1",
+                "resourceType": "document",
+                "operationType": "replace",
+                "resourceId": "AbCdAA==",
+                "conflict_lsn": 42,
+                "content": "{\"id\":\"item1\",\"value\":7,\"_etag\":\"\\\"0000\\\"\"}",
+                "_rid": "AbCdAA==AAAA",
+                "_ts": 1700000000
+            }"#,
+        )
+        .unwrap();
+        assert_eq!("conflict1", conflict.id);
+        assert_eq!(ConflictOperationType::Replace, conflict.operation_type);
+        assert_eq!(
+            Some(Item {
+                id: "item1".to_string(),
+                value: 7
+            }),
+            conflict.content::<Item>().unwrap()
+        );
+        assert!(conflict.content::<u32>().is_err());
+    }
+}
@@ -12,17 +12,27 @@ use azure_core::{
 };
 use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize};
 
+mod conflict_properties;
 mod container_properties;
 mod indexing_policy;
 mod partition_key_definition;
 mod patch_operations;
+mod read_many;
+mod script_properties;
+mod search_results;
 mod throughput_properties;
+mod user_properties;
 
+pub use conflict_properties::*;
 pub use container_properties::*;
 pub use indexing_policy::*;
 pub use partition_key_definition::*;
 pub use patch_operations::*;
+pub use read_many::*;
+pub use script_properties::*;
+pub use search_results::*;
 pub use throughput_properties::*;
+pub use user_properties::*;
 
 fn deserialize_cosmos_timestamp<'de, D>(deserializer: D) -> Result<Option<OffsetDateTime>, D::Error>
 where
@@ -33,6 +33,7 @@ use serde::{Deserialize, Serialize};
 /// #            to: "/to".into(),
 /// #        },
 /// #    ],
+/// #    condition: None,
 /// # });
 /// # Ok(())
 /// # }
@@ -40,9 +41,40 @@ use serde::{Deserialize, Serialize};
 #[derive(Model, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct PatchDocument {
     pub operations: Vec<PatchOperation>,
+
+    /// An optional filter predicate that the item must match for the patch to be applied.
+    ///
+    /// See [`PatchDocument::with_condition`] for more information.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Cow<'static, str>>,
 }
 
 impl PatchDocument {
+    /// Sets a filter predicate that must be satisfied for the patch to be applied.
+    ///
+    /// The predicate is a SQL-like filter expression, evaluated against the item, using `FROM` and the root alias of your choice.
+    /// If the item doesn't match the predicate, the service rejects the patch with a `412 Precondition Failed` status.
+    ///
+    /// # Arguments
+    /// * `condition` - The filter predicate, for example `"FROM c WHERE c.status = 'active'"`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use azure_data_cosmos::models::PatchDocument;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let patch = PatchDocument::default()
+    ///     .with_condition("FROM c WHERE c.inventory.quantity > 0")
+    ///     .with_increment("/inventory/quantity", -1)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_condition(mut self, condition: impl Into<Cow<'static, str>>) -> Self {
+        self.condition = Some(condition.into());
+        self
+    }
+
     /// Adds a new "add" operation to the patch document.
     ///
     /// See the [type documentation](PatchDocument) for more information on patch operations.
@@ -352,6 +384,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn serialize_condition() -> Result<(), Box<dyn std::error::Error>> {
+        let patch_document = PatchDocument::default()
+            .with_condition("FROM c WHERE c.value > 10")
+            .with_remove("/value")?;
+
+        let serialized = serde_json::to_string(&patch_document).unwrap();
+        assert_eq!(
+            serialized,
+            "{\"operations\":[{\"op\":\"remove\",\"path\":\"/value\"}],\"condition\":\"FROM c WHERE c.value > 10\"}"
+        );
+
+        Ok(())
+    }
+
     #[test]
     pub fn cosmos_docs_example() -> Result<(), Box<dyn std::error::Error>> {
         const TEST_DOC: &str = r#"{
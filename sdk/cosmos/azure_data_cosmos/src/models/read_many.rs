@@ -0,0 +1,77 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use std::sync::Arc;
+
+use crate::PartitionKey;
+
+/// Identifies a single item in a container, by its ID and partition key.
+///
+/// Used as input to [`ContainerClient::read_many_items()`](crate::clients::ContainerClient::read_many_items()).
+/// Any `(id, partition_key)` tuple can be converted into an `ItemReference`.
+#[derive(Clone, Debug)]
+pub struct ItemReference {
+    /// The ID of the item.
+    pub id: String,
+
+    /// The partition key of the item.
+    pub partition_key: PartitionKey,
+}
+
+impl ItemReference {
+    /// Creates a new [`ItemReference`] from an item ID and partition key.
+    pub fn new(id: impl Into<String>, partition_key: impl Into<PartitionKey>) -> Self {
+        Self {
+            id: id.into(),
+            partition_key: partition_key.into(),
+        }
+    }
+}
+
+impl<I: Into<String>, P: Into<PartitionKey>> From<(I, P)> for ItemReference {
+    fn from((id, partition_key): (I, P)) -> Self {
+        Self::new(id, partition_key)
+    }
+}
+
+/// The results of [`ContainerClient::read_many_items()`](crate::clients::ContainerClient::read_many_items()).
+///
+/// Every requested item appears in exactly one of [`items`](ReadManyResults::items), [`missing`](ReadManyResults::missing) or [`failures`](ReadManyResults::failures).
+#[non_exhaustive]
+#[derive(Debug)]
+pub struct ReadManyResults<T> {
+    /// The items that were found, in no particular order.
+    pub items: Vec<T>,
+
+    /// The requested items that don't exist.
+    pub missing: Vec<ItemReference>,
+
+    /// The requested items that couldn't be read, along with the error that occurred.
+    pub failures: Vec<ReadManyFailure>,
+
+    /// The total number of request units (RUs) consumed by all the requests made to read the items.
+    pub request_charge: f64,
+}
+
+impl<T> Default for ReadManyResults<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            missing: Vec::new(),
+            failures: Vec::new(),
+            request_charge: 0.0,
+        }
+    }
+}
+
+/// An item that couldn't be read by [`ContainerClient::read_many_items()`](crate::clients::ContainerClient::read_many_items()).
+#[derive(Debug)]
+pub struct ReadManyFailure {
+    /// The item that couldn't be read.
+    pub item: ItemReference,
+
+    /// The error that occurred.
+    ///
+    /// Items are read in batches, so the same error may be shared by several items.
+    pub error: Arc<azure_core::Error>,
+}
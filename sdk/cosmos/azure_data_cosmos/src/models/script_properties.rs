@@ -0,0 +1,234 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use std::{borrow::Cow, fmt};
+
+use azure_core::http::{headers::Headers, response::Model};
+use serde::{Deserialize, Serialize};
+
+use crate::{constants, models::SystemProperties, utils::url_decode};
+
+/// Properties of a Cosmos DB stored procedure.
+///
+/// Stored procedures are written in JavaScript, and run transactionally within a single logical partition.
+/// See <https://learn.microsoft.com/azure/cosmos-db/nosql/stored-procedures-triggers-udfs> for more information.
+///
+/// Returned by [`ContainerClient::read_stored_procedure()`](crate::clients::ContainerClient::read_stored_procedure()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredProcedureProperties {
+    /// The ID of the stored procedure.
+    pub id: Cow<'static, str>,
+
+    /// The JavaScript source of the stored procedure.
+    pub body: Cow<'static, str>,
+
+    /// A [`SystemProperties`] object containing common system properties for the stored procedure.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl StoredProcedureProperties {
+    /// Creates a new [`StoredProcedureProperties`] with the provided ID and JavaScript source.
+    pub fn new(id: impl Into<Cow<'static, str>>, body: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id: id.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// The output of the `console.log` calls made by a stored procedure.
+///
+/// The service only returns the log when [`ExecuteStoredProcedureOptions::enable_script_logging`](crate::ExecuteStoredProcedureOptions::enable_script_logging) is set.
+/// Use [`ScriptLog::from_headers`] to get it from the response of [`ContainerClient::execute_stored_procedure()`](crate::clients::ContainerClient::execute_stored_procedure()).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptLog(String);
+
+impl ScriptLog {
+    /// Gets the script log from the `x-ms-documentdb-script-log-results` header of a response, if there is one.
+    pub fn from_headers(headers: &Headers) -> Option<Self> {
+        headers
+            .get_optional_str(&constants::SCRIPT_LOG_RESULTS)
+            .map(|log| Self(url_decode(log)))
+    }
+
+    /// Gets the logged text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ScriptLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Properties of a Cosmos DB trigger.
+///
+/// Triggers are written in JavaScript and run before ([`TriggerType::Pre`]) or after ([`TriggerType::Post`]) an item operation.
+/// Triggers only run when they are requested using [`ItemOptions::pre_triggers`](crate::ItemOptions::pre_triggers) or [`ItemOptions::post_triggers`](crate::ItemOptions::post_triggers).
+///
+/// Returned by [`ContainerClient::read_trigger()`](crate::clients::ContainerClient::read_trigger()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerProperties {
+    /// The ID of the trigger.
+    pub id: Cow<'static, str>,
+
+    /// The JavaScript source of the trigger.
+    pub body: Cow<'static, str>,
+
+    /// Indicates when the trigger runs, relative to the operation.
+    pub trigger_type: TriggerType,
+
+    /// The operation(s) that can run the trigger.
+    pub trigger_operation: TriggerOperation,
+
+    /// A [`SystemProperties`] object containing common system properties for the trigger.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl TriggerProperties {
+    /// Creates a new [`TriggerProperties`] with the provided ID, JavaScript source, type and operation.
+    pub fn new(
+        id: impl Into<Cow<'static, str>>,
+        body: impl Into<Cow<'static, str>>,
+        trigger_type: TriggerType,
+        trigger_operation: TriggerOperation,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            body: body.into(),
+            trigger_type,
+            trigger_operation,
+            ..Default::default()
+        }
+    }
+}
+
+/// Indicates when a trigger runs, relative to the operation that triggers it.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum TriggerType {
+    /// The trigger runs before the operation.
+    #[default]
+    Pre,
+
+    /// The trigger runs after the operation, within the same transaction.
+    Post,
+}
+
+/// The operation(s) that can run a trigger.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub enum TriggerOperation {
+    #[default]
+    All,
+    Create,
+    Update,
+    Delete,
+    Replace,
+}
+
+/// Properties of a Cosmos DB user-defined function (UDF).
+///
+/// User-defined functions are written in JavaScript and can be called from queries, using the `udf.` prefix (for example, `SELECT udf.tax(c.price) FROM c`).
+///
+/// Returned by [`ContainerClient::read_user_defined_function()`](crate::clients::ContainerClient::read_user_defined_function()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDefinedFunctionProperties {
+    /// The ID of the user-defined function.
+    pub id: Cow<'static, str>,
+
+    /// The JavaScript source of the user-defined function.
+    pub body: Cow<'static, str>,
+
+    /// A [`SystemProperties`] object containing common system properties for the user-defined function.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl UserDefinedFunctionProperties {
+    /// Creates a new [`UserDefinedFunctionProperties`] with the provided ID and JavaScript source.
+    pub fn new(id: impl Into<Cow<'static, str>>, body: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            id: id.into(),
+            body: body.into(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use azure_core::http::headers::Headers;
+
+    use crate::{
+        constants,
+        models::{
+            ScriptLog, StoredProcedureProperties, TriggerOperation, TriggerProperties, TriggerType,
+        },
+    };
+
+    #[test]
+    pub fn serialize_stored_procedure() {
+        let sproc = StoredProcedureProperties::new("sproc1", "function () {}");
+        let json = serde_json::to_string(&sproc).unwrap();
+        assert_eq!(r#"{"id":"sproc1","body":"function () {}"}"#, json);
+    }
+
+    #[test]
+    pub fn script_log_from_headers() {
+        let mut headers = Headers::new();
+        assert_eq!(None, ScriptLog::from_headers(&headers));
+
+        headers.insert(
+            constants::SCRIPT_LOG_RESULTS,
+            "Hello%2C%20World%21%0Aitems%3A%202",
+        );
+        let log = ScriptLog::from_headers(&headers).unwrap();
+        assert_eq!("Hello, World!\nitems: 2", log.as_str());
+        assert_eq!("Hello, World!\nitems: 2", log.to_string());
+    }
+
+    #[test]
+    pub fn serialize_trigger() {
+        let trigger = TriggerProperties::new(
+            "trigger1",
+            "function () {}",
+            TriggerType::Post,
+            TriggerOperation::Create,
+        );
+        let json = serde_json::to_string(&trigger).unwrap();
+        assert_eq!(
+            r#"{"id":"trigger1","body":"function () {}","triggerType":"Post","triggerOperation":"Create"}"#,
+            json
+        );
+    }
+
+    #[test]
+    pub fn deserialize_trigger() {
+        let json = r#"{
+            "id": "trigger1",
+            "body": "function () {}",
+            "triggerType": "Pre",
+            "triggerOperation": "All",
+            "_rid": "abc",
+            "_etag": "\"0000\"",
+            "_ts": 1729036800
+        }"#;
+        let trigger: TriggerProperties = serde_json::from_str(json).unwrap();
+        assert_eq!("trigger1", trigger.id);
+        assert_eq!(TriggerType::Pre, trigger.trigger_type);
+        assert_eq!(TriggerOperation::All, trigger.trigger_operation);
+        assert_eq!(
+            Some("abc"),
+            trigger.system_properties.resource_id.as_deref()
+        );
+    }
+}
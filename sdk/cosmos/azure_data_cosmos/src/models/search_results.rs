@@ -0,0 +1,41 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+/// An item returned by [`ContainerClient::search_items()`](crate::clients::ContainerClient::search_items()), along with its score.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScoredItem<T> {
+    /// The item.
+    pub item: T,
+
+    /// The score used to rank the item.
+    ///
+    /// For a vector search (`ORDER BY VectorDistance(...)`), this is the similarity score computed by `VectorDistance`.
+    /// For a full-text search (`ORDER BY RANK FullTextScore(...)`), this is the BM25 score computed by `FullTextScore`.
+    /// For a hybrid search (`ORDER BY RANK RRF(...)`), this is the Reciprocal Rank Fusion score, combining the ranks of the item in each of the component searches.
+    pub score: f64,
+
+    /// For a full-text or hybrid search, the score of the item in each of the component searches passed to `RRF`, in order.
+    ///
+    /// This is empty for a vector search.
+    pub component_scores: Vec<f64>,
+}
+
+/// The results of [`ContainerClient::search_items()`](crate::clients::ContainerClient::search_items()).
+#[non_exhaustive]
+#[derive(Clone, Debug)]
+pub struct SearchResults<T> {
+    /// The items, ranked from best to worst match.
+    pub items: Vec<ScoredItem<T>>,
+
+    /// The total number of request units (RUs) consumed by all the requests made to execute the search.
+    pub request_charge: f64,
+}
+
+impl<T> Default for SearchResults<T> {
+    fn default() -> Self {
+        Self {
+            items: Vec::new(),
+            request_charge: 0.0,
+        }
+    }
+}
@@ -4,7 +4,7 @@
 use std::borrow::Cow;
 
 use azure_core::http::{
-    headers::{AsHeaders, HeaderName, HeaderValue},
+    headers::{AsHeaders, HeaderName, HeaderValue, Headers},
     response::Model,
 };
 use serde::{Deserialize, Serialize};
@@ -26,6 +26,12 @@ pub struct ThroughputProperties {
     offer_version: Cow<'static, str>, // When we serialize, this is always going to be a constant.
     #[serde(flatten)]
     pub(crate) system_properties: SystemProperties,
+
+    // The following values are returned in the response headers, rather than in the offer itself.
+    #[serde(skip)]
+    minimum_throughput: Option<usize>,
+    #[serde(skip)]
+    replace_pending: bool,
 }
 
 impl ThroughputProperties {
@@ -81,6 +87,52 @@ impl ThroughputProperties {
                 .increment_percent,
         )
     }
+
+    /// Returns `true` if the offer uses autoscale throughput.
+    pub fn is_autoscale(&self) -> bool {
+        self.offer.offer_autopilot_settings.is_some()
+    }
+
+    /// Gets the minimum throughput (in RU/s) the offer can currently be set to.
+    ///
+    /// The minimum depends on the storage used by the resource and on the highest throughput ever provisioned.
+    /// This is only available on offers returned by the service, when the service provides it.
+    pub fn minimum_throughput(&self) -> Option<usize> {
+        self.minimum_throughput
+    }
+
+    /// Gets the highest throughput (in RU/s) ever provisioned on the offer, if returned by the service.
+    pub fn maximum_throughput_ever_provisioned(&self) -> Option<usize> {
+        self.offer
+            .offer_minimum_throughput_parameters
+            .as_ref()?
+            .max_throughput_ever_provisioned
+    }
+
+    /// Returns `true` if a change to the throughput was still being applied by the service when the offer was read.
+    ///
+    /// Use [`ThroughputPoller`](crate::ThroughputPoller) to wait for the change to complete.
+    pub fn is_replace_pending(&self) -> bool {
+        self.replace_pending
+    }
+
+    /// Marks the offer as having a pending change, so that a [`ThroughputPoller`](crate::ThroughputPoller) reads it again.
+    pub(crate) fn set_replace_pending(&mut self) {
+        self.replace_pending = true;
+    }
+
+    /// Updates the values returned in the response headers, rather than in the offer itself.
+    pub(crate) fn apply_headers(&mut self, headers: &Headers) {
+        self.replace_pending = headers
+            .get_optional_str(&constants::OFFER_REPLACE_PENDING)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        if let Some(minimum) = headers
+            .get_optional_str(&constants::MIN_THROUGHPUT)
+            .and_then(|v| v.parse().ok())
+        {
+            self.minimum_throughput = Some(minimum);
+        }
+    }
 }
 
 impl AsHeaders for ThroughputProperties {
@@ -111,6 +163,15 @@ pub(crate) struct Offer {
     pub offer_throughput: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offer_autopilot_settings: Option<OfferAutoscaleSettings>,
+    #[serde(default, skip_serializing)]
+    pub offer_minimum_throughput_parameters: Option<OfferMinimumThroughputParameters>,
+}
+
+#[derive(Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OfferMinimumThroughputParameters {
+    #[serde(default)]
+    pub max_throughput_ever_provisioned: Option<usize>,
 }
 
 #[derive(Clone, Default, Deserialize, Serialize)]
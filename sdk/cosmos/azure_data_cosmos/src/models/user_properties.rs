@@ -0,0 +1,148 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use azure_core::{credentials::Secret, http::response::Model};
+use serde::{Deserialize, Serialize};
+
+use crate::{models::SystemProperties, PartitionKey};
+
+/// Properties of a Cosmos DB user.
+///
+/// Users are scoped to a database, and are used to group [permissions](PermissionProperties) granting access to resources in that database.
+///
+/// Returned by [`DatabaseClient::read_user()`](crate::clients::DatabaseClient::read_user()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct UserProperties {
+    /// The ID of the user.
+    pub id: String,
+
+    /// A [`SystemProperties`] object containing common system properties for the user.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl UserProperties {
+    /// Creates a new [`UserProperties`] with the provided ID.
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Properties of a Cosmos DB permission.
+///
+/// A permission grants a [user](UserProperties) access to a single resource (such as a container), or to a single logical partition of a container.
+/// When a permission is read, the service returns a resource token in [`PermissionProperties::token`], which can be handed to a client application
+/// and used with [`CosmosClient::with_resource_token()`](crate::CosmosClient::with_resource_token()).
+///
+/// Returned by [`DatabaseClient::read_permission()`](crate::clients::DatabaseClient::read_permission()).
+#[derive(Model, Clone, Default, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionProperties {
+    /// The ID of the permission.
+    pub id: String,
+
+    /// The access granted by the permission.
+    pub permission_mode: PermissionMode,
+
+    /// The link of the resource the permission grants access to, for example `dbs/MyDatabase/colls/MyContainer`.
+    pub resource: String,
+
+    /// The partition key of the logical partition the permission is scoped to, if any.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_partition_key: Option<PartitionKey>,
+
+    /// The resource token for the permission.
+    ///
+    /// This is always returned by the service, and is ignored when creating or replacing a permission.
+    #[serde(default)]
+    #[serde(rename = "_token")]
+    #[serde(skip_serializing)]
+    pub token: Option<Secret>,
+
+    /// A [`SystemProperties`] object containing common system properties for the permission.
+    #[serde(flatten)]
+    pub system_properties: SystemProperties,
+}
+
+impl PermissionProperties {
+    /// Creates a new [`PermissionProperties`] granting the provided access to a resource.
+    ///
+    /// # Arguments
+    /// * `id` - The ID of the permission.
+    /// * `permission_mode` - The access granted by the permission.
+    /// * `resource` - The link of the resource the permission grants access to, for example `dbs/MyDatabase/colls/MyContainer`.
+    pub fn new(
+        id: impl Into<String>,
+        permission_mode: PermissionMode,
+        resource: impl Into<String>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            permission_mode,
+            resource: resource.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Scopes the permission to a single logical partition of the resource.
+    pub fn with_resource_partition_key(mut self, partition_key: impl Into<PartitionKey>) -> Self {
+        self.resource_partition_key = Some(partition_key.into());
+        self
+    }
+}
+
+/// The access granted by a [`PermissionProperties`].
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum PermissionMode {
+    /// Allows reading the resource.
+    #[default]
+    Read,
+
+    /// Allows reading, writing and deleting the resource.
+    All,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{PermissionMode, PermissionProperties};
+
+    #[test]
+    pub fn serialize_permission() {
+        let permission = PermissionProperties::new(
+            "read-orders",
+            PermissionMode::Read,
+            "dbs/MyDatabase/colls/Orders",
+        )
+        .with_resource_partition_key("customer1");
+        let json = serde_json::to_string(&permission).unwrap();
+        assert_eq!(
+            r#"{"id":"read-orders","permissionMode":"Read","resource":"dbs/MyDatabase/colls/Orders","resourcePartitionKey":["customer1"]}"#,
+            json
+        );
+    }
+
+    #[test]
+    pub fn deserialize_permission() {
+        let json = r#"{
+            "id": "all-orders",
+            "permissionMode": "All",
+            "resource": "dbs/MyDatabase/colls/Orders",
+            "_rid": "abc",
+            "_token": "type=resource&ver=1&sig=abc;def",
+            "_etag": "\"0000\"",
+            "_ts": 1729036800
+        }"#;
+        let permission: PermissionProperties = serde_json::from_str(json).unwrap();
+        assert_eq!("all-orders", permission.id);
+        assert_eq!(PermissionMode::All, permission.permission_mode);
+        assert!(permission.resource_partition_key.is_none());
+        assert_eq!(
+            "type=resource&ver=1&sig=abc;def",
+            permission.token.unwrap().secret()
+        );
+    }
+}
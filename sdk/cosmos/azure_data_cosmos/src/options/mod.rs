@@ -1,14 +1,70 @@
 // Copyright (c) Microsoft Corporation. All rights reserved.
 // Licensed under the MIT License.
 
-use azure_core::http::{ClientMethodOptions, ClientOptions};
+use std::time::Duration;
 
-use crate::models::ThroughputProperties;
+use azure_core::http::{
+    headers::{self, Header, HeaderName, HeaderValue},
+    ClientMethodOptions, ClientOptions, Etag,
+};
+
+use crate::{constants, models::ThroughputProperties, DiagnosticsThresholds, SessionToken};
 
 /// Options used when creating a [`CosmosClient`](crate::CosmosClient).
 #[derive(Clone, Default)]
 pub struct CosmosClientOptions {
+    /// The options shared by all Azure clients.
+    ///
+    /// [`ClientOptions::retry`] is ignored: transient errors (`408`, `5xx` and I/O errors) are retried by the default Azure Core retry policy,
+    /// except writes failing with an I/O error after they were sent, and throttled requests are retried as described by [`CosmosClientOptions::throttling_retry_options`].
     pub client_options: ClientOptions,
+
+    /// The regions the client should prefer when routing requests, in order of preference (for example, `["West US 2", "East US"]`).
+    ///
+    /// When this list is not empty, the client reads the account's readable and writable regions and sends reads to the first available preferred region.
+    /// Writes are sent to the account's write region (or to the first available preferred region, for accounts with multiple write regions).
+    /// If a regional endpoint fails, the client marks it unavailable and retries the request in the next region.
+    ///
+    /// When this list is empty (the default), all requests are sent to the endpoint provided when creating the client.
+    pub preferred_regions: Vec<String>,
+
+    /// How often the client refreshes the account's regions, when [`CosmosClientOptions::preferred_regions`] is set.
+    ///
+    /// Defaults to 5 minutes. The regions are also refreshed after a regional endpoint fails.
+    pub location_refresh_interval: Option<Duration>,
+
+    /// Thresholds above which operations are logged as slow or expensive. Operations are not logged when this is `None` (the default).
+    ///
+    /// See [`DiagnosticsThresholds`] for more information.
+    pub diagnostics_thresholds: Option<DiagnosticsThresholds>,
+
+    /// Controls how requests throttled by the service (`429 Too Many Requests`) are retried.
+    ///
+    /// See [`ThrottlingRetryOptions`] for the default values.
+    pub throttling_retry_options: ThrottlingRetryOptions,
+}
+
+/// Controls how requests throttled by the service (`429 Too Many Requests`) are retried.
+///
+/// Throttled requests are retried after the delay requested by the service, in the `x-ms-retry-after-ms` header,
+/// until either the maximum number of retries or the maximum total wait time is reached.
+/// The throttling error is then returned, see [`CosmosError`](crate::CosmosError).
+#[derive(Clone, Debug)]
+pub struct ThrottlingRetryOptions {
+    /// The maximum number of times a throttled request is retried. Defaults to 9.
+    pub max_retries: u32,
+
+    /// The maximum total time spent waiting to retry a throttled request. Defaults to 30 seconds.
+    pub max_wait: Duration,
+}
+
+impl Default for ThrottlingRetryOptions {
+    fn default() -> Self {
+        Self {
+            max_retries: 9,
+            max_wait: Duration::from_secs(30),
+        }
+    }
 }
 
 /// Options to be passed to [`DatabaseClient::create_container()`](crate::clients::DatabaseClient::create_container()).
@@ -52,6 +108,141 @@ pub struct ItemOptions<'a> {
     ///
     /// The default for this is `false`, which reduces the network and CPU burden that comes from serializing and deserializing the response.
     pub enable_content_response_on_write: bool,
+
+    /// An optional condition the item's current ETag must satisfy for the operation to proceed.
+    ///
+    /// Use [`Precondition::IfMatch`] with the ETag from a previous read to implement optimistic concurrency on
+    /// [`replace_item`](crate::clients::ContainerClient::replace_item()), [`upsert_item`](crate::clients::ContainerClient::upsert_item()),
+    /// [`patch_item`](crate::clients::ContainerClient::patch_item()) and [`delete_item`](crate::clients::ContainerClient::delete_item()).
+    /// If the item has changed since it was read, the operation fails with an error for which [`CosmosError::is_precondition_failed()`](crate::CosmosError::is_precondition_failed()) returns `true`.
+    ///
+    /// Use [`Precondition::IfNoneMatch`] on [`read_item`](crate::clients::ContainerClient::read_item()) to only fetch the item if it has changed.
+    /// If it hasn't, the read fails with an error for which [`CosmosError::is_not_modified()`](crate::CosmosError::is_not_modified()) returns `true`.
+    ///
+    /// This option is ignored by [`create_item`](crate::clients::ContainerClient::create_item()).
+    pub precondition: Option<Precondition>,
+
+    /// The [`SessionToken`] to send with the request, when reading an item.
+    ///
+    /// By default, the client sends the latest session token it has observed for the container, which guarantees that reads observe writes made through the same [`CosmosClient`](crate::CosmosClient).
+    /// Set this to provide "read-your-writes" guarantees across client instances, for example using a token received from a previous web request.
+    /// This option is ignored by write operations.
+    pub session_token: Option<SessionToken>,
+
+    /// Overrides the account's default [`ConsistencyLevel`] when reading an item.
+    ///
+    /// The consistency level can only be relaxed (made weaker) relative to the account's default.
+    /// This option is ignored by write operations.
+    pub consistency_level: Option<ConsistencyLevel>,
+
+    /// The names of the [pre-triggers](crate::models::TriggerType::Pre) to run before a write operation.
+    ///
+    /// Triggers are never run automatically, they must be requested on each operation. This option is ignored by [`read_item`](crate::clients::ContainerClient::read_item()).
+    pub pre_triggers: Vec<String>,
+
+    /// The names of the [post-triggers](crate::models::TriggerType::Post) to run after a write operation.
+    ///
+    /// Triggers are never run automatically, they must be requested on each operation. This option is ignored by [`read_item`](crate::clients::ContainerClient::read_item()).
+    pub post_triggers: Vec<String>,
+}
+
+/// The consistency level to use for a read operation.
+///
+/// See <https://learn.microsoft.com/azure/cosmos-db/consistency-levels> for more information on each consistency level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsistencyLevel {
+    Strong,
+    BoundedStaleness,
+    Session,
+    ConsistentPrefix,
+    Eventual,
+}
+
+impl ConsistencyLevel {
+    /// Gets the value of this consistency level, as used by the Azure Cosmos DB REST API.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConsistencyLevel::Strong => "Strong",
+            ConsistencyLevel::BoundedStaleness => "BoundedStaleness",
+            ConsistencyLevel::Session => "Session",
+            ConsistencyLevel::ConsistentPrefix => "ConsistentPrefix",
+            ConsistencyLevel::Eventual => "Eventual",
+        }
+    }
+}
+
+impl Header for ConsistencyLevel {
+    fn name(&self) -> HeaderName {
+        constants::CONSISTENCY_LEVEL
+    }
+
+    fn value(&self) -> HeaderValue {
+        HeaderValue::from_static(self.as_str())
+    }
+}
+
+/// A condition on the ETag of an item, sent as the `If-Match` or `If-None-Match` header of an item operation.
+///
+/// An [`Etag`] converts into [`Precondition::IfMatch`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Precondition {
+    /// The operation only proceeds if the ETag of the item matches this one, or if the item exists when the ETag is `*`.
+    IfMatch(Etag),
+
+    /// The operation only proceeds if the ETag of the item doesn't match this one, or if the item doesn't exist when the ETag is `*`.
+    IfNoneMatch(Etag),
+}
+
+impl From<Etag> for Precondition {
+    fn from(etag: Etag) -> Self {
+        Precondition::IfMatch(etag)
+    }
+}
+
+impl Header for Precondition {
+    fn name(&self) -> HeaderName {
+        match self {
+            Precondition::IfMatch(_) => headers::IF_MATCH,
+            Precondition::IfNoneMatch(_) => headers::IF_NONE_MATCH,
+        }
+    }
+
+    fn value(&self) -> HeaderValue {
+        match self {
+            Precondition::IfMatch(etag) | Precondition::IfNoneMatch(etag) => {
+                etag.to_string().into()
+            }
+        }
+    }
+}
+
+/// Options to be passed to APIs that manage users, such as [`DatabaseClient::create_user()`](crate::clients::DatabaseClient::create_user()).
+#[derive(Clone, Default)]
+pub struct UserOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to APIs that manage permissions, such as [`DatabaseClient::create_permission()`](crate::clients::DatabaseClient::create_permission()).
+#[derive(Clone, Default)]
+pub struct PermissionOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// How long the resource token returned in the response remains valid.
+    ///
+    /// The service defaults to one hour, and allows values between 10 minutes and 24 hours (or 5 hours, for some accounts).
+    pub resource_token_expiry: Option<Duration>,
+}
+
+/// Options to be passed to [`DatabaseClient::query_users()`](crate::clients::DatabaseClient::query_users())
+#[derive(Clone, Default)]
+pub struct QueryUsersOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to [`DatabaseClient::query_permissions()`](crate::clients::DatabaseClient::query_permissions())
+#[derive(Clone, Default)]
+pub struct QueryPermissionsOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
 }
 
 /// Options to be passed to [`DatabaseClient::query_containers()`](crate::clients::DatabaseClient::query_containers())
@@ -70,6 +261,83 @@ pub struct QueryDatabasesOptions<'a> {
 #[derive(Clone, Default)]
 pub struct QueryOptions<'a> {
     pub method_options: ClientMethodOptions<'a>,
+
+    /// The [`SessionToken`] to send with the query.
+    ///
+    /// See [`ItemOptions::session_token`] for more information.
+    pub session_token: Option<SessionToken>,
+
+    /// Overrides the account's default [`ConsistencyLevel`] for the query.
+    ///
+    /// The consistency level can only be relaxed (made weaker) relative to the account's default.
+    pub consistency_level: Option<ConsistencyLevel>,
+
+    /// When this value is true, the service returns detailed query execution metrics, available in [`CosmosDiagnostics::query_metrics`](crate::CosmosDiagnostics::query_metrics).
+    pub populate_query_metrics: bool,
+
+    /// When this value is true, the service returns index utilization metrics, available in [`CosmosDiagnostics::index_metrics`](crate::CosmosDiagnostics::index_metrics).
+    ///
+    /// Computing index metrics consumes additional request units, so this should only be enabled when tuning the indexing policy.
+    pub populate_index_metrics: bool,
+}
+
+/// Options to be passed to APIs that manage stored procedures, triggers and user-defined functions.
+#[derive(Clone, Default)]
+pub struct ScriptOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to [`ContainerClient::query_stored_procedures()`](crate::clients::ContainerClient::query_stored_procedures()),
+/// [`ContainerClient::query_triggers()`](crate::clients::ContainerClient::query_triggers()) and [`ContainerClient::query_user_defined_functions()`](crate::clients::ContainerClient::query_user_defined_functions()).
+#[derive(Clone, Default)]
+pub struct QueryScriptsOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to [`ContainerClient::read_conflict()`](crate::clients::ContainerClient::read_conflict()) and [`ContainerClient::delete_conflict()`](crate::clients::ContainerClient::delete_conflict()).
+#[derive(Clone, Default)]
+pub struct ConflictOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to [`ContainerClient::query_conflicts()`](crate::clients::ContainerClient::query_conflicts()).
+#[derive(Clone, Default)]
+pub struct QueryConflictsOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+}
+
+/// Options to be passed to [`ContainerClient::execute_stored_procedure()`](crate::clients::ContainerClient::execute_stored_procedure()).
+#[derive(Clone, Default)]
+pub struct ExecuteStoredProcedureOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// When this value is true, the output of `console.log` calls made by the stored procedure is returned with the response.
+    ///
+    /// Use [`ScriptLog::from_headers`](crate::models::ScriptLog::from_headers) to get it from the response headers.
+    pub enable_script_logging: bool,
+}
+
+/// Options to be passed to [`ContainerClient::read_many_items()`](crate::clients::ContainerClient::read_many_items()).
+#[derive(Clone, Default)]
+pub struct ReadManyOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The [`SessionToken`] to send with each request.
+    ///
+    /// See [`ItemOptions::session_token`] for more information.
+    pub session_token: Option<SessionToken>,
+
+    /// Overrides the account's default [`ConsistencyLevel`] for each request.
+    pub consistency_level: Option<ConsistencyLevel>,
+
+    /// The maximum number of requests sent concurrently. Defaults to 10.
+    pub max_concurrency: Option<usize>,
+}
+
+/// Options to be passed to [`ContainerClient::read_feed_ranges()`](crate::clients::ContainerClient::read_feed_ranges()).
+#[derive(Clone, Default)]
+pub struct ReadFeedRangesOptions<'a> {
+    pub method_options: ClientMethodOptions<'a>,
 }
 
 /// Options to be passed to [`ContainerClient::read()`](crate::clients::ContainerClient::read()).
@@ -88,4 +356,23 @@ pub struct ReadDatabaseOptions<'a> {
 #[derive(Clone, Default)]
 pub struct ThroughputOptions<'a> {
     pub method_options: ClientMethodOptions<'a>,
+
+    /// The delay between two reads of the offer by the [`ThroughputPoller`](crate::ThroughputPoller) returned by operations which change the throughput.
+    ///
+    /// Defaults to 5 seconds.
+    pub polling_interval: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use azure_core::http::headers::Header;
+
+    use crate::ConsistencyLevel;
+
+    #[test]
+    pub fn consistency_level_header() {
+        let level = ConsistencyLevel::BoundedStaleness;
+        assert_eq!("x-ms-consistency-level", level.name().as_str());
+        assert_eq!("BoundedStaleness", level.value().as_str());
+    }
 }
@@ -3,19 +3,42 @@
 
 use std::borrow::Cow;
 
-use azure_core::http::headers::{AsHeaders, HeaderName, HeaderValue};
+use azure_core::{
+    error::ErrorKind,
+    http::headers::{AsHeaders, HeaderName, HeaderValue},
+};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::constants;
+use crate::{
+    constants,
+    models::{PartitionKeyDefinition, PartitionKeyKind},
+    FeedRange,
+};
+
+mod hashing;
 
 /// Describes the partition strategy that will be used when querying.
 ///
-/// Currently, the only supported strategy is [`QueryPartitionStrategy::SinglePartition`], which executes the query against a single partition, specified by the [`PartitionKey`] provided.
+/// The following strategies are supported:
+/// * [`QueryPartitionStrategy::SinglePartition`] executes the query against a single partition, specified by the [`PartitionKey`] provided.
+/// * [`QueryPartitionStrategy::FeedRange`] executes the query against every partition whose effective partition key is within the [`FeedRange`] provided.
 ///
-/// [`QueryPartitionStrategy`] implements [`From`] for any type that is convertible to a `PartitionKey`.
+/// [`QueryPartitionStrategy`] implements [`From`] for any type that is convertible to a `PartitionKey`, and for [`FeedRange`].
 /// This allows you to use any of the syntaxes specified in the [`PartitionKey`] docs any place an [`Into<QueryPartitionStrategy>`] is expected.
 #[derive(Debug, Clone)]
 pub enum QueryPartitionStrategy {
     SinglePartition(PartitionKey),
+
+    /// Executes the query against the items within a [`FeedRange`], such as one of the ranges returned by [`ContainerClient::read_feed_ranges()`](crate::clients::ContainerClient::read_feed_ranges()).
+    ///
+    /// The query is executed against each physical partition overlapping the feed range in turn, so `ORDER BY` clauses and aggregates apply to each physical partition separately.
+    FeedRange(FeedRange),
+}
+
+impl From<FeedRange> for QueryPartitionStrategy {
+    fn from(value: FeedRange) -> Self {
+        QueryPartitionStrategy::FeedRange(value)
+    }
 }
 
 impl<T: Into<PartitionKey>> From<T> for QueryPartitionStrategy {
@@ -95,6 +118,64 @@ impl<T: Into<PartitionKey>> From<T> for QueryPartitionStrategy {
 #[derive(Debug, Clone)]
 pub struct PartitionKey(Vec<PartitionKeyValue>);
 
+impl PartitionKey {
+    /// Computes the effective partition key of this partition key: the hash Cosmos DB uses to place items in physical partitions.
+    ///
+    /// The hash depends on the [`PartitionKeyKind`] and version of the container's [`PartitionKeyDefinition`].
+    /// A hierarchical partition key may include only the first levels of the key, in which case the effective partition key is a prefix of the effective partition keys of all the items with those first levels.
+    /// Use [`FeedRange::contains()`] to find which feed range contains the items with a given partition key.
+    ///
+    /// ```rust
+    /// # use azure_data_cosmos::{models::PartitionKeyDefinition, PartitionKey};
+    /// let definition = PartitionKeyDefinition::from("/city");
+    /// assert_eq!(
+    ///     "22E342F38A486A088463DFF7838A5963",
+    ///     PartitionKey::from("redmond").effective_partition_key(&definition).unwrap()
+    /// );
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number of values in the partition key doesn't match the definition, or the definition uses an unknown hash version.
+    pub fn effective_partition_key(
+        &self,
+        definition: &PartitionKeyDefinition,
+    ) -> azure_core::Result<String> {
+        let values: Vec<InnerPartitionKeyValue> = self.0.iter().map(|v| v.0.clone()).collect();
+        let invalid = |message: String| {
+            Err(azure_core::Error::message(
+                ErrorKind::DataConversion,
+                message,
+            ))
+        };
+        match definition.kind {
+            PartitionKeyKind::Hash => {
+                if values.len() != 1 {
+                    return invalid(format!(
+                        "expected a single partition key value, but got {}",
+                        values.len()
+                    ));
+                }
+                match definition.version {
+                    None | Some(1) => Ok(hashing::effective_partition_key_v1(&values)),
+                    Some(2) => Ok(hashing::effective_partition_key_v2(&values)),
+                    Some(v) => invalid(format!("unknown partition key hash version {}", v)),
+                }
+            }
+            PartitionKeyKind::MultiHash => {
+                if values.is_empty() || values.len() > definition.paths.len() {
+                    return invalid(format!(
+                        "expected between 1 and {} partition key values, but got {}",
+                        definition.paths.len(),
+                        values.len()
+                    ));
+                }
+                Ok(hashing::effective_partition_key_multi_hash(&values))
+            }
+        }
+    }
+}
+
 impl AsHeaders for PartitionKey {
     type Error = azure_core::Error;
     type Iter = std::iter::Once<(HeaderName, HeaderValue)>;
@@ -154,6 +235,19 @@ impl AsHeaders for PartitionKey {
     }
 }
 
+// Partition keys are represented in resource bodies (such as a permission's `resourcePartitionKey`) as a JSON array of values.
+impl Serialize for PartitionKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PartitionKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(PartitionKey(Vec::deserialize(deserializer)?))
+    }
+}
+
 /// Represents a value for a single partition key.
 ///
 /// You shouldn't need to construct this type directly. The various implementations of [`Into<PartitionKey>`] will handle it for you.
@@ -168,6 +262,29 @@ enum InnerPartitionKeyValue {
     Number(serde_json::Number), // serde_json::Number has special integer handling, so we'll use that.
 }
 
+impl Serialize for PartitionKeyValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match &self.0 {
+            InnerPartitionKeyValue::Null => serializer.serialize_none(),
+            InnerPartitionKeyValue::String(s) => serializer.serialize_str(s),
+            InnerPartitionKeyValue::Number(n) => n.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PartitionKeyValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Null => Ok(InnerPartitionKeyValue::Null.into()),
+            serde_json::Value::String(s) => Ok(InnerPartitionKeyValue::String(s.into()).into()),
+            serde_json::Value::Number(n) => Ok(InnerPartitionKeyValue::Number(n).into()),
+            _ => Err(de::Error::custom(
+                "partition key values must be strings, numbers or null",
+            )),
+        }
+    }
+}
+
 impl From<InnerPartitionKeyValue> for PartitionKeyValue {
     fn from(value: InnerPartitionKeyValue) -> Self {
         PartitionKeyValue(value)
@@ -290,7 +407,9 @@ impl_from_tuple!(0 A 1 B 2 C);
 
 #[cfg(test)]
 mod tests {
-    use crate::{constants, PartitionKey, QueryPartitionStrategy};
+    use crate::{
+        constants, models::PartitionKeyDefinition, FeedRange, PartitionKey, QueryPartitionStrategy,
+    };
     use typespec_client_core::http::headers::AsHeaders;
 
     fn key_to_string(v: impl Into<PartitionKey>) -> String {
@@ -304,10 +423,64 @@ mod tests {
     /// Validates that a given value is `impl Into<QueryPartitionStrategy>` and works as-expected.
     fn key_to_single_partition_strategy_string(v: impl Into<QueryPartitionStrategy>) -> String {
         let strategy = v.into();
-        let QueryPartitionStrategy::SinglePartition(key) = strategy;
+        let QueryPartitionStrategy::SinglePartition(key) = strategy else {
+            panic!("expected a single partition strategy");
+        };
         key_to_string(key)
     }
 
+    #[test]
+    pub fn effective_partition_keys() {
+        let v1 = PartitionKeyDefinition {
+            version: None,
+            ..PartitionKeyDefinition::from("/city")
+        };
+        let v2 = PartitionKeyDefinition::from("/city");
+        let hierarchical = PartitionKeyDefinition::from(("/city", "/zip"));
+
+        let key = PartitionKey::from("redmond");
+        assert_eq!(
+            "05C1EFE313830C087366656E706F6500",
+            key.effective_partition_key(&v1).unwrap()
+        );
+        assert_eq!(
+            "22E342F38A486A088463DFF7838A5963",
+            key.effective_partition_key(&v2).unwrap()
+        );
+        assert_eq!(
+            "22E342F38A486A088463DFF7838A5963",
+            key.effective_partition_key(&hierarchical).unwrap()
+        );
+
+        let full_key = PartitionKey::from(("redmond", 5));
+        assert_eq!(
+            "22E342F38A486A088463DFF7838A596319C08621B135968252FB34B4CF66F811",
+            full_key.effective_partition_key(&hierarchical).unwrap()
+        );
+        assert!(full_key.effective_partition_key(&v2).is_err());
+        assert!(PartitionKey::from(("a", "b", "c"))
+            .effective_partition_key(&hierarchical)
+            .is_err());
+    }
+
+    #[test]
+    pub fn feed_range_strategy() {
+        let strategy: QueryPartitionStrategy = FeedRange::full().into();
+        assert!(
+            matches!(strategy, QueryPartitionStrategy::FeedRange(range) if range == FeedRange::full())
+        );
+    }
+
+    #[test]
+    pub fn serde_round_trip() {
+        let key = PartitionKey::from(("parent", 42, None::<&str>));
+        let json = serde_json::to_string(&key).unwrap();
+        assert_eq!(r#"["parent",42,null]"#, json);
+        let key: PartitionKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(r#"["parent",42,null]"#, key_to_string(key));
+        assert!(serde_json::from_str::<PartitionKey>(r#"[true]"#).is_err());
+    }
+
     #[test]
     pub fn static_str() {
         assert_eq!(key_to_string("my_partition_key"), r#"["my_partition_key"]"#);
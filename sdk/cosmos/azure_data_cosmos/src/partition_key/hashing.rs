@@ -0,0 +1,318 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Computes effective partition keys, the hashes Cosmos DB uses to assign logical partitions to physical partitions.
+//!
+//! The encodings here must match the ones used by the service (and the other Cosmos DB SDKs) byte-for-byte.
+
+use super::InnerPartitionKeyValue;
+
+/// The type markers used in the binary encodings of partition key components.
+mod marker {
+    pub const NULL: u8 = 0x01;
+    pub const NUMBER: u8 = 0x05;
+    pub const STRING: u8 = 0x08;
+}
+
+/// Strings are truncated to this many characters before being hashed with version 1 of the hash.
+const V1_MAX_STRING_CHARS: usize = 100;
+
+/// Only this many bytes of a string are included in the binary encoding of a version 1 effective partition key.
+const V1_MAX_STRING_BYTES: usize = 100;
+
+/// Computes the effective partition key of a (possibly hierarchical) partition key using version 1 of the hash.
+///
+/// The effective partition key is the binary encoding of the 32-bit MurmurHash3 of the components, followed by the (truncated) components themselves.
+pub(super) fn effective_partition_key_v1(values: &[InnerPartitionKeyValue]) -> String {
+    let truncated: Vec<InnerPartitionKeyValue> = values
+        .iter()
+        .map(|value| match value {
+            InnerPartitionKeyValue::String(s) if s.chars().count() > V1_MAX_STRING_CHARS => {
+                InnerPartitionKeyValue::String(s.chars().take(V1_MAX_STRING_CHARS).collect())
+            }
+            other => other.clone(),
+        })
+        .collect();
+
+    let mut hashed = Vec::new();
+    for value in &truncated {
+        write_for_hashing(value, 0x00, &mut hashed);
+    }
+    let hash = murmur3_32(&hashed, 0);
+
+    let mut encoded = Vec::new();
+    write_number_for_binary_encoding(hash as f64, &mut encoded);
+    for value in &truncated {
+        write_for_binary_encoding(value, &mut encoded);
+    }
+    to_hex(&encoded)
+}
+
+/// Computes the effective partition key of a single-level partition key, or of all the levels of a hierarchical partition key together, using version 2 of the hash.
+pub(super) fn effective_partition_key_v2(values: &[InnerPartitionKeyValue]) -> String {
+    let mut hashed = Vec::new();
+    for value in values {
+        write_for_hashing(value, 0xFF, &mut hashed);
+    }
+    let (h1, h2) = murmur3_128(&hashed, 0);
+
+    // The hash is treated as a big-endian 128-bit number, with the two high bits cleared to leave room for the values the service reserves.
+    let mut bytes = [0u8; 16];
+    bytes[..8].copy_from_slice(&h2.to_be_bytes());
+    bytes[8..].copy_from_slice(&h1.to_be_bytes());
+    bytes[0] &= 0x3F;
+    to_hex(&bytes)
+}
+
+/// Computes the effective partition key of a hierarchical partition key: the version 2 hash of each level, concatenated.
+///
+/// Concatenating the hashes keeps all the items sharing a prefix of their partition key in a contiguous range.
+pub(super) fn effective_partition_key_multi_hash(values: &[InnerPartitionKeyValue]) -> String {
+    values
+        .iter()
+        .map(|value| effective_partition_key_v2(std::slice::from_ref(value)))
+        .collect()
+}
+
+fn write_for_hashing(value: &InnerPartitionKeyValue, string_terminator: u8, buffer: &mut Vec<u8>) {
+    match value {
+        InnerPartitionKeyValue::Null => buffer.push(marker::NULL),
+        InnerPartitionKeyValue::String(s) => {
+            buffer.push(marker::STRING);
+            buffer.extend_from_slice(s.as_bytes());
+            buffer.push(string_terminator);
+        }
+        InnerPartitionKeyValue::Number(n) => {
+            buffer.push(marker::NUMBER);
+            buffer.extend_from_slice(&number_as_f64(n).to_le_bytes());
+        }
+    }
+}
+
+fn write_for_binary_encoding(value: &InnerPartitionKeyValue, buffer: &mut Vec<u8>) {
+    match value {
+        InnerPartitionKeyValue::Null => buffer.push(marker::NULL),
+        InnerPartitionKeyValue::String(s) => {
+            buffer.push(marker::STRING);
+            let bytes = s.as_bytes();
+            let short_string = bytes.len() <= V1_MAX_STRING_BYTES;
+            let len = if short_string {
+                bytes.len()
+            } else {
+                V1_MAX_STRING_BYTES + 1
+            };
+            // Bytes are shifted up by one, so that 0x00 can terminate the string and still sort before any other byte.
+            buffer.extend(bytes[..len].iter().map(|b| b.saturating_add(1)));
+            if short_string {
+                buffer.push(0x00);
+            }
+        }
+        InnerPartitionKeyValue::Number(n) => {
+            write_number_for_binary_encoding(number_as_f64(n), buffer)
+        }
+    }
+}
+
+/// Writes a number in an order-preserving, variable-length encoding: the first byte of the (sign-adjusted) IEEE 754 bits,
+/// followed by the remaining bits in groups of 7, where the low bit of each byte is set when more bytes follow.
+fn write_number_for_binary_encoding(value: f64, buffer: &mut Vec<u8>) {
+    buffer.push(marker::NUMBER);
+
+    let bits = value.to_bits();
+    let mut payload = if bits < 0x8000_0000_0000_0000 {
+        bits ^ 0x8000_0000_0000_0000
+    } else {
+        (!bits).wrapping_add(1)
+    };
+
+    buffer.push((payload >> 56) as u8);
+    payload <<= 8;
+
+    let mut byte_to_write = 0u8;
+    let mut first_iteration = true;
+    loop {
+        if !first_iteration {
+            buffer.push(byte_to_write);
+        }
+        first_iteration = false;
+        byte_to_write = ((payload >> 56) as u8) | 0x01;
+        payload <<= 7;
+        if payload == 0 {
+            break;
+        }
+    }
+    buffer.push(byte_to_write & 0xFE);
+}
+
+fn number_as_f64(n: &serde_json::Number) -> f64 {
+    // Every number that can be stored in a serde_json::Number can be converted to an f64, possibly losing precision, as the service does.
+    n.as_f64().unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+/// The 32-bit x86 variant of MurmurHash3.
+fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut h = seed;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes(chunk.try_into().unwrap());
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+        h = h.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = 0u32;
+        for (i, b) in tail.iter().enumerate() {
+            k ^= (*b as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h ^= k;
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+/// The 128-bit x64 variant of MurmurHash3, returning the two 64-bit halves of the hash (low, high).
+fn murmur3_128(data: &[u8], seed: u64) -> (u64, u64) {
+    const C1: u64 = 0x87c3_7b91_1142_53d5;
+    const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+    let mut h1 = seed;
+    let mut h2 = seed;
+    let mut chunks = data.chunks_exact(16);
+    for chunk in &mut chunks {
+        let k1 = u64::from_le_bytes(chunk[..8].try_into().unwrap());
+        let k2 = u64::from_le_bytes(chunk[8..].try_into().unwrap());
+
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        h1 = h1
+            .rotate_left(27)
+            .wrapping_add(h2)
+            .wrapping_mul(5)
+            .wrapping_add(0x52dc_e729);
+
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+        h2 = h2
+            .rotate_left(31)
+            .wrapping_add(h1)
+            .wrapping_mul(5)
+            .wrapping_add(0x3849_5ab5);
+    }
+
+    let tail = chunks.remainder();
+    let mut k1 = 0u64;
+    let mut k2 = 0u64;
+    for (i, b) in tail.iter().enumerate() {
+        if i < 8 {
+            k1 ^= (*b as u64) << (8 * i);
+        } else {
+            k2 ^= (*b as u64) << (8 * (i - 8));
+        }
+    }
+    if tail.len() > 8 {
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+    }
+    if !tail.is_empty() {
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+    }
+
+    h1 ^= data.len() as u64;
+    h2 ^= data.len() as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix64(h1);
+    h2 = fmix64(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    (h1, h2)
+}
+
+fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+    k
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{effective_partition_key_v1, effective_partition_key_v2, murmur3_128, murmur3_32};
+    use crate::partition_key::InnerPartitionKeyValue;
+
+    fn string(s: &str) -> Vec<InnerPartitionKeyValue> {
+        vec![InnerPartitionKeyValue::String(s.to_string().into())]
+    }
+
+    fn number(n: f64) -> Vec<InnerPartitionKeyValue> {
+        vec![InnerPartitionKeyValue::Number(
+            serde_json::Number::from_f64(n).unwrap(),
+        )]
+    }
+
+    #[test]
+    pub fn murmur3_reference_values() {
+        assert_eq!(0, murmur3_32(b"", 0));
+        assert_eq!(0x248b_fa47, murmur3_32(b"hello", 0));
+        assert_eq!((0, 0), murmur3_128(b"", 0));
+        assert_eq!(
+            (0xcbd8_a7b3_41bd_9b02, 0x5b1e_906a_48ae_1d19),
+            murmur3_128(b"hello", 0)
+        );
+    }
+
+    #[test]
+    pub fn v1_strings() {
+        assert_eq!(
+            "05C1CF33970FF80800",
+            effective_partition_key_v1(&string(""))
+        );
+        assert_eq!(
+            "05C1EFE313830C087366656E706F6500",
+            effective_partition_key_v1(&string("redmond"))
+        );
+    }
+
+    #[test]
+    pub fn v2_strings() {
+        assert_eq!(
+            "32E9366E637A71B4E710384B2F4970A0",
+            effective_partition_key_v2(&string(""))
+        );
+        assert_eq!(
+            "013AEFCF77FA271571CF665A58C933F1",
+            effective_partition_key_v2(&string("partitionKey"))
+        );
+        assert_eq!(
+            "22E342F38A486A088463DFF7838A5963",
+            effective_partition_key_v2(&string("redmond"))
+        );
+    }
+
+    #[test]
+    pub fn v2_numbers() {
+        assert_eq!(
+            "19C08621B135968252FB34B4CF66F811",
+            effective_partition_key_v2(&number(5.0))
+        );
+        assert_eq!(
+            "27E7ECA8F2EE3E53424DE8D5220631C6",
+            effective_partition_key_v2(&number(12313.1221))
+        );
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Caches the partition key ranges (physical partitions) of each container.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use serde::Deserialize;
+
+use crate::FeedRange;
+
+/// A partition key range, as returned by reading the `pkranges` feed of a container.
+///
+/// Each partition key range corresponds to a physical partition of the container.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PartitionKeyRange {
+    pub id: String,
+    pub min_inclusive: String,
+    pub max_exclusive: String,
+}
+
+impl PartitionKeyRange {
+    pub fn feed_range(&self) -> FeedRange {
+        FeedRange::new(&self.min_inclusive, &self.max_exclusive)
+    }
+}
+
+/// Tracks the partition key ranges of each container, sorted by their effective partition keys.
+///
+/// A single [`PartitionKeyRangeCache`] is shared by all the clients created from the same [`CosmosClient`](crate::CosmosClient).
+#[derive(Debug, Default)]
+pub(crate) struct PartitionKeyRangeCache {
+    // Maps a container path (e.g. "dbs/MyDatabase/colls/MyContainer") to its partition key ranges.
+    containers: RwLock<HashMap<String, Arc<Vec<PartitionKeyRange>>>>,
+}
+
+impl PartitionKeyRangeCache {
+    /// Gets the cached partition key ranges of the container, if any.
+    pub fn get(&self, container_path: &str) -> Option<Arc<Vec<PartitionKeyRange>>> {
+        self.containers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(container_path)
+            .cloned()
+    }
+
+    /// Removes the cached partition key ranges of the container, so that they're read again the next time they're needed.
+    pub fn remove(&self, container_path: &str) {
+        self.containers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(container_path);
+    }
+
+    /// Replaces the cached partition key ranges of the container, returning the sorted ranges.
+    pub fn insert(
+        &self,
+        container_path: &str,
+        mut ranges: Vec<PartitionKeyRange>,
+    ) -> Arc<Vec<PartitionKeyRange>> {
+        ranges.sort_by(|a, b| a.min_inclusive.cmp(&b.min_inclusive));
+        let ranges = Arc::new(ranges);
+        self.containers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(container_path.to_string(), ranges.clone());
+        ranges
+    }
+}
+
+/// Gets the partition key ranges overlapping a feed range, in order.
+pub(crate) fn overlapping_ranges<'a>(
+    ranges: &'a [PartitionKeyRange],
+    feed_range: &'a FeedRange,
+) -> impl Iterator<Item = &'a PartitionKeyRange> {
+    ranges
+        .iter()
+        .filter(move |range| range.feed_range().overlaps(feed_range))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        partition_key_range_cache::{
+            overlapping_ranges, PartitionKeyRange, PartitionKeyRangeCache,
+        },
+        FeedRange,
+    };
+
+    fn range(id: &str, min: &str, max: &str) -> PartitionKeyRange {
+        PartitionKeyRange {
+            id: id.to_string(),
+            min_inclusive: min.to_string(),
+            max_exclusive: max.to_string(),
+        }
+    }
+
+    #[test]
+    pub fn sorts_and_finds_overlapping_ranges() {
+        let cache = PartitionKeyRangeCache::default();
+        assert!(cache.get("dbs/db/colls/c").is_none());
+        let ranges = cache.insert(
+            "dbs/db/colls/c",
+            vec![range("2", "80", "FF"), range("1", "", "80")],
+        );
+        assert_eq!(ranges, cache.get("dbs/db/colls/c").unwrap());
+        assert_eq!("1", ranges[0].id);
+
+        let ids = |feed_range: FeedRange| {
+            overlapping_ranges(&ranges, &feed_range)
+                .map(|r| r.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(vec!["1", "2"], ids(FeedRange::full()));
+        assert_eq!(vec!["1"], ids(FeedRange::new("10", "20")));
+        assert_eq!(vec!["2"], ids(FeedRange::new("80", "FF")));
+        assert_eq!(vec!["1", "2"], ids(FeedRange::new("70", "90")));
+    }
+}
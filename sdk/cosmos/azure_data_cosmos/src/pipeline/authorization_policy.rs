@@ -21,6 +21,8 @@ use azure_core::{
 use std::sync::Arc;
 use tracing::trace;
 
+#[cfg(feature = "key_auth")]
+use crate::ResourceTokenResolver;
 use crate::{pipeline::signature_target::SignatureTarget, resource_context::ResourceLink};
 
 use crate::utils::url_encode;
@@ -35,6 +37,10 @@ enum Credential {
     /// The credential is a key to be used to sign the HTTP request (a shared key)
     #[cfg(feature = "key_auth")]
     PrimaryKey(Secret),
+
+    /// The credential resolves the resource token to use for each request.
+    #[cfg(feature = "key_auth")]
+    ResourceToken(Arc<dyn ResourceTokenResolver>),
 }
 
 #[derive(Debug, Clone)]
@@ -55,6 +61,13 @@ impl AuthorizationPolicy {
             credential: Credential::PrimaryKey(key),
         }
     }
+
+    #[cfg(feature = "key_auth")]
+    pub(crate) fn from_resource_tokens(resolver: Arc<dyn ResourceTokenResolver>) -> Self {
+        Self {
+            credential: Credential::ResourceToken(resolver),
+        }
+    }
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
@@ -101,13 +114,14 @@ impl Policy for AuthorizationPolicy {
 /// The specific result format depends on the type of the auth token provided.
 ///   - "primary": one of the two service-level tokens
 ///   - "aad": Azure Active Directory token
+///   - "resource": a resource token issued for a permission
 ///
 /// In the "primary" case the signature must be constructed by signing the HTTP method,
 /// resource type, resource link (the relative URI) and the current time.
 ///
 /// In the "aad" case, the signature is the AAD token.
 ///
-/// NOTE: Resource tokens are not yet supported.
+/// In the "resource" case, the resource token for the resource link is used as-is.
 async fn generate_authorization(
     auth_token: &Credential,
     url: &Url,
@@ -128,6 +142,13 @@ async fn generate_authorization(
 
         #[cfg(feature = "key_auth")]
         Credential::PrimaryKey(key) => signature_target.into_authorization(key)?,
+
+        #[cfg(feature = "key_auth")]
+        Credential::ResourceToken(resolver) => {
+            signature_target
+                .into_resource_authorization(resolver.as_ref())
+                .await?
+        }
     };
 
     Ok(url_encode(token))
@@ -271,6 +292,50 @@ mod tests {
         assert_eq!(ret, expected);
     }
 
+    #[tokio::test]
+    #[cfg(feature = "key_auth")]
+    async fn generate_authorization_for_resource_token() {
+        use azure_core::credentials::Secret;
+        use std::collections::HashMap;
+
+        let date_string = date::to_rfc7231(&OffsetDateTime::now_utc()).to_lowercase();
+        let auth_token = Credential::ResourceToken(Arc::new(HashMap::from([(
+            "dbs/MyDatabase/colls/MyCollection".to_string(),
+            Secret::new("type=resource&ver=1&sig=abc/def+=;ghi"),
+        )])));
+        let url = Url::parse("https://test_account.example.com/dbs/ToDoList").unwrap();
+        let container_link = ResourceLink::root(ResourceType::Databases)
+            .item("MyDatabase")
+            .feed(ResourceType::Containers)
+            .item("MyCollection");
+
+        let ret = generate_authorization(
+            &auth_token,
+            &url,
+            SignatureTarget::new(
+                Method::Get,
+                &container_link.feed(ResourceType::Items).item("item1"),
+                &date_string,
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(url_encode(b"type=resource&ver=1&sig=abc/def+=;ghi"), ret);
+
+        let err = generate_authorization(
+            &auth_token,
+            &url,
+            SignatureTarget::new(
+                Method::Get,
+                &ResourceLink::root(ResourceType::Databases).item("OtherDatabase"),
+                &date_string,
+            ),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(&azure_core::error::ErrorKind::Credential, err.kind());
+    }
+
     #[test]
     fn scope_from_url_extracts_correct_scope() {
         let scope = scope_from_url(&Url::parse("https://example.com/dbs/test_db/colls").unwrap());
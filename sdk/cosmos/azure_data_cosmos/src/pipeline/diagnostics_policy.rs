@@ -0,0 +1,295 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Defines policies that collect diagnostics for each operation, and log slow or expensive operations.
+
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use azure_core::{
+    date::OffsetDateTime,
+    http::{
+        policies::{Policy, PolicyResult},
+        request::Request,
+        Context,
+    },
+};
+use tracing::warn;
+
+use crate::{CosmosDiagnostics, CosmosError, DiagnosticsRecorder, DiagnosticsThresholds};
+
+/// Counts the attempts made to send a request, shared between [`DiagnosticsPolicy`] and [`AttemptCountPolicy`] through the [`Context`].
+#[derive(Debug, Clone, Default)]
+struct AttemptCount(Arc<AtomicU32>);
+
+/// A policy that records the number of retries of each operation, and logs operations exceeding the configured [`DiagnosticsThresholds`].
+///
+/// This policy must be a per-call policy (running once per operation, before the retry policy).
+/// It relies on an [`AttemptCountPolicy`] in the per-retry policies to count the attempts.
+/// The diagnostics of the operation, including its retry count, are recorded in the [`DiagnosticsRecorder`] of the [`Context`], if there is one.
+#[derive(Debug, Clone)]
+pub struct DiagnosticsPolicy {
+    thresholds: Option<DiagnosticsThresholds>,
+}
+
+impl DiagnosticsPolicy {
+    pub fn new(thresholds: Option<DiagnosticsThresholds>) -> Self {
+        Self { thresholds }
+    }
+
+    fn log_if_exceeded(
+        &self,
+        request: &Request,
+        elapsed: Duration,
+        diagnostics: &CosmosDiagnostics,
+        status: Option<u16>,
+    ) {
+        let Some(thresholds) = &self.thresholds else {
+            return;
+        };
+        if thresholds.is_exceeded(elapsed, diagnostics) {
+            warn!(
+                method = %request.method(),
+                path = request.url().path(),
+                status,
+                elapsed_ms = elapsed.as_millis() as u64,
+                request_charge = diagnostics.request_charge,
+                activity_id = diagnostics.activity_id.as_deref(),
+                retry_count = diagnostics.retry_count,
+                query_metrics = ?diagnostics.query_metrics,
+                "Cosmos DB operation exceeded diagnostics thresholds"
+            );
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for DiagnosticsPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let attempts = AttemptCount::default();
+        let ctx = ctx.clone().with_value(attempts.clone());
+        let start = OffsetDateTime::now_utc();
+        let result = next[0].send(&ctx, request, &next[1..]).await;
+        let elapsed = (OffsetDateTime::now_utc() - start)
+            .try_into()
+            .unwrap_or_default();
+        let retry_count = attempts.0.load(Ordering::Relaxed).saturating_sub(1);
+
+        let (diagnostics, status) = match &result {
+            Ok(response) => (
+                CosmosDiagnostics {
+                    retry_count,
+                    ..CosmosDiagnostics::from_headers(response.headers())
+                },
+                Some(response.status().into()),
+            ),
+            Err(e) => {
+                let error = CosmosError::from_error(e);
+                (
+                    CosmosDiagnostics {
+                        retry_count,
+                        request_charge: error
+                            .as_ref()
+                            .and_then(CosmosError::request_charge)
+                            .unwrap_or_default(),
+                        activity_id: error
+                            .as_ref()
+                            .and_then(|e| e.activity_id().map(ToOwned::to_owned)),
+                        ..Default::default()
+                    },
+                    e.http_status().map(Into::into),
+                )
+            }
+        };
+        self.log_if_exceeded(request, elapsed, &diagnostics, status);
+        if let Some(recorder) = ctx.value::<DiagnosticsRecorder>() {
+            recorder.record(diagnostics);
+        }
+        result
+    }
+}
+
+/// A policy that counts each attempt to send a request, for the enclosing [`DiagnosticsPolicy`].
+///
+/// This policy must be a per-retry policy.
+#[derive(Debug, Clone, Default)]
+pub struct AttemptCountPolicy;
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for AttemptCountPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if let Some(attempts) = ctx.value::<AttemptCount>() {
+            attempts.0.fetch_add(1, Ordering::Relaxed);
+        }
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use azure_core::{
+        error::{ErrorKind, HttpError},
+        http::{
+            headers::Headers,
+            policies::{Policy, PolicyResult},
+            request::Request,
+            response::Response,
+            Context, Method, StatusCode,
+        },
+        Bytes,
+    };
+
+    use crate::{
+        constants,
+        pipeline::diagnostics_policy::{AttemptCountPolicy, DiagnosticsPolicy},
+        CosmosDiagnostics, DiagnosticsRecorder,
+    };
+
+    /// A fake retry policy which sends the request the configured number of times, returning the last response.
+    #[derive(Debug)]
+    struct FakeRetry(u32);
+
+    #[async_trait::async_trait]
+    impl Policy for FakeRetry {
+        async fn send(
+            &self,
+            ctx: &Context,
+            request: &mut Request,
+            next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            let mut result = next[0].send(ctx, request, &next[1..]).await;
+            for _ in 1..self.0 {
+                result = next[0].send(ctx, request, &next[1..]).await;
+            }
+            result
+        }
+    }
+
+    /// A fake transport policy which counts the requests, and responds with the configured status (as an error, if it isn't a success).
+    #[derive(Debug)]
+    struct FakeTransport {
+        status: StatusCode,
+        requests: Mutex<u32>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for FakeTransport {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            *self.requests.lock().unwrap() += 1;
+            let mut headers = Headers::new();
+            headers.insert(constants::REQUEST_CHARGE, "1.5");
+            headers.insert(constants::ACTIVITY_ID, "a1b2c3");
+            let response = Response::from_bytes(self.status, headers, Bytes::from_static(b"{}"));
+            if self.status.is_success() {
+                return Ok(response);
+            }
+            let http_error = HttpError::new(response).await;
+            Err(azure_core::Error::new(
+                ErrorKind::http_response(self.status, None),
+                http_error,
+            ))
+        }
+    }
+
+    async fn send(
+        status: StatusCode,
+        attempts: u32,
+        recorder: Option<&DiagnosticsRecorder>,
+    ) -> PolicyResult {
+        let transport = Arc::new(FakeTransport {
+            status,
+            requests: Mutex::new(0),
+        });
+        let next: Vec<Arc<dyn Policy>> = vec![
+            Arc::new(FakeRetry(attempts)),
+            Arc::new(AttemptCountPolicy),
+            transport.clone(),
+        ];
+        let mut request = Request::new(
+            "https://account.documents.azure.com/dbs".parse().unwrap(),
+            Method::Get,
+        );
+        let ctx = match recorder {
+            Some(recorder) => Context::new().with_value(recorder.clone()),
+            None => Context::new(),
+        };
+        let result = DiagnosticsPolicy::new(None)
+            .send(&ctx, &mut request, &next)
+            .await;
+        assert_eq!(attempts, *transport.requests.lock().unwrap());
+        result
+    }
+
+    #[tokio::test]
+    pub async fn records_retries() {
+        let recorder = DiagnosticsRecorder::default();
+        assert_eq!(None, recorder.diagnostics());
+
+        send(StatusCode::Ok, 1, Some(&recorder)).await.unwrap();
+        let diagnostics = recorder.diagnostics().unwrap();
+        assert_eq!(0, diagnostics.retry_count);
+        assert_eq!(1.5, diagnostics.request_charge);
+        assert_eq!(Some("a1b2c3"), diagnostics.activity_id.as_deref());
+
+        // The recorder keeps the diagnostics of the last operation.
+        send(StatusCode::Ok, 3, Some(&recorder)).await.unwrap();
+        assert_eq!(2, recorder.diagnostics().unwrap().retry_count);
+    }
+
+    #[tokio::test]
+    pub async fn records_failed_operations() {
+        let recorder = DiagnosticsRecorder::default();
+        let error = send(StatusCode::TooManyRequests, 4, Some(&recorder))
+            .await
+            .unwrap_err();
+        assert_eq!(Some(StatusCode::TooManyRequests), error.http_status());
+
+        let diagnostics = recorder.diagnostics().unwrap();
+        assert_eq!(3, diagnostics.retry_count);
+        assert_eq!(1.5, diagnostics.request_charge);
+        assert_eq!(Some("a1b2c3"), diagnostics.activity_id.as_deref());
+    }
+
+    #[tokio::test]
+    pub async fn leaves_responses_untouched() {
+        let response = send(StatusCode::Ok, 2, None).await.unwrap();
+
+        // Without a recorder, the response is passed through as the transport returned it.
+        let mut expected = Headers::new();
+        expected.insert(constants::REQUEST_CHARGE, "1.5");
+        expected.insert(constants::ACTIVITY_ID, "a1b2c3");
+        assert_eq!(expected.iter().count(), response.headers().iter().count());
+        assert_eq!(
+            CosmosDiagnostics::from_headers(&expected),
+            CosmosDiagnostics::from_headers(response.headers())
+        );
+        assert_eq!(
+            b"{}",
+            response.into_raw_body().collect().await.unwrap().as_ref()
+        );
+    }
+}
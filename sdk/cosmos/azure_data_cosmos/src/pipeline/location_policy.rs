@@ -0,0 +1,347 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Defines a policy that routes requests to regional endpoints, and fails over to other regions when an endpoint is unavailable.
+
+use std::sync::Arc;
+
+use azure_core::{
+    error::ErrorKind,
+    http::{
+        policies::{Policy, PolicyResult},
+        request::Request,
+        Context, StatusCode, Url,
+    },
+};
+use tracing::debug;
+
+use crate::{
+    constants,
+    location_cache::LocationCache,
+    pipeline::{is_connect_error, is_read_request},
+};
+
+/// The sub-status returned with a `403 Forbidden` when a write is sent to a region that no longer accepts writes.
+const WRITE_FORBIDDEN_SUB_STATUS: &str = "3";
+
+/// A policy that sends each request to the most preferred available regional endpoint.
+///
+/// If the endpoint is unavailable (the service returns `503 Service Unavailable`, `403 Forbidden` with sub-status 3, or the connection fails),
+/// the endpoint is marked unavailable and the request is sent to the next endpoint.
+/// Other I/O errors (such as the connection dropping while the response is read) only fail over reads,
+/// since a write may already have been applied by the service, and resending it could apply it twice.
+/// This policy must come after the authorization policy, so that Entra ID tokens are always requested for the account endpoint's scope.
+/// The authorization headers don't depend on the regional endpoint, so they can be reused when failing over.
+#[derive(Debug, Clone)]
+pub struct LocationPolicy {
+    locations: Arc<LocationCache>,
+}
+
+impl LocationPolicy {
+    pub fn new(locations: Arc<LocationCache>) -> Self {
+        Self { locations }
+    }
+}
+
+fn set_endpoint(request: &mut Request, endpoint: &Url) {
+    let mut url = endpoint.clone();
+    url.set_path(request.url().path());
+    url.set_query(request.url().query());
+    *request.url_mut() = url;
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for LocationPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        if !self.locations.is_enabled() {
+            return next[0].send(ctx, request, &next[1..]).await;
+        }
+
+        let endpoints = self.locations.endpoints(!is_read_request(request));
+        let last = endpoints.len() - 1;
+        for (i, endpoint) in endpoints.iter().enumerate() {
+            set_endpoint(request, endpoint);
+            let result = next[0].send(ctx, request, &next[1..]).await;
+            let should_fail_over = match &result {
+                Ok(response) => match response.status() {
+                    StatusCode::ServiceUnavailable => true,
+                    StatusCode::Forbidden => {
+                        response.headers().get_optional_str(&constants::SUB_STATUS)
+                            == Some(WRITE_FORBIDDEN_SUB_STATUS)
+                    }
+                    _ => false,
+                },
+                Err(e) => {
+                    is_connect_error(e) || (e.kind() == &ErrorKind::Io && is_read_request(request))
+                }
+            };
+
+            if !should_fail_over {
+                return result;
+            }
+
+            self.locations.mark_unavailable(endpoint);
+            if i == last {
+                return result;
+            }
+            debug!(%endpoint, "endpoint unavailable, failing over to the next endpoint");
+        }
+
+        unreachable!("the list of endpoints always contains at least the default endpoint")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use azure_core::{
+        error::ErrorKind,
+        http::{
+            headers::Headers,
+            policies::{Policy, PolicyResult},
+            request::Request,
+            response::Response,
+            Context, Method, StatusCode, Url,
+        },
+        Bytes, Error,
+    };
+
+    use crate::{
+        constants,
+        location_cache::{AccountProperties, AccountRegion, LocationCache},
+        pipeline::location_policy::LocationPolicy,
+    };
+
+    /// A fake transport policy which records the hosts it was called with and returns the pre-configured results in order.
+    #[derive(Debug)]
+    struct FakeTransport {
+        hosts: Mutex<Vec<String>>,
+        results: Mutex<Vec<fn() -> PolicyResult>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for FakeTransport {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.hosts
+                .lock()
+                .unwrap()
+                .push(request.url().host_str().unwrap().to_string());
+            let result = self.results.lock().unwrap().remove(0);
+            result()
+        }
+    }
+
+    fn ok() -> PolicyResult {
+        Ok(Response::from_bytes(
+            StatusCode::Ok,
+            Headers::new(),
+            Bytes::new(),
+        ))
+    }
+
+    fn unavailable() -> PolicyResult {
+        Ok(Response::from_bytes(
+            StatusCode::ServiceUnavailable,
+            Headers::new(),
+            Bytes::new(),
+        ))
+    }
+
+    fn write_forbidden() -> PolicyResult {
+        let mut headers = Headers::new();
+        headers.insert(constants::SUB_STATUS, "3");
+        Ok(Response::from_bytes(
+            StatusCode::Forbidden,
+            headers,
+            Bytes::new(),
+        ))
+    }
+
+    fn connection_error() -> PolicyResult {
+        Err(Error::new(
+            ErrorKind::Io,
+            io::Error::from(io::ErrorKind::ConnectionRefused),
+        ))
+    }
+
+    fn connection_reset() -> PolicyResult {
+        Err(Error::new(
+            ErrorKind::Io,
+            io::Error::from(io::ErrorKind::ConnectionReset),
+        ))
+    }
+
+    fn locations() -> Arc<LocationCache> {
+        let cache = LocationCache::new(
+            "https://account.documents.azure.com/".parse().unwrap(),
+            &["West US 2".to_string(), "East US".to_string()],
+            None,
+        );
+        let region = |name: &str, endpoint: &str| AccountRegion {
+            name: name.to_string(),
+            endpoint: endpoint.to_string(),
+        };
+        cache.update(&AccountProperties {
+            writable_locations: vec![
+                region("East US", "https://account-eastus.documents.azure.com/"),
+                region("West US 2", "https://account-westus2.documents.azure.com/"),
+            ],
+            readable_locations: vec![
+                region("East US", "https://account-eastus.documents.azure.com/"),
+                region("West US 2", "https://account-westus2.documents.azure.com/"),
+            ],
+            enable_multiple_write_locations: false,
+        });
+        Arc::new(cache)
+    }
+
+    async fn send(
+        locations: Arc<LocationCache>,
+        method: Method,
+        results: Vec<fn() -> PolicyResult>,
+    ) -> (PolicyResult, Vec<String>) {
+        let transport = Arc::new(FakeTransport {
+            hosts: Mutex::new(Vec::new()),
+            results: Mutex::new(results),
+        });
+        let policy = LocationPolicy::new(locations);
+        let url: Url = "https://account.documents.azure.com/dbs/db/colls/c/docs/item?a=b"
+            .parse()
+            .unwrap();
+        let mut request = Request::new(url, method);
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let result = policy.send(&Context::new(), &mut request, &next).await;
+        assert_eq!("/dbs/db/colls/c/docs/item", request.url().path());
+        assert_eq!(Some("a=b"), request.url().query());
+        let hosts = transport.hosts.lock().unwrap().clone();
+        (result, hosts)
+    }
+
+    #[tokio::test]
+    pub async fn reads_go_to_preferred_region() {
+        let (result, hosts) = send(locations(), Method::Get, vec![ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(vec!["account-westus2.documents.azure.com"], hosts);
+    }
+
+    #[tokio::test]
+    pub async fn writes_go_to_write_region() {
+        let (result, hosts) = send(locations(), Method::Put, vec![ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(vec!["account-eastus.documents.azure.com"], hosts);
+    }
+
+    #[tokio::test]
+    pub async fn fails_over_on_service_unavailable() {
+        let locations = locations();
+        let (result, hosts) = send(locations.clone(), Method::Get, vec![unavailable, ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(
+            vec![
+                "account-westus2.documents.azure.com",
+                "account-eastus.documents.azure.com"
+            ],
+            hosts
+        );
+
+        // The failed endpoint should now be tried last.
+        let (_, hosts) = send(locations, Method::Get, vec![ok]).await;
+        assert_eq!(vec!["account-eastus.documents.azure.com"], hosts);
+    }
+
+    #[tokio::test]
+    pub async fn fails_over_on_write_forbidden() {
+        let (result, hosts) = send(locations(), Method::Put, vec![write_forbidden, ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(
+            vec![
+                "account-eastus.documents.azure.com",
+                "account-westus2.documents.azure.com"
+            ],
+            hosts
+        );
+    }
+
+    #[tokio::test]
+    pub async fn fails_over_on_connection_error() {
+        let (result, hosts) = send(locations(), Method::Get, vec![connection_error, ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(2, hosts.len());
+    }
+
+    #[tokio::test]
+    pub async fn writes_fail_over_on_connection_error() {
+        let (result, hosts) = send(locations(), Method::Post, vec![connection_error, ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(
+            vec![
+                "account-eastus.documents.azure.com",
+                "account-westus2.documents.azure.com"
+            ],
+            hosts
+        );
+    }
+
+    #[tokio::test]
+    pub async fn reads_fail_over_on_mid_response_error() {
+        let (result, hosts) = send(locations(), Method::Get, vec![connection_reset, ok]).await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(2, hosts.len());
+    }
+
+    #[tokio::test]
+    pub async fn does_not_resend_writes_after_mid_response_error() {
+        // The create may already have been applied, so it must not be sent to another region.
+        let (result, hosts) = send(locations(), Method::Post, vec![connection_reset, ok]).await;
+        assert_eq!(ErrorKind::Io, *result.unwrap_err().kind());
+        assert_eq!(vec!["account-eastus.documents.azure.com"], hosts);
+    }
+
+    #[tokio::test]
+    pub async fn returns_last_failure_when_all_endpoints_fail() {
+        let (result, hosts) = send(
+            locations(),
+            Method::Get,
+            vec![unavailable, unavailable, unavailable],
+        )
+        .await;
+        assert_eq!(StatusCode::ServiceUnavailable, result.unwrap().status());
+        assert_eq!(
+            vec![
+                "account-westus2.documents.azure.com",
+                "account-eastus.documents.azure.com",
+                "account.documents.azure.com"
+            ],
+            hosts
+        );
+    }
+
+    #[tokio::test]
+    pub async fn does_not_fail_over_on_other_errors() {
+        fn not_found() -> PolicyResult {
+            Ok(Response::from_bytes(
+                StatusCode::NotFound,
+                Headers::new(),
+                Bytes::new(),
+            ))
+        }
+        let (result, hosts) = send(locations(), Method::Get, vec![not_found]).await;
+        assert_eq!(StatusCode::NotFound, result.unwrap().status());
+        assert_eq!(1, hosts.len());
+    }
+}
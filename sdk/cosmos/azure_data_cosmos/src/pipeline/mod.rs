@@ -2,27 +2,98 @@
 // Licensed under the MIT License.
 
 mod authorization_policy;
+mod diagnostics_policy;
+mod location_policy;
+mod retry_policy;
+mod session_policy;
 mod signature_target;
 
-use std::sync::Arc;
+use std::{
+    io,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 pub use authorization_policy::AuthorizationPolicy;
-use azure_core::http::{request::Request, response::Response, ClientOptions, Context, Method};
+use azure_core::{
+    error::ErrorKind,
+    http::{request::Request, response::Response, Context, Method},
+};
+pub use diagnostics_policy::{AttemptCountPolicy, DiagnosticsPolicy};
 use futures::TryStreamExt;
-use serde::de::DeserializeOwned;
+pub use location_policy::LocationPolicy;
+pub use retry_policy::CosmosRetryPolicy;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+pub use session_policy::SessionPolicy;
+use tracing::warn;
 use url::Url;
 
 use crate::{
     constants,
+    location_cache::{AccountProperties, LocationCache},
     models::ThroughputProperties,
+    partition_key_range_cache::{overlapping_ranges, PartitionKeyRange, PartitionKeyRangeCache},
     resource_context::{ResourceLink, ResourceType},
-    FeedPage, FeedPager, Query,
+    session::SessionContainer,
+    CosmosClientOptions, CosmosDiagnostics, CosmosError, DiagnosticsRecorder, FeedPage, FeedPager,
+    FeedRange, Query, ThroughputPoller,
 };
 
+/// The maximum number of times the partition key ranges are resolved for a page of a feed range query, when they were split or merged.
+const MAX_FEED_RANGE_RESOLUTIONS: u32 = 3;
+
+/// A part of a feed range which remains to be read by a feed range query, along with the continuation token of the query within that part.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct FeedRangeContinuation {
+    min: String,
+    max: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+}
+
+/// Determines if a request reads data (as opposed to writing it).
+pub(crate) fn is_read_request(request: &Request) -> bool {
+    match request.method() {
+        Method::Get | Method::Head => true,
+        Method::Post => request
+            .headers()
+            .get_optional_str(&constants::QUERY)
+            .is_some(),
+        _ => false,
+    }
+}
+
+/// Returns `true` if `error` was raised while connecting to the endpoint, meaning the request was never sent.
+pub(crate) fn is_connect_error(error: &azure_core::Error) -> bool {
+    if error.kind() != &ErrorKind::Io {
+        return false;
+    }
+    let mut source = error
+        .get_ref()
+        .map(|e| e as &(dyn std::error::Error + 'static));
+    while let Some(e) = source {
+        if let Some(e) = e.downcast_ref::<io::Error>() {
+            if matches!(
+                e.kind(),
+                io::ErrorKind::ConnectionRefused
+                    | io::ErrorKind::AddrNotAvailable
+                    | io::ErrorKind::NotConnected
+            ) {
+                return true;
+            }
+        }
+        source = e.source();
+    }
+    false
+}
+
 /// Newtype that wraps an Azure Core pipeline to provide a Cosmos-specific pipeline which configures our authorization policy and enforces that a [`ResourceType`] is set on the context.
 #[derive(Debug, Clone)]
 pub struct CosmosPipeline {
     pub endpoint: Url,
+    pub sessions: Arc<SessionContainer>,
+    locations: Arc<LocationCache>,
+    partition_key_ranges: Arc<PartitionKeyRangeCache>,
     pipeline: azure_core::http::Pipeline,
 }
 
@@ -30,17 +101,64 @@ impl CosmosPipeline {
     pub fn new(
         endpoint: Url,
         auth_policy: AuthorizationPolicy,
-        client_options: ClientOptions,
+        options: CosmosClientOptions,
     ) -> Self {
+        let sessions = Arc::new(SessionContainer::default());
+        let locations = Arc::new(LocationCache::new(
+            endpoint.clone(),
+            &options.preferred_regions,
+            options.location_refresh_interval,
+        ));
+        let partition_key_ranges = Arc::new(PartitionKeyRangeCache::default());
+
         CosmosPipeline {
             endpoint,
             pipeline: azure_core::http::Pipeline::new(
                 option_env!("CARGO_PKG_NAME"),
                 option_env!("CARGO_PKG_VERSION"),
-                client_options,
-                Vec::new(),
-                vec![Arc::new(auth_policy)],
+                options.client_options,
+                vec![Arc::new(DiagnosticsPolicy::new(
+                    options.diagnostics_thresholds,
+                ))],
+                vec![
+                    Arc::new(CosmosRetryPolicy::new(
+                        options.throttling_retry_options,
+                        partition_key_ranges.clone(),
+                    )),
+                    Arc::new(AttemptCountPolicy),
+                    Arc::new(SessionPolicy::new(sessions.clone())),
+                    Arc::new(auth_policy),
+                    Arc::new(LocationPolicy::new(locations.clone())),
+                ],
             ),
+            sessions,
+            locations,
+            partition_key_ranges,
+        }
+    }
+
+    /// Refreshes the regional endpoints of the account, if endpoint discovery is enabled and the current topology is stale.
+    ///
+    /// Failures are logged and otherwise ignored: requests continue to use the endpoints we already know about (or the default endpoint).
+    async fn refresh_locations(&self, ctx: &Context<'_>) {
+        if !self.locations.begin_refresh() {
+            return;
+        }
+
+        let account_link = ResourceLink::root(ResourceType::DatabaseAccount);
+        let mut req = Request::new(self.url(&account_link), Method::Get);
+        let ctx = ctx.clone().with_value(account_link);
+        let result = async {
+            self.pipeline
+                .send::<AccountProperties>(&ctx, &mut req)
+                .await?
+                .into_body()
+                .await
+        }
+        .await;
+        match result {
+            Ok(account) => self.locations.update(&account),
+            Err(e) => warn!("failed to refresh Cosmos DB account endpoints: {}", e),
         }
     }
 
@@ -59,6 +177,7 @@ impl CosmosPipeline {
         request: &mut Request,
         resource_link: ResourceLink,
     ) -> azure_core::Result<Response<T>> {
+        self.refresh_locations(&ctx).await;
         let ctx = ctx.with_value(resource_link);
         self.pipeline.send(&ctx, request).await
     }
@@ -70,14 +189,14 @@ impl CosmosPipeline {
         mut base_request: Request,
         resource_link: ResourceLink,
     ) -> azure_core::Result<FeedPager<T>> {
-        base_request.insert_header(constants::QUERY, "True");
-        base_request.add_mandatory_header(&constants::QUERY_CONTENT_TYPE);
-        base_request.set_json(&query)?;
+        prepare_query_request(&mut base_request, &query)?;
 
         // We have to double-clone here.
         // First we clone the pipeline to pass it in to the closure
-        let pipeline = self.pipeline.clone();
+        let pipeline = self.clone();
         let ctx = ctx.with_value(resource_link).into_owned();
+        // The diagnostics of each page are accumulated, so that callers can see the total cost of the query.
+        let totals = Arc::new(Mutex::new(CosmosDiagnostics::default()));
         Ok(FeedPager::from_callback(move |continuation| {
             // Then we have to clone it again to pass it in to the async block.
             // This is because Pageable can't borrow any data, it has to own it all.
@@ -85,19 +204,189 @@ impl CosmosPipeline {
             let pipeline = pipeline.clone();
             let mut req = base_request.clone();
             let ctx = ctx.clone();
+            let totals = totals.clone();
             async move {
                 if let Some(continuation) = continuation {
                     req.insert_header(constants::CONTINUATION, continuation);
                 }
 
-                let resp = pipeline.send(&ctx, &mut req).await?;
-                let page = FeedPage::<T>::from_response(resp).await?;
+                let mut page = pipeline.send_feed_page::<T>(&ctx, &mut req).await?;
+                page.accumulate_diagnostics(&totals);
 
                 Ok(page.into())
             }
         }))
     }
 
+    /// Sends a request for a page of a feed, recording the diagnostics of the page.
+    async fn send_feed_page<T: DeserializeOwned>(
+        &self,
+        ctx: &Context<'_>,
+        request: &mut Request,
+    ) -> azure_core::Result<FeedPage<T>> {
+        self.refresh_locations(ctx).await;
+        let recorder = DiagnosticsRecorder::default();
+        let ctx = ctx.clone().with_value(recorder.clone());
+        let resp = self.pipeline.send(&ctx, request).await?;
+        let mut page = FeedPage::<T>::from_response(resp).await?;
+        if let Some(diagnostics) = recorder.diagnostics() {
+            page.set_diagnostics(diagnostics);
+        }
+        Ok(page)
+    }
+
+    /// Sends a query to each of the partition key ranges overlapping `feed_range`, one after the other.
+    ///
+    /// The continuation tokens of the pages list the parts of the feed range which remain to be read, by effective partition key, along with the continuation within the part being read.
+    /// The partition key ranges are resolved again for each page, so that a query can continue after a partition key range was split:
+    /// the part being read is then split in the same way, and read from each of the new ranges.
+    pub fn send_feed_range_query_request<T: DeserializeOwned>(
+        &self,
+        ctx: Context<'_>,
+        query: Query,
+        mut base_request: Request,
+        resource_link: ResourceLink,
+        container_link: ResourceLink,
+        feed_range: FeedRange,
+    ) -> azure_core::Result<FeedPager<T>> {
+        prepare_query_request(&mut base_request, &query)?;
+        base_request.insert_header(constants::ENABLE_CROSS_PARTITION_QUERY, "True");
+
+        let pipeline = self.clone();
+        let ctx = ctx.with_value(resource_link).into_owned();
+        let totals = Arc::new(Mutex::new(CosmosDiagnostics::default()));
+        Ok(FeedPager::from_callback(
+            move |continuation: Option<String>| {
+                let pipeline = pipeline.clone();
+                let base_request = base_request.clone();
+                let ctx = ctx.clone();
+                let totals = totals.clone();
+                let container_link = container_link.clone();
+                let feed_range = feed_range.clone();
+                async move {
+                    let mut remaining = match continuation.as_deref() {
+                        Some(c) => parse_feed_range_continuation(c)?,
+                        None => vec![FeedRangeContinuation {
+                            min: feed_range.min_inclusive().to_string(),
+                            max: feed_range.max_exclusive().to_string(),
+                            token: None,
+                        }],
+                    };
+
+                    let mut resolutions = 0;
+                    let mut page = loop {
+                        resolutions += 1;
+                        let ranges = pipeline
+                            .partition_key_ranges(ctx.clone(), &container_link, resolutions > 1)
+                            .await?;
+                        let current = FeedRange::new(&remaining[0].min, &remaining[0].max);
+                        let targets: Vec<_> = overlapping_ranges(&ranges, &current).collect();
+                        let Some(target) = targets.first() else {
+                            return Err(azure_core::Error::message(
+                                ErrorKind::DataConversion,
+                                format!(
+                                    "feed range {} doesn't overlap any partition key range of the container",
+                                    current
+                                ),
+                            ));
+                        };
+
+                        // The part being read is split along the partition key ranges it overlaps.
+                        // Its continuation token remains valid in each of them, as the service filters the results by effective partition key.
+                        let token = remaining[0].token.clone();
+                        let parts: Vec<_> = targets
+                            .iter()
+                            .map(|range| FeedRangeContinuation {
+                                min: current
+                                    .min_inclusive()
+                                    .max(&range.min_inclusive)
+                                    .to_string(),
+                                max: current
+                                    .max_exclusive()
+                                    .min(&range.max_exclusive)
+                                    .to_string(),
+                                token: token.clone(),
+                            })
+                            .collect();
+                        remaining.splice(0..1, parts);
+                        let part = &remaining[0];
+
+                        let mut req = base_request.clone();
+                        req.insert_header(constants::PARTITION_KEY_RANGE_ID, target.id.clone());
+                        // When the part only covers some of the partition key range, the service filters the results to the requested effective partition keys.
+                        if part.min != target.min_inclusive || part.max != target.max_exclusive {
+                            req.insert_header(constants::START_EPK, part.min.clone());
+                            req.insert_header(constants::END_EPK, part.max.clone());
+                        }
+                        if let Some(token) = &part.token {
+                            req.insert_header(constants::CONTINUATION, token.clone());
+                        }
+
+                        match pipeline.send_feed_page::<T>(&ctx, &mut req).await {
+                            Ok(page) => break page,
+                            // The partition key range was split or merged since the ranges were cached: they're read again.
+                            Err(e)
+                                if resolutions < MAX_FEED_RANGE_RESOLUTIONS
+                                    && CosmosError::from_error(&e)
+                                        .is_some_and(|e| e.is_partition_key_range_gone()) => {}
+                            Err(e) => return Err(e),
+                        }
+                    };
+                    page.accumulate_diagnostics(&totals);
+
+                    match page.continuation() {
+                        Some(token) => remaining[0].token = Some(token.to_string()),
+                        None => {
+                            remaining.remove(0);
+                        }
+                    }
+                    let next = match remaining.is_empty() {
+                        true => None,
+                        false => Some(serde_json::to_string(&remaining)?),
+                    };
+                    page.set_continuation(next);
+                    Ok(page.into())
+                }
+            },
+        ))
+    }
+
+    /// Gets the partition key ranges of a container, sorted by their effective partition keys.
+    ///
+    /// The ranges are cached, and only read from the service the first time they're needed, or when `force_refresh` is set.
+    pub async fn partition_key_ranges(
+        &self,
+        ctx: Context<'_>,
+        container_link: &ResourceLink,
+        force_refresh: bool,
+    ) -> azure_core::Result<Arc<Vec<PartitionKeyRange>>> {
+        let container_path = container_link.path();
+        if !force_refresh {
+            if let Some(ranges) = self.partition_key_ranges.get(&container_path) {
+                return Ok(ranges);
+            }
+        }
+
+        let link = container_link.feed(ResourceType::PartitionKeyRanges);
+        let mut ranges = Vec::new();
+        let mut continuation = None;
+        loop {
+            let mut req = Request::new(self.url(&link), Method::Get);
+            if let Some(continuation) = continuation.take() {
+                req.insert_header(constants::CONTINUATION, continuation);
+            }
+            let resp = self.send(ctx.clone(), &mut req, link.clone()).await?;
+            let page = FeedPage::<PartitionKeyRange>::from_response(resp).await?;
+            let (items, next, _) = page.deconstruct();
+            ranges.extend(items);
+            match next {
+                Some(next) => continuation = Some(next),
+                None => break,
+            }
+        }
+        Ok(self.partition_key_ranges.insert(&container_path, ranges))
+    }
+
     /// Helper function to read a throughput offer given a resource ID.
     ///
     /// ## Arguments
@@ -172,4 +461,463 @@ impl CosmosPipeline {
 
         self.send(context, &mut req, offer_link).await
     }
+
+    /// Helper function to change a throughput offer given a resource ID, returning a [`ThroughputPoller`] tracking the change.
+    ///
+    /// When `migrate` is set and the new throughput doesn't use the same mode (manual or autoscale) as the current one,
+    /// the offer is migrated to the new mode. Otherwise, the offer is replaced.
+    ///
+    /// ## Arguments
+    /// * `context` - The context for the request.
+    /// * `resource_id` - The resource ID to update the throughput offer for.
+    /// * `throughput` - The new throughput to set.
+    /// * `migrate` - Whether to migrate the offer between manual and autoscale throughput.
+    /// * `polling_interval` - The polling interval of the returned poller.
+    pub async fn begin_throughput_offer_update(
+        &self,
+        context: Context<'_>,
+        resource_id: &str,
+        throughput: ThroughputProperties,
+        migrate: bool,
+        polling_interval: Option<Duration>,
+    ) -> azure_core::Result<ThroughputPoller> {
+        let context = context.into_owned();
+        let Some(response) = self
+            .read_throughput_offer(context.clone(), resource_id)
+            .await?
+        else {
+            return Err(azure_core::Error::message(
+                ErrorKind::Other,
+                format!("resource {} doesn't have a throughput offer", resource_id),
+            ));
+        };
+        let mut offer = response.into_body().await?;
+        let migrating = migrate && offer.is_autoscale() != throughput.is_autoscale();
+        offer.offer = throughput.offer;
+
+        let offer_link = ResourceLink::root(ResourceType::Offers).item(&offer.offer_id);
+        let mut url = self.url(&offer_link);
+        let method = if migrating {
+            url.path_segments_mut()
+                .expect("offer URLs always have a path")
+                .push("_offerMigration");
+            Method::Post
+        } else {
+            Method::Put
+        };
+        let mut req = Request::new(url, method);
+        req.set_json(&offer)?;
+
+        let response = self
+            .send::<ThroughputProperties>(context.clone(), &mut req, offer_link.clone())
+            .await?;
+        let (_, headers, body) = response.deconstruct();
+        // Migrations may be accepted without returning the offer, in which case the offer we sent is tracked until it's read again.
+        let body = body.collect().await?;
+        if !body.is_empty() {
+            offer = serde_json::from_slice(&body)?;
+        }
+        offer.apply_headers(&headers);
+        if migrating
+            && headers
+                .get_optional_str(&constants::OFFER_REPLACE_PENDING)
+                .is_none()
+        {
+            // The service always applies migrations asynchronously, so read the offer again to get its actual state.
+            offer.set_replace_pending();
+        }
+
+        Ok(ThroughputPoller::new(
+            self.clone(),
+            context,
+            offer_link,
+            offer,
+            polling_interval,
+        ))
+    }
+}
+
+/// Sets the headers and body shared by all query requests.
+pub(crate) fn prepare_query_request(
+    request: &mut Request,
+    query: &Query,
+) -> azure_core::Result<()> {
+    request.insert_header(constants::QUERY, "True");
+    request.add_mandatory_header(&constants::QUERY_CONTENT_TYPE);
+    request.set_json(query)
+}
+
+/// Parses a continuation token produced by [`CosmosPipeline::send_feed_range_query_request`].
+fn parse_feed_range_continuation(
+    continuation: &str,
+) -> azure_core::Result<Vec<FeedRangeContinuation>> {
+    serde_json::from_str::<Vec<FeedRangeContinuation>>(continuation)
+        .ok()
+        .filter(|remaining| !remaining.is_empty())
+        .ok_or_else(|| {
+            azure_core::Error::message(
+                ErrorKind::DataConversion,
+                format!("invalid feed range continuation token: {}", continuation),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use azure_core::{
+        credentials::{AccessToken, TokenCredential},
+        error::ErrorKind,
+        http::{
+            headers::Headers, ClientMethodOptions, ClientOptions, Context, Method, Response,
+            StatusCode, TransportOptions,
+        },
+        Bytes,
+    };
+    use azure_core_test::http::MockHttpClient;
+    use futures::{FutureExt, TryStreamExt};
+    use time::OffsetDateTime;
+
+    use crate::{
+        constants, CosmosClient, CosmosClientOptions, CosmosError, DiagnosticsRecorder,
+        ItemOptions, ThrottlingRetryOptions,
+    };
+
+    #[derive(Debug)]
+    struct TestTokenCredential;
+
+    #[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+    #[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+    impl TokenCredential for TestTokenCredential {
+        async fn get_token(&self, _scopes: &[&str]) -> azure_core::Result<AccessToken> {
+            Ok(AccessToken::new(
+                "test_token",
+                OffsetDateTime::now_utc().saturating_add(time::Duration::minutes(5)),
+            ))
+        }
+    }
+
+    const ACCOUNT: &str = r#"{
+        "id": "account",
+        "writableLocations": [{"name": "East US", "databaseAccountEndpoint": "https://account-eastus.documents.azure.com:443/"}],
+        "readableLocations": [
+            {"name": "East US", "databaseAccountEndpoint": "https://account-eastus.documents.azure.com:443/"},
+            {"name": "West US 2", "databaseAccountEndpoint": "https://account-westus2.documents.azure.com:443/"}
+        ],
+        "enableMultipleWriteLocations": false
+    }"#;
+
+    #[tokio::test]
+    pub async fn routes_requests_to_discovered_regions() -> Result<(), Box<dyn std::error::Error>> {
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let requests_clone = requests.clone();
+        let mock_client = MockHttpClient::new(move |req| {
+            let requests = requests_clone.clone();
+            async move {
+                let host = req.url().host_str().unwrap().to_string();
+                let path = req.url().path().to_string();
+                let body = if path == "/" {
+                    Bytes::from_static(ACCOUNT.as_bytes())
+                } else {
+                    Bytes::from_static(br#"{"id":"item1"}"#)
+                };
+                requests.lock().unwrap().push((*req.method(), host, path));
+                Ok(Response::from_bytes(StatusCode::Ok, Headers::new(), body))
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            preferred_regions: vec!["westus2".to_string()],
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        let container_client = client.database_client("db").container_client("c");
+        container_client.read_item("pk", "item1", None).await?;
+        container_client
+            .upsert_item("pk", serde_json::json!({"id": "item1"}), None)
+            .await?;
+
+        assert_eq!(
+            vec![
+                (
+                    Method::Get,
+                    "account.documents.azure.com".to_string(),
+                    "/".to_string()
+                ),
+                (
+                    Method::Get,
+                    "account-westus2.documents.azure.com".to_string(),
+                    "/dbs/db/colls/c/docs/item1".to_string()
+                ),
+                (
+                    Method::Post,
+                    "account-eastus.documents.azure.com".to_string(),
+                    "/dbs/db/colls/c/docs".to_string()
+                ),
+            ],
+            *requests.lock().unwrap()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn uses_default_endpoint_without_preferred_regions(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let hosts = Arc::new(Mutex::new(Vec::new()));
+        let hosts_clone = hosts.clone();
+        let mock_client = MockHttpClient::new(move |req| {
+            let hosts = hosts_clone.clone();
+            async move {
+                hosts
+                    .lock()
+                    .unwrap()
+                    .push(req.url().host_str().unwrap().to_string());
+                Ok(Response::from_bytes(
+                    StatusCode::Ok,
+                    Headers::new(),
+                    Bytes::from_static(br#"{"id":"item1"}"#),
+                ))
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        client
+            .database_client("db")
+            .container_client("c")
+            .read_item("pk", "item1", None)
+            .await?;
+
+        assert_eq!(vec!["account.documents.azure.com"], *hosts.lock().unwrap());
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn throttled_requests_are_only_retried_by_the_cosmos_retry_policy(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let attempts = Arc::new(Mutex::new(0));
+        let attempts_clone = attempts.clone();
+        let mock_client = MockHttpClient::new(move |_| {
+            let attempts = attempts_clone.clone();
+            async move {
+                *attempts.lock().unwrap() += 1;
+                let mut headers = Headers::new();
+                headers.insert(constants::RETRY_AFTER_MS, "1");
+                Ok(Response::from_bytes(
+                    StatusCode::TooManyRequests,
+                    headers,
+                    Bytes::new(),
+                ))
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            throttling_retry_options: ThrottlingRetryOptions {
+                max_retries: 3,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        let recorder = DiagnosticsRecorder::default();
+        let options = ItemOptions {
+            method_options: ClientMethodOptions {
+                context: Context::new().with_value(recorder.clone()),
+            },
+            ..Default::default()
+        };
+        let error = client
+            .database_client("db")
+            .container_client("c")
+            .read_item("pk", "item1", Some(options))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            Some(StatusCode::TooManyRequests),
+            CosmosError::from_error(&error).map(|e| e.status())
+        );
+        // The first attempt, and 3 retries.
+        assert_eq!(4, *attempts.lock().unwrap());
+        assert_eq!(3, recorder.diagnostics().unwrap().retry_count);
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn writes_are_not_resent_after_io_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let attempts = Arc::new(Mutex::new(0));
+        let attempts_clone = attempts.clone();
+        let mock_client = MockHttpClient::new(move |_| {
+            let attempts = attempts_clone.clone();
+            async move {
+                *attempts.lock().unwrap() += 1;
+                Err(azure_core::Error::new(
+                    ErrorKind::Io,
+                    std::io::Error::from(std::io::ErrorKind::ConnectionReset),
+                ))
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        let error = client
+            .database_client("db")
+            .container_client("c")
+            .create_item("pk", serde_json::json!({"id": "item1"}), None)
+            .await
+            .unwrap_err();
+
+        assert_eq!(ErrorKind::Other, *error.kind());
+        assert_eq!(1, *attempts.lock().unwrap());
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn reads_are_retried_after_transient_errors() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let attempts = Arc::new(Mutex::new(0));
+        let attempts_clone = attempts.clone();
+        let mock_client = MockHttpClient::new(move |_| {
+            let attempts = attempts_clone.clone();
+            async move {
+                let mut attempts = attempts.lock().unwrap();
+                *attempts += 1;
+                match *attempts {
+                    1 => Ok(Response::from_bytes(
+                        StatusCode::ServiceUnavailable,
+                        Headers::new(),
+                        Bytes::new(),
+                    )),
+                    2 => Err(azure_core::Error::new(
+                        ErrorKind::Io,
+                        std::io::Error::from(std::io::ErrorKind::ConnectionReset),
+                    )),
+                    _ => Ok(Response::from_bytes(
+                        StatusCode::Ok,
+                        Headers::new(),
+                        Bytes::from_static(br#"{"id":"item1"}"#),
+                    )),
+                }
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        let item: serde_json::Value = client
+            .database_client("db")
+            .container_client("c")
+            .read_item("pk", "item1", None)
+            .await?
+            .into_json_body()
+            .await?;
+
+        assert_eq!("item1", item["id"]);
+        assert_eq!(3, *attempts.lock().unwrap());
+        Ok(())
+    }
+
+    #[tokio::test]
+    pub async fn feed_pages_include_retry_count() -> Result<(), Box<dyn std::error::Error>> {
+        let attempts = Arc::new(Mutex::new(0));
+        let attempts_clone = attempts.clone();
+        let mock_client = MockHttpClient::new(move |_| {
+            let attempts = attempts_clone.clone();
+            async move {
+                let mut attempts = attempts.lock().unwrap();
+                *attempts += 1;
+                let mut headers = Headers::new();
+                headers.insert(constants::REQUEST_CHARGE, "2");
+                if *attempts == 1 {
+                    headers.insert(constants::RETRY_AFTER_MS, "1");
+                    return Ok(Response::from_bytes(
+                        StatusCode::TooManyRequests,
+                        headers,
+                        Bytes::new(),
+                    ));
+                }
+                Ok(Response::from_bytes(
+                    StatusCode::Ok,
+                    headers,
+                    Bytes::from_static(br#"{"Documents":[{"id":"item1"}]}"#),
+                ))
+            }
+            .boxed()
+        });
+
+        let options = CosmosClientOptions {
+            client_options: ClientOptions {
+                transport: Some(TransportOptions::new(Arc::new(mock_client))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let client = CosmosClient::new(
+            "https://account.documents.azure.com/",
+            Arc::new(TestTokenCredential),
+            Some(options),
+        )?;
+        let mut pages = client
+            .database_client("db")
+            .container_client("c")
+            .query_items::<serde_json::Value>("SELECT * FROM c", "pk", None)?;
+        let page = pages.try_next().await?.unwrap();
+
+        assert_eq!(1, page.items().len());
+        assert_eq!(1, page.diagnostics().retry_count);
+        assert_eq!(2.0, page.diagnostics().request_charge);
+        assert_eq!(1, page.total_diagnostics().retry_count);
+        Ok(())
+    }
 }
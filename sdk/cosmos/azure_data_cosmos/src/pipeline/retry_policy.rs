@@ -0,0 +1,424 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Defines a policy that retries the transient errors specific to Cosmos DB, such as throttling and partition splits.
+
+use std::{sync::Arc, time::Duration};
+
+use azure_core::{
+    date::OffsetDateTime,
+    error::{ErrorKind, HttpError},
+    http::{
+        policies::{get_retry_after, Policy, PolicyResult},
+        request::Request,
+        response::Response,
+        Context,
+    },
+    sleep::sleep,
+};
+use tracing::debug;
+
+use crate::{
+    constants::{self, substatus},
+    partition_key_range_cache::PartitionKeyRangeCache,
+    pipeline::{is_connect_error, is_read_request},
+    resource_context::ResourceLink,
+    ThrottlingRetryOptions,
+};
+
+/// The delay before retrying a throttled request, when the service doesn't provide one.
+const DEFAULT_THROTTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// The delay before the first retry of a `449 Retry With` response, doubled for each retry up to [`MAX_RETRY_WITH_DELAY`].
+const INITIAL_RETRY_WITH_DELAY: Duration = Duration::from_millis(10);
+
+/// The maximum delay between two retries of a `449 Retry With` response.
+const MAX_RETRY_WITH_DELAY: Duration = Duration::from_secs(1);
+
+/// The maximum number of retries of `449 Retry With` responses.
+const MAX_RETRY_WITH_RETRIES: u32 = 10;
+
+/// The delay before the first retry when the session isn't available yet (`404 Not Found` with sub-status 1002), doubled for each retry.
+const INITIAL_READ_SESSION_DELAY: Duration = Duration::from_millis(5);
+
+/// The maximum number of retries after a partition split (`410 Gone`) or when the session isn't available yet (`404 Not Found` with sub-status 1002).
+const MAX_PARTITION_RETRIES: u32 = 2;
+
+/// The `449 Retry With` status code, returned when a write conflicts with a concurrent write to the same item.
+const RETRY_WITH: u16 = 449;
+
+/// What to do with a failed response.
+enum RetryAction {
+    Retry(Duration),
+    Return,
+    Fail,
+}
+
+/// A policy that retries the transient errors specific to Cosmos DB:
+///
+/// * `429 Too Many Requests` is retried after the delay in the `x-ms-retry-after-ms` header, until either the maximum number of retries
+///   or the maximum total wait time of the [`ThrottlingRetryOptions`] is reached.
+/// * `449 Retry With` is retried with an exponential backoff.
+/// * `410 Gone` with a sub-status indicating that the partition key range was split or moved invalidates the cached partition key ranges of the container,
+///   then the request is retried, unless it targets a specific partition key range.
+/// * `404 Not Found` with the sub-status 1002 (the replica hasn't caught up with the session token yet) is retried with an exponential backoff.
+///
+/// Other transient failures (`408`, `500`, `502`, `503`, `504` and I/O errors) are left to the generic retry policy of the pipeline, which runs before this one.
+/// This policy must be the first per-retry policy, so that it sits right below the generic retry policy, and the other per-retry policies (such as authorization) run again for each retry.
+/// It returns errors the generic retry policy doesn't retry for the throttled requests it gives up on,
+/// and for writes failing with an I/O error after they may have reached the service, since resending them could apply them twice.
+#[derive(Debug, Clone)]
+pub struct CosmosRetryPolicy {
+    options: ThrottlingRetryOptions,
+    partition_key_ranges: Arc<PartitionKeyRangeCache>,
+}
+
+impl CosmosRetryPolicy {
+    pub fn new(
+        options: ThrottlingRetryOptions,
+        partition_key_ranges: Arc<PartitionKeyRangeCache>,
+    ) -> Self {
+        Self {
+            options,
+            partition_key_ranges,
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for CosmosRetryPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        let mut throttle_retries = 0;
+        let mut throttle_wait = Duration::ZERO;
+        let mut retry_with_retries = 0;
+        let mut partition_retries = 0;
+        let mut read_session_retries = 0;
+
+        loop {
+            let response = match next[0].send(ctx, request, &next[1..]).await {
+                Ok(response) => response,
+                Err(e)
+                    if e.kind() == &ErrorKind::Io
+                        && !is_read_request(request)
+                        && !is_connect_error(&e) =>
+                {
+                    return Err(write_io_error(e));
+                }
+                Err(e) => return Err(e),
+            };
+            let status = response.status();
+            if status.is_success() {
+                return Ok(response);
+            }
+            let substatus = response
+                .headers()
+                .get_optional_str(&constants::SUB_STATUS)
+                .and_then(|s| s.parse::<u32>().ok());
+
+            let action = match (u16::from(status), substatus) {
+                (429, _) => {
+                    let delay = get_retry_after(response.headers(), OffsetDateTime::now_utc)
+                        .unwrap_or(DEFAULT_THROTTLE_DELAY);
+                    if throttle_retries < self.options.max_retries
+                        && throttle_wait + delay <= self.options.max_wait
+                    {
+                        throttle_retries += 1;
+                        throttle_wait += delay;
+                        RetryAction::Retry(delay)
+                    } else {
+                        RetryAction::Fail
+                    }
+                }
+                (RETRY_WITH, _) if retry_with_retries < MAX_RETRY_WITH_RETRIES => {
+                    let delay = INITIAL_RETRY_WITH_DELAY
+                        .saturating_mul(1 << retry_with_retries)
+                        .min(MAX_RETRY_WITH_DELAY);
+                    retry_with_retries += 1;
+                    RetryAction::Retry(delay)
+                }
+                (
+                    410,
+                    Some(
+                        substatus::PARTITION_KEY_RANGE_GONE
+                        | substatus::COMPLETING_SPLIT
+                        | substatus::COMPLETING_PARTITION_MIGRATION,
+                    ),
+                ) => {
+                    if let Some(container_path) = ctx
+                        .value::<ResourceLink>()
+                        .and_then(ResourceLink::container_path)
+                    {
+                        self.partition_key_ranges.remove(&container_path);
+                    }
+                    // A request targeting a partition key range which no longer exists would fail again, the caller has to target the new ranges.
+                    let targets_range = request
+                        .headers()
+                        .get_optional_str(&constants::PARTITION_KEY_RANGE_ID)
+                        .is_some();
+                    if !targets_range && partition_retries < MAX_PARTITION_RETRIES {
+                        partition_retries += 1;
+                        RetryAction::Retry(Duration::ZERO)
+                    } else {
+                        RetryAction::Return
+                    }
+                }
+                (404, Some(substatus::READ_SESSION_NOT_AVAILABLE))
+                    if read_session_retries < MAX_PARTITION_RETRIES =>
+                {
+                    let delay =
+                        INITIAL_READ_SESSION_DELAY.saturating_mul(1 << read_session_retries);
+                    read_session_retries += 1;
+                    RetryAction::Retry(delay)
+                }
+                _ => RetryAction::Return,
+            };
+
+            match action {
+                RetryAction::Retry(delay) => {
+                    debug!(%status, ?substatus, ?delay, "retrying Cosmos DB request");
+                    sleep(delay).await;
+                }
+                RetryAction::Return => return Ok(response),
+                RetryAction::Fail => return Err(throttled_error(response).await),
+            }
+        }
+    }
+}
+
+/// Converts a throttled response into an error, since the generic retry policy would otherwise retry the `429` response on top of the retries of this policy.
+async fn throttled_error(response: Response) -> azure_core::Error {
+    let status = response.status();
+    let http_error = HttpError::new(response).await;
+    let error_kind = ErrorKind::http_response(
+        status,
+        http_error.error_code().map(std::borrow::ToOwned::to_owned),
+    );
+    azure_core::Error::full(
+        error_kind,
+        http_error,
+        "request was throttled, and the maximum number of retries or wait time was reached",
+    )
+}
+
+/// Wraps an I/O error raised while sending a write into an error of another kind, so that the generic retry policy doesn't send the write again.
+fn write_io_error(error: azure_core::Error) -> azure_core::Error {
+    azure_core::Error::full(
+        ErrorKind::Other,
+        error,
+        "the connection failed after the write was sent, it isn't retried since the service may have applied it",
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use azure_core::{
+        http::{
+            headers::Headers,
+            policies::{Policy, PolicyResult},
+            request::Request,
+            response::Response,
+            Context, Method, StatusCode, Url,
+        },
+        Bytes,
+    };
+
+    use crate::{
+        constants,
+        partition_key_range_cache::{PartitionKeyRange, PartitionKeyRangeCache},
+        pipeline::retry_policy::{CosmosRetryPolicy, INITIAL_READ_SESSION_DELAY},
+        resource_context::{ResourceLink, ResourceType},
+        CosmosError, ThrottlingRetryOptions,
+    };
+
+    /// A status code, with optional sub-status and `x-ms-retry-after-ms` headers.
+    type FakeResponse = (u16, Option<&'static str>, Option<&'static str>);
+
+    /// A fake transport policy which returns the pre-configured responses in order, and counts the requests.
+    #[derive(Debug)]
+    struct FakeTransport {
+        responses: Mutex<Vec<FakeResponse>>,
+        requests: Mutex<usize>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for FakeTransport {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            _request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            *self.requests.lock().unwrap() += 1;
+            let (status, substatus, retry_after) = self.responses.lock().unwrap().remove(0);
+            let mut headers = Headers::new();
+            if let Some(substatus) = substatus {
+                headers.insert(constants::SUB_STATUS, substatus);
+            }
+            if let Some(retry_after) = retry_after {
+                headers.insert(constants::RETRY_AFTER_MS, retry_after);
+            }
+            Ok(Response::from_bytes(
+                StatusCode::from(status),
+                headers,
+                Bytes::new(),
+            ))
+        }
+    }
+
+    fn container_link() -> ResourceLink {
+        ResourceLink::root(ResourceType::Databases)
+            .item("db")
+            .feed(ResourceType::Containers)
+            .item("c")
+    }
+
+    async fn send(
+        responses: Vec<FakeResponse>,
+        cache: Arc<PartitionKeyRangeCache>,
+        target_range: bool,
+    ) -> (PolicyResult, usize) {
+        let transport = Arc::new(FakeTransport {
+            responses: Mutex::new(responses),
+            requests: Mutex::new(0),
+        });
+        let policy = CosmosRetryPolicy::new(
+            ThrottlingRetryOptions {
+                max_retries: 3,
+                max_wait: Duration::from_millis(50),
+            },
+            cache,
+        );
+        let url: Url = "https://account.documents.azure.com/dbs/db/colls/c/docs"
+            .parse()
+            .unwrap();
+        let mut request = Request::new(url, Method::Post);
+        if target_range {
+            request.insert_header(constants::PARTITION_KEY_RANGE_ID, "0");
+        }
+        let ctx = Context::new().with_value(container_link().feed(ResourceType::Items));
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        let result = policy.send(&ctx, &mut request, &next).await;
+        let requests = *transport.requests.lock().unwrap();
+        (result, requests)
+    }
+
+    #[tokio::test]
+    pub async fn retries_throttled_requests_within_budget() {
+        let cache = Arc::new(PartitionKeyRangeCache::default());
+        let (result, requests) = send(
+            vec![
+                (429, None, Some("10")),
+                (429, None, Some("10")),
+                (200, None, None),
+            ],
+            cache.clone(),
+            false,
+        )
+        .await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(3, requests);
+
+        // The second delay would exceed the maximum wait time, so the error is returned.
+        let (result, requests) = send(
+            vec![
+                (429, Some("3200"), Some("30")),
+                (429, Some("3200"), Some("30")),
+            ],
+            cache,
+            false,
+        )
+        .await;
+        let error = CosmosError::from_error(&result.unwrap_err()).unwrap();
+        assert_eq!(StatusCode::TooManyRequests, error.status());
+        assert_eq!(Some(3200), error.substatus());
+        assert_eq!(2, requests);
+    }
+
+    #[tokio::test]
+    pub async fn retries_retry_with_and_read_session_not_available() {
+        let cache = Arc::new(PartitionKeyRangeCache::default());
+        let (result, requests) = send(
+            vec![
+                (449, None, None),
+                (404, Some("1002"), None),
+                (200, None, None),
+            ],
+            cache.clone(),
+            false,
+        )
+        .await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(3, requests);
+
+        // A plain 404 isn't retried.
+        let (result, requests) = send(vec![(404, None, None)], cache, false).await;
+        assert_eq!(StatusCode::NotFound, result.unwrap().status());
+        assert_eq!(1, requests);
+    }
+
+    #[tokio::test]
+    pub async fn backs_off_when_read_session_not_available() {
+        let cache = Arc::new(PartitionKeyRangeCache::default());
+        let start = std::time::Instant::now();
+        let (result, requests) = send(
+            vec![
+                (404, Some("1002"), None),
+                (404, Some("1002"), None),
+                (200, None, None),
+            ],
+            cache.clone(),
+            false,
+        )
+        .await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(3, requests);
+        assert!(start.elapsed() >= INITIAL_READ_SESSION_DELAY * 3);
+
+        // The session still isn't available after the last retry.
+        let (result, requests) = send(vec![(404, Some("1002"), None); 4], cache, false).await;
+        assert_eq!(StatusCode::NotFound, result.unwrap().status());
+        assert_eq!(3, requests);
+    }
+
+    #[tokio::test]
+    pub async fn refreshes_partition_key_ranges_after_split() {
+        let cache = Arc::new(PartitionKeyRangeCache::default());
+        let ranges = || {
+            vec![PartitionKeyRange {
+                id: "0".to_string(),
+                min_inclusive: "".to_string(),
+                max_exclusive: "FF".to_string(),
+            }]
+        };
+        let container_path = container_link().path();
+
+        cache.insert(&container_path, ranges());
+        let (result, requests) = send(
+            vec![(410, Some("1002"), None), (200, None, None)],
+            cache.clone(),
+            false,
+        )
+        .await;
+        assert_eq!(StatusCode::Ok, result.unwrap().status());
+        assert_eq!(2, requests);
+        assert!(cache.get(&container_path).is_none());
+
+        // Requests targeting a partition key range aren't retried, since the range is gone.
+        cache.insert(&container_path, ranges());
+        let (result, requests) = send(vec![(410, Some("1002"), None)], cache.clone(), true).await;
+        assert_eq!(StatusCode::Gone, result.unwrap().status());
+        assert_eq!(1, requests);
+        assert!(cache.get(&container_path).is_none());
+    }
+}
@@ -0,0 +1,246 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Defines a policy that captures session tokens from responses and sends them on subsequent reads.
+
+use std::sync::Arc;
+
+use azure_core::http::{
+    headers::SESSION_TOKEN,
+    policies::{Policy, PolicyResult},
+    request::Request,
+    Context,
+};
+use tracing::trace;
+
+use crate::{
+    pipeline::is_read_request,
+    resource_context::{ResourceLink, ResourceType},
+    session::{SessionContainer, SessionToken},
+};
+
+/// A policy that provides "read-your-writes" guarantees for accounts using Session consistency.
+///
+/// Session tokens returned by the service are tracked per container (and per partition key range) in a [`SessionContainer`].
+/// Item reads and queries that don't already specify a session token are sent with the latest token tracked for their container.
+#[derive(Debug, Clone)]
+pub struct SessionPolicy {
+    sessions: Arc<SessionContainer>,
+}
+
+impl SessionPolicy {
+    pub fn new(sessions: Arc<SessionContainer>) -> Self {
+        Self { sessions }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl Policy for SessionPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        // Session tokens are scoped to a container, so we only track them for item operations.
+        // Requests without a resource link (which the CosmosPipeline always provides) are passed through untouched.
+        let container_path = ctx
+            .value::<ResourceLink>()
+            .filter(|link| link.resource_type() == ResourceType::Items)
+            .and_then(ResourceLink::parent_path);
+
+        if let Some(container_path) = container_path {
+            if is_read_request(request)
+                && request.headers().get_optional_str(&SESSION_TOKEN).is_none()
+            {
+                if let Some(token) = self.sessions.get(container_path) {
+                    trace!(?token, "adding session token to request");
+                    request.add_mandatory_header(&token);
+                }
+            }
+        }
+
+        let response = next[0].send(ctx, request, &next[1..]).await?;
+
+        // Error responses (such as a 404 for a missing item) still carry a valid session token, so we capture it regardless of the status.
+        if let Some(container_path) = container_path {
+            if let Some(token) = SessionToken::from_headers(response.headers()) {
+                self.sessions.merge(container_path, &token);
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use azure_core::{
+        http::{
+            headers::{Headers, SESSION_TOKEN},
+            policies::{Policy, PolicyResult},
+            request::Request,
+            response::Response,
+            Context, Method, StatusCode, Url,
+        },
+        Bytes,
+    };
+
+    use crate::{
+        constants,
+        pipeline::session_policy::SessionPolicy,
+        resource_context::{ResourceLink, ResourceType},
+        session::{SessionContainer, SessionToken},
+    };
+
+    const CONTAINER: &str = "dbs/db/colls/c";
+
+    /// A fake transport policy which records the session token of each request, and responds with the configured session token.
+    #[derive(Debug, Default)]
+    struct FakeTransport {
+        response_token: Option<&'static str>,
+        sent_tokens: Mutex<Vec<Option<String>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Policy for FakeTransport {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.sent_tokens
+                .lock()
+                .unwrap()
+                .push(request.headers().get_optional_string(&SESSION_TOKEN));
+            let mut headers = Headers::new();
+            if let Some(token) = self.response_token {
+                headers.insert(SESSION_TOKEN, token);
+            }
+            Ok(Response::from_bytes(StatusCode::Ok, headers, Bytes::new()))
+        }
+    }
+
+    fn container_link() -> ResourceLink {
+        ResourceLink::root(ResourceType::Databases)
+            .item("db")
+            .feed(ResourceType::Containers)
+            .item("c")
+    }
+
+    async fn send(
+        sessions: &Arc<SessionContainer>,
+        link: Option<ResourceLink>,
+        mut request: Request,
+        response_token: Option<&'static str>,
+    ) -> Option<String> {
+        let transport = Arc::new(FakeTransport {
+            response_token,
+            ..Default::default()
+        });
+        let policy = SessionPolicy::new(sessions.clone());
+        let ctx = match link {
+            Some(link) => Context::new().with_value(link),
+            None => Context::new(),
+        };
+        let next: Vec<Arc<dyn Policy>> = vec![transport.clone()];
+        policy.send(&ctx, &mut request, &next).await.unwrap();
+        let sent = transport.sent_tokens.lock().unwrap().remove(0);
+        sent
+    }
+
+    fn request(method: Method) -> Request {
+        let url: Url = "https://account.documents.azure.com/dbs/db/colls/c/docs/item1"
+            .parse()
+            .unwrap();
+        Request::new(url, method)
+    }
+
+    #[tokio::test]
+    pub async fn captures_token_from_write() {
+        let sessions = Arc::new(SessionContainer::default());
+        let sent = send(
+            &sessions,
+            Some(container_link().feed(ResourceType::Items)),
+            request(Method::Post),
+            Some("0:1#12"),
+        )
+        .await;
+
+        // Writes are never sent with a session token.
+        assert_eq!(None, sent);
+        assert_eq!(Some(SessionToken::from("0:1#12")), sessions.get(CONTAINER));
+    }
+
+    #[tokio::test]
+    pub async fn sends_merged_token_on_next_read() {
+        let sessions = Arc::new(SessionContainer::default());
+        let items = container_link().feed(ResourceType::Items);
+        send(
+            &sessions,
+            Some(items.clone()),
+            request(Method::Post),
+            Some("0:1#12"),
+        )
+        .await;
+        send(
+            &sessions,
+            Some(items.clone()),
+            request(Method::Put),
+            Some("1:1#4"),
+        )
+        .await;
+
+        let sent = send(
+            &sessions,
+            Some(items.item("item1")),
+            request(Method::Get),
+            None,
+        )
+        .await;
+        assert_eq!(Some("0:1#12,1:1#4".to_string()), sent);
+
+        // Queries are reads too.
+        let mut query = request(Method::Post);
+        query.insert_header(constants::QUERY, "True");
+        let sent = send(&sessions, Some(items.clone()), query, None).await;
+        assert_eq!(Some("0:1#12,1:1#4".to_string()), sent);
+
+        // An explicit session token is left alone.
+        let mut read = request(Method::Get);
+        read.insert_header(SESSION_TOKEN, "0:1#2");
+        let sent = send(&sessions, Some(items.item("item1")), read, None).await;
+        assert_eq!(Some("0:1#2".to_string()), sent);
+    }
+
+    #[tokio::test]
+    pub async fn ignores_non_item_resources() {
+        let sessions = Arc::new(SessionContainer::default());
+        sessions.merge(CONTAINER, &"0:1#12".into());
+
+        // A container read is neither sent the container's token, nor captures the token it returns.
+        let sent = send(
+            &sessions,
+            Some(container_link()),
+            request(Method::Get),
+            Some("0:1#20"),
+        )
+        .await;
+        assert_eq!(None, sent);
+        assert_eq!(Some(SessionToken::from("0:1#12")), sessions.get(CONTAINER));
+    }
+
+    #[tokio::test]
+    pub async fn passes_through_requests_without_resource_link() {
+        let sessions = Arc::new(SessionContainer::default());
+        sessions.merge(CONTAINER, &"0:1#12".into());
+
+        let sent = send(&sessions, None, request(Method::Get), Some("0:1#20")).await;
+        assert_eq!(None, sent);
+        assert_eq!(Some(SessionToken::from("0:1#12")), sessions.get(CONTAINER));
+    }
+}
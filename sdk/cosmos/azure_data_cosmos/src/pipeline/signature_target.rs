@@ -5,6 +5,8 @@
 use azure_core::{credentials::Secret, hmac::hmac_sha256, http::Method};
 
 use crate::resource_context::ResourceLink;
+#[cfg(feature = "key_auth")]
+use crate::ResourceTokenResolver;
 
 #[cfg_attr(not(feature = "key_auth"), allow(dead_code))]
 pub struct SignatureTarget<'a> {
@@ -31,6 +33,27 @@ impl<'a> SignatureTarget<'a> {
         Ok(format!("type=master&ver=1.0&sig={signature}"))
     }
 
+    /// Resolves the resource token to use for this request.
+    ///
+    /// Resource tokens are issued by the service (with the format `type=resource&ver=1.0&sig=...`), so unlike primary keys there is nothing to sign.
+    #[cfg(feature = "key_auth")]
+    pub async fn into_resource_authorization(
+        self,
+        resolver: &dyn ResourceTokenResolver,
+    ) -> azure_core::Result<String> {
+        let resource_link = self.link.resource_link();
+        let token = resolver
+            .resolve_token(&resource_link)
+            .await?
+            .ok_or_else(|| {
+                azure_core::Error::message(
+                    azure_core::error::ErrorKind::Credential,
+                    format!("no resource token is available for resource link '{resource_link}'"),
+                )
+            })?;
+        Ok(token.secret().to_string())
+    }
+
     /// This function generates a valid authorization string, according to the documentation.
     /// In case of authorization problems we can compare the `string_to_sign` generated by Azure against
     /// our own.
@@ -78,6 +78,16 @@ impl Query {
 
         Ok(self)
     }
+
+    /// Creates a new [`Query`] with different text, but the same parameters as this one.
+    ///
+    /// Used to execute the queries rewritten by the query plan.
+    pub(crate) fn with_text(&self, text: impl Into<String>) -> Self {
+        Self {
+            query: text.into(),
+            parameters: self.parameters.clone(),
+        }
+    }
 }
 
 impl<T: Into<String>> From<T> for Query {
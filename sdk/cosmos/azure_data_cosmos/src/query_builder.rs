@@ -0,0 +1,653 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! A typed builder for Cosmos DB SQL queries.
+//!
+//! [`QueryBuilder`] generates a parameterized [`Query`] from document [`Path`]s and expressions.
+//! Every value used in the query is bound to a generated parameter (`@p0`, `@p1`, ...), so values never need to be quoted or escaped,
+//! and property names which aren't valid identifiers (or are reserved words) are escaped automatically.
+//!
+//! ```rust
+//! use azure_data_cosmos::query_builder::{array_contains, field, is_defined, QueryBuilder, SortOrder};
+//!
+//! let query = QueryBuilder::new()
+//!     .select([field("id"), field("name")])
+//!     .filter(field("category").eq("electronics"))
+//!     .filter(field("price").lt(100).or(array_contains(field("tags"), "sale")))
+//!     .filter(is_defined(field("discontinued")).not())
+//!     .order_by(field("price"), SortOrder::Descending)
+//!     .offset_limit(20, 10)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(
+//!     serde_json::json!({
+//!         "query": "SELECT c.id, c.name FROM c WHERE ((c.category = @p0) AND ((c.price < @p1) OR ARRAY_CONTAINS(c.tags, @p2))) AND NOT IS_DEFINED(c.discontinued) ORDER BY c.price DESC OFFSET 20 LIMIT 10",
+//!         "parameters": [
+//!             { "name": "@p0", "value": "electronics" },
+//!             { "name": "@p1", "value": 100 },
+//!             { "name": "@p2", "value": "sale" },
+//!         ]
+//!     }),
+//!     serde_json::to_value(&query).unwrap()
+//! );
+//! ```
+//!
+//! With the `derive` feature enabled, `#[derive(QueryDocument)]` generates the paths of the fields of a struct, using the same names as `serde`,
+//! so that typos in property names are caught at compile time:
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```rust,ignore")]
+//! use azure_data_cosmos::query_builder::{QueryBuilder, QueryDocument};
+//!
+//! #[derive(serde::Serialize, serde::Deserialize, QueryDocument)]
+//! #[serde(rename_all = "camelCase")]
+//! struct Product {
+//!     id: String,
+//!     unit_price: f64,
+//! }
+//!
+//! let fields = Product::fields();
+//! let query = QueryBuilder::new().filter(fields.unit_price.gt(10)).build().unwrap();
+//! assert_eq!(
+//!     "SELECT * FROM c WHERE c.unitPrice > @p0",
+//!     serde_json::to_value(&query).unwrap()["query"]
+//! );
+//! ```
+
+use std::fmt;
+
+use azure_core::error::ErrorKind;
+use serde::Serialize;
+
+use crate::Query;
+
+#[cfg(feature = "derive")]
+pub use azure_data_cosmos_macros::QueryDocument;
+
+/// The alias of the documents in the generated queries (`SELECT * FROM c`).
+const ROOT_ALIAS: &str = "c";
+
+/// Keywords of the Cosmos DB query language, which can't be used as identifiers in a path.
+const RESERVED_WORDS: &[&str] = &[
+    "and",
+    "array",
+    "as",
+    "asc",
+    "between",
+    "by",
+    "case",
+    "cast",
+    "convert",
+    "cross",
+    "desc",
+    "distinct",
+    "else",
+    "end",
+    "escape",
+    "exists",
+    "false",
+    "for",
+    "from",
+    "group",
+    "having",
+    "in",
+    "inner",
+    "insert",
+    "into",
+    "is",
+    "join",
+    "left",
+    "like",
+    "limit",
+    "not",
+    "null",
+    "offset",
+    "on",
+    "or",
+    "order",
+    "outer",
+    "over",
+    "right",
+    "select",
+    "set",
+    "then",
+    "top",
+    "true",
+    "udf",
+    "undefined",
+    "update",
+    "value",
+    "when",
+    "where",
+    "with",
+];
+
+/// A type whose fields can be referenced in queries, usually implemented using `#[derive(QueryDocument)]` (requires the `derive` feature).
+///
+/// The derive macro generates a struct, named after the type with a `Fields` suffix, with a public [`Path`] for each field.
+/// The paths use the same property names as `serde`, honoring `#[serde(rename = "...")]` and `#[serde(rename_all = "...")]`,
+/// and fields marked `#[serde(skip)]` or `#[serde(flatten)]` are omitted.
+pub trait QueryDocument {
+    /// The type holding the [`Path`] of each field.
+    type Fields;
+
+    /// Gets the paths of the fields of this type.
+    fn fields() -> Self::Fields;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Property(String),
+    Index(usize),
+}
+
+/// The path of a property within a document, such as `c.address.city` or `c.tags[0]`.
+///
+/// Use [`field()`] to create a path to a top-level property, and [`Path::field()`] and [`Path::index()`] to navigate into nested objects and arrays.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    /// Creates a path referring to the whole document.
+    pub fn root() -> Self {
+        Self {
+            segments: Vec::new(),
+        }
+    }
+
+    /// Creates a path to a property of the object at this path.
+    pub fn field(mut self, name: impl Into<String>) -> Self {
+        self.segments.push(Segment::Property(name.into()));
+        self
+    }
+
+    /// Creates a path to an element of the array at this path.
+    pub fn index(mut self, index: usize) -> Self {
+        self.segments.push(Segment::Index(index));
+        self
+    }
+}
+
+/// Creates a [`Path`] to a top-level property of the document.
+pub fn field(name: impl Into<String>) -> Path {
+    Path::root().field(name)
+}
+
+impl From<&str> for Path {
+    fn from(value: &str) -> Self {
+        field(value)
+    }
+}
+
+impl From<String> for Path {
+    fn from(value: String) -> Self {
+        field(value)
+    }
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let valid_start = matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_');
+    valid_start
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !RESERVED_WORDS.contains(&name.to_ascii_lowercase().as_str())
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(ROOT_ALIAS)?;
+        for segment in &self.segments {
+            match segment {
+                Segment::Property(name) if is_identifier(name) => write!(f, ".{}", name)?,
+                Segment::Property(name) => {
+                    // Property names which aren't identifiers use the bracket syntax, with a JSON string literal.
+                    let quoted = serde_json::to_string(name).map_err(|_| fmt::Error)?;
+                    write!(f, "[{}]", quoted)?
+                }
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An expression in a query, such as a path, a parameter, a comparison or a function call.
+///
+/// Expressions are usually created from a [`Path`] (for example `field("price").lt(100)`) or from the functions in this module (for example [`array_contains()`]).
+#[derive(Clone, Debug)]
+pub struct Expr(ExprKind);
+
+#[derive(Clone, Debug)]
+enum ExprKind {
+    Path(Path),
+    // Values are serialized when the expression is created, but errors are only reported when the query is built.
+    Parameter(Result<serde_json::Value, String>),
+    Binary(Box<Expr>, &'static str, Box<Expr>),
+    Not(Box<Expr>),
+    Function(&'static str, Vec<Expr>),
+}
+
+/// Converts a value into an [`Expr`].
+///
+/// This is implemented for [`Expr`] and [`Path`], and for any serializable value, which is bound to a query parameter.
+pub trait IntoExpr {
+    /// Converts this value into an [`Expr`].
+    fn into_expr(self) -> Expr;
+}
+
+impl IntoExpr for Expr {
+    fn into_expr(self) -> Expr {
+        self
+    }
+}
+
+impl IntoExpr for Path {
+    fn into_expr(self) -> Expr {
+        Expr(ExprKind::Path(self))
+    }
+}
+
+impl<T: Serialize> IntoExpr for T {
+    fn into_expr(self) -> Expr {
+        Expr(ExprKind::Parameter(
+            serde_json::to_value(self).map_err(|e| e.to_string()),
+        ))
+    }
+}
+
+/// Binds a value to a query parameter.
+///
+/// Serializable values are bound automatically when passed to a method accepting [`IntoExpr`], so this is only needed to use a value on the left-hand side of a comparison.
+pub fn param(value: impl Serialize) -> Expr {
+    value.into_expr()
+}
+
+macro_rules! comparison_methods {
+    () => {
+        /// Creates an expression testing whether this value is equal to `other` (`=`).
+        pub fn eq(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, "=", other)
+        }
+
+        /// Creates an expression testing whether this value isn't equal to `other` (`!=`).
+        pub fn ne(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, "!=", other)
+        }
+
+        /// Creates an expression testing whether this value is less than `other` (`<`).
+        pub fn lt(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, "<", other)
+        }
+
+        /// Creates an expression testing whether this value is less than or equal to `other` (`<=`).
+        pub fn le(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, "<=", other)
+        }
+
+        /// Creates an expression testing whether this value is greater than `other` (`>`).
+        pub fn gt(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, ">", other)
+        }
+
+        /// Creates an expression testing whether this value is greater than or equal to `other` (`>=`).
+        pub fn ge(self, other: impl IntoExpr) -> Expr {
+            Expr::binary(self, ">=", other)
+        }
+    };
+}
+
+impl Path {
+    comparison_methods!();
+}
+
+impl Expr {
+    comparison_methods!();
+
+    /// Creates an expression which is true when both this expression and `other` are true (`AND`).
+    pub fn and(self, other: impl IntoExpr) -> Expr {
+        Expr::binary(self, "AND", other)
+    }
+
+    /// Creates an expression which is true when either this expression or `other` is true (`OR`).
+    pub fn or(self, other: impl IntoExpr) -> Expr {
+        Expr::binary(self, "OR", other)
+    }
+
+    /// Creates an expression which is true when this expression is false (`NOT`).
+    #[allow(clippy::should_implement_trait)] // REASON: `Not` would consume the expression the same way, but `.not()` reads better in a builder chain.
+    pub fn not(self) -> Expr {
+        Expr(ExprKind::Not(Box::new(self)))
+    }
+
+    fn binary(left: impl IntoExpr, operator: &'static str, right: impl IntoExpr) -> Expr {
+        Expr(ExprKind::Binary(
+            Box::new(left.into_expr()),
+            operator,
+            Box::new(right.into_expr()),
+        ))
+    }
+
+    fn function(name: &'static str, args: Vec<Expr>) -> Expr {
+        Expr(ExprKind::Function(name, args))
+    }
+
+    fn render(
+        &self,
+        out: &mut String,
+        parameters: &mut Vec<serde_json::Value>,
+    ) -> azure_core::Result<()> {
+        match &self.0 {
+            ExprKind::Path(path) => out.push_str(&path.to_string()),
+            ExprKind::Parameter(Ok(value)) => {
+                out.push_str(&format!("@p{}", parameters.len()));
+                parameters.push(value.clone());
+            }
+            ExprKind::Parameter(Err(e)) => {
+                return Err(azure_core::Error::message(
+                    ErrorKind::DataConversion,
+                    format!("failed to serialize query parameter: {}", e),
+                ))
+            }
+            ExprKind::Binary(left, operator, right) => {
+                out.push('(');
+                left.render(out, parameters)?;
+                out.push_str(&format!(" {} ", operator));
+                right.render(out, parameters)?;
+                out.push(')');
+            }
+            ExprKind::Not(inner) => {
+                out.push_str("NOT ");
+                inner.render(out, parameters)?;
+            }
+            ExprKind::Function(name, args) => {
+                out.push_str(&format!("{}(", name));
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(", ");
+                    }
+                    arg.render(out, parameters)?;
+                }
+                out.push(')');
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders the expression at the top level of a clause, where the outer parentheses aren't needed.
+    fn render_clause(
+        &self,
+        out: &mut String,
+        parameters: &mut Vec<serde_json::Value>,
+    ) -> azure_core::Result<()> {
+        match &self.0 {
+            ExprKind::Binary(left, operator, right) => {
+                left.render(out, parameters)?;
+                out.push_str(&format!(" {} ", operator));
+                right.render(out, parameters)
+            }
+            _ => self.render(out, parameters),
+        }
+    }
+}
+
+/// Creates an expression testing whether `array` contains `item` (`ARRAY_CONTAINS`).
+pub fn array_contains(array: impl IntoExpr, item: impl IntoExpr) -> Expr {
+    Expr::function("ARRAY_CONTAINS", vec![array.into_expr(), item.into_expr()])
+}
+
+/// Creates an expression returning the number of elements in `array` (`ARRAY_LENGTH`).
+pub fn array_length(array: impl IntoExpr) -> Expr {
+    Expr::function("ARRAY_LENGTH", vec![array.into_expr()])
+}
+
+/// Creates an expression testing whether a property has been assigned a value (`IS_DEFINED`).
+pub fn is_defined(value: impl IntoExpr) -> Expr {
+    Expr::function("IS_DEFINED", vec![value.into_expr()])
+}
+
+/// Creates an expression testing whether a value is `null` (`IS_NULL`).
+pub fn is_null(value: impl IntoExpr) -> Expr {
+    Expr::function("IS_NULL", vec![value.into_expr()])
+}
+
+/// Creates an expression testing whether the string `value` contains `substring` (`CONTAINS`).
+pub fn contains(value: impl IntoExpr, substring: impl IntoExpr) -> Expr {
+    Expr::function("CONTAINS", vec![value.into_expr(), substring.into_expr()])
+}
+
+/// Creates an expression testing whether the string `value` starts with `prefix` (`STARTSWITH`).
+pub fn starts_with(value: impl IntoExpr, prefix: impl IntoExpr) -> Expr {
+    Expr::function("STARTSWITH", vec![value.into_expr(), prefix.into_expr()])
+}
+
+/// Creates an expression testing whether the string `value` ends with `suffix` (`ENDSWITH`).
+pub fn ends_with(value: impl IntoExpr, suffix: impl IntoExpr) -> Expr {
+    Expr::function("ENDSWITH", vec![value.into_expr(), suffix.into_expr()])
+}
+
+/// Creates an expression converting the string `value` to lowercase (`LOWER`).
+pub fn lower(value: impl IntoExpr) -> Expr {
+    Expr::function("LOWER", vec![value.into_expr()])
+}
+
+/// Creates an expression converting the string `value` to uppercase (`UPPER`).
+pub fn upper(value: impl IntoExpr) -> Expr {
+    Expr::function("UPPER", vec![value.into_expr()])
+}
+
+/// The direction of an `ORDER BY` clause.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Sorts from the lowest to the highest value (`ASC`).
+    #[default]
+    Ascending,
+
+    /// Sorts from the highest to the lowest value (`DESC`).
+    Descending,
+}
+
+#[derive(Clone, Debug, Default)]
+enum Projection {
+    #[default]
+    All,
+    Fields(Vec<Path>),
+    Value(Expr),
+}
+
+/// Builds a parameterized [`Query`].
+///
+/// See the [module documentation](crate::query_builder) for an example.
+#[derive(Clone, Debug, Default)]
+pub struct QueryBuilder {
+    projection: Projection,
+    top: Option<u64>,
+    filter: Option<Expr>,
+    order_by: Vec<(Expr, SortOrder)>,
+    offset_limit: Option<(u64, u64)>,
+}
+
+impl QueryBuilder {
+    /// Creates a new [`QueryBuilder`], which selects whole documents (`SELECT * FROM c`).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects only the provided properties of the documents (`SELECT c.a, c.b FROM c`).
+    pub fn select(mut self, fields: impl IntoIterator<Item = impl Into<Path>>) -> Self {
+        self.projection = Projection::Fields(fields.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Selects the value of an expression for each document, rather than an object (`SELECT VALUE ... FROM c`).
+    pub fn select_value(mut self, value: impl IntoExpr) -> Self {
+        self.projection = Projection::Value(value.into_expr());
+        self
+    }
+
+    /// Limits the number of results (`TOP`).
+    pub fn top(mut self, count: u64) -> Self {
+        self.top = Some(count);
+        self
+    }
+
+    /// Adds a condition to the `WHERE` clause of the query.
+    ///
+    /// Calling this method several times requires all the conditions to be true.
+    pub fn filter(mut self, condition: impl IntoExpr) -> Self {
+        let condition = condition.into_expr();
+        self.filter = Some(match self.filter.take() {
+            Some(existing) => existing.and(condition),
+            None => condition,
+        });
+        self
+    }
+
+    /// Adds an expression to the `ORDER BY` clause of the query.
+    pub fn order_by(mut self, value: impl IntoExpr, order: SortOrder) -> Self {
+        self.order_by.push((value.into_expr(), order));
+        self
+    }
+
+    /// Skips the first `offset` results, and then returns at most `limit` results (`OFFSET ... LIMIT`).
+    pub fn offset_limit(mut self, offset: u64, limit: u64) -> Self {
+        self.offset_limit = Some((offset, limit));
+        self
+    }
+
+    /// Builds the [`Query`].
+    ///
+    /// Returns an error if one of the values in the query couldn't be serialized.
+    pub fn build(self) -> azure_core::Result<Query> {
+        let mut text = String::from("SELECT ");
+        let mut parameters = Vec::new();
+        if let Some(top) = self.top {
+            text.push_str(&format!("TOP {} ", top));
+        }
+        match &self.projection {
+            Projection::All => text.push('*'),
+            Projection::Fields(fields) => {
+                let fields: Vec<String> = fields.iter().map(Path::to_string).collect();
+                text.push_str(&fields.join(", "));
+            }
+            Projection::Value(value) => {
+                text.push_str("VALUE ");
+                value.render_clause(&mut text, &mut parameters)?;
+            }
+        }
+        text.push_str(&format!(" FROM {}", ROOT_ALIAS));
+
+        if let Some(filter) = &self.filter {
+            text.push_str(" WHERE ");
+            filter.render_clause(&mut text, &mut parameters)?;
+        }
+
+        for (i, (value, order)) in self.order_by.iter().enumerate() {
+            text.push_str(if i == 0 { " ORDER BY " } else { ", " });
+            value.render_clause(&mut text, &mut parameters)?;
+            text.push_str(match order {
+                SortOrder::Ascending => " ASC",
+                SortOrder::Descending => " DESC",
+            });
+        }
+
+        if let Some((offset, limit)) = self.offset_limit {
+            text.push_str(&format!(" OFFSET {} LIMIT {}", offset, limit));
+        }
+
+        parameters
+            .into_iter()
+            .enumerate()
+            .try_fold(Query::from(text), |query, (i, value)| {
+                query.with_parameter(format!("@p{}", i), value)
+            })
+    }
+}
+
+impl TryFrom<QueryBuilder> for Query {
+    type Error = azure_core::Error;
+
+    fn try_from(value: QueryBuilder) -> Result<Self, Self::Error> {
+        value.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::query_builder::{
+        array_contains, field, is_defined, lower, param, starts_with, Path, QueryBuilder, SortOrder,
+    };
+
+    #[test]
+    pub fn select_all() {
+        let query = QueryBuilder::new().build().unwrap();
+        assert_eq!(
+            json!({ "query": "SELECT * FROM c" }),
+            serde_json::to_value(&query).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn escapes_property_names() {
+        assert_eq!("c.name", field("name").to_string());
+        assert_eq!(r#"c["first-name"]"#, field("first-name").to_string());
+        assert_eq!(r#"c["value"]"#, field("value").to_string());
+        assert_eq!(r#"c["Select"]"#, field("Select").to_string());
+        assert_eq!(r#"c["1st"]"#, field("1st").to_string());
+        assert_eq!(r#"c["say \"hi\""]"#, field(r#"say "hi""#).to_string());
+        assert_eq!(
+            "c.address.lines[0]",
+            field("address").field("lines").index(0).to_string()
+        );
+        assert_eq!("c", Path::root().to_string());
+    }
+
+    #[test]
+    pub fn binds_parameters_in_order() {
+        let query = QueryBuilder::new()
+            .select_value(field("name"))
+            .top(5)
+            .filter(starts_with(lower(field("name")), "con"))
+            .filter(param(3).le(field("rating")))
+            .filter(array_contains(["a", "b"], field("kind")))
+            .order_by(field("rating"), SortOrder::Descending)
+            .order_by(field("name"), SortOrder::Ascending)
+            .build()
+            .unwrap();
+        assert_eq!(
+            json!({
+                "query": "SELECT TOP 5 VALUE c.name FROM c WHERE (STARTSWITH(LOWER(c.name), @p0) AND (@p1 <= c.rating)) AND ARRAY_CONTAINS(@p2, c.kind) ORDER BY c.rating DESC, c.name ASC",
+                "parameters": [
+                    { "name": "@p0", "value": "con" },
+                    { "name": "@p1", "value": 3 },
+                    { "name": "@p2", "value": ["a", "b"] },
+                ]
+            }),
+            serde_json::to_value(&query).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn compares_paths() {
+        let query = QueryBuilder::new()
+            .filter(field("a").ne(field("b")).and(is_defined(field("c")).not()))
+            .build()
+            .unwrap();
+        assert_eq!(
+            "SELECT * FROM c WHERE (c.a != c.b) AND NOT IS_DEFINED(c.c)",
+            serde_json::to_value(&query).unwrap()["query"]
+        );
+    }
+
+    #[test]
+    pub fn reports_serialization_errors() {
+        let invalid = std::collections::HashMap::from([((1, 2), "not a string key")]);
+        assert!(QueryBuilder::new()
+            .filter(field("a").eq(invalid))
+            .build()
+            .is_err());
+    }
+}
@@ -8,6 +8,7 @@ use crate::utils::url_encode;
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 #[allow(dead_code)] // For the variants. Can be removed when we have them all implemented.
 pub enum ResourceType {
+    DatabaseAccount,
     Databases,
     Containers,
     Items,
@@ -17,12 +18,15 @@ pub enum ResourceType {
     PartitionKeyRanges,
     UserDefinedFunctions,
     Triggers,
+    Conflicts,
     Offers,
 }
 
 impl ResourceType {
     pub fn path_segment(self) -> &'static str {
         match self {
+            // The database account is the root of the endpoint, and has an empty resource type in the authorization signature.
+            ResourceType::DatabaseAccount => "",
             ResourceType::Databases => "dbs",
             ResourceType::Containers => "colls",
             ResourceType::Items => "docs",
@@ -32,6 +36,7 @@ impl ResourceType {
             ResourceType::PartitionKeyRanges => "pkranges",
             ResourceType::UserDefinedFunctions => "udfs",
             ResourceType::Triggers => "triggers",
+            ResourceType::Conflicts => "conflicts",
             ResourceType::Offers => "offers",
         }
     }
@@ -96,6 +101,28 @@ impl ResourceLink {
         self.resource_type
     }
 
+    /// Gets the path of the parent resource of this link, if any.
+    ///
+    /// For example, for a link to an item (or to the items feed), this is the path of the container.
+    pub fn parent_path(&self) -> Option<&str> {
+        self.parent.as_deref()
+    }
+
+    /// Gets the path of the container this link refers to, or belongs to, if any.
+    ///
+    /// For example, for a link to an item (or to the items feed), this is `dbs/{database}/colls/{container}`.
+    pub fn container_path(&self) -> Option<String> {
+        if self.resource_type == ResourceType::Containers && self.item_id.is_some() {
+            return Some(self.path());
+        }
+        let parent = self.parent.as_deref()?;
+        let segments: Vec<&str> = parent.split('/').collect();
+        match segments.as_slice() {
+            ["dbs", _, "colls", _] => Some(parent.to_string()),
+            _ => None,
+        }
+    }
+
     /// Gets the path that must be appended to the root account endpoint to access this resource.
     pub fn path(&self) -> String {
         match (self.parent.as_ref(), self.item_id.as_ref()) {
@@ -143,6 +170,7 @@ mod tests {
         );
         assert_eq!("", link.resource_link());
         assert_eq!(ResourceType::Databases, link.resource_type());
+        assert_eq!(None, link.parent_path());
     }
 
     #[test]
@@ -208,6 +236,7 @@ mod tests {
         );
         assert_eq!("dbs/TestDB/colls/TestContainer", link.resource_link());
         assert_eq!(ResourceType::Containers, link.resource_type());
+        assert_eq!(Some("dbs/TestDB"), link.parent_path());
     }
 
     #[test]
@@ -232,4 +261,36 @@ mod tests {
         assert_eq!("dbs/Test+DB/colls/Test%2FContainer", link.resource_link());
         assert_eq!(ResourceType::Containers, link.resource_type());
     }
+
+    #[test]
+    pub fn container_path() {
+        let container = ResourceLink::root(ResourceType::Databases)
+            .item("db")
+            .feed(ResourceType::Containers)
+            .item("c");
+        assert_eq!(
+            Some("dbs/db/colls/c".to_string()),
+            container.container_path()
+        );
+        assert_eq!(
+            Some("dbs/db/colls/c".to_string()),
+            container
+                .feed(ResourceType::Items)
+                .item("item")
+                .container_path()
+        );
+        assert_eq!(
+            None,
+            ResourceLink::root(ResourceType::Databases)
+                .item("db")
+                .feed(ResourceType::Containers)
+                .container_path()
+        );
+        assert_eq!(
+            None,
+            ResourceLink::root(ResourceType::Offers)
+                .item("offer")
+                .container_path()
+        );
+    }
 }
@@ -0,0 +1,111 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Types used to authenticate using resource tokens.
+//!
+//! A resource token grants access to a single resource (or logical partition), and is obtained by reading a permission using a client authenticated with the account key.
+//! See <https://learn.microsoft.com/azure/cosmos-db/secure-access-to-data#resource-tokens> for more information.
+
+use std::{collections::HashMap, fmt::Debug};
+
+use azure_core::credentials::Secret;
+
+/// Provides the resource token to use for a request to a specific resource.
+///
+/// Use [`CosmosClient::with_resource_token_resolver()`](crate::CosmosClient::with_resource_token_resolver()) to create a client which authenticates using a resolver.
+/// This is useful when a client application holds several resource tokens, for example one per container, or needs to refresh its tokens as they expire.
+///
+/// This trait is implemented for [`HashMap<String, Secret>`], which maps resource links (as found in [`PermissionProperties::resource`](crate::models::PermissionProperties::resource))
+/// to resource tokens. A request for a resource uses the token for the resource itself, or for the closest of its parents, so a token for
+/// `dbs/MyDatabase/colls/MyContainer` is used for all the items in that container.
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+pub trait ResourceTokenResolver: Send + Sync + Debug {
+    /// Gets the resource token to use for a request to the resource identified by `resource_link`, if any.
+    ///
+    /// The resource link is the path of the resource relative to the account endpoint (for example `dbs/MyDatabase/colls/MyContainer/docs/MyItem`).
+    /// For requests to a feed (such as creating or querying items), this is the path of the parent resource (for example `dbs/MyDatabase/colls/MyContainer`).
+    async fn resolve_token(&self, resource_link: &str) -> azure_core::Result<Option<Secret>>;
+}
+
+// A single resource token is used for every request.
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl ResourceTokenResolver for Secret {
+    async fn resolve_token(&self, _resource_link: &str) -> azure_core::Result<Option<Secret>> {
+        Ok(Some(self.clone()))
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait::async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait::async_trait)]
+impl ResourceTokenResolver for HashMap<String, Secret> {
+    async fn resolve_token(&self, resource_link: &str) -> azure_core::Result<Option<Secret>> {
+        let mut link = resource_link.trim_matches('/');
+        loop {
+            if let Some(token) = self.get(link) {
+                return Ok(Some(token.clone()));
+            }
+            match link.rsplit_once('/') {
+                Some((parent, _)) => link = parent,
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use azure_core::credentials::Secret;
+
+    use super::ResourceTokenResolver;
+
+    #[tokio::test]
+    pub async fn single_token_resolves_every_link() {
+        let token = Secret::new("type=resource&ver=1&sig=abc");
+        assert_eq!(
+            Some(token.clone()),
+            token.resolve_token("dbs/db/colls/c").await.unwrap()
+        );
+        assert_eq!(Some(token.clone()), token.resolve_token("").await.unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn map_resolves_closest_parent() {
+        let container_token = Secret::new("type=resource&ver=1&sig=container");
+        let item_token = Secret::new("type=resource&ver=1&sig=item");
+        let tokens = HashMap::from([
+            ("dbs/db/colls/c".to_string(), container_token.clone()),
+            (
+                "dbs/db/colls/c/docs/special".to_string(),
+                item_token.clone(),
+            ),
+        ]);
+
+        assert_eq!(
+            Some(container_token.clone()),
+            tokens.resolve_token("dbs/db/colls/c").await.unwrap()
+        );
+        assert_eq!(
+            Some(container_token),
+            tokens
+                .resolve_token("dbs/db/colls/c/docs/item1")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(item_token),
+            tokens
+                .resolve_token("dbs/db/colls/c/docs/special")
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            tokens.resolve_token("dbs/db/colls/other").await.unwrap()
+        );
+        assert_eq!(None, tokens.resolve_token("").await.unwrap());
+    }
+}
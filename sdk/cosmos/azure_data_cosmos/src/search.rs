@@ -0,0 +1,647 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Executes vector, full-text and hybrid search queries across all the partitions of a container.
+//!
+//! Search queries rank items by a score which can only be computed within a single partition.
+//! The gateway provides a query plan describing how to run the query: the query is rewritten to return the top items of each partition along with their scores,
+//! and the results of all the partitions are then merged by score.
+//! Hybrid searches (`ORDER BY RANK RRF(...)`) run several component queries (and, for full-text scoring, a query gathering statistics across all partitions),
+//! and combine the ranks of each item in each component using Reciprocal Rank Fusion.
+
+use std::{cmp::Ordering, collections::HashSet};
+
+use azure_core::{
+    error::ErrorKind,
+    http::{request::Request, response::Model, Context},
+};
+use futures::TryStreamExt;
+use serde::{de::DeserializeOwned, Deserialize};
+
+use crate::{
+    constants,
+    models::{ScoredItem, SearchResults},
+    partition_key_range_cache::PartitionKeyRange,
+    pipeline::{prepare_query_request, CosmosPipeline},
+    resource_context::ResourceLink,
+    FeedPage, Query,
+};
+
+/// The query features this client can execute, sent when requesting a query plan.
+///
+/// The gateway rejects queries which need any other feature (such as aggregates or `DISTINCT`).
+const SUPPORTED_QUERY_FEATURES: &str =
+    "OrderBy, MultipleOrderBy, Top, OffsetTop, NonStreamingOrderBy, HybridSearch";
+
+/// The version of the query plan format this client understands.
+const QUERY_VERSION: &str = "1.0";
+
+/// The constant used in Reciprocal Rank Fusion, which reduces the weight of the top ranks relative to the lower ranks.
+const RRF_CONSTANT: f64 = 60.0;
+
+/// Placeholders in the rewritten queries, which must be replaced before the queries are executed.
+const ORDER_BY_FILTER_PLACEHOLDER: &str = "{documentdb-formattableorderbyquery-filter}";
+const TOTAL_DOCUMENT_COUNT_PLACEHOLDER: &str =
+    "{documentdb-formattablehybridsearchquery-totaldocumentcount}";
+
+/// The execution plan for a query, as returned by the gateway.
+#[derive(Model, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct QueryPlan {
+    #[serde(default)]
+    query_info: Option<QueryInfo>,
+
+    #[serde(default)]
+    hybrid_search_query_info: Option<HybridSearchQueryInfo>,
+
+    /// The effective partition key ranges targeted by the query.
+    #[serde(default)]
+    query_ranges: Vec<QueryRange>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryInfo {
+    #[serde(default)]
+    top: Option<usize>,
+
+    #[serde(default)]
+    offset: Option<usize>,
+
+    #[serde(default)]
+    limit: Option<usize>,
+
+    #[serde(default)]
+    order_by: Vec<SortOrder>,
+
+    #[serde(default)]
+    rewritten_query: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HybridSearchQueryInfo {
+    #[serde(default)]
+    global_statistics_query: String,
+
+    component_query_infos: Vec<QueryInfo>,
+
+    #[serde(default)]
+    skip: Option<usize>,
+
+    #[serde(default)]
+    take: Option<usize>,
+
+    #[serde(default)]
+    requires_global_statistics: bool,
+
+    #[serde(default)]
+    component_weights: Vec<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryRange {
+    min: String,
+    max: String,
+
+    #[serde(default)]
+    is_max_inclusive: bool,
+}
+
+impl QueryRange {
+    fn overlaps(&self, range: &PartitionKeyRange) -> bool {
+        if self.is_max_inclusive && self.min == self.max {
+            // A single effective partition key, for queries filtering on a partition key.
+            return range.feed_range().contains(&self.min);
+        }
+        range
+            .feed_range()
+            .overlaps(&crate::FeedRange::new(&self.min, &self.max))
+    }
+}
+
+/// A row returned by a query rewritten for `ORDER BY`.
+#[derive(Debug, Deserialize)]
+struct OrderByRow<P> {
+    #[serde(rename = "_rid")]
+    rid: String,
+
+    #[serde(rename = "orderByItems")]
+    order_by_items: Vec<OrderByItem>,
+
+    payload: P,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderByItem {
+    #[serde(default)]
+    item: Option<serde_json::Value>,
+}
+
+impl<P> OrderByRow<P> {
+    /// Gets the value of the first `ORDER BY` expression, which is the score of a search.
+    fn score(&self) -> Option<f64> {
+        self.order_by_items.first()?.item.as_ref()?.as_f64()
+    }
+}
+
+/// The payload of a row returned by a hybrid search component query.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HybridPayload {
+    payload: serde_json::Value,
+    component_scores: Vec<f64>,
+}
+
+/// Statistics about the full-text search terms across all the partitions of a container, used to compute full-text scores.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct GlobalStatistics {
+    document_count: u64,
+
+    #[serde(default)]
+    full_text_statistics: Vec<FullTextStatistics>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+struct FullTextStatistics {
+    total_word_count: u64,
+    hit_counts: Vec<u64>,
+}
+
+impl GlobalStatistics {
+    fn merge(&mut self, other: GlobalStatistics) {
+        self.document_count += other.document_count;
+        for (index, statistics) in other.full_text_statistics.into_iter().enumerate() {
+            match self.full_text_statistics.get_mut(index) {
+                Some(existing) => {
+                    existing.total_word_count += statistics.total_word_count;
+                    for (i, count) in statistics.hit_counts.into_iter().enumerate() {
+                        match existing.hit_counts.get_mut(i) {
+                            Some(existing) => *existing += count,
+                            None => existing.hit_counts.push(count),
+                        }
+                    }
+                }
+                None => self.full_text_statistics.push(statistics),
+            }
+        }
+    }
+
+    /// Replaces the statistics placeholders in a component query.
+    fn format_query(&self, query: &str) -> String {
+        let mut query = query.replace(
+            TOTAL_DOCUMENT_COUNT_PLACEHOLDER,
+            &self.document_count.to_string(),
+        );
+        for (index, statistics) in self.full_text_statistics.iter().enumerate() {
+            let hit_counts: Vec<String> =
+                statistics.hit_counts.iter().map(u64::to_string).collect();
+            query = query
+                .replace(
+                    &format!(
+                        "{{documentdb-formattablehybridsearchquery-totalwordcount-{}}}",
+                        index
+                    ),
+                    &statistics.total_word_count.to_string(),
+                )
+                .replace(
+                    &format!(
+                        "{{documentdb-formattablehybridsearchquery-hitcountsarray-{}}}",
+                        index
+                    ),
+                    &format!("[{}]", hit_counts.join(",")),
+                );
+        }
+        query
+    }
+}
+
+/// Executes queries against each of the partition key ranges targeted by a search.
+struct RangeExecutor<'a> {
+    pipeline: &'a CosmosPipeline,
+    ctx: Context<'static>,
+    items_link: &'a ResourceLink,
+    base_request: &'a Request,
+    range_ids: Vec<String>,
+}
+
+impl RangeExecutor<'_> {
+    /// Runs the query against every targeted partition key range concurrently, returning all the rows and the total request charge.
+    async fn query_all_ranges<R: DeserializeOwned + Send + 'static>(
+        &self,
+        query: Query,
+    ) -> azure_core::Result<(Vec<R>, f64)> {
+        let results = futures::future::try_join_all(self.range_ids.iter().map(|range_id| {
+            let mut request = self.base_request.clone();
+            request.insert_header(constants::PARTITION_KEY_RANGE_ID, range_id.clone());
+            request.insert_header(constants::ENABLE_CROSS_PARTITION_QUERY, "True");
+            let query = query.clone();
+            async move {
+                let pages: Vec<FeedPage<R>> = self
+                    .pipeline
+                    .send_query_request(self.ctx.clone(), query, request, self.items_link.clone())?
+                    .try_collect()
+                    .await?;
+                let request_charge = pages
+                    .last()
+                    .map(|page| page.total_diagnostics().request_charge)
+                    .unwrap_or_default();
+                let rows: Vec<R> = pages.into_iter().flat_map(FeedPage::into_items).collect();
+                azure_core::Result::Ok((rows, request_charge))
+            }
+        }))
+        .await?;
+
+        let mut rows = Vec::new();
+        let mut request_charge = 0.0;
+        for (range_rows, range_charge) in results {
+            rows.extend(range_rows);
+            request_charge += range_charge;
+        }
+        Ok((rows, request_charge))
+    }
+}
+
+/// Executes a search query across all the partitions of a container.
+pub(crate) async fn execute_search<T: DeserializeOwned>(
+    pipeline: &CosmosPipeline,
+    ctx: Context<'_>,
+    container_link: &ResourceLink,
+    items_link: &ResourceLink,
+    query: Query,
+    base_request: Request,
+) -> azure_core::Result<SearchResults<T>> {
+    let ctx = ctx.into_owned();
+
+    let mut plan_request = base_request.clone();
+    plan_request.insert_header(constants::IS_QUERY_PLAN_REQUEST, "True");
+    plan_request.insert_header(
+        constants::SUPPORTED_QUERY_FEATURES,
+        SUPPORTED_QUERY_FEATURES,
+    );
+    plan_request.insert_header(constants::QUERY_VERSION, QUERY_VERSION);
+    plan_request.insert_header(constants::ENABLE_CROSS_PARTITION_QUERY, "True");
+    prepare_query_request(&mut plan_request, &query)?;
+    let response = pipeline
+        .send::<QueryPlan>(ctx.clone(), &mut plan_request, items_link.clone())
+        .await?;
+    let mut request_charge =
+        crate::CosmosDiagnostics::from_headers(response.headers()).request_charge;
+    let plan = response.into_body().await?;
+
+    let ranges = pipeline
+        .partition_key_ranges(ctx.clone(), container_link, false)
+        .await?;
+    let executor = RangeExecutor {
+        pipeline,
+        ctx,
+        items_link,
+        base_request: &base_request,
+        range_ids: ranges
+            .iter()
+            .filter(|range| plan.query_ranges.iter().any(|q| q.overlaps(range)))
+            .map(|range| range.id.clone())
+            .collect(),
+    };
+
+    let scored = match (plan.hybrid_search_query_info, plan.query_info) {
+        (Some(hybrid), _) => {
+            let (scored, charge) = execute_hybrid_search(&executor, &query, hybrid).await?;
+            request_charge += charge;
+            scored
+        }
+        (None, Some(info)) if !info.order_by.is_empty() => {
+            let rewritten = query.with_text(
+                info.rewritten_query
+                    .replace(ORDER_BY_FILTER_PLACEHOLDER, "true"),
+            );
+            let (rows, charge) = executor
+                .query_all_ranges::<OrderByRow<serde_json::Value>>(rewritten)
+                .await?;
+            request_charge += charge;
+            let (skip, take) = match (info.top, info.offset, info.limit) {
+                (Some(top), _, _) => (0, Some(top)),
+                (None, offset, limit) => (offset.unwrap_or_default(), limit),
+            };
+            merge_by_score(rows, info.order_by[0], skip, take)
+        }
+        _ => {
+            return Err(azure_core::Error::message(
+                ErrorKind::Other,
+                "search queries must be ordered by a score, using ORDER BY VectorDistance(...) or ORDER BY RANK",
+            ))
+        }
+    };
+
+    let items = scored
+        .into_iter()
+        .map(|item| {
+            Ok(ScoredItem {
+                item: serde_json::from_value(item.item)?,
+                score: item.score,
+                component_scores: item.component_scores,
+            })
+        })
+        .collect::<azure_core::Result<Vec<_>>>()?;
+    Ok(SearchResults {
+        items,
+        request_charge,
+    })
+}
+
+/// Merges the top items of each partition, returned in `rows`, into a single ranking.
+fn merge_by_score(
+    rows: Vec<OrderByRow<serde_json::Value>>,
+    order: SortOrder,
+    skip: usize,
+    take: Option<usize>,
+) -> Vec<ScoredItem<serde_json::Value>> {
+    let mut scored: Vec<_> = rows
+        .into_iter()
+        .map(|row| ScoredItem {
+            score: row.score().unwrap_or(f64::NAN),
+            item: row.payload,
+            component_scores: Vec::new(),
+        })
+        .collect();
+    scored.sort_by(|a, b| compare_scores(a.score, b.score, order));
+    scored
+        .into_iter()
+        .skip(skip)
+        .take(take.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Compares scores so that the best score comes first, and missing scores (NaN) come last.
+fn compare_scores(a: f64, b: f64, order: SortOrder) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => match order {
+            SortOrder::Ascending => a.total_cmp(&b),
+            SortOrder::Descending => b.total_cmp(&a),
+        },
+    }
+}
+
+async fn execute_hybrid_search(
+    executor: &RangeExecutor<'_>,
+    query: &Query,
+    info: HybridSearchQueryInfo,
+) -> azure_core::Result<(Vec<ScoredItem<serde_json::Value>>, f64)> {
+    let mut request_charge = 0.0;
+
+    let mut statistics = GlobalStatistics::default();
+    if info.requires_global_statistics {
+        let (rows, charge) = executor
+            .query_all_ranges::<GlobalStatistics>(query.with_text(&info.global_statistics_query))
+            .await?;
+        request_charge += charge;
+        for row in rows {
+            statistics.merge(row);
+        }
+    }
+
+    let mut rows = Vec::new();
+    let mut seen = HashSet::new();
+    for component in &info.component_query_infos {
+        let rewritten = statistics
+            .format_query(&component.rewritten_query)
+            .replace(ORDER_BY_FILTER_PLACEHOLDER, "true");
+        let (component_rows, charge) = executor
+            .query_all_ranges::<OrderByRow<HybridPayload>>(query.with_text(rewritten))
+            .await?;
+        request_charge += charge;
+        // The same item is usually returned by several components. Each row contains the scores of the item for all the components, so we only need one.
+        rows.extend(
+            component_rows
+                .into_iter()
+                .filter(|row| seen.insert(row.rid.clone())),
+        );
+    }
+
+    let orders: Vec<SortOrder> = info
+        .component_query_infos
+        .iter()
+        .map(|c| c.order_by.first().copied().unwrap_or(SortOrder::Descending))
+        .collect();
+    let scored = fuse_ranks(rows, &orders, &info.component_weights);
+    let scored = scored
+        .into_iter()
+        .skip(info.skip.unwrap_or_default())
+        .take(info.take.unwrap_or(usize::MAX))
+        .collect();
+    Ok((scored, request_charge))
+}
+
+/// Ranks the items by Reciprocal Rank Fusion of their ranks in each component.
+///
+/// Items with equal scores in a component share the same rank.
+/// When there's a single component (a full-text search without `RRF`), the items are ranked by their score in that component.
+fn fuse_ranks(
+    rows: Vec<OrderByRow<HybridPayload>>,
+    orders: &[SortOrder],
+    weights: &[f64],
+) -> Vec<ScoredItem<serde_json::Value>> {
+    let mut fused = vec![0.0; rows.len()];
+    for (component, order) in orders.iter().enumerate() {
+        let score = |row: usize| {
+            rows[row]
+                .payload
+                .component_scores
+                .get(component)
+                .copied()
+                .unwrap_or(f64::NAN)
+        };
+        let mut ranked: Vec<usize> = (0..rows.len()).collect();
+        ranked.sort_by(|a, b| compare_scores(score(*a), score(*b), *order));
+
+        let weight = weights.get(component).copied().unwrap_or(1.0);
+        let mut rank = 1;
+        for (position, row) in ranked.iter().enumerate() {
+            if position > 0 && score(*row) != score(ranked[position - 1]) {
+                rank += 1;
+            }
+            fused[*row] += weight / (RRF_CONSTANT + rank as f64);
+        }
+    }
+
+    let single_component = orders.len() == 1;
+    let mut scored: Vec<_> = rows
+        .into_iter()
+        .zip(fused)
+        .map(|(row, fused)| ScoredItem {
+            score: if single_component {
+                row.payload
+                    .component_scores
+                    .first()
+                    .copied()
+                    .unwrap_or(f64::NAN)
+            } else {
+                fused
+            },
+            item: row.payload.payload,
+            component_scores: row.payload.component_scores,
+        })
+        .collect();
+    let order = match (single_component, orders.first()) {
+        (true, Some(order)) => *order,
+        _ => SortOrder::Descending,
+    };
+    scored.sort_by(|a, b| compare_scores(a.score, b.score, order));
+    scored
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{
+        fuse_ranks, merge_by_score, FullTextStatistics, GlobalStatistics, HybridPayload,
+        OrderByItem, OrderByRow, QueryPlan, SortOrder,
+    };
+    use crate::models::ScoredItem;
+
+    fn row<P>(rid: &str, score: f64, payload: P) -> OrderByRow<P> {
+        OrderByRow {
+            rid: rid.to_string(),
+            order_by_items: vec![OrderByItem {
+                item: Some(json!(score)),
+            }],
+            payload,
+        }
+    }
+
+    fn ids(items: &[ScoredItem<serde_json::Value>]) -> Vec<&str> {
+        items
+            .iter()
+            .map(|i| i.item["id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    pub fn deserialize_vector_search_plan() {
+        let plan: QueryPlan = serde_json::from_value(json!({
+            "partitionedQueryExecutionInfoVersion": 2,
+            "queryInfo": {
+                "distinctType": "None",
+                "top": 2,
+                "orderBy": ["Descending"],
+                "orderByExpressions": ["VectorDistance(c.embedding, [1, 2])"],
+                "rewrittenQuery": "SELECT TOP 2 c._rid, [{\"item\": VectorDistance(c.embedding, [1, 2])}] AS orderByItems, c AS payload FROM c WHERE ({documentdb-formattableorderbyquery-filter}) ORDER BY VectorDistance(c.embedding, [1, 2]) DESC",
+                "hasNonStreamingOrderBy": true
+            },
+            "queryRanges": [{ "min": "", "max": "FF", "isMinInclusive": true, "isMaxInclusive": false }]
+        }))
+        .unwrap();
+        let info = plan.query_info.unwrap();
+        assert_eq!(Some(2), info.top);
+        assert_eq!(vec![SortOrder::Descending], info.order_by);
+        assert_eq!(1, plan.query_ranges.len());
+    }
+
+    #[test]
+    pub fn merges_partitions_by_score() {
+        let rows = vec![
+            row("1", 0.5, json!({"id": "a"})),
+            row("2", 0.9, json!({"id": "b"})),
+            row("3", 0.7, json!({"id": "c"})),
+            row("4", 0.1, json!({"id": "d"})),
+        ];
+        let merged = merge_by_score(rows, SortOrder::Descending, 0, Some(3));
+        assert_eq!(vec!["b", "c", "a"], ids(&merged));
+        assert_eq!(0.9, merged[0].score);
+
+        let rows = vec![
+            row("1", 0.5, json!({"id": "a"})),
+            row("2", 0.9, json!({"id": "b"})),
+            row("3", 0.7, json!({"id": "c"})),
+        ];
+        let merged = merge_by_score(rows, SortOrder::Ascending, 1, Some(5));
+        assert_eq!(vec!["c", "b"], ids(&merged));
+    }
+
+    #[test]
+    pub fn merges_global_statistics() {
+        let mut statistics = GlobalStatistics::default();
+        statistics.merge(GlobalStatistics {
+            document_count: 10,
+            full_text_statistics: vec![FullTextStatistics {
+                total_word_count: 100,
+                hit_counts: vec![1, 2],
+            }],
+        });
+        statistics.merge(GlobalStatistics {
+            document_count: 5,
+            full_text_statistics: vec![FullTextStatistics {
+                total_word_count: 50,
+                hit_counts: vec![3, 4],
+            }],
+        });
+        assert_eq!(15, statistics.document_count);
+        assert_eq!(
+            "FullTextScore(c.text, ['a', 'b'], 15, 150, [4,6])",
+            statistics.format_query("FullTextScore(c.text, ['a', 'b'], {documentdb-formattablehybridsearchquery-totaldocumentcount}, {documentdb-formattablehybridsearchquery-totalwordcount-0}, {documentdb-formattablehybridsearchquery-hitcountsarray-0})")
+        );
+    }
+
+    fn hybrid_row(id: &str, scores: &[f64]) -> OrderByRow<HybridPayload> {
+        row(
+            id,
+            scores[0],
+            HybridPayload {
+                payload: json!({ "id": id }),
+                component_scores: scores.to_vec(),
+            },
+        )
+    }
+
+    #[test]
+    pub fn fuses_ranks() {
+        // "b" is best in both components.
+        let rows = vec![
+            hybrid_row("a", &[1.0, 0.5]),
+            hybrid_row("b", &[3.0, 0.9]),
+            hybrid_row("c", &[2.0, 0.1]),
+        ];
+        let fused = fuse_ranks(
+            rows,
+            &[SortOrder::Descending, SortOrder::Descending],
+            &[1.0, 1.0],
+        );
+        assert_eq!("b", ids(&fused)[0]);
+        assert_eq!(2.0 / 61.0, fused[0].score);
+        assert_eq!(vec![3.0, 0.9], fused[0].component_scores);
+
+        // Weighting the second component favors "c".
+        let rows = vec![
+            hybrid_row("a", &[3.0, 0.1]),
+            hybrid_row("b", &[2.0, 0.5]),
+            hybrid_row("c", &[1.0, 0.9]),
+        ];
+        let fused = fuse_ranks(
+            rows,
+            &[SortOrder::Descending, SortOrder::Descending],
+            &[1.0, 3.0],
+        );
+        assert_eq!(vec!["c", "b", "a"], ids(&fused));
+    }
+
+    #[test]
+    pub fn single_component_uses_component_score() {
+        let rows = vec![hybrid_row("a", &[1.5]), hybrid_row("b", &[4.0])];
+        let fused = fuse_ranks(rows, &[SortOrder::Descending], &[]);
+        assert_eq!(vec!["b", "a"], ids(&fused));
+        assert_eq!(4.0, fused[0].score);
+    }
+}
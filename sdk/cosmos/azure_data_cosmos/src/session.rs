@@ -0,0 +1,235 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Types used to track session consistency tokens.
+//!
+//! When an account uses [Session consistency](https://learn.microsoft.com/azure/cosmos-db/consistency-levels#session-consistency),
+//! every response includes an `x-ms-session-token` header describing the latest write observed by the service, for each partition key range.
+//! Sending that token on subsequent reads guarantees that those reads observe the write ("read-your-writes").
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{PoisonError, RwLock},
+};
+
+use azure_core::http::headers::{self, Header, HeaderName, HeaderValue, Headers};
+
+/// A session token, as returned by Azure Cosmos DB in the `x-ms-session-token` header.
+///
+/// A session token is an opaque value which may contain tokens for multiple partition key ranges, separated by commas.
+/// You can get the session token from a response using [`SessionToken::from_headers`], and store it (for example, in a cookie or user session)
+/// in order to provide it in [`ItemOptions::session_token`](crate::ItemOptions::session_token) or [`QueryOptions::session_token`](crate::QueryOptions::session_token) on a later request,
+/// potentially from a different [`CosmosClient`](crate::CosmosClient) instance.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+    /// Gets the session token from the headers of a Cosmos DB response, if one was returned.
+    pub fn from_headers(headers: &Headers) -> Option<Self> {
+        headers
+            .get_optional_string(&headers::SESSION_TOKEN)
+            .map(SessionToken)
+    }
+
+    /// Gets the string value of the session token.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl<T: Into<String>> From<T> for SessionToken {
+    fn from(value: T) -> Self {
+        SessionToken(value.into())
+    }
+}
+
+impl AsRef<str> for SessionToken {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SessionToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Header for SessionToken {
+    fn name(&self) -> HeaderName {
+        headers::SESSION_TOKEN
+    }
+
+    fn value(&self) -> HeaderValue {
+        self.0.clone().into()
+    }
+}
+
+/// The progress of a single partition key range, as described by its session token.
+///
+/// Tokens have the format `{pk_range_id}:{version}#{global_lsn}[#{region_id}={region_lsn}...]` (or `{pk_range_id}:{lsn}` for legacy tokens).
+/// We only need the version and the global LSN to decide which of two tokens for the same range is the most recent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RangeToken {
+    version: i64,
+    global_lsn: u64,
+    token: String,
+}
+
+impl RangeToken {
+    fn parse(token: &str) -> Option<(String, RangeToken)> {
+        let (range_id, value) = token.trim().split_once(':')?;
+        let mut parts = value.split('#');
+        let first = parts.next()?;
+        let (version, global_lsn) = match parts.next() {
+            Some(lsn) => (first.parse().ok()?, lsn.parse().ok()?),
+            None => (0, first.parse().ok()?),
+        };
+        Some((
+            range_id.to_string(),
+            RangeToken {
+                version,
+                global_lsn,
+                token: value.to_string(),
+            },
+        ))
+    }
+
+    fn is_newer_than(&self, other: &RangeToken) -> bool {
+        (self.version, self.global_lsn) > (other.version, other.global_lsn)
+    }
+}
+
+/// Tracks the latest session token seen for each partition key range of each container.
+///
+/// A single [`SessionContainer`] is shared by all the clients created from the same [`CosmosClient`](crate::CosmosClient).
+#[derive(Debug, Default)]
+pub(crate) struct SessionContainer {
+    // Maps a container path (e.g. "dbs/MyDatabase/colls/MyContainer") to the tokens for each of its partition key ranges.
+    containers: RwLock<HashMap<String, HashMap<String, RangeToken>>>,
+}
+
+impl SessionContainer {
+    /// Gets a session token covering every partition key range we've observed for the container, if any.
+    pub fn get(&self, container_path: &str) -> Option<SessionToken> {
+        let containers = self
+            .containers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let ranges = containers.get(container_path)?;
+        if ranges.is_empty() {
+            return None;
+        }
+
+        // Sort the ranges to produce a stable token, which makes it easier to compare tokens in logs and recordings.
+        let mut tokens: Vec<_> = ranges
+            .iter()
+            .map(|(range_id, token)| format!("{}:{}", range_id, token.token))
+            .collect();
+        tokens.sort();
+        Some(SessionToken(tokens.join(",")))
+    }
+
+    /// Merges the provided session token into the tokens tracked for the container.
+    ///
+    /// For each partition key range, we keep whichever token is the most recent.
+    pub fn merge(&self, container_path: &str, token: &SessionToken) {
+        let mut containers = self
+            .containers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let ranges = containers.entry(container_path.to_string()).or_default();
+        for (range_id, new_token) in token.0.split(',').filter_map(RangeToken::parse) {
+            match ranges.get(&range_id) {
+                Some(existing) if !new_token.is_newer_than(existing) => {}
+                _ => {
+                    ranges.insert(range_id, new_token);
+                }
+            }
+        }
+    }
+
+    /// Replaces all the tokens tracked for the container with the provided session token.
+    pub fn set(&self, container_path: &str, token: &SessionToken) {
+        self.containers
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(container_path);
+        self.merge(container_path, token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use azure_core::http::headers::{Headers, SESSION_TOKEN};
+
+    use super::{SessionContainer, SessionToken};
+
+    const CONTAINER: &str = "dbs/MyDatabase/colls/MyContainer";
+
+    #[test]
+    pub fn from_headers() {
+        let mut headers = Headers::new();
+        assert_eq!(None, SessionToken::from_headers(&headers));
+        headers.insert(SESSION_TOKEN, "0:-1#12");
+        assert_eq!(
+            Some(SessionToken::from("0:-1#12")),
+            SessionToken::from_headers(&headers)
+        );
+    }
+
+    #[test]
+    pub fn empty_container_has_no_token() {
+        let sessions = SessionContainer::default();
+        assert_eq!(None, sessions.get(CONTAINER));
+    }
+
+    #[test]
+    pub fn merge_keeps_latest_token_per_range() {
+        let sessions = SessionContainer::default();
+        sessions.merge(CONTAINER, &"0:1#12#3=8".into());
+        sessions.merge(CONTAINER, &"1:1#4".into());
+        sessions.merge(CONTAINER, &"0:1#10#3=7".into());
+        assert_eq!(
+            Some(SessionToken::from("0:1#12#3=8,1:1#4")),
+            sessions.get(CONTAINER)
+        );
+
+        sessions.merge(CONTAINER, &"0:1#13,1:2#1".into());
+        assert_eq!(
+            Some(SessionToken::from("0:1#13,1:2#1")),
+            sessions.get(CONTAINER)
+        );
+    }
+
+    #[test]
+    pub fn merge_legacy_tokens() {
+        let sessions = SessionContainer::default();
+        sessions.merge(CONTAINER, &"0:42".into());
+        sessions.merge(CONTAINER, &"0:41".into());
+        assert_eq!(Some(SessionToken::from("0:42")), sessions.get(CONTAINER));
+    }
+
+    #[test]
+    pub fn merge_ignores_malformed_tokens() {
+        let sessions = SessionContainer::default();
+        sessions.merge(CONTAINER, &"not a token,0:1#5".into());
+        assert_eq!(Some(SessionToken::from("0:1#5")), sessions.get(CONTAINER));
+    }
+
+    #[test]
+    pub fn set_replaces_existing_tokens() {
+        let sessions = SessionContainer::default();
+        sessions.merge(CONTAINER, &"0:1#12,1:1#4".into());
+        sessions.set(CONTAINER, &"0:1#2".into());
+        assert_eq!(Some(SessionToken::from("0:1#2")), sessions.get(CONTAINER));
+    }
+
+    #[test]
+    pub fn containers_are_tracked_separately() {
+        let sessions = SessionContainer::default();
+        sessions.merge(CONTAINER, &"0:1#12".into());
+        assert_eq!(None, sessions.get("dbs/MyDatabase/colls/OtherContainer"));
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use std::time::Duration;
+
+use azure_core::http::{poller::PollerStatus, request::Request, Context, Method};
+
+use crate::{
+    models::ThroughputProperties, pipeline::CosmosPipeline, resource_context::ResourceLink,
+};
+
+/// The default delay between two reads of an offer by a [`ThroughputPoller`].
+const DEFAULT_POLLING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Tracks a change to the throughput of a database or container, which the service may apply asynchronously.
+///
+/// Some throughput changes, such as migrating between manual and autoscale throughput, or increasing the throughput beyond what the current physical partitions can serve,
+/// are applied in the background by the service. While the change is in progress, the offer is returned with [`ThroughputProperties::is_replace_pending()`] set,
+/// and the previous throughput remains in effect.
+///
+/// Returned by [`ContainerClient::begin_replace_throughput()`](crate::clients::ContainerClient::begin_replace_throughput()), [`ContainerClient::migrate_throughput()`](crate::clients::ContainerClient::migrate_throughput())
+/// and the equivalent methods on [`DatabaseClient`](crate::clients::DatabaseClient).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+/// use azure_data_cosmos::models::ThroughputProperties;
+/// # let container_client: azure_data_cosmos::clients::ContainerClient = panic!("this is a non-running example");
+/// let poller = container_client
+///     .migrate_throughput(ThroughputProperties::autoscale(4000, None), None)
+///     .await?;
+/// let throughput = poller.wait().await?;
+/// println!("Maximum autoscale throughput: {:?}", throughput.autoscale_maximum());
+/// # Ok(())
+/// # }
+/// ```
+pub struct ThroughputPoller {
+    pipeline: CosmosPipeline,
+    context: Context<'static>,
+    offer_link: ResourceLink,
+    current: ThroughputProperties,
+    polling_interval: Duration,
+}
+
+impl ThroughputPoller {
+    pub(crate) fn new(
+        pipeline: CosmosPipeline,
+        context: Context<'static>,
+        offer_link: ResourceLink,
+        current: ThroughputProperties,
+        polling_interval: Option<Duration>,
+    ) -> Self {
+        Self {
+            pipeline,
+            context,
+            offer_link,
+            current,
+            polling_interval: polling_interval.unwrap_or(DEFAULT_POLLING_INTERVAL),
+        }
+    }
+
+    /// Gets the status of the throughput change, as of the last time the offer was read.
+    pub fn status(&self) -> PollerStatus {
+        if self.current.is_replace_pending() {
+            PollerStatus::InProgress
+        } else {
+            PollerStatus::Succeeded
+        }
+    }
+
+    /// Gets the offer, as of the last time it was read.
+    pub fn current(&self) -> &ThroughputProperties {
+        &self.current
+    }
+
+    /// Reads the offer again if the throughput change is still in progress, and returns the updated status.
+    pub async fn poll(&mut self) -> azure_core::Result<PollerStatus> {
+        if self.current.is_replace_pending() {
+            let mut req = Request::new(self.pipeline.url(&self.offer_link), Method::Get);
+            let response = self
+                .pipeline
+                .send::<ThroughputProperties>(
+                    self.context.clone(),
+                    &mut req,
+                    self.offer_link.clone(),
+                )
+                .await?;
+            let headers = response.headers().clone();
+            let mut current = response.into_body().await?;
+            current.apply_headers(&headers);
+            self.current = current;
+        }
+        Ok(self.status())
+    }
+
+    /// Waits for the throughput change to complete, reading the offer at the polling interval, and returns the final offer.
+    pub async fn wait(mut self) -> azure_core::Result<ThroughputProperties> {
+        while matches!(self.status(), PollerStatus::InProgress) {
+            azure_core::sleep::sleep(self.polling_interval).await;
+            self.poll().await?;
+        }
+        Ok(self.current)
+    }
+}
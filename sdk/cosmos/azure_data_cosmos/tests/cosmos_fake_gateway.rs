@@ -0,0 +1,455 @@
+#![cfg(feature = "fake")]
+
+use std::error::Error;
+
+use azure_core::http::{
+    headers::{self, HeaderName},
+    request::Request,
+    Etag, HttpClient, Method, StatusCode,
+};
+use azure_data_cosmos::{
+    clients::ContainerClient,
+    fake::FakeCosmosGateway,
+    models::{ContainerProperties, PatchDocument},
+    CosmosError, FeedRange, ItemOptions, Precondition, Query, QueryPartitionStrategy,
+};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct Product {
+    id: String,
+    category: String,
+    name: String,
+    price: f64,
+}
+
+fn product(id: &str, category: &str, name: &str, price: f64) -> Product {
+    Product {
+        id: id.into(),
+        category: category.into(),
+        name: name.into(),
+        price,
+    }
+}
+
+async fn create_container(gateway: &FakeCosmosGateway) -> azure_core::Result<ContainerClient> {
+    let client = gateway.client();
+    client.create_database("SampleDB", None).await?;
+    let database = client.database_client("SampleDB");
+    database
+        .create_container(
+            ContainerProperties {
+                id: "Products".into(),
+                partition_key: "/category".into(),
+                ..Default::default()
+            },
+            None,
+        )
+        .await?;
+    let container = database.container_client("Products");
+    for item in [
+        product("1", "gear", "Tent", 120.0),
+        product("2", "gear", "Stove", 45.5),
+        product("3", "food", "Coffee", 9.0),
+        product("4", "food", "Tea", 6.5),
+    ] {
+        container
+            .create_item(item.category.clone(), item, None)
+            .await?;
+    }
+    Ok(container)
+}
+
+async fn query<T: serde::de::DeserializeOwned + Send>(
+    container: &ContainerClient,
+    query: impl Into<Query>,
+    partition: impl Into<QueryPartitionStrategy>,
+) -> azure_core::Result<Vec<T>> {
+    let mut pager = container.query_items(query, partition, None)?;
+    let mut items = Vec::new();
+    while let Some(page) = pager.try_next().await? {
+        items.extend(page.into_items());
+    }
+    Ok(items)
+}
+
+#[tokio::test]
+pub async fn databases_and_containers() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+    let client = gateway.client();
+
+    let properties = container.read(None).await?.into_body().await?;
+    assert_eq!("Products", properties.id);
+    assert_eq!(
+        "/category",
+        properties.partition_key.paths.first().unwrap().as_str()
+    );
+
+    let error = client.create_database("SampleDB", None).await.unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_conflict());
+
+    client.database_client("SampleDB").delete(None).await?;
+    let error = container.read(None).await.unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn item_crud_with_etags() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+
+    let response = container.read_item("gear", "1", None).await?;
+    let etag: Etag = response.headers().get_as(&headers::ETAG)?;
+    let mut tent: Product = response.into_json_body().await?;
+    assert_eq!(product("1", "gear", "Tent", 120.0), tent);
+
+    // The item exists in "gear", not in "food".
+    let error = container.read_item("food", "1", None).await.unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+
+    tent.price = 99.0;
+    let if_match = ItemOptions {
+        precondition: Some(etag.clone().into()),
+        ..Default::default()
+    };
+    container
+        .replace_item("gear", "1", &tent, Some(if_match.clone()))
+        .await?;
+
+    // The ETag changed, so the same precondition now fails.
+    let error = container
+        .replace_item("gear", "1", &tent, Some(if_match))
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error)
+        .unwrap()
+        .is_precondition_failed());
+
+    // The item changed, so it is returned, until it is read with its current ETag.
+    let if_none_match = |etag| ItemOptions {
+        precondition: Some(Precondition::IfNoneMatch(etag)),
+        ..Default::default()
+    };
+    let response = container
+        .read_item("gear", "1", Some(if_none_match(etag)))
+        .await?;
+    let etag: Etag = response.headers().get_as(&headers::ETAG)?;
+    let error = container
+        .read_item("gear", "1", Some(if_none_match(etag)))
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_modified());
+
+    let error = container
+        .create_item("gear", &tent, None)
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_conflict());
+    container.upsert_item("gear", &tent, None).await?;
+
+    // The partition key of the item must match the one of the request.
+    let error = container
+        .create_item("food", product("5", "gear", "Lamp", 20.0), None)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        StatusCode::BadRequest,
+        CosmosError::from_error(&error).unwrap().status()
+    );
+
+    container.delete_item("gear", "1", None).await?;
+    let error = container.read_item("gear", "1", None).await.unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn stale_etags_fail_writes() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+
+    let response = container.read_item("gear", "2", None).await?;
+    let stale = ItemOptions {
+        precondition: Some(Precondition::IfMatch(
+            response.headers().get_as(&headers::ETAG)?,
+        )),
+        ..Default::default()
+    };
+    let mut stove: Product = response.into_json_body().await?;
+    stove.price = 40.0;
+    container.upsert_item("gear", &stove, None).await?;
+
+    let error = container
+        .replace_item("gear", "2", &stove, Some(stale.clone()))
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error)
+        .unwrap()
+        .is_precondition_failed());
+
+    let patch = PatchDocument::default().with_set("/price", 35.0)?;
+    let error = container
+        .patch_item("gear", "2", patch, Some(stale.clone()))
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error)
+        .unwrap()
+        .is_precondition_failed());
+
+    let error = container
+        .delete_item("gear", "2", Some(stale))
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error)
+        .unwrap()
+        .is_precondition_failed());
+
+    // None of the writes was applied.
+    let current: Product = container
+        .read_item("gear", "2", None)
+        .await?
+        .into_json_body()
+        .await?;
+    assert_eq!(stove, current);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn patch_items() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+
+    let patch = PatchDocument::default()
+        .with_increment("/price", 1.5)?
+        .with_set("/name", "Big tent")?;
+    container.patch_item("gear", "1", patch, None).await?;
+    let tent: Product = container
+        .read_item("gear", "1", None)
+        .await?
+        .into_json_body()
+        .await?;
+    assert_eq!(product("1", "gear", "Big tent", 121.5), tent);
+
+    let conditional = PatchDocument::default()
+        .with_condition("FROM c WHERE c.price < 100")
+        .with_set("/name", "Cheap tent")?;
+    let error = container
+        .patch_item("gear", "1", conditional, None)
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error)
+        .unwrap()
+        .is_precondition_failed());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn queries() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+
+    let names: Vec<String> = query(
+        &container,
+        "SELECT VALUE c.name FROM c ORDER BY c.price DESC",
+        "gear",
+    )
+    .await?;
+    assert_eq!(vec!["Tent".to_string(), "Stove".to_string()], names);
+
+    let cheap: Vec<Product> = query(
+        &container,
+        Query::from("SELECT * FROM c WHERE c.price < @max").with_parameter("@max", 10)?,
+        "food",
+    )
+    .await?;
+    assert_eq!(
+        vec![
+            product("3", "food", "Coffee", 9.0),
+            product("4", "food", "Tea", 6.5)
+        ],
+        cheap
+    );
+
+    let count: Vec<u64> = query(
+        &container,
+        "SELECT VALUE COUNT(1) FROM c WHERE STARTSWITH(c.name, 'T')",
+        QueryPartitionStrategy::FeedRange(FeedRange::full()),
+    )
+    .await?;
+    assert_eq!(vec![2], count);
+
+    let error = query::<serde_json::Value>(&container, "SELECT * FROM c JOIN t IN c.tags", "gear")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        StatusCode::BadRequest,
+        CosmosError::from_error(&error).unwrap().status()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn missing_parents_are_not_found() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let client = gateway.client();
+
+    let error = client
+        .database_client("Missing")
+        .create_container(
+            ContainerProperties {
+                id: "Products".into(),
+                partition_key: "/category".into(),
+                ..Default::default()
+            },
+            None,
+        )
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+
+    create_container(&gateway).await?;
+    let missing = client
+        .database_client("SampleDB")
+        .container_client("Missing");
+    let error = missing
+        .create_item("gear", product("5", "gear", "Lamp", 20.0), None)
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+    let error = query::<Product>(&missing, "SELECT * FROM c", "gear")
+        .await
+        .unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+
+    // Deleting a container deletes its items.
+    let products = client
+        .database_client("SampleDB")
+        .container_client("Products");
+    products.delete(None).await?;
+    let error = products.read_item("gear", "1", None).await.unwrap_err();
+    assert!(CosmosError::from_error(&error).unwrap().is_not_found());
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn conditional_requests() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    let container = create_container(&gateway).await?;
+    let precondition = |condition| ItemOptions {
+        precondition: Some(condition),
+        ..Default::default()
+    };
+
+    // Any version of an existing item matches `*`.
+    let error = container
+        .read_item(
+            "gear",
+            "1",
+            Some(precondition(Precondition::IfNoneMatch("*".into()))),
+        )
+        .await
+        .unwrap_err();
+    let error = CosmosError::from_error(&error).unwrap();
+    assert!(error.is_not_modified());
+    assert_eq!(StatusCode::NotModified, error.status());
+
+    let tent = product("1", "gear", "Tent", 110.0);
+    let response = container
+        .replace_item(
+            "gear",
+            "1",
+            &tent,
+            Some(precondition(Precondition::IfMatch("*".into()))),
+        )
+        .await?;
+    let etag: Etag = response.headers().get_as(&headers::ETAG)?;
+
+    // Upserts of existing items check their precondition too.
+    let error = container
+        .upsert_item(
+            "gear",
+            &tent,
+            Some(precondition(Precondition::IfMatch("\"stale\"".into()))),
+        )
+        .await
+        .unwrap_err();
+    let error = CosmosError::from_error(&error).unwrap();
+    assert!(error.is_precondition_failed());
+    assert_eq!(StatusCode::PreconditionFailed, error.status());
+    container
+        .upsert_item(
+            "gear",
+            &tent,
+            Some(precondition(Precondition::IfMatch(etag))),
+        )
+        .await?;
+    Ok(())
+}
+
+/// Sends a query for all the items of the "Products" container straight to the gateway, with the given headers.
+async fn send_raw_query(
+    gateway: &FakeCosmosGateway,
+    headers: &[(&'static str, &'static str)],
+) -> azure_core::Result<(StatusCode, Option<String>, serde_json::Value)> {
+    let url = gateway
+        .endpoint()
+        .join("dbs/SampleDB/colls/Products/docs")?;
+    let mut request = Request::new(url, Method::Post);
+    request.insert_header(HeaderName::from_static("x-ms-documentdb-query"), "True");
+    for (name, value) in headers {
+        request.insert_header(HeaderName::from_static(name), *value);
+    }
+    request.set_json(&serde_json::json!({ "query": "SELECT * FROM c", "parameters": [] }))?;
+
+    let response = gateway.execute_request(&request).await?;
+    let status = response.status();
+    let substatus = response
+        .headers()
+        .get_optional_string(&HeaderName::from_static("x-ms-substatus"));
+    let body = response.into_json_body().await?;
+    Ok((status, substatus, body))
+}
+
+#[tokio::test]
+pub async fn cross_partition_queries_must_be_enabled() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    create_container(&gateway).await?;
+
+    let (status, _, _) = send_raw_query(&gateway, &[]).await?;
+    assert_eq!(StatusCode::BadRequest, status);
+
+    let (status, _, body) = send_raw_query(
+        &gateway,
+        &[("x-ms-documentdb-query-enablecrosspartition", "True")],
+    )
+    .await?;
+    assert_eq!(StatusCode::Ok, status);
+    assert_eq!(4, body["_count"]);
+
+    let (status, _, body) =
+        send_raw_query(&gateway, &[("x-ms-documentdb-partitionkey", "[\"food\"]")]).await?;
+    assert_eq!(StatusCode::Ok, status);
+    assert_eq!(2, body["_count"]);
+    Ok(())
+}
+
+#[tokio::test]
+pub async fn unknown_partition_key_ranges_are_gone() -> Result<(), Box<dyn Error>> {
+    let gateway = FakeCosmosGateway::new();
+    create_container(&gateway).await?;
+
+    let (status, _, body) =
+        send_raw_query(&gateway, &[("x-ms-documentdb-partitionkeyrangeid", "0")]).await?;
+    assert_eq!(StatusCode::Ok, status);
+    assert_eq!(4, body["_count"]);
+
+    // The container only has the partition key range "0", so a query targeting another range reports it as split.
+    let (status, substatus, _) =
+        send_raw_query(&gateway, &[("x-ms-documentdb-partitionkeyrangeid", "1")]).await?;
+    assert_eq!(StatusCode::Gone, status);
+    assert_eq!(Some("1002"), substatus.as_deref());
+    Ok(())
+}
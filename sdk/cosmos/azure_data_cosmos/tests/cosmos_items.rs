@@ -3,6 +3,8 @@
 mod framework;
 
 use azure_core_test::{recorded, TestContext};
+#[cfg(feature = "fake")]
+use azure_data_cosmos::fake::FakeCosmosGateway;
 use azure_data_cosmos::{
     clients::ContainerClient,
     models::{ContainerProperties, PatchDocument},
@@ -10,7 +12,7 @@ use azure_data_cosmos::{
 };
 use framework::TestAccount;
 use serde::{Deserialize, Serialize};
-use std::{borrow::Cow, error::Error};
+use std::{borrow::Cow, error::Error, future::Future};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
 struct NestedItem {
@@ -27,14 +29,12 @@ struct TestItem {
 }
 
 async fn create_container(
-    account: &TestAccount,
     cosmos_client: &CosmosClient,
+    test_db_id: &str,
 ) -> azure_core::Result<ContainerClient> {
-    let test_db_id = account.unique_db("ItemCRUD");
-
     // Create a database and a container
-    cosmos_client.create_database(&test_db_id, None).await?;
-    let db_client = cosmos_client.database_client(&test_db_id);
+    cosmos_client.create_database(test_db_id, None).await?;
+    let db_client = cosmos_client.database_client(test_db_id);
     db_client
         .create_container(
             ContainerProperties {
@@ -50,12 +50,36 @@ async fn create_container(
     Ok(container_client)
 }
 
-#[recorded::test]
-pub async fn item_create_read_replace_delete(context: TestContext) -> Result<(), Box<dyn Error>> {
+/// Runs a test against a container in the test account.
+async fn run_recorded<F, Fut>(context: TestContext, test: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(ContainerClient) -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn Error>>>,
+{
     let account = TestAccount::from_env(context, None).await?;
     let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+    let container_client = create_container(&cosmos_client, &account.unique_db("ItemCRUD")).await?;
+    test(container_client).await?;
+
+    account.cleanup().await?;
+    Ok(())
+}
 
+/// Runs a test against a container in a [`FakeCosmosGateway`].
+#[cfg(feature = "fake")]
+async fn run_fake<F, Fut>(test: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnOnce(ContainerClient) -> Fut,
+    Fut: Future<Output = Result<(), Box<dyn Error>>>,
+{
+    let gateway = FakeCosmosGateway::new();
+    let container_client = create_container(&gateway.client(), "ItemCRUD").await?;
+    test(container_client).await
+}
+
+async fn create_read_replace_delete(
+    container_client: ContainerClient,
+) -> Result<(), Box<dyn Error>> {
     // Create an item
     let mut item = TestItem {
         id: "Item1".into(),
@@ -129,21 +153,25 @@ pub async fn item_create_read_replace_delete(context: TestContext) -> Result<(),
             );
         }
     }
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_create_content_response_on_write(
-    context: TestContext,
+pub async fn item_create_read_replace_delete(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, create_read_replace_delete).await
+}
+
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_create_read_replace_delete() -> Result<(), Box<dyn Error>> {
+    run_fake(create_read_replace_delete).await
+}
+
+async fn create_content_response_on_write(
+    container_client: ContainerClient,
 ) -> Result<(), Box<dyn Error>> {
     use azure_data_cosmos::ItemOptions;
 
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
-
     // Create an item
     let item = TestItem {
         id: "Item1".into(),
@@ -168,17 +196,23 @@ pub async fn item_create_content_response_on_write(
         .into_json_body()
         .await?;
     assert_eq!(item, response_item);
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_read_system_properties(context: TestContext) -> Result<(), Box<dyn Error>> {
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+pub async fn item_create_content_response_on_write(
+    context: TestContext,
+) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, create_content_response_on_write).await
+}
+
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_create_content_response_on_write() -> Result<(), Box<dyn Error>> {
+    run_fake(create_content_response_on_write).await
+}
 
+async fn read_system_properties(container_client: ContainerClient) -> Result<(), Box<dyn Error>> {
     // Create an item
     let item = TestItem {
         id: "Item1".into(),
@@ -206,17 +240,21 @@ pub async fn item_read_system_properties(context: TestContext) -> Result<(), Box
         read_item.get("_etag").is_some(),
         "expected _etag to be present"
     );
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_upsert_new(context: TestContext) -> Result<(), Box<dyn Error>> {
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+pub async fn item_read_system_properties(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, read_system_properties).await
+}
+
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_read_system_properties() -> Result<(), Box<dyn Error>> {
+    run_fake(read_system_properties).await
+}
 
+async fn upsert_new(container_client: ContainerClient) -> Result<(), Box<dyn Error>> {
     let item = TestItem {
         id: "Item1".into(),
         partition_key: Some("Partition1".into()),
@@ -236,17 +274,21 @@ pub async fn item_upsert_new(context: TestContext) -> Result<(), Box<dyn Error>>
         .into_json_body()
         .await?;
     assert_eq!(item, read_item);
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_upsert_existing(context: TestContext) -> Result<(), Box<dyn Error>> {
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+pub async fn item_upsert_new(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, upsert_new).await
+}
 
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_upsert_new() -> Result<(), Box<dyn Error>> {
+    run_fake(upsert_new).await
+}
+
+async fn upsert_existing(container_client: ContainerClient) -> Result<(), Box<dyn Error>> {
     let mut item = TestItem {
         id: "Item1".into(),
         partition_key: Some("Partition1".into()),
@@ -276,17 +318,21 @@ pub async fn item_upsert_existing(context: TestContext) -> Result<(), Box<dyn Er
         .into_json_body()
         .await?;
     assert_eq!(item, updated_item);
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_patch(context: TestContext) -> Result<(), Box<dyn Error>> {
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+pub async fn item_upsert_existing(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, upsert_existing).await
+}
 
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_upsert_existing() -> Result<(), Box<dyn Error>> {
+    run_fake(upsert_existing).await
+}
+
+async fn patch(container_client: ContainerClient) -> Result<(), Box<dyn Error>> {
     let item = TestItem {
         id: "Item1".into(),
         partition_key: Some("Partition1".into()),
@@ -330,17 +376,21 @@ pub async fn item_patch(context: TestContext) -> Result<(), Box<dyn Error>> {
         .into_json_body()
         .await?;
     assert!(!response_item.bool_value);
-
-    account.cleanup().await?;
     Ok(())
 }
 
 #[recorded::test]
-pub async fn item_null_partition_key(context: TestContext) -> Result<(), Box<dyn Error>> {
-    let account = TestAccount::from_env(context, None).await?;
-    let cosmos_client = account.connect_with_key(None)?;
-    let container_client = create_container(&account, &cosmos_client).await?;
+pub async fn item_patch(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, patch).await
+}
 
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_patch() -> Result<(), Box<dyn Error>> {
+    run_fake(patch).await
+}
+
+async fn null_partition_key(container_client: ContainerClient) -> Result<(), Box<dyn Error>> {
     let mut item = TestItem {
         id: "Item1".into(),
         partition_key: None,
@@ -392,7 +442,16 @@ pub async fn item_null_partition_key(context: TestContext) -> Result<(), Box<dyn
             );
         }
     }
-
-    account.cleanup().await?;
     Ok(())
 }
+
+#[recorded::test]
+pub async fn item_null_partition_key(context: TestContext) -> Result<(), Box<dyn Error>> {
+    run_recorded(context, null_partition_key).await
+}
+
+#[cfg(feature = "fake")]
+#[tokio::test]
+pub async fn fake_item_null_partition_key() -> Result<(), Box<dyn Error>> {
+    run_fake(null_partition_key).await
+}
@@ -0,0 +1,48 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+#![cfg(feature = "derive")]
+
+use azure_data_cosmos::query_builder::{array_contains, QueryBuilder, QueryDocument, SortOrder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, QueryDocument)]
+#[serde(rename_all = "camelCase")]
+pub struct Product {
+    id: String,
+    unit_price: f64,
+    #[serde(rename = "categoryName")]
+    category: String,
+    tags: Vec<String>,
+    r#type: String,
+    #[serde(skip)]
+    #[allow(dead_code)]
+    cached: bool,
+}
+
+#[test]
+pub fn derived_fields_follow_serde_names() {
+    let fields = Product::fields();
+    assert_eq!("c.id", fields.id.to_string());
+    assert_eq!("c.unitPrice", fields.unit_price.to_string());
+    assert_eq!("c.categoryName", fields.category.to_string());
+    assert_eq!("c.type", fields.r#type.to_string());
+
+    let query = QueryBuilder::new()
+        .select([fields.id.clone(), fields.unit_price.clone()])
+        .filter(fields.category.eq("books"))
+        .filter(array_contains(fields.tags, "sale"))
+        .order_by(fields.unit_price, SortOrder::Ascending)
+        .build()
+        .unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "query": "SELECT c.id, c.unitPrice FROM c WHERE (c.categoryName = @p0) AND ARRAY_CONTAINS(c.tags, @p1) ORDER BY c.unitPrice ASC",
+            "parameters": [
+                { "name": "@p0", "value": "books" },
+                { "name": "@p1", "value": "sale" },
+            ]
+        }),
+        serde_json::to_value(&query).unwrap()
+    );
+}
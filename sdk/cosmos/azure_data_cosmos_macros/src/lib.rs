@@ -0,0 +1,42 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use syn::{parse_macro_input, DeriveInput};
+
+extern crate proc_macro;
+
+mod query_document;
+
+type Result<T> = ::std::result::Result<T, syn::Error>;
+
+// NOTE: Proc macros must appear in the root of the crate. Just re-exporting them with `pub use` is **not sufficient**.
+// So, all the top-level entry functions for the proc macros will appear here, but they just call inner "impl" functions in the modules.
+
+/// Defines the function signature expected by run_derive_macro
+type DeriveImpl = fn(DeriveInput) -> Result<proc_macro2::TokenStream>;
+
+/// Runs the provided derive macro implementation, automatically generating errors if it returns errors.
+fn run_derive_macro(input: proc_macro::TokenStream, imp: DeriveImpl) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    match imp(ast) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Derive macro for implementing the `azure_data_cosmos::query_builder::QueryDocument` trait.
+///
+/// Deriving this trait generates a struct named after the type with a `Fields` suffix (for example, `ProductFields` for `Product`),
+/// with a public `Path` for each named field of the struct, which can be used with the `azure_data_cosmos` query builder.
+///
+/// ## Attributes
+///
+/// The property names follow the `serde` attributes of the struct, so that queries match the way the documents are serialized:
+///
+/// * `#[serde(rename_all = "...")]` on the struct, using the same casing rules as `serde`.
+/// * `#[serde(rename = "...")]` or `#[serde(rename(serialize = "..."))]` on a field.
+/// * Fields marked `#[serde(skip)]`, `#[serde(skip_serializing)]` or `#[serde(flatten)]` don't have a path.
+#[proc_macro_derive(QueryDocument, attributes(serde))]
+pub fn derive_query_document(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    run_derive_macro(input, query_document::derive_query_document_impl)
+}
@@ -0,0 +1,245 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use proc_macro2::TokenStream;
+use syn::{spanned::Spanned, Attribute, Data, DeriveInput, Error, Fields, LitStr, Meta};
+
+use crate::Result;
+
+pub fn derive_query_document_impl(ast: DeriveInput) -> Result<TokenStream> {
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    let name = &ast.ident;
+    let vis = &ast.vis;
+    let fields_name = quote::format_ident!("{}Fields", name);
+
+    let Data::Struct(data) = &ast.data else {
+        return Err(Error::new(
+            ast.span(),
+            "QueryDocument can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(Error::new(
+            data.fields.span(),
+            "QueryDocument can only be derived for structs with named fields",
+        ));
+    };
+
+    let container = SerdeAttrs::from_attrs(&ast.attrs)?;
+    let rename_all = container
+        .rename_all
+        .map(|lit| {
+            RenameRule::parse(&lit.value())
+                .ok_or_else(|| Error::new(lit.span(), "unsupported rename_all rule"))
+        })
+        .transpose()?;
+
+    let mut declarations = Vec::new();
+    let mut initializers = Vec::new();
+    for field in &named.named {
+        let attrs = SerdeAttrs::from_attrs(&field.attrs)?;
+        if attrs.skip || attrs.flatten {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let property = match attrs.rename {
+            Some(rename) => rename.value(),
+            None => {
+                let field_name = ident.to_string();
+                let field_name = field_name.trim_start_matches("r#");
+                match rename_all {
+                    Some(rule) => rule.apply(field_name),
+                    None => field_name.to_string(),
+                }
+            }
+        };
+        let doc = format!("The path of the `{}` property.", property);
+        declarations.push(quote::quote! {
+            #[doc = #doc]
+            pub #ident: ::azure_data_cosmos::query_builder::Path
+        });
+        initializers.push(quote::quote! {
+            #ident: ::azure_data_cosmos::query_builder::field(#property)
+        });
+    }
+
+    let struct_doc = format!(
+        "The paths of the properties of [`{}`], used to build queries.",
+        name
+    );
+
+    // The fields struct is declared outside of the const block so that callers can name it.
+    Ok(quote::quote! {
+        #[doc = #struct_doc]
+        #[derive(Clone, Debug)]
+        #vis struct #fields_name {
+            #(#declarations,)*
+        }
+
+        #[doc(hidden)]
+        const _: () = {
+            #[automatically_derived]
+            impl #impl_generics ::azure_data_cosmos::query_builder::QueryDocument for #name #ty_generics #where_clause {
+                type Fields = #fields_name;
+
+                fn fields() -> Self::Fields {
+                    #fields_name {
+                        #(#initializers,)*
+                    }
+                }
+            }
+        };
+    })
+}
+
+/// The `serde` attributes which affect the names of the properties.
+#[derive(Default)]
+struct SerdeAttrs {
+    rename: Option<LitStr>,
+    rename_all: Option<LitStr>,
+    skip: bool,
+    flatten: bool,
+}
+
+impl SerdeAttrs {
+    fn from_attrs(attributes: &[Attribute]) -> Result<SerdeAttrs> {
+        let mut attrs = SerdeAttrs::default();
+        for attribute in attributes {
+            if !attribute.path().is_ident("serde") || !matches!(attribute.meta, Meta::List(_)) {
+                continue;
+            }
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") || meta.path.is_ident("rename_all") {
+                    let value = parse_serialize_name(&meta)?;
+                    if meta.path.is_ident("rename") {
+                        attrs.rename = value.or(attrs.rename.take());
+                    } else {
+                        attrs.rename_all = value.or(attrs.rename_all.take());
+                    }
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    attrs.skip = true;
+                } else if meta.path.is_ident("flatten") {
+                    attrs.flatten = true;
+                } else if meta.input.peek(syn::Token![=]) {
+                    // Ignore the other serde attributes, and their values.
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|nested| {
+                        if nested.input.peek(syn::Token![=]) {
+                            nested.value()?.parse::<syn::Expr>()?;
+                        }
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(attrs)
+    }
+}
+
+/// Parses the name used when serializing, from either `rename = "..."` or `rename(serialize = "...", deserialize = "...")`.
+fn parse_serialize_name(meta: &syn::meta::ParseNestedMeta) -> Result<Option<LitStr>> {
+    if meta.input.peek(syn::Token![=]) {
+        return Ok(Some(meta.value()?.parse()?));
+    }
+    let mut name = None;
+    meta.parse_nested_meta(|nested| {
+        let value: LitStr = nested.value()?.parse()?;
+        if nested.path.is_ident("serialize") {
+            name = Some(value);
+        }
+        Ok(())
+    })?;
+    Ok(name)
+}
+
+/// The casing rules supported by `#[serde(rename_all = "...")]`, applied to field names.
+#[derive(Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    fn parse(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => Self::Lower,
+            "UPPERCASE" => Self::Upper,
+            "PascalCase" => Self::Pascal,
+            "camelCase" => Self::Camel,
+            "snake_case" => Self::Snake,
+            "SCREAMING_SNAKE_CASE" => Self::ScreamingSnake,
+            "kebab-case" => Self::Kebab,
+            "SCREAMING-KEBAB-CASE" => Self::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Renames a field, using the same rules as `serde`.
+    fn apply(self, field: &str) -> String {
+        match self {
+            Self::Lower | Self::Snake => field.to_string(),
+            Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+            Self::Pascal => {
+                let mut pascal = String::new();
+                let mut capitalize = true;
+                for ch in field.chars() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if capitalize {
+                        pascal.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        pascal.push(ch);
+                    }
+                }
+                pascal
+            }
+            Self::Camel => {
+                let pascal = Self::Pascal.apply(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+                    None => pascal,
+                }
+            }
+            Self::Kebab => field.replace('_', "-"),
+            Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    #[test]
+    pub fn rename_rules() {
+        let cases = [
+            ("lowercase", "unit_price"),
+            ("UPPERCASE", "UNIT_PRICE"),
+            ("PascalCase", "UnitPrice"),
+            ("camelCase", "unitPrice"),
+            ("snake_case", "unit_price"),
+            ("SCREAMING_SNAKE_CASE", "UNIT_PRICE"),
+            ("kebab-case", "unit-price"),
+            ("SCREAMING-KEBAB-CASE", "UNIT-PRICE"),
+        ];
+        for (rule, expected) in cases {
+            assert_eq!(
+                expected,
+                RenameRule::parse(rule).unwrap().apply("unit_price"),
+                "{}",
+                rule
+            );
+        }
+        assert!(RenameRule::parse("Train-Case").is_none());
+    }
+}
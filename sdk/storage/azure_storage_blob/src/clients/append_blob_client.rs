@@ -0,0 +1,266 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
+    generated::{
+        clients::AppendBlobClient as GeneratedAppendBlobClient,
+        models::AppendBlobClientAppendBlockOptions as GeneratedAppendBlockOptions,
+    },
+    models::{
+        AppendBlobClientAppendBlockFromUrlResult, AppendBlobClientAppendBlockResult,
+        AppendBlobClientCreateResult, AppendBlobClientSealResult,
+    },
+    pipeline,
+    transfer::body_checksum_headers,
+    AppendBlobClientAppendBlockFromUrlOptions, AppendBlobClientAppendBlockOptions,
+    AppendBlobClientCreateOptions, AppendBlobClientOptions, AppendBlobClientSealOptions,
+};
+use azure_core::{
+    http::{Body, RequestContent, Response, Url},
+    Bytes, Result,
+};
+use azure_storage_common::{SharedKeyCredential, StorageCredential};
+
+/// A client to interact with a specific Azure storage Append blob, although that blob may not yet exist.
+///
+/// Append blobs are made of blocks added at their end, such as the lines of a log.
+pub struct AppendBlobClient {
+    pub(crate) endpoint: Url,
+    pub(crate) client: GeneratedAppendBlobClient,
+}
+
+impl AppendBlobClient {
+    /// Creates a new AppendBlobClient.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this Append blob.
+    /// * `blob_name` - The name of the Append blob to interact with.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
+    /// * `options` - Optional configuration for the client.
+    pub fn new(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: impl Into<StorageCredential>,
+        options: Option<AppendBlobClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        let client = GeneratedAppendBlobClient {
+            blob_name,
+            container_name,
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
+        Ok(Self {
+            endpoint: endpoint.parse()?,
+            client,
+        })
+    }
+
+    /// Creates a new AppendBlobClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this Append blob.
+    /// * `blob_name` - The name of the Append blob to interact with.
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: SharedKeyCredential,
+        options: Option<AppendBlobClientOptions>,
+    ) -> Result<Self> {
+        Self::new(
+            endpoint,
+            container_name,
+            blob_name,
+            StorageCredential::SharedKey(credential),
+            options,
+        )
+    }
+
+    /// Creates a new AppendBlobClient from the URL of an Append blob, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of an Append blob, for example `https://myaccount.blob.core.windows.net/container/blob?sv=2025-01-05&sr=b&sp=a&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<AppendBlobClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        let (container_name, blob_name) = url.blob()?;
+        Self::new(
+            &url.endpoint,
+            container_name,
+            blob_name,
+            url.credential,
+            options,
+        )
+    }
+
+    /// Creates a new AppendBlobClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `container_name` - The name of the container containing this Append blob.
+    /// * `blob_name` - The name of the Append blob to interact with.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: String,
+        blob_name: String,
+        options: Option<AppendBlobClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, container_name, blob_name, credential, options)
+    }
+
+    /// Gets the endpoint of the Storage account this client is connected to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the container name of the Storage account this client is connected to.
+    pub fn container_name(&self) -> &str {
+        &self.client.container_name
+    }
+
+    /// Gets the blob name of the Storage account this client is connected to.
+    pub fn blob_name(&self) -> &str {
+        &self.client.blob_name
+    }
+
+    /// Creates a new, empty Append blob, replacing any blob of the same name unless `if_none_match` is set to `*`.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn create(
+        &self,
+        options: Option<AppendBlobClientCreateOptions<'_>>,
+    ) -> Result<Response<AppendBlobClientCreateResult>> {
+        self.client.create(0, options).await
+    }
+
+    /// Adds a block of data at the end of the Append blob.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The content of the block, up to 100 MiB.
+    /// * `options` - Optional configuration for the request.
+    pub async fn append_block(
+        &self,
+        data: RequestContent<Bytes>,
+        options: Option<AppendBlobClientAppendBlockOptions<'_>>,
+    ) -> Result<Response<AppendBlobClientAppendBlockResult>> {
+        let options = options.unwrap_or_default();
+        let mut body = Body::from(data);
+        let content_length = body.len() as u64;
+        let (transactional_content_md5, transactional_content_crc64) =
+            body_checksum_headers(options.validation, &mut body).await?;
+        let options = GeneratedAppendBlockOptions {
+            append_position: options.append_position,
+            client_request_id: options.client_request_id,
+            encryption_algorithm: options.encryption_algorithm,
+            encryption_key: options.encryption_key,
+            encryption_key_sha256: options.encryption_key_sha256,
+            encryption_scope: options.encryption_scope,
+            if_match: options.if_match,
+            if_modified_since: options.if_modified_since,
+            if_none_match: options.if_none_match,
+            if_tags: options.if_tags,
+            if_unmodified_since: options.if_unmodified_since,
+            lease_id: options.lease_id,
+            max_size: options.max_size,
+            method_options: options.method_options,
+            timeout: options.timeout,
+            transactional_content_crc64,
+            transactional_content_md5,
+            ..Default::default()
+        };
+        self.client
+            .append_block(body.into(), content_length, Some(options))
+            .await
+    }
+
+    /// Adds a block at the end of the Append blob, whose content is read by the service from a URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_url` - The URL of the blob or file to read, authorized with a SAS token unless it is public.
+    /// * `options` - Optional configuration for the request.
+    pub async fn append_block_from_url(
+        &self,
+        source_url: String,
+        options: Option<AppendBlobClientAppendBlockFromUrlOptions<'_>>,
+    ) -> Result<Response<AppendBlobClientAppendBlockFromUrlResult>> {
+        self.client
+            .append_block_from_url(source_url, 0, options)
+            .await
+    }
+
+    /// Seals the Append blob, making it read-only.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn seal(
+        &self,
+        options: Option<AppendBlobClientSealOptions<'_>>,
+    ) -> Result<Response<AppendBlobClientSealResult>> {
+        self.client.seal(options).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fake::{header, FakeStorage},
+        AppendBlobClientAppendBlockOptions, TransferValidation,
+    };
+    use azure_core::{
+        base64,
+        http::{headers::Headers, Response, StatusCode},
+    };
+    use md5::{Digest, Md5};
+
+    #[tokio::test]
+    pub async fn appends_validated_blocks() {
+        let storage =
+            FakeStorage::new(|_, _| Response::from_bytes(StatusCode::Created, Headers::new(), ""));
+        let client = storage.append_blob_client("logs", "service.log");
+        let options = AppendBlobClientAppendBlockOptions {
+            append_position: Some(0),
+            validation: Some(TransferValidation::Md5),
+            ..Default::default()
+        };
+        client
+            .append_block("service started\n".try_into().unwrap(), Some(options))
+            .await
+            .unwrap();
+        client
+            .append_block("service stopped\n".try_into().unwrap(), None)
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(
+            Some("0"),
+            header(&requests[0], "x-ms-blob-condition-appendpos").as_deref()
+        );
+        assert_eq!(
+            Some(base64::encode(Md5::digest(b"service started\n"))),
+            header(&requests[0], "content-md5")
+        );
+        assert_eq!(None, header(&requests[1], "content-md5"));
+        assert_eq!(None, header(&requests[1], "x-ms-content-crc64"));
+    }
+}
@@ -2,29 +2,30 @@
 // Licensed under the MIT License.
 
 use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
     generated::clients::BlobClient as GeneratedBlobClient,
     generated::models::{
-        BlobClientDownloadResult, BlobClientGetPropertiesResult,
-        BlockBlobClientCommitBlockListResult, BlockBlobClientStageBlockResult,
-        BlockBlobClientUploadResult,
+        BlobClientAbortCopyFromUrlResult, BlobClientCopyFromUrlResult, BlobClientDownloadResult,
+        BlobClientGetPropertiesResult, BlockBlobClientCommitBlockListResult,
+        BlockBlobClientStageBlockResult, BlockBlobClientUploadResult,
     },
     models::{AccessTier, BlockList, BlockListType, BlockLookupList},
-    pipeline::StorageHeadersPolicy,
-    BlobClientDeleteOptions, BlobClientDownloadOptions, BlobClientGetPropertiesOptions,
-    BlobClientOptions, BlobClientSetMetadataOptions, BlobClientSetPropertiesOptions,
-    BlobClientSetTierOptions, BlockBlobClient, BlockBlobClientCommitBlockListOptions,
+    pipeline,
+    transfer::{copy, download},
+    AppendBlobClient, BlobClientAbortCopyFromUrlOptions, BlobClientBeginCopyFromUrlOptions,
+    BlobClientCopyFromUrlOptions, BlobClientDeleteOptions, BlobClientDownloadOptions,
+    BlobClientDownloadToOptions, BlobClientGetPropertiesOptions, BlobClientOptions,
+    BlobClientSetMetadataOptions, BlobClientSetPropertiesOptions, BlobClientSetTierOptions,
+    BlobCopyPoller, BlobLeaseClient, BlockBlobClient, BlockBlobClientCommitBlockListOptions,
     BlockBlobClientGetBlockListOptions, BlockBlobClientStageBlockOptions,
-    BlockBlobClientUploadOptions,
+    BlockBlobClientUploadOptions, PageBlobClient,
 };
 use azure_core::{
-    credentials::TokenCredential,
-    http::{
-        policies::{BearerTokenCredentialPolicy, Policy},
-        RequestContent, Response, Url,
-    },
+    http::{RequestContent, Response, Url},
     Bytes, Result,
 };
-use std::sync::Arc;
+use azure_storage_common::{SharedKeyCredential, StorageCredential};
+use futures::io::AsyncWrite;
 
 /// A client to interact with a specific Azure storage blob, although that blob may not yet exist.
 pub struct BlobClient {
@@ -33,52 +34,123 @@ pub struct BlobClient {
 }
 
 impl BlobClient {
-    /// Creates a new BlobClient, using Entra ID authentication.
+    /// Creates a new BlobClient.
     ///
     /// # Arguments
     ///
     /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
     /// * `container_name` - The name of the container containing this blob.
     /// * `blob_name` - The name of the blob to interact with.
-    /// * `credential` - An implementation of [`TokenCredential`] that can provide an Entra ID token to use when authenticating.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
     /// * `options` - Optional configuration for the client.
     pub fn new(
         endpoint: &str,
         container_name: String,
         blob_name: String,
-        credential: Arc<dyn TokenCredential>,
+        credential: impl Into<StorageCredential>,
         options: Option<BlobClientOptions>,
     ) -> Result<Self> {
-        let mut options = options.unwrap_or_default();
-
-        let storage_headers_policy = Arc::new(StorageHeadersPolicy);
-        options
-            .client_options
-            .per_call_policies
-            .push(storage_headers_policy);
-
-        let oauth_token_policy = BearerTokenCredentialPolicy::new(
-            credential.clone(),
-            ["https://storage.azure.com/.default"],
-        );
-        options
-            .client_options
-            .per_try_policies
-            .push(Arc::new(oauth_token_policy) as Arc<dyn Policy>);
-
-        let client = GeneratedBlobClient::new(
-            endpoint,
-            credential.clone(),
-            container_name.clone(),
-            blob_name.clone(),
-            Some(options),
-        )?;
+        let options = options.unwrap_or_default();
+        let client = GeneratedBlobClient {
+            blob_name,
+            container_name,
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
         Ok(Self {
             endpoint: endpoint.parse()?,
             client,
         })
     }
 
+    /// Creates a new BlobClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this blob.
+    /// * `blob_name` - The name of the blob to interact with.
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: SharedKeyCredential,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        Self::new(
+            endpoint,
+            container_name,
+            blob_name,
+            StorageCredential::SharedKey(credential),
+            options,
+        )
+    }
+
+    /// Creates a new BlobClient from the URL of a blob, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of a blob, for example `https://myaccount.blob.core.windows.net/container/blob?sv=2025-01-05&sr=b&sp=r&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<BlobClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        let (container_name, blob_name) = url.blob()?;
+        Self::new(
+            &url.endpoint,
+            container_name,
+            blob_name,
+            url.credential,
+            options,
+        )
+    }
+
+    /// Creates a new BlobClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `container_name` - The name of the container containing this blob.
+    /// * `blob_name` - The name of the blob to interact with.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: String,
+        blob_name: String,
+        options: Option<BlobClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, container_name, blob_name, credential, options)
+    }
+
+    /// Returns a new instance of AppendBlobClient, sharing the pipeline of this client.
+    pub fn append_blob_client(&self) -> AppendBlobClient {
+        AppendBlobClient {
+            endpoint: self.client.endpoint.clone(),
+            client: self.client.get_append_blob_client(),
+        }
+    }
+
+    /// Returns a new instance of PageBlobClient, sharing the pipeline of this client.
+    pub fn page_blob_client(&self) -> PageBlobClient {
+        PageBlobClient {
+            endpoint: self.client.endpoint.clone(),
+            client: self.client.get_page_blob_client(),
+        }
+    }
+
+    /// Returns a new instance of BlobLeaseClient to manage the lease of this blob, sharing the pipeline of this client.
+    ///
+    /// # Arguments
+    ///
+    /// * `lease_id` - The ID of the lease to acquire or manage, or `None` to propose a random ID when acquiring a new lease.
+    pub fn lease_client(&self, lease_id: Option<String>) -> BlobLeaseClient {
+        BlobLeaseClient::for_blob(self.generated_client(), lease_id)
+    }
+
     /// Returns a new instance of BlockBlobClient.
     ///
     /// # Arguments
@@ -140,6 +212,45 @@ impl BlobClient {
         self.client.download(options).await
     }
 
+    /// Downloads the blob to a writer, splitting it in ranges of `range_size` bytes fetched with up to `concurrency`
+    /// requests at a time, and written in order. Returns the number of bytes written.
+    ///
+    /// All the ranges require the ETag of the first one, so that the download fails if the blob is changed before it
+    /// completes. A range whose content fails to be received or validated is requested again on its own, from the first byte
+    /// not received yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer the content of the blob is written to.
+    /// * `options` - Optional configuration for the request.
+    pub async fn download_to<W>(
+        &self,
+        writer: W,
+        options: Option<BlobClientDownloadToOptions<'_>>,
+    ) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + Send,
+    {
+        download::download_to(&self.client, writer, options.unwrap_or_default()).await
+    }
+
+    /// Downloads the blob to a file, as [`download_to()`](Self::download_to) does, except that each range is written at its
+    /// offset as soon as it completes. The file is created, or truncated if it exists. Returns the number of bytes written.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to write.
+    /// * `options` - Optional configuration for the request.
+    #[cfg(feature = "tokio")]
+    pub async fn download_to_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: Option<BlobClientDownloadToOptions<'_>>,
+    ) -> Result<u64> {
+        let file = tokio::fs::File::create(path).await?;
+        download::download_to_file(&self.client, file, options.unwrap_or_default()).await
+    }
+
     /// Creates a new blob from a data source.
     ///
     /// # Arguments
@@ -209,4 +320,60 @@ impl BlobClient {
     ) -> Result<Response<()>> {
         self.client.set_tier(tier, options).await
     }
+
+    /// Starts copying a blob or any URL to this blob, returning a [`BlobCopyPoller`] which tracks the copy until the service
+    /// has completed it.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_url` - The URL of the blob or file to copy, authorized with a SAS token unless it is public or `copy_source_authorization`
+    ///   is set.
+    /// * `options` - Optional configuration for the request.
+    pub async fn begin_copy_from_url(
+        &self,
+        source_url: String,
+        options: Option<BlobClientBeginCopyFromUrlOptions<'_>>,
+    ) -> Result<BlobCopyPoller> {
+        copy::begin_copy(self.generated_client(), source_url, options).await
+    }
+
+    /// Copies a blob or any URL of up to 256 MiB to this blob, returning once the service has copied it.
+    ///
+    /// # Arguments
+    ///
+    /// * `source_url` - The URL of the blob or file to copy, authorized with a SAS token unless it is public or `copy_source_authorization`
+    ///   is set.
+    /// * `options` - Optional configuration for the request.
+    pub async fn copy_from_url(
+        &self,
+        source_url: String,
+        options: Option<BlobClientCopyFromUrlOptions<'_>>,
+    ) -> Result<Response<BlobClientCopyFromUrlResult>> {
+        copy::copy_from_url(&self.client, source_url, options).await
+    }
+
+    /// Aborts a pending copy to this blob, leaving it empty, with its metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `copy_id` - The ID of the copy, returned by [`BlobCopyPoller::copy_id()`] or in the properties of the blob.
+    /// * `options` - Optional configuration for the request.
+    pub async fn abort_copy_from_url(
+        &self,
+        copy_id: &str,
+        options: Option<BlobClientAbortCopyFromUrlOptions<'_>>,
+    ) -> Result<Response<BlobClientAbortCopyFromUrlResult>> {
+        copy::abort_copy(&self.client, copy_id, options).await
+    }
+
+    /// Returns a new generated client for this blob, sharing the pipeline of this client.
+    fn generated_client(&self) -> GeneratedBlobClient {
+        GeneratedBlobClient {
+            blob_name: self.client.blob_name.clone(),
+            container_name: self.client.container_name.clone(),
+            endpoint: self.client.endpoint.clone(),
+            pipeline: self.client.pipeline.clone(),
+            version: self.client.version.clone(),
+        }
+    }
 }
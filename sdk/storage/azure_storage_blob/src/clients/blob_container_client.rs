@@ -2,21 +2,20 @@
 // Licensed under the MIT License.
 
 use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
     generated::clients::BlobContainerClient as GeneratedBlobContainerClient,
-    generated::models::BlobContainerClientGetPropertiesResult, pipeline::StorageHeadersPolicy,
-    BlobClient, BlobClientOptions, BlobContainerClientCreateOptions,
+    generated::models::BlobContainerClientGetPropertiesResult,
+    models::{ListBlobsHierarchyResult, ListBlobsResult},
+    pipeline, BlobClient, BlobClientOptions, BlobContainerClientCreateOptions,
     BlobContainerClientDeleteOptions, BlobContainerClientGetPropertiesOptions,
-    BlobContainerClientOptions, BlobContainerClientSetMetadataOptions,
+    BlobContainerClientListBlobsOptions, BlobContainerClientOptions,
+    BlobContainerClientSetMetadataOptions, BlobLeaseClient,
 };
 use azure_core::{
-    credentials::TokenCredential,
-    http::{
-        policies::{BearerTokenCredentialPolicy, Policy},
-        Response, Url,
-    },
+    http::{Pager, Response, Url},
     Result,
 };
-use std::sync::Arc;
+use azure_storage_common::{SharedKeyCredential, StorageCredential};
 
 /// A client to interact with a specified Azure storage container.
 pub struct BlobContainerClient {
@@ -25,50 +24,84 @@ pub struct BlobContainerClient {
 }
 
 impl BlobContainerClient {
-    /// Creates a new BlobContainerClient, using Entra ID authentication.
+    /// Creates a new BlobContainerClient.
     ///
     /// # Arguments
     ///
     /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
     /// * `container_name` - The name of the container.
-    /// * `credential` - An implementation of [`TokenCredential`] that can provide an Entra ID token to use when authenticating.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
     /// * `options` - Optional configuration for the client.
     pub fn new(
         endpoint: &str,
         container_name: String,
-        credential: Arc<dyn TokenCredential>,
+        credential: impl Into<StorageCredential>,
         options: Option<BlobContainerClientOptions>,
     ) -> Result<Self> {
-        let mut options = options.unwrap_or_default();
-
-        let storage_headers_policy = Arc::new(StorageHeadersPolicy);
-        options
-            .client_options
-            .per_call_policies
-            .push(storage_headers_policy);
-
-        let oauth_token_policy = BearerTokenCredentialPolicy::new(
-            credential.clone(),
-            ["https://storage.azure.com/.default"],
-        );
-        options
-            .client_options
-            .per_try_policies
-            .push(Arc::new(oauth_token_policy) as Arc<dyn Policy>);
-
-        let client = GeneratedBlobContainerClient::new(
-            endpoint,
-            credential.clone(),
-            container_name.clone(),
-            Some(options),
-        )?;
-
+        let options = options.unwrap_or_default();
+        let client = GeneratedBlobContainerClient {
+            container_name,
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
         Ok(Self {
             endpoint: endpoint.parse()?,
             client,
         })
     }
 
+    /// Creates a new BlobContainerClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container.
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        container_name: String,
+        credential: SharedKeyCredential,
+        options: Option<BlobContainerClientOptions>,
+    ) -> Result<Self> {
+        Self::new(
+            endpoint,
+            container_name,
+            StorageCredential::SharedKey(credential),
+            options,
+        )
+    }
+
+    /// Creates a new BlobContainerClient from the URL of a container, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of a container, for example `https://myaccount.blob.core.windows.net/container?sv=2025-01-05&sr=c&sp=rl&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<BlobContainerClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        let container_name = url.container()?;
+        Self::new(&url.endpoint, container_name, url.credential, options)
+    }
+
+    /// Creates a new BlobContainerClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `container_name` - The name of the container.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: String,
+        options: Option<BlobContainerClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, container_name, credential, options)
+    }
+
     /// Returns a new instance of BlobClient.
     ///
     /// # Arguments
@@ -81,6 +114,21 @@ impl BlobContainerClient {
         }
     }
 
+    /// Returns a new instance of BlobLeaseClient to manage the lease of this container, sharing the pipeline of this client.
+    ///
+    /// # Arguments
+    ///
+    /// * `lease_id` - The ID of the lease to acquire or manage, or `None` to propose a random ID when acquiring a new lease.
+    pub fn lease_client(&self, lease_id: Option<String>) -> BlobLeaseClient {
+        let client = GeneratedBlobContainerClient {
+            container_name: self.client.container_name.clone(),
+            endpoint: self.client.endpoint.clone(),
+            pipeline: self.client.pipeline.clone(),
+            version: self.client.version.clone(),
+        };
+        BlobLeaseClient::for_container(client, lease_id)
+    }
+
     /// Gets the endpoint of the Storage account this client is connected to.
     pub fn endpoint(&self) -> &Url {
         &self.endpoint
@@ -141,4 +189,84 @@ impl BlobContainerClient {
     ) -> Result<Response<BlobContainerClientGetPropertiesResult>> {
         self.client.get_properties(options).await
     }
+
+    /// Returns a pager over the blobs of the container, in lexicographical order of their names.
+    ///
+    /// Each page is requested with the marker of the previous page, until the service returns no marker.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub fn list_blobs(
+        &self,
+        options: Option<BlobContainerClientListBlobsOptions<'_>>,
+    ) -> Result<Pager<ListBlobsResult>> {
+        let options = options.unwrap_or_default().into_owned();
+        let first_url = self.list_blobs_url(None, &options)?;
+        Ok(pipeline::marker_pager(
+            self.client.pipeline.clone(),
+            pipeline::xml_request(first_url, &self.client.version),
+            options.method_options.context,
+        ))
+    }
+
+    /// Returns a pager over the blobs and virtual directories of the container, treating the names of the blobs as paths
+    /// separated by `delimiter`.
+    ///
+    /// Each page contains the blobs directly under the prefix of the options, and a [`BlobPrefix`](crate::models::BlobPrefix)
+    /// for each virtual directory directly under it, which can be listed in turn by using its name as the prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `delimiter` - The delimiter of the virtual directories, usually `/`.
+    /// * `options` - Optional configuration for the request.
+    pub fn list_blobs_by_hierarchy(
+        &self,
+        delimiter: &str,
+        options: Option<BlobContainerClientListBlobsOptions<'_>>,
+    ) -> Result<Pager<ListBlobsHierarchyResult>> {
+        let options = options.unwrap_or_default().into_owned();
+        let first_url = self.list_blobs_url(Some(delimiter), &options)?;
+        Ok(pipeline::marker_pager(
+            self.client.pipeline.clone(),
+            pipeline::xml_request(first_url, &self.client.version),
+            options.method_options.context,
+        ))
+    }
+
+    fn list_blobs_url(
+        &self,
+        delimiter: Option<&str>,
+        options: &BlobContainerClientListBlobsOptions<'_>,
+    ) -> Result<Url> {
+        let mut url = self.client.endpoint.join(&self.client.container_name)?;
+        url.query_pairs_mut()
+            .append_pair("restype", "container")
+            .append_pair("comp", "list");
+        if let Some(delimiter) = delimiter {
+            url.query_pairs_mut().append_pair("delimiter", delimiter);
+        }
+        if let Some(include) = &options.include {
+            url.query_pairs_mut().append_pair(
+                "include",
+                &include
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        if let Some(maxresults) = options.maxresults {
+            url.query_pairs_mut()
+                .append_pair("maxresults", &maxresults.to_string());
+        }
+        if let Some(prefix) = &options.prefix {
+            url.query_pairs_mut().append_pair("prefix", prefix);
+        }
+        if let Some(timeout) = options.timeout {
+            url.query_pairs_mut()
+                .append_pair("timeout", &timeout.to_string());
+        }
+        Ok(url)
+    }
 }
@@ -0,0 +1,655 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{
+    generated::{
+        clients::{
+            BlobClient as GeneratedBlobClient, BlobContainerClient as GeneratedBlobContainerClient,
+        },
+        models::{
+            BlobClientAcquireLeaseOptions, BlobClientBreakLeaseOptions,
+            BlobClientChangeLeaseOptions, BlobClientReleaseLeaseOptions,
+            BlobClientRenewLeaseOptions, BlobContainerClientAcquireLeaseOptions,
+            BlobContainerClientBreakLeaseOptions, BlobContainerClientChangeLeaseOptions,
+            BlobContainerClientReleaseLeaseOptions, BlobContainerClientRenewLeaseOptions,
+        },
+    },
+    models::{
+        BlobClientAcquireLeaseResult, BlobClientBreakLeaseResult, BlobClientChangeLeaseResult,
+        BlobClientReleaseLeaseResult, BlobClientRenewLeaseResult,
+    },
+    BlobLeaseClientAcquireOptions, BlobLeaseClientBreakOptions, BlobLeaseClientChangeOptions,
+    BlobLeaseClientReleaseOptions, BlobLeaseClientRenewOptions,
+};
+use azure_core::{
+    error::ErrorKind,
+    http::{Response, StatusCode},
+    sleep::sleep,
+    task::{new_task_spawner, SpawnedTask},
+    Error, Result,
+};
+use futures::{
+    channel::oneshot,
+    future::{self, Either, FutureExt, Shared},
+};
+use std::{
+    pin::pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// The resource whose lease is managed by a [`BlobLeaseClient`].
+#[derive(Clone)]
+enum LeaseTarget {
+    Blob(Arc<GeneratedBlobClient>),
+    Container(Arc<GeneratedBlobContainerClient>),
+}
+
+/// A client to acquire and manage the lease of a blob or of a container.
+///
+/// The client holds the ID of the lease, proposed to the service when acquiring the lease and sent with the other lease
+/// operations. Get one with [`BlobClient::lease_client()`](crate::BlobClient::lease_client) or
+/// [`BlobContainerClient::lease_client()`](crate::BlobContainerClient::lease_client).
+#[derive(Clone)]
+pub struct BlobLeaseClient {
+    lease_id: String,
+    target: LeaseTarget,
+}
+
+impl BlobLeaseClient {
+    /// Creates a lease client for a blob, with a random lease ID unless one is given.
+    pub(crate) fn for_blob(client: GeneratedBlobClient, lease_id: Option<String>) -> Self {
+        Self {
+            lease_id: lease_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            target: LeaseTarget::Blob(Arc::new(client)),
+        }
+    }
+
+    /// Creates a lease client for a container, with a random lease ID unless one is given.
+    pub(crate) fn for_container(
+        client: GeneratedBlobContainerClient,
+        lease_id: Option<String>,
+    ) -> Self {
+        Self {
+            lease_id: lease_id.unwrap_or_else(|| Uuid::new_v4().to_string()),
+            target: LeaseTarget::Container(Arc::new(client)),
+        }
+    }
+
+    /// Gets the ID of the lease.
+    pub fn lease_id(&self) -> &str {
+        &self.lease_id
+    }
+
+    /// Acquires the lease, or renews it if it is already held with the ID of this client.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration of the lease, between 15 and 60 seconds, or `None` for a lease that never expires.
+    /// * `options` - Optional configuration for the request.
+    pub async fn acquire(
+        &self,
+        duration: Option<Duration>,
+        options: Option<BlobLeaseClientAcquireOptions<'_>>,
+    ) -> Result<Response<BlobClientAcquireLeaseResult>> {
+        let options = options.unwrap_or_default();
+        let duration = Some(duration.map_or(-1, |duration| duration.as_secs() as i32));
+        let proposed_lease_id = Some(self.lease_id.clone());
+        match &self.target {
+            LeaseTarget::Blob(client) => {
+                let options = BlobClientAcquireLeaseOptions {
+                    duration,
+                    if_match: options.if_match,
+                    if_modified_since: options.if_modified_since,
+                    if_none_match: options.if_none_match,
+                    if_tags: options.if_tags,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    proposed_lease_id,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                client.acquire_lease(Some(options)).await
+            }
+            LeaseTarget::Container(client) => {
+                check_container_conditions(
+                    &options.if_match,
+                    &options.if_none_match,
+                    &options.if_tags,
+                )?;
+                let options = BlobContainerClientAcquireLeaseOptions {
+                    duration,
+                    if_modified_since: options.if_modified_since,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    proposed_lease_id,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                retype(client.acquire_lease(Some(options)).await?).await
+            }
+        }
+    }
+
+    /// Acquires a lease of a fixed duration and keeps renewing it in the background until the returned guard is released or dropped.
+    ///
+    /// The lease is renewed when half of its duration has elapsed. Use [`BlobLeaseGuard::lost()`] or [`BlobLeaseGuard::ensure_held()`]
+    /// to learn whether it could not be renewed, for example because it was broken or expired meanwhile.
+    ///
+    /// # Arguments
+    ///
+    /// * `duration` - The duration of the lease, between 15 and 60 seconds.
+    /// * `options` - Optional configuration for the request acquiring the lease.
+    pub async fn acquire_guard(
+        self,
+        duration: Duration,
+        options: Option<BlobLeaseClientAcquireOptions<'_>>,
+    ) -> Result<BlobLeaseGuard> {
+        self.acquire(Some(duration), options).await?;
+        Ok(BlobLeaseGuard::start(self, duration, duration / 2))
+    }
+
+    /// Renews the lease, resetting its duration.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn renew(
+        &self,
+        options: Option<BlobLeaseClientRenewOptions<'_>>,
+    ) -> Result<Response<BlobClientRenewLeaseResult>> {
+        let options = options.unwrap_or_default();
+        let lease_id = self.lease_id.clone();
+        match &self.target {
+            LeaseTarget::Blob(client) => {
+                let options = BlobClientRenewLeaseOptions {
+                    if_match: options.if_match,
+                    if_modified_since: options.if_modified_since,
+                    if_none_match: options.if_none_match,
+                    if_tags: options.if_tags,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                client.renew_lease(lease_id, Some(options)).await
+            }
+            LeaseTarget::Container(client) => {
+                check_container_conditions(
+                    &options.if_match,
+                    &options.if_none_match,
+                    &options.if_tags,
+                )?;
+                let options = BlobContainerClientRenewLeaseOptions {
+                    if_modified_since: options.if_modified_since,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                retype(client.renew_lease(lease_id, Some(options)).await?).await
+            }
+        }
+    }
+
+    /// Changes the ID of the lease, and uses the new ID for the next operations of this client.
+    ///
+    /// # Arguments
+    ///
+    /// * `proposed_lease_id` - The new ID of the lease, in a GUID string format.
+    /// * `options` - Optional configuration for the request.
+    pub async fn change(
+        &mut self,
+        proposed_lease_id: String,
+        options: Option<BlobLeaseClientChangeOptions<'_>>,
+    ) -> Result<Response<BlobClientChangeLeaseResult>> {
+        let options = options.unwrap_or_default();
+        let lease_id = self.lease_id.clone();
+        let response = match &self.target {
+            LeaseTarget::Blob(client) => {
+                let options = BlobClientChangeLeaseOptions {
+                    if_match: options.if_match,
+                    if_modified_since: options.if_modified_since,
+                    if_none_match: options.if_none_match,
+                    if_tags: options.if_tags,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    proposed_lease_id: Some(proposed_lease_id.clone()),
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                client.change_lease(lease_id, Some(options)).await?
+            }
+            LeaseTarget::Container(client) => {
+                check_container_conditions(
+                    &options.if_match,
+                    &options.if_none_match,
+                    &options.if_tags,
+                )?;
+                let options = BlobContainerClientChangeLeaseOptions {
+                    if_modified_since: options.if_modified_since,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                retype(
+                    client
+                        .change_lease(lease_id, proposed_lease_id.clone(), Some(options))
+                        .await?,
+                )
+                .await?
+            }
+        };
+        self.lease_id = proposed_lease_id;
+        Ok(response)
+    }
+
+    /// Releases the lease, so that another client can acquire a lease immediately.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn release(
+        &self,
+        options: Option<BlobLeaseClientReleaseOptions<'_>>,
+    ) -> Result<Response<BlobClientReleaseLeaseResult>> {
+        let options = options.unwrap_or_default();
+        let lease_id = self.lease_id.clone();
+        match &self.target {
+            LeaseTarget::Blob(client) => {
+                let options = BlobClientReleaseLeaseOptions {
+                    if_match: options.if_match,
+                    if_modified_since: options.if_modified_since,
+                    if_none_match: options.if_none_match,
+                    if_tags: options.if_tags,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                client.release_lease(lease_id, Some(options)).await
+            }
+            LeaseTarget::Container(client) => {
+                check_container_conditions(
+                    &options.if_match,
+                    &options.if_none_match,
+                    &options.if_tags,
+                )?;
+                let options = BlobContainerClientReleaseLeaseOptions {
+                    if_modified_since: options.if_modified_since,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                retype(client.release_lease(lease_id, Some(options)).await?).await
+            }
+        }
+    }
+
+    /// Breaks the lease, whatever its ID. No lease can be acquired until the break period has elapsed.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn break_lease(
+        &self,
+        options: Option<BlobLeaseClientBreakOptions<'_>>,
+    ) -> Result<Response<BlobClientBreakLeaseResult>> {
+        let options = options.unwrap_or_default();
+        match &self.target {
+            LeaseTarget::Blob(client) => {
+                let options = BlobClientBreakLeaseOptions {
+                    break_period: options.break_period,
+                    if_match: options.if_match,
+                    if_modified_since: options.if_modified_since,
+                    if_none_match: options.if_none_match,
+                    if_tags: options.if_tags,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                client.break_lease(Some(options)).await
+            }
+            LeaseTarget::Container(client) => {
+                check_container_conditions(
+                    &options.if_match,
+                    &options.if_none_match,
+                    &options.if_tags,
+                )?;
+                let options = BlobContainerClientBreakLeaseOptions {
+                    break_period: options.break_period,
+                    if_modified_since: options.if_modified_since,
+                    if_unmodified_since: options.if_unmodified_since,
+                    method_options: options.method_options,
+                    timeout: options.timeout,
+                    ..Default::default()
+                };
+                retype(client.break_lease(Some(options)).await?).await
+            }
+        }
+    }
+}
+
+/// A lease renewed in the background, released when the guard is released or dropped.
+///
+/// Returned by [`BlobLeaseClient::acquire_guard()`]. A guard is a good fit for leader election: the holder of the lease is the
+/// leader until it releases the lease, or until [`lost()`](Self::lost) completes because the lease could not be renewed.
+///
+/// Dropping the guard stops the renewals and releases the lease in the background. Use [`release()`](Self::release) to wait for
+/// the lease to be released and learn whether the release succeeded.
+pub struct BlobLeaseGuard {
+    client: BlobLeaseClient,
+    lost_error: Arc<Mutex<Option<(ErrorKind, String)>>>,
+    lost: Shared<oneshot::Receiver<()>>,
+    stop: Option<oneshot::Sender<bool>>,
+    task: Mutex<Option<SpawnedTask>>,
+}
+
+impl BlobLeaseGuard {
+    /// Starts renewing the lease held by `client` every `renew_interval`.
+    pub(crate) fn start(
+        client: BlobLeaseClient,
+        duration: Duration,
+        renew_interval: Duration,
+    ) -> Self {
+        let lost_error = Arc::new(Mutex::new(None));
+        let (lost_sender, lost) = oneshot::channel();
+        let (stop, stop_receiver) = oneshot::channel();
+        let task = new_task_spawner().spawn(Box::pin(keep_renewing(
+            client.clone(),
+            duration,
+            renew_interval,
+            lost_error.clone(),
+            lost_sender,
+            stop_receiver,
+        )));
+        Self {
+            client,
+            lost_error,
+            lost: lost.shared(),
+            stop: Some(stop),
+            task: Mutex::new(Some(task)),
+        }
+    }
+
+    /// Gets the ID of the lease.
+    pub fn lease_id(&self) -> &str {
+        self.client.lease_id()
+    }
+
+    /// Gets the lease client, to pass the ID of the lease to the operations of a blob or a container.
+    pub fn lease_client(&self) -> &BlobLeaseClient {
+        &self.client
+    }
+
+    /// Returns an error if the lease could not be renewed, and is therefore no longer held.
+    pub fn ensure_held(&self) -> Result<()> {
+        match &*self.lost_error.lock().unwrap() {
+            Some((kind, message)) => Err(Error::message(kind.clone(), message.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits until the lease is lost because it could not be renewed, and returns the error of the last renewal.
+    ///
+    /// The future never completes while the lease is held, so it is meant to be raced with the work done under the lease.
+    pub async fn lost(&self) -> Error {
+        if self.lost.clone().await.is_err() {
+            // The renewals were stopped without losing the lease.
+            future::pending::<()>().await;
+        }
+        self.ensure_held()
+            .expect_err("the lease was reported lost without an error")
+    }
+
+    /// Stops renewing the lease and releases it.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request releasing the lease.
+    pub async fn release(
+        mut self,
+        options: Option<BlobLeaseClientReleaseOptions<'_>>,
+    ) -> Result<Response<BlobClientReleaseLeaseResult>> {
+        if let Some(stop) = self.stop.take() {
+            // The renewals are done once the task completes, so that none can follow the release.
+            let _ = stop.send(false);
+        }
+        let task = self.task.lock().unwrap().take();
+        if let Some(task) = task {
+            let _ = task.await;
+        }
+        self.client.release(options).await
+    }
+}
+
+impl Drop for BlobLeaseGuard {
+    fn drop(&mut self) {
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(true);
+        }
+    }
+}
+
+/// Renews a lease every `renew_interval` until asked to stop, releasing it if `stop` says so.
+///
+/// A renewal failing with a transient error is retried sooner, until the lease would have expired. Any other error means that
+/// the lease is lost: it is recorded in `lost_error` and reported through `lost`.
+async fn keep_renewing(
+    client: BlobLeaseClient,
+    duration: Duration,
+    renew_interval: Duration,
+    lost_error: Arc<Mutex<Option<(ErrorKind, String)>>>,
+    lost: oneshot::Sender<()>,
+    mut stop: oneshot::Receiver<bool>,
+) {
+    let retry_interval = renew_interval / 5;
+    let mut wait = renew_interval;
+    let mut remaining = duration;
+    loop {
+        if let Either::Right((release, _)) = future::select(pin!(sleep(wait)), &mut stop).await {
+            // A dropped sender means that the guard is gone.
+            if release.unwrap_or(true) {
+                let _ = client.release(None).await;
+            }
+            return;
+        }
+        remaining = remaining.saturating_sub(wait);
+        match client.renew(None).await {
+            Ok(_) => {
+                wait = renew_interval;
+                remaining = duration;
+            }
+            Err(error) if is_transient(&error) && remaining > retry_interval => {
+                wait = retry_interval;
+            }
+            Err(error) => {
+                *lost_error.lock().unwrap() = Some((
+                    error.kind().clone(),
+                    format!("the lease {} was lost: {error}", client.lease_id()),
+                ));
+                let _ = lost.send(());
+                return;
+            }
+        }
+    }
+}
+
+/// Returns whether a failed renewal may succeed if retried, rather than meaning that the lease is lost.
+fn is_transient(error: &Error) -> bool {
+    match error.kind() {
+        ErrorKind::HttpResponse { status, .. } => {
+            !status.is_client_error()
+                || *status == StatusCode::RequestTimeout
+                || *status == StatusCode::TooManyRequests
+        }
+        _ => true,
+    }
+}
+
+/// Returns an error if conditions that the service only supports for blobs are set for a container.
+fn check_container_conditions(
+    if_match: &Option<String>,
+    if_none_match: &Option<String>,
+    if_tags: &Option<String>,
+) -> Result<()> {
+    if if_match.is_some() || if_none_match.is_some() || if_tags.is_some() {
+        return Err(Error::message(
+            ErrorKind::Other,
+            "if_match, if_none_match and if_tags are only supported for the leases of blobs",
+        ));
+    }
+    Ok(())
+}
+
+/// Converts a response to a container lease operation into the response to the same blob lease operation, with the same headers.
+async fn retype<T, U>(response: Response<T>) -> Result<Response<U>> {
+    let (status, headers, body) = response.deconstruct();
+    Ok(Response::from_bytes(status, headers, body.collect().await?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlobLeaseGuard;
+    use crate::fake::{header, FakeStorage};
+    use azure_core::{
+        error::ErrorKind,
+        http::{headers::Headers, Request, Response, StatusCode},
+    };
+    use std::time::Duration;
+
+    /// Accepts the lease actions it receives, failing the renewals once `renewals` have succeeded.
+    fn fake_lease(renewals: usize) -> FakeStorage {
+        FakeStorage::new(move |request, requests| {
+            let mut headers = Headers::new();
+            let renewed = requests
+                .iter()
+                .filter(|request| action(request) == "renew")
+                .count();
+            let action = action(request);
+            if action == "renew" && renewed > renewals {
+                headers.insert("x-ms-error-code", "LeaseIdMismatchWithLeaseOperation");
+                return Response::from_bytes(StatusCode::Conflict, headers, "");
+            }
+            let status = match action {
+                "acquire" => StatusCode::Created,
+                "break" => StatusCode::Accepted,
+                _ => StatusCode::Ok,
+            };
+            Response::from_bytes(status, headers, "")
+        })
+    }
+
+    fn action(request: &Request) -> &'static str {
+        ["acquire", "renew", "change", "release", "break"]
+            .into_iter()
+            .find(|action| request.url().query_pairs().any(|(key, _)| key == *action))
+            .unwrap()
+    }
+
+    fn actions(storage: &FakeStorage) -> Vec<&'static str> {
+        storage.requests().iter().map(action).collect()
+    }
+
+    #[tokio::test]
+    pub async fn sends_lease_ids() {
+        let storage = fake_lease(usize::MAX);
+        let container_client = storage.container_client("locks");
+
+        let mut lease_client = container_client.lease_client(Some("lease-1".to_string()));
+        lease_client
+            .acquire(Some(Duration::from_secs(15)), None)
+            .await
+            .unwrap();
+        lease_client
+            .change("lease-2".to_string(), None)
+            .await
+            .unwrap();
+        lease_client.release(None).await.unwrap();
+
+        assert_eq!("lease-2", lease_client.lease_id());
+        let requests = storage.requests();
+        let acquire = &requests[0];
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net/locks?acquire&comp=lease&restype=container",
+            acquire.url().as_str()
+        );
+        assert_eq!(
+            Some("15"),
+            header(acquire, "x-ms-lease-duration").as_deref()
+        );
+        assert_eq!(
+            Some("lease-1"),
+            header(acquire, "x-ms-proposed-lease-id").as_deref()
+        );
+        assert_eq!(
+            Some("lease-1"),
+            header(&requests[1], "x-ms-lease-id").as_deref()
+        );
+        assert_eq!(
+            Some("lease-2"),
+            header(&requests[2], "x-ms-lease-id").as_deref()
+        );
+    }
+
+    #[tokio::test]
+    pub async fn renews_until_dropped() {
+        let storage = fake_lease(usize::MAX);
+        let lease_client = storage.blob_client("locks", "leader").lease_client(None);
+        lease_client.acquire(None, None).await.unwrap();
+
+        let guard = BlobLeaseGuard::start(
+            lease_client,
+            Duration::from_millis(200),
+            Duration::from_millis(20),
+        );
+        tokio::time::sleep(Duration::from_millis(110)).await;
+        guard.ensure_held().unwrap();
+        drop(guard);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let actions = actions(&storage);
+        assert!(actions.iter().filter(|action| **action == "renew").count() >= 3);
+        assert_eq!(Some(&"release"), actions.last());
+        assert_eq!(
+            1,
+            actions
+                .iter()
+                .filter(|action| **action == "release")
+                .count()
+        );
+    }
+
+    #[tokio::test]
+    pub async fn reports_lost_lease() {
+        let storage = fake_lease(2);
+        let lease_client = storage.blob_client("locks", "leader").lease_client(None);
+        lease_client.acquire(None, None).await.unwrap();
+
+        let guard = BlobLeaseGuard::start(
+            lease_client,
+            Duration::from_millis(200),
+            Duration::from_millis(20),
+        );
+        let error = tokio::time::timeout(Duration::from_secs(5), guard.lost())
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            error.kind(),
+            ErrorKind::HttpResponse {
+                status: StatusCode::Conflict,
+                ..
+            }
+        ));
+        assert!(guard.ensure_held().is_err());
+        assert_eq!(
+            vec!["acquire", "renew", "renew", "renew"],
+            actions(&storage)
+        );
+    }
+}
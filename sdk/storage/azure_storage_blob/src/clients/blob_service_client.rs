@@ -2,19 +2,19 @@
 // Licensed under the MIT License.
 
 use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
     generated::clients::BlobServiceClient as GeneratedBlobServiceClient,
-    models::StorageServiceProperties, pipeline::StorageHeadersPolicy, BlobContainerClient,
-    BlobContainerClientOptions, BlobServiceClientGetPropertiesOptions, BlobServiceClientOptions,
+    models::{ListContainersSegmentResponse, StorageServiceProperties, UserDelegationKey},
+    pipeline, BlobContainerClient, BlobContainerClientOptions,
+    BlobServiceClientGetPropertiesOptions, BlobServiceClientGetUserDelegationKeyOptions,
+    BlobServiceClientListContainersOptions, BlobServiceClientOptions,
 };
 use azure_core::{
-    credentials::TokenCredential,
-    http::{
-        policies::{BearerTokenCredentialPolicy, Policy},
-        Response, Url,
-    },
+    date::OffsetDateTime,
+    http::{Pager, Response, Url},
     Result,
 };
-use std::sync::Arc;
+use azure_storage_common::{sas::to_sas_time, SharedKeyCredential, StorageCredential};
 
 /// A client to interact with an Azure storage account.
 pub struct BlobServiceClient {
@@ -23,43 +23,72 @@ pub struct BlobServiceClient {
 }
 
 impl BlobServiceClient {
-    /// Creates a new BlobServiceClient, using Entra ID authentication.
+    /// Creates a new BlobServiceClient.
     ///
     /// # Arguments
     ///
     /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
-    /// * `credential` - An implementation of [`TokenCredential`] that can provide an Entra ID token to use when authenticating.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
     /// * `options` - Optional configuration for the client.
     pub fn new(
         endpoint: &str,
-        credential: Arc<dyn TokenCredential>,
+        credential: impl Into<StorageCredential>,
         options: Option<BlobServiceClientOptions>,
     ) -> Result<Self> {
-        let mut options = options.unwrap_or_default();
-
-        let storage_headers_policy = Arc::new(StorageHeadersPolicy);
-        options
-            .client_options
-            .per_call_policies
-            .push(storage_headers_policy);
-
-        let oauth_token_policy = BearerTokenCredentialPolicy::new(
-            credential.clone(),
-            ["https://storage.azure.com/.default"],
-        );
-        options
-            .client_options
-            .per_try_policies
-            .push(Arc::new(oauth_token_policy) as Arc<dyn Policy>);
-
-        let client = GeneratedBlobServiceClient::new(endpoint, credential.clone(), Some(options))?;
-
+        let options = options.unwrap_or_default();
+        let client = GeneratedBlobServiceClient {
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
         Ok(Self {
             endpoint: endpoint.parse()?,
             client,
         })
     }
 
+    /// Creates a new BlobServiceClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        credential: SharedKeyCredential,
+        options: Option<BlobServiceClientOptions>,
+    ) -> Result<Self> {
+        Self::new(endpoint, StorageCredential::SharedKey(credential), options)
+    }
+
+    /// Creates a new BlobServiceClient from the URL of a Storage account, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of a Storage account, for example `https://myaccount.blob.core.windows.net/?sv=2025-01-05&ss=b&srt=sco&sp=rl&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<BlobServiceClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        url.account()?;
+        Self::new(&url.endpoint, url.credential, options)
+    }
+
+    /// Creates a new BlobServiceClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        options: Option<BlobServiceClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, credential, options)
+    }
+
     /// Returns a new instance of BlobContainerClient.
     ///
     /// # Arguments
@@ -88,4 +117,68 @@ impl BlobServiceClient {
     ) -> Result<Response<StorageServiceProperties>> {
         self.client.get_properties(options).await
     }
+
+    /// Gets a key to sign user delegation SAS tokens with, using the Entra ID credential of this client.
+    ///
+    /// See [`BlobSasBuilder::sign_with_user_delegation_key()`](crate::sas::BlobSasBuilder::sign_with_user_delegation_key).
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The time from which the key is valid.
+    /// * `expiry` - The time after which the key is no longer valid, at most seven days after `start`.
+    /// * `options` - Optional configuration for the request.
+    pub async fn get_user_delegation_key(
+        &self,
+        start: OffsetDateTime,
+        expiry: OffsetDateTime,
+        options: Option<BlobServiceClientGetUserDelegationKeyOptions<'_>>,
+    ) -> Result<Response<UserDelegationKey>> {
+        self.client
+            .get_user_delegation_key(to_sas_time(&start), to_sas_time(&expiry), options)
+            .await
+    }
+
+    /// Returns a pager over the containers of the Storage account, in lexicographical order of their names.
+    ///
+    /// Each page is requested with the marker of the previous page, until the service returns no marker.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub fn list_containers(
+        &self,
+        options: Option<BlobServiceClientListContainersOptions<'_>>,
+    ) -> Result<Pager<ListContainersSegmentResponse>> {
+        let options = options.unwrap_or_default().into_owned();
+        let mut first_url = self.client.endpoint.clone();
+        first_url.query_pairs_mut().append_pair("comp", "list");
+        if let Some(include) = &options.include {
+            first_url.query_pairs_mut().append_pair(
+                "include",
+                &include
+                    .iter()
+                    .map(|i| i.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            );
+        }
+        if let Some(maxresults) = options.maxresults {
+            first_url
+                .query_pairs_mut()
+                .append_pair("maxresults", &maxresults.to_string());
+        }
+        if let Some(prefix) = &options.prefix {
+            first_url.query_pairs_mut().append_pair("prefix", prefix);
+        }
+        if let Some(timeout) = options.timeout {
+            first_url
+                .query_pairs_mut()
+                .append_pair("timeout", &timeout.to_string());
+        }
+        Ok(pipeline::marker_pager(
+            self.client.pipeline.clone(),
+            pipeline::xml_request(first_url, &self.client.version),
+            options.method_options.context,
+        ))
+    }
 }
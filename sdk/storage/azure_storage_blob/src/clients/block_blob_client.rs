@@ -2,6 +2,7 @@
 // Licensed under the MIT License.
 
 use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
     generated::clients::BlockBlobClient as GeneratedBlockBlobClient,
     generated::models::{
         BlobClientDownloadResult, BlobClientGetPropertiesResult,
@@ -9,22 +10,21 @@ use crate::{
         BlockBlobClientUploadResult,
     },
     models::{BlockList, BlockListType, BlockLookupList, StorageServiceProperties},
-    pipeline::StorageHeadersPolicy,
+    pipeline,
+    transfer::upload,
     BlobClientDeleteOptions, BlobClientDownloadOptions, BlobClientGetPropertiesOptions,
     BlobClientOptions, BlobClientSetMetadataOptions, BlobClientSetPropertiesOptions,
     BlobClientSetTierOptions, BlockBlobClientCommitBlockListOptions,
     BlockBlobClientGetBlockListOptions, BlockBlobClientOptions, BlockBlobClientStageBlockOptions,
-    BlockBlobClientUploadOptions,
+    BlockBlobClientUploadOptions, BlockBlobClientUploadStreamOptions,
 };
 use azure_core::{
-    credentials::TokenCredential,
-    http::{
-        policies::{BearerTokenCredentialPolicy, Policy},
-        RequestContent, Response, Url,
-    },
+    http::{RequestContent, Response, Url},
+    stream::SeekableStream,
     Bytes, Result,
 };
-use std::sync::Arc;
+use azure_storage_common::{SharedKeyCredential, StorageCredential};
+use futures::io::AsyncRead;
 
 /// A client to interact with a specific Azure storage Block blob, although that blob may not yet exist.
 pub struct BlockBlobClient {
@@ -33,52 +33,98 @@ pub struct BlockBlobClient {
 }
 
 impl BlockBlobClient {
-    /// Creates a new BlockBlobClient, using Entra ID authentication.
+    /// Creates a new BlockBlobClient.
     ///
     /// # Arguments
     ///
     /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
     /// * `container_name` - The name of the container containing this Block blob.
     /// * `blob_name` - The name of the Block blob to interact with.
-    /// * `credential` - An implementation of [`TokenCredential`] that can provide an Entra ID token to use when authenticating.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
     /// * `options` - Optional configuration for the client.
     pub fn new(
         endpoint: &str,
         container_name: String,
         blob_name: String,
-        credential: Arc<dyn TokenCredential>,
+        credential: impl Into<StorageCredential>,
         options: Option<BlockBlobClientOptions>,
     ) -> Result<Self> {
-        let mut options = options.unwrap_or_default();
-
-        let storage_headers_policy = Arc::new(StorageHeadersPolicy);
-        options
-            .client_options
-            .per_call_policies
-            .push(storage_headers_policy);
-
-        let oauth_token_policy = BearerTokenCredentialPolicy::new(
-            credential.clone(),
-            ["https://storage.azure.com/.default"],
-        );
-        options
-            .client_options
-            .per_try_policies
-            .push(Arc::new(oauth_token_policy) as Arc<dyn Policy>);
-
-        let client = GeneratedBlockBlobClient::new(
-            endpoint,
-            credential.clone(),
-            container_name.clone(),
-            blob_name.clone(),
-            Some(options),
-        )?;
+        let options = options.unwrap_or_default();
+        let client = GeneratedBlockBlobClient {
+            blob_name,
+            container_name,
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
         Ok(Self {
             endpoint: endpoint.parse()?,
             client,
         })
     }
 
+    /// Creates a new BlockBlobClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this Block blob.
+    /// * `blob_name` - The name of the Block blob to interact with.
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: SharedKeyCredential,
+        options: Option<BlockBlobClientOptions>,
+    ) -> Result<Self> {
+        Self::new(
+            endpoint,
+            container_name,
+            blob_name,
+            StorageCredential::SharedKey(credential),
+            options,
+        )
+    }
+
+    /// Creates a new BlockBlobClient from the URL of a Block blob, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of a Block blob, for example `https://myaccount.blob.core.windows.net/container/blob?sv=2025-01-05&sr=b&sp=r&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<BlockBlobClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        let (container_name, blob_name) = url.blob()?;
+        Self::new(
+            &url.endpoint,
+            container_name,
+            blob_name,
+            url.credential,
+            options,
+        )
+    }
+
+    /// Creates a new BlockBlobClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `container_name` - The name of the container containing this Block blob.
+    /// * `blob_name` - The name of the Block blob to interact with.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: String,
+        blob_name: String,
+        options: Option<BlockBlobClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, container_name, blob_name, credential, options)
+    }
+
     /// Gets the endpoint of the Storage account this client is connected to.
     pub fn endpoint(&self) -> &Url {
         &self.endpoint
@@ -142,4 +188,61 @@ impl BlockBlobClient {
     ) -> Result<Response<BlockList>> {
         self.client.get_block_list(list_type, options).await
     }
+
+    /// Uploads a stream to the blob, replacing its content.
+    ///
+    /// The stream is sent with a single request when it is not larger than the `max_single_upload_size` of the options.
+    /// Otherwise it is read in blocks of `block_size` bytes, staged with up to `concurrency` requests at a time, then committed
+    /// with the properties, metadata, tags and tier of the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The data to upload.
+    /// * `options` - Optional configuration for the request.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn upload_stream(
+        &self,
+        stream: Box<dyn SeekableStream>,
+        options: Option<BlockBlobClientUploadStreamOptions<'_>>,
+    ) -> Result<Response<BlockBlobClientUploadResult>> {
+        upload::upload_stream(&self.client, stream, options.unwrap_or_default()).await
+    }
+
+    /// Uploads the data of a reader to the blob, replacing its content.
+    ///
+    /// The data is read in blocks of `block_size` bytes, and buffered until it is larger than the `max_single_upload_size` of
+    /// the options. If it is not, it is sent with a single request. Otherwise the blocks are staged with up to `concurrency`
+    /// requests at a time, then committed with the properties, metadata, tags and tier of the options.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The data to upload.
+    /// * `options` - Optional configuration for the request.
+    pub async fn upload_reader<R>(
+        &self,
+        reader: R,
+        options: Option<BlockBlobClientUploadStreamOptions<'_>>,
+    ) -> Result<Response<BlockBlobClientUploadResult>>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        upload::upload_reader(&self.client, reader, options.unwrap_or_default()).await
+    }
+
+    /// Uploads a file to the blob, replacing its content, as [`upload_stream()`](Self::upload_stream) does.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the file to upload.
+    /// * `options` - Optional configuration for the request.
+    #[cfg(feature = "tokio")]
+    pub async fn upload_file(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        options: Option<BlockBlobClientUploadStreamOptions<'_>>,
+    ) -> Result<Response<BlockBlobClientUploadResult>> {
+        let file = tokio::fs::File::open(path).await?;
+        let stream = azure_core::fs::FileStreamBuilder::new(file).build().await?;
+        self.upload_stream(Box::new(stream), options).await
+    }
 }
@@ -0,0 +1,170 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use azure_core::{error::ErrorKind, http::Url, Result};
+use azure_storage_common::{ConnectionString, StorageCredential};
+use url::Host;
+
+/// The ports of the Azurite emulator, whose URLs contain the account name in their path.
+const EMULATOR_PORTS: [u16; 3] = [10000, 10001, 10002];
+
+/// The URL of a resource, split into the endpoint of its account and the path of the resource.
+pub(crate) struct ResourceUrl {
+    pub(crate) endpoint: String,
+    pub(crate) path: Vec<String>,
+    pub(crate) credential: StorageCredential,
+}
+
+impl ResourceUrl {
+    /// Splits the URL of a resource, using the SAS token in its query as the credential, if any.
+    ///
+    /// The account name is part of the endpoint of URLs using an IP address, `localhost` or the ports of the Azurite emulator,
+    /// such as `http://127.0.0.1:10000/devstoreaccount1/container/blob`.
+    pub(crate) fn parse(url: &str) -> Result<Self> {
+        let url = Url::parse(url)?;
+        let path_style = match url.host() {
+            Some(Host::Ipv4(_) | Host::Ipv6(_)) => true,
+            Some(Host::Domain(domain)) => {
+                domain == "localhost"
+                    || url
+                        .port()
+                        .is_some_and(|port| EMULATOR_PORTS.contains(&port))
+            }
+            None => false,
+        };
+        let mut path: Vec<String> = url
+            .path_segments()
+            .into_iter()
+            .flatten()
+            .filter(|segment| !segment.is_empty())
+            .map(percent_decode)
+            .collect();
+
+        let mut endpoint = url.clone();
+        endpoint.set_query(None);
+        endpoint.set_fragment(None);
+        let account = if path_style && !path.is_empty() {
+            format!("/{}/", path.remove(0))
+        } else {
+            String::from("/")
+        };
+        endpoint.set_path(&account);
+
+        let credential = match url.query() {
+            Some(query) if url.query_pairs().any(|(name, _)| name == "sig") => {
+                StorageCredential::Sas(query.parse()?)
+            }
+            _ => StorageCredential::Anonymous,
+        };
+        Ok(Self {
+            endpoint: endpoint.to_string(),
+            path,
+            credential,
+        })
+    }
+
+    /// Checks the URL is the one of an account, without the path of a resource.
+    pub(crate) fn account(&self) -> Result<()> {
+        match self.path.len() {
+            0 => Ok(()),
+            _ => Err(invalid_url("an account", "no container nor blob name")),
+        }
+    }
+
+    /// Gets the container name from the URL of a container.
+    pub(crate) fn container(&self) -> Result<String> {
+        match self.path.as_slice() {
+            [container_name] => Ok(container_name.clone()),
+            _ => Err(invalid_url("a container", "the container name only")),
+        }
+    }
+
+    /// Gets the container and blob names from the URL of a blob. The blob name may contain `/`.
+    pub(crate) fn blob(&self) -> Result<(String, String)> {
+        match self.path.as_slice() {
+            [container_name, blob_name @ ..] if !blob_name.is_empty() => {
+                Ok((container_name.clone(), blob_name.join("/")))
+            }
+            _ => Err(invalid_url("a blob", "the container and blob names")),
+        }
+    }
+}
+
+fn invalid_url(resource: &str, expected: &str) -> azure_core::Error {
+    azure_core::Error::message(
+        ErrorKind::DataConversion,
+        format!("the URL of {resource} must contain {expected}"),
+    )
+}
+
+/// Parses a connection string, returning the Blob service endpoint and the credential.
+pub(crate) fn parse_connection_string(
+    connection_string: &str,
+) -> Result<(String, StorageCredential)> {
+    let connection_string: ConnectionString = connection_string.parse()?;
+    let endpoint = connection_string.blob_endpoint().ok_or_else(|| {
+        azure_core::Error::message(
+            ErrorKind::DataConversion,
+            "the connection string has no Blob service endpoint",
+        )
+    })?;
+    Ok((endpoint.to_string(), connection_string.credential().clone()))
+}
+
+/// Decodes the percent-encoded bytes of a URL path segment, or of an encoded blob name.
+pub(crate) fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceUrl;
+    use azure_storage_common::StorageCredential;
+
+    #[test]
+    pub fn splits_resource_urls() {
+        let url = ResourceUrl::parse(
+            "https://myaccount.blob.core.windows.net/photos/2025/hello%20world.jpg?sv=2025-01-05&sr=b&sp=r&sig=abc%3D",
+        )
+        .unwrap();
+        assert_eq!("https://myaccount.blob.core.windows.net/", url.endpoint);
+        assert_eq!(
+            ("photos".to_string(), "2025/hello world.jpg".to_string()),
+            url.blob().unwrap()
+        );
+        assert!(url.container().is_err());
+        assert!(
+            matches!(url.credential, StorageCredential::Sas(token) if token.get("sig") == Some("abc="))
+        );
+
+        let url = ResourceUrl::parse("http://127.0.0.1:10000/devstoreaccount1/photos").unwrap();
+        assert_eq!("http://127.0.0.1:10000/devstoreaccount1/", url.endpoint);
+        assert_eq!("photos", url.container().unwrap());
+        assert!(url.blob().is_err());
+        assert!(url.account().is_err());
+        assert!(matches!(url.credential, StorageCredential::Anonymous));
+
+        let url = ResourceUrl::parse("http://azurite:10000/devstoreaccount1/").unwrap();
+        assert_eq!("http://azurite:10000/devstoreaccount1/", url.endpoint);
+        assert!(url.account().is_ok());
+    }
+}
@@ -3,12 +3,19 @@
 // Licensed under the MIT License. See License.txt in the project root for license information.
 // Code generated by Microsoft (R) Rust Code Generator. DO NOT EDIT.
 
+mod append_blob_client;
 mod blob_client;
 mod blob_container_client;
+mod blob_lease_client;
 mod blob_service_client;
 mod block_blob_client;
+pub(crate) mod endpoint;
+mod page_blob_client;
 
+pub use append_blob_client::AppendBlobClient;
 pub use blob_client::BlobClient;
 pub use blob_container_client::BlobContainerClient;
+pub use blob_lease_client::{BlobLeaseClient, BlobLeaseGuard};
 pub use blob_service_client::BlobServiceClient;
 pub use block_blob_client::BlockBlobClient;
+pub use page_blob_client::PageBlobClient;
@@ -0,0 +1,492 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{
+    clients::endpoint::{parse_connection_string, ResourceUrl},
+    generated::{
+        clients::PageBlobClient as GeneratedPageBlobClient,
+        models::PageBlobClientUploadPagesOptions as GeneratedUploadPagesOptions,
+    },
+    models::{
+        PageBlobClientClearPagesResult, PageBlobClientCopyIncrementalResult,
+        PageBlobClientCreateResult, PageBlobClientResizeResult,
+        PageBlobClientUpdateSequenceNumberResult, PageBlobClientUploadPagesResult,
+        PageRangesResult, SequenceNumberActionType,
+    },
+    pipeline,
+    transfer::body_checksum_headers,
+    PageBlobClientClearPagesOptions, PageBlobClientCopyIncrementalOptions,
+    PageBlobClientCreateOptions, PageBlobClientGetPageRangesOptions, PageBlobClientOptions,
+    PageBlobClientResizeOptions, PageBlobClientUpdateSequenceNumberOptions,
+    PageBlobClientUploadPagesOptions,
+};
+use azure_core::{
+    date,
+    error::ErrorKind,
+    http::{Body, Pager, RequestContent, Response, Url},
+    Bytes, Result,
+};
+use azure_storage_common::{SharedKeyCredential, StorageCredential};
+use std::ops::Range;
+
+/// The size of the pages of a page blob. The size of a page blob, and the offsets and lengths of its ranges, are multiples of
+/// this size.
+const PAGE_SIZE: u64 = 512;
+
+/// A client to interact with a specific Azure storage Page blob, although that blob may not yet exist.
+///
+/// Page blobs are made of 512-byte pages written at random offsets, such as the sectors of a virtual hard disk.
+pub struct PageBlobClient {
+    pub(crate) endpoint: Url,
+    pub(crate) client: GeneratedPageBlobClient,
+}
+
+impl PageBlobClient {
+    /// Creates a new PageBlobClient.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this Page blob.
+    /// * `blob_name` - The name of the Page blob to interact with.
+    /// * `credential` - The credential used to authorize requests: an implementation of [`TokenCredential`](azure_core::credentials::TokenCredential)
+    ///   that can provide an Entra ID token, a [`SharedKeyCredential`], a [`SasToken`](crate::sas::SasToken), or [`StorageCredential::Anonymous`] for public access.
+    /// * `options` - Optional configuration for the client.
+    pub fn new(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: impl Into<StorageCredential>,
+        options: Option<PageBlobClientOptions>,
+    ) -> Result<Self> {
+        let options = options.unwrap_or_default();
+        let client = GeneratedPageBlobClient {
+            blob_name,
+            container_name,
+            endpoint: pipeline::parse_endpoint(endpoint)?,
+            pipeline: pipeline::pipeline(options.client_options, credential.into()),
+            version: options.version,
+        };
+        Ok(Self {
+            endpoint: endpoint.parse()?,
+            client,
+        })
+    }
+
+    /// Creates a new PageBlobClient, using Shared Key authentication.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The full URL of the Azure storage account, for example `https://myaccount.blob.core.windows.net/`
+    /// * `container_name` - The name of the container containing this Page blob.
+    /// * `blob_name` - The name of the Page blob to interact with.
+    /// * `credential` - The name and key of the Storage account, used to sign requests with Shared Key authorization.
+    /// * `options` - Optional configuration for the client.
+    pub fn with_shared_key(
+        endpoint: &str,
+        container_name: String,
+        blob_name: String,
+        credential: SharedKeyCredential,
+        options: Option<PageBlobClientOptions>,
+    ) -> Result<Self> {
+        Self::new(
+            endpoint,
+            container_name,
+            blob_name,
+            StorageCredential::SharedKey(credential),
+            options,
+        )
+    }
+
+    /// Creates a new PageBlobClient from the URL of a Page blob, using the SAS token in its query if any, or anonymous access otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - The full URL of a Page blob, for example `https://myaccount.blob.core.windows.net/container/disk.vhd?sv=2025-01-05&sr=b&sp=rw&sig=...`
+    /// * `options` - Optional configuration for the client.
+    pub fn from_url(url: &str, options: Option<PageBlobClientOptions>) -> Result<Self> {
+        let url = ResourceUrl::parse(url)?;
+        let (container_name, blob_name) = url.blob()?;
+        Self::new(
+            &url.endpoint,
+            container_name,
+            blob_name,
+            url.credential,
+            options,
+        )
+    }
+
+    /// Creates a new PageBlobClient from the connection string of a Storage account.
+    ///
+    /// # Arguments
+    ///
+    /// * `connection_string` - The connection string of the Storage account, or `UseDevelopmentStorage=true` for the Azurite emulator.
+    /// * `container_name` - The name of the container containing this Page blob.
+    /// * `blob_name` - The name of the Page blob to interact with.
+    /// * `options` - Optional configuration for the client.
+    pub fn from_connection_string(
+        connection_string: &str,
+        container_name: String,
+        blob_name: String,
+        options: Option<PageBlobClientOptions>,
+    ) -> Result<Self> {
+        let (endpoint, credential) = parse_connection_string(connection_string)?;
+        Self::new(&endpoint, container_name, blob_name, credential, options)
+    }
+
+    /// Gets the endpoint of the Storage account this client is connected to.
+    pub fn endpoint(&self) -> &Url {
+        &self.endpoint
+    }
+
+    /// Gets the container name of the Storage account this client is connected to.
+    pub fn container_name(&self) -> &str {
+        &self.client.container_name
+    }
+
+    /// Gets the blob name of the Storage account this client is connected to.
+    pub fn blob_name(&self) -> &str {
+        &self.client.blob_name
+    }
+
+    /// Creates a new Page blob of the given size, whose pages are all cleared, replacing any blob of the same name unless
+    /// `if_none_match` is set to `*`.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The size of the Page blob, a multiple of 512 bytes.
+    /// * `options` - Optional configuration for the request.
+    pub async fn create(
+        &self,
+        size: u64,
+        options: Option<PageBlobClientCreateOptions<'_>>,
+    ) -> Result<Response<PageBlobClientCreateResult>> {
+        check_aligned("size", size)?;
+        self.client.create(0, size, options).await
+    }
+
+    /// Writes pages to the Page blob, from the given offset.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The offset of the first page to write, a multiple of 512 bytes.
+    /// * `data` - The content of the pages, a multiple of 512 bytes and up to 4 MiB.
+    /// * `options` - Optional configuration for the request.
+    pub async fn upload_pages(
+        &self,
+        offset: u64,
+        data: RequestContent<Bytes>,
+        options: Option<PageBlobClientUploadPagesOptions<'_>>,
+    ) -> Result<Response<PageBlobClientUploadPagesResult>> {
+        let options = options.unwrap_or_default();
+        let mut body = Body::from(data);
+        let length = body.len() as u64;
+        let range = range_header(offset..offset + length)?;
+        let (transactional_content_md5, transactional_content_crc64) =
+            body_checksum_headers(options.validation, &mut body).await?;
+        let options = GeneratedUploadPagesOptions {
+            client_request_id: options.client_request_id,
+            encryption_algorithm: options.encryption_algorithm,
+            encryption_key: options.encryption_key,
+            encryption_key_sha256: options.encryption_key_sha256,
+            encryption_scope: options.encryption_scope,
+            if_match: options.if_match,
+            if_modified_since: options.if_modified_since,
+            if_none_match: options.if_none_match,
+            if_sequence_number_equal_to: options.if_sequence_number_equal_to,
+            if_sequence_number_less_than: options.if_sequence_number_less_than,
+            if_sequence_number_less_than_or_equal_to: options
+                .if_sequence_number_less_than_or_equal_to,
+            if_tags: options.if_tags,
+            if_unmodified_since: options.if_unmodified_since,
+            lease_id: options.lease_id,
+            method_options: options.method_options,
+            range: Some(range),
+            timeout: options.timeout,
+            transactional_content_crc64,
+            transactional_content_md5,
+            ..Default::default()
+        };
+        self.client
+            .upload_pages(body.into(), length, Some(options))
+            .await
+    }
+
+    /// Clears pages of the Page blob, which then read as zeros and are no longer billed.
+    ///
+    /// # Arguments
+    ///
+    /// * `range` - The bytes to clear, which must start and end at multiples of 512 bytes.
+    /// * `options` - Optional configuration for the request. Its `range` is set from the range to clear.
+    pub async fn clear_pages(
+        &self,
+        range: Range<u64>,
+        options: Option<PageBlobClientClearPagesOptions<'_>>,
+    ) -> Result<Response<PageBlobClientClearPagesResult>> {
+        let mut options = options.unwrap_or_default();
+        options.range = Some(range_header(range)?);
+        self.client.clear_pages(0, Some(options)).await
+    }
+
+    /// Resizes the Page blob, clearing the pages added when it grows, and discarding the pages removed when it shrinks.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The new size of the Page blob, a multiple of 512 bytes.
+    /// * `options` - Optional configuration for the request.
+    pub async fn resize(
+        &self,
+        size: u64,
+        options: Option<PageBlobClientResizeOptions<'_>>,
+    ) -> Result<Response<PageBlobClientResizeResult>> {
+        check_aligned("size", size)?;
+        self.client.resize(size, options).await
+    }
+
+    /// Sets the sequence number of the Page blob, which requests can then require with their `if_sequence_number_*` options.
+    ///
+    /// # Arguments
+    ///
+    /// * `sequence_number_action` - Whether to set the sequence number to the `blob_sequence_number` of the options, to the
+    ///   maximum of both, or to increment it.
+    /// * `options` - Optional configuration for the request.
+    pub async fn update_sequence_number(
+        &self,
+        sequence_number_action: SequenceNumberActionType,
+        options: Option<PageBlobClientUpdateSequenceNumberOptions<'_>>,
+    ) -> Result<Response<PageBlobClientUpdateSequenceNumberResult>> {
+        self.client
+            .update_sequence_number(sequence_number_action, options)
+            .await
+    }
+
+    /// Copies a snapshot of another Page blob to this Page blob, only sending the differences since the previous incremental
+    /// copy. The copy completes asynchronously.
+    ///
+    /// # Arguments
+    ///
+    /// * `copy_source` - The URL of the snapshot of the source Page blob, authorized with a SAS token unless it is public.
+    /// * `options` - Optional configuration for the request.
+    pub async fn copy_incremental(
+        &self,
+        copy_source: String,
+        options: Option<PageBlobClientCopyIncrementalOptions<'_>>,
+    ) -> Result<Response<PageBlobClientCopyIncrementalResult>> {
+        self.client.copy_incremental(copy_source, options).await
+    }
+
+    /// Returns a pager over the ranges of the Page blob holding data, in order of their offsets.
+    ///
+    /// Each page is requested with the marker of the previous page, until the service returns no marker.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub fn get_page_ranges(
+        &self,
+        options: Option<PageBlobClientGetPageRangesOptions<'_>>,
+    ) -> Result<Pager<PageRangesResult>> {
+        self.page_ranges_pager(None, options)
+    }
+
+    /// Returns a pager over the ranges of the Page blob changed or cleared since a previous snapshot, in order of their
+    /// offsets. The changed ranges are returned as `page_ranges`, and the cleared ones as `clear_ranges`.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_snapshot` - The snapshot of the Page blob to compare to, older than the blob or the snapshot of the options.
+    /// * `options` - Optional configuration for the request.
+    pub fn get_page_ranges_diff(
+        &self,
+        previous_snapshot: &str,
+        options: Option<PageBlobClientGetPageRangesOptions<'_>>,
+    ) -> Result<Pager<PageRangesResult>> {
+        self.page_ranges_pager(Some(previous_snapshot), options)
+    }
+
+    fn page_ranges_pager(
+        &self,
+        previous_snapshot: Option<&str>,
+        options: Option<PageBlobClientGetPageRangesOptions<'_>>,
+    ) -> Result<Pager<PageRangesResult>> {
+        let options = options.unwrap_or_default().into_owned();
+        let mut url = self.client.endpoint.join(&format!(
+            "{}/{}",
+            self.client.container_name, self.client.blob_name
+        ))?;
+        url.query_pairs_mut().append_pair("comp", "pagelist");
+        if let Some(maxresults) = options.maxresults {
+            url.query_pairs_mut()
+                .append_pair("maxresults", &maxresults.to_string());
+        }
+        if let Some(previous_snapshot) = previous_snapshot {
+            url.query_pairs_mut()
+                .append_pair("prevsnapshot", previous_snapshot);
+        }
+        if let Some(snapshot) = &options.snapshot {
+            url.query_pairs_mut().append_pair("snapshot", snapshot);
+        }
+        if let Some(timeout) = options.timeout {
+            url.query_pairs_mut()
+                .append_pair("timeout", &timeout.to_string());
+        }
+
+        let mut request = pipeline::xml_request(url, &self.client.version);
+        if let Some(if_match) = options.if_match {
+            request.insert_header("if-match", if_match);
+        }
+        if let Some(if_modified_since) = options.if_modified_since {
+            request.insert_header("if-modified-since", date::to_rfc7231(&if_modified_since));
+        }
+        if let Some(if_none_match) = options.if_none_match {
+            request.insert_header("if-none-match", if_none_match);
+        }
+        if let Some(if_unmodified_since) = options.if_unmodified_since {
+            request.insert_header(
+                "if-unmodified-since",
+                date::to_rfc7231(&if_unmodified_since),
+            );
+        }
+        if let Some(if_tags) = options.if_tags {
+            request.insert_header("x-ms-if-tags", if_tags);
+        }
+        if let Some(lease_id) = options.lease_id {
+            request.insert_header("x-ms-lease-id", lease_id);
+        }
+        if let Some(range) = options.range {
+            request.insert_header("x-ms-range", range_header(range)?);
+        }
+        Ok(pipeline::marker_pager(
+            self.client.pipeline.clone(),
+            request,
+            options.method_options.context,
+        ))
+    }
+}
+
+fn check_aligned(name: &str, value: u64) -> Result<()> {
+    if value % PAGE_SIZE != 0 {
+        return Err(azure_core::Error::message(
+            ErrorKind::Other,
+            format!(
+                "the {name} of a page blob must be a multiple of {PAGE_SIZE} bytes, not {value}"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Formats the `x-ms-range` header of pages, which must start and end at page boundaries.
+fn range_header(range: Range<u64>) -> Result<String> {
+    check_aligned("start of a range", range.start)?;
+    check_aligned("end of a range", range.end)?;
+    if range.is_empty() {
+        return Err(azure_core::Error::message(
+            ErrorKind::Other,
+            format!("the range {range:?} of a page blob is empty"),
+        ));
+    }
+    Ok(format!("bytes={}-{}", range.start, range.end - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::range_header;
+    use crate::{
+        fake::{header, FakeStorage},
+        models::PageRange,
+        PageBlobClientGetPageRangesOptions, PageBlobClientUploadPagesOptions, TransferValidation,
+    };
+    use azure_core::{
+        base64,
+        http::{headers::Headers, Response, StatusCode},
+        Bytes,
+    };
+    use azure_storage_common::crc64;
+    use futures::TryStreamExt;
+
+    #[test]
+    pub fn formats_aligned_ranges() {
+        assert_eq!("bytes=512-1535", range_header(512..1536).unwrap());
+        assert!(range_header(0..1000).is_err());
+        assert!(range_header(1..512).is_err());
+        assert!(range_header(512..512).is_err());
+    }
+
+    #[tokio::test]
+    pub async fn lists_page_ranges() {
+        // Returns the page ranges of a page blob in two pages.
+        let storage = FakeStorage::new(|request, _| {
+            let body = match request.url().query_pairs().any(|(key, _)| key == "marker") {
+                false => "<PageList><PageRange><Start>0</Start><End>511</End></PageRange><NextMarker>page2</NextMarker></PageList>",
+                true => "<PageList><PageRange><Start>1024</Start><End>1535</End></PageRange><NextMarker /></PageList>",
+            };
+            Response::from_bytes(StatusCode::Ok, Headers::new(), body)
+        });
+        let client = storage.page_blob_client("disks", "disk.vhd");
+
+        let options = PageBlobClientGetPageRangesOptions {
+            range: Some(0..4096),
+            lease_id: Some("lease".to_string()),
+            ..Default::default()
+        };
+        let mut ranges = Vec::new();
+        let mut pages = client
+            .get_page_ranges_diff("2025-03-01T12:00:00.0000000Z", Some(options))
+            .unwrap();
+        while let Some(page) = pages.try_next().await.unwrap() {
+            ranges.extend(page.into_body().await.unwrap().page_ranges);
+        }
+
+        assert_eq!(
+            vec![
+                PageRange { start: 0, end: 511 },
+                PageRange {
+                    start: 1024,
+                    end: 1535
+                }
+            ],
+            ranges
+        );
+        let requests = storage.requests();
+        assert_eq!(2, requests.len());
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net/disks/disk.vhd?comp=pagelist&prevsnapshot=2025-03-01T12%3A00%3A00.0000000Z&marker=page2",
+            requests[1].url().as_str()
+        );
+        for request in requests.iter() {
+            assert_eq!(
+                Some("bytes=0-4095"),
+                header(request, "x-ms-range").as_deref()
+            );
+            assert_eq!(Some("lease"), header(request, "x-ms-lease-id").as_deref());
+        }
+    }
+
+    #[tokio::test]
+    pub async fn uploads_validated_pages() {
+        let storage =
+            FakeStorage::new(|_, _| Response::from_bytes(StatusCode::Created, Headers::new(), ""));
+        let data = Bytes::from(vec![7; 1024]);
+        let options = PageBlobClientUploadPagesOptions {
+            validation: Some(TransferValidation::Crc64),
+            ..Default::default()
+        };
+        storage
+            .page_blob_client("disks", "disk.vhd")
+            .upload_pages(512, data.clone().try_into().unwrap(), Some(options))
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(
+            Some("bytes=512-1535"),
+            header(&requests[0], "x-ms-range").as_deref()
+        );
+        assert_eq!(
+            Some(base64::encode(crc64::checksum(&data).to_le_bytes())),
+            header(&requests[0], "x-ms-content-crc64")
+        );
+        assert_eq!(None, header(&requests[0], "content-md5"));
+    }
+}
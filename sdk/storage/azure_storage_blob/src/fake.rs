@@ -0,0 +1,154 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! A fake Storage service shared by the unit tests of the crate.
+
+use crate::{
+    AppendBlobClient, AppendBlobClientOptions, BlobClient, BlobClientOptions, BlobContainerClient,
+    BlobContainerClientOptions, BlockBlobClient, BlockBlobClientOptions, PageBlobClient,
+    PageBlobClientOptions,
+};
+use azure_core::http::{ClientOptions, Request, Response, TransportOptions};
+use azure_core_test::http::MockHttpClient;
+use azure_storage_common::StorageCredential;
+use futures::FutureExt as _;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+pub(crate) const ENDPOINT: &str = "https://myaccount.blob.core.windows.net/";
+
+/// Records the requests sent by the clients it creates, answering each with the response `respond` returns for it.
+///
+/// `respond` is given the request and all the requests recorded so far, the request included.
+pub(crate) struct FakeStorage {
+    transport: TransportOptions,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl FakeStorage {
+    pub(crate) fn new(
+        respond: impl Fn(&Request, &[Request]) -> Response + Send + Sync + 'static,
+    ) -> Self {
+        let requests: Arc<Mutex<Vec<Request>>> = Arc::default();
+        let recorded = requests.clone();
+        let client = MockHttpClient::new(move |request| {
+            let response = {
+                let mut requests = recorded.lock().unwrap();
+                requests.push(request.clone());
+                respond(request, &requests)
+            };
+            async move { Ok(response) }.boxed()
+        });
+        Self {
+            transport: TransportOptions::new(Arc::new(client)),
+            requests,
+        }
+    }
+
+    /// The requests sent so far, in the order they were sent.
+    pub(crate) fn requests(&self) -> MutexGuard<'_, Vec<Request>> {
+        self.requests.lock().unwrap()
+    }
+
+    pub(crate) fn client_options(&self) -> ClientOptions {
+        ClientOptions {
+            transport: Some(self.transport.clone()),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn blob_client(&self, container_name: &str, blob_name: &str) -> BlobClient {
+        let options = BlobClientOptions {
+            client_options: self.client_options(),
+            ..Default::default()
+        };
+        BlobClient::new(
+            ENDPOINT,
+            container_name.to_string(),
+            blob_name.to_string(),
+            StorageCredential::Anonymous,
+            Some(options),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn block_blob_client(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> BlockBlobClient {
+        let options = BlockBlobClientOptions {
+            client_options: self.client_options(),
+            ..Default::default()
+        };
+        BlockBlobClient::new(
+            ENDPOINT,
+            container_name.to_string(),
+            blob_name.to_string(),
+            StorageCredential::Anonymous,
+            Some(options),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn append_blob_client(
+        &self,
+        container_name: &str,
+        blob_name: &str,
+    ) -> AppendBlobClient {
+        let options = AppendBlobClientOptions {
+            client_options: self.client_options(),
+            ..Default::default()
+        };
+        AppendBlobClient::new(
+            ENDPOINT,
+            container_name.to_string(),
+            blob_name.to_string(),
+            StorageCredential::Anonymous,
+            Some(options),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn page_blob_client(&self, container_name: &str, blob_name: &str) -> PageBlobClient {
+        let options = PageBlobClientOptions {
+            client_options: self.client_options(),
+            ..Default::default()
+        };
+        PageBlobClient::new(
+            ENDPOINT,
+            container_name.to_string(),
+            blob_name.to_string(),
+            StorageCredential::Anonymous,
+            Some(options),
+        )
+        .unwrap()
+    }
+
+    pub(crate) fn container_client(&self, container_name: &str) -> BlobContainerClient {
+        let options = BlobContainerClientOptions {
+            client_options: self.client_options(),
+            ..Default::default()
+        };
+        BlobContainerClient::new(
+            ENDPOINT,
+            container_name.to_string(),
+            StorageCredential::Anonymous,
+            Some(options),
+        )
+        .unwrap()
+    }
+}
+
+/// Returns the value of the header `name` of `request`.
+pub(crate) fn header(request: &Request, name: &'static str) -> Option<String> {
+    request.headers().get_optional_string(&name.into())
+}
+
+/// Returns the value of the query parameter `name` of `request`.
+pub(crate) fn query(request: &Request, name: &str) -> Option<String> {
+    request
+        .url()
+        .query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
@@ -8,31 +8,45 @@
 #![allow(unused_imports)]
 
 pub mod clients;
+#[cfg(test)]
+mod fake;
 mod generated;
+pub mod models;
+mod options;
 mod pipeline;
+pub mod sas;
+mod transfer;
 
-pub use crate::clients::{BlobClient, BlobContainerClient, BlobServiceClient, BlockBlobClient};
+pub use crate::clients::{
+    AppendBlobClient, BlobClient, BlobContainerClient, BlobLeaseClient, BlobLeaseGuard,
+    BlobServiceClient, BlockBlobClient, PageBlobClient,
+};
 pub use crate::generated::clients::{
-    BlobClientOptions, BlobContainerClientOptions, BlobServiceClientOptions, BlockBlobClientOptions,
+    AppendBlobClientOptions, BlobClientOptions, BlobContainerClientOptions,
+    BlobServiceClientOptions, BlockBlobClientOptions, PageBlobClientOptions,
 };
 pub use crate::generated::models::{
-    BlobClientDeleteOptions, BlobClientDownloadOptions, BlobClientGetPropertiesOptions,
-    BlobClientSetMetadataOptions, BlobClientSetPropertiesOptions, BlobClientSetTierOptions,
-    BlobContainerClientCreateOptions, BlobContainerClientDeleteOptions,
-    BlobContainerClientGetPropertiesOptions, BlobContainerClientSetMetadataOptions,
-    BlobServiceClientGetPropertiesOptions, BlockBlobClientCommitBlockListOptions,
+    AppendBlobClientAppendBlockFromUrlOptions, AppendBlobClientCreateOptions,
+    AppendBlobClientSealOptions, BlobClientAbortCopyFromUrlOptions, BlobClientDeleteOptions,
+    BlobClientDownloadOptions, BlobClientGetPropertiesOptions, BlobClientSetMetadataOptions,
+    BlobClientSetPropertiesOptions, BlobClientSetTierOptions, BlobContainerClientCreateOptions,
+    BlobContainerClientDeleteOptions, BlobContainerClientGetPropertiesOptions,
+    BlobContainerClientSetMetadataOptions, BlobServiceClientGetPropertiesOptions,
+    BlobServiceClientGetUserDelegationKeyOptions, BlockBlobClientCommitBlockListOptions,
     BlockBlobClientGetBlockListOptions, BlockBlobClientStageBlockOptions,
-    BlockBlobClientUploadOptions,
+    BlockBlobClientUploadOptions, PageBlobClientClearPagesOptions,
+    PageBlobClientCopyIncrementalOptions, PageBlobClientCreateOptions, PageBlobClientResizeOptions,
+    PageBlobClientUpdateSequenceNumberOptions,
+};
+pub use crate::options::{
+    AppendBlobClientAppendBlockOptions, BlobClientBeginCopyFromUrlOptions,
+    BlobClientCopyFromUrlOptions, BlobClientDownloadToOptions, BlobContainerClientListBlobsOptions,
+    BlobLeaseClientAcquireOptions, BlobLeaseClientBreakOptions, BlobLeaseClientChangeOptions,
+    BlobLeaseClientReleaseOptions, BlobLeaseClientRenewOptions,
+    BlobServiceClientListContainersOptions, BlockBlobClientUploadStreamOptions,
+    PageBlobClientGetPageRangesOptions, PageBlobClientUploadPagesOptions,
+};
+pub use crate::transfer::{BlobCopyPoller, MetadataCopyMode, TransferProgress, TransferValidation};
+pub use azure_storage_common::{
+    SharedKeyCredential, StorageCredential, StorageError, StorageErrorKind,
 };
-
-pub mod models {
-    pub use crate::generated::models::{
-        AccessTier, ArchiveStatus, BlobClientDownloadResult, BlobClientDownloadResultHeaders,
-        BlobClientGetPropertiesResult, BlobClientGetPropertiesResultHeaders,
-        BlobContainerClientGetPropertiesResult, BlobContainerClientGetPropertiesResultHeaders,
-        BlobImmutabilityPolicyMode, BlobType, BlockBlobClientCommitBlockListResult,
-        BlockBlobClientStageBlockResult, BlockBlobClientUploadResult, BlockList, BlockListType,
-        BlockLookupList, CopyStatus, LeaseState, LeaseStatus, PublicAccessType, RehydratePriority,
-        StorageServiceProperties,
-    };
-}
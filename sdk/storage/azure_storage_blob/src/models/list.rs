@@ -0,0 +1,384 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! The pages returned when listing the blobs of a container.
+//!
+//! The blobs and prefixes of a page are interleaved in the `Blobs` element of the response, so they are read through private types
+//! and exposed as [`BlobItem`] and [`BlobPrefix`] values, with decoded names, metadata and tags.
+
+use crate::{
+    clients::endpoint::percent_decode,
+    generated::models::{BlobName, BlobPropertiesInternal, BlobTags},
+};
+use azure_core::fmt::SafeDebug;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// A blob returned when listing the blobs of a container.
+#[derive(Clone, Default, Deserialize, SafeDebug)]
+#[non_exhaustive]
+#[serde(from = "BlobXml")]
+pub struct BlobItem {
+    /// The name of the blob.
+    pub name: String,
+
+    /// Whether the blob is soft deleted. Deleted blobs are only listed with [`ListBlobsIncludeItem::Deleted`](crate::models::ListBlobsIncludeItem::Deleted).
+    pub deleted: bool,
+
+    /// The snapshot of the blob, if this item is a snapshot.
+    pub snapshot: Option<String>,
+
+    /// The version of the blob, if versioning is enabled.
+    pub version_id: Option<String>,
+
+    /// Whether this item is the current version of the blob.
+    pub is_current_version: Option<bool>,
+
+    /// Whether the blob was deleted, but has versions.
+    pub has_versions_only: Option<bool>,
+
+    /// The properties of the blob.
+    pub properties: BlobPropertiesInternal,
+
+    /// The metadata of the blob. Only listed with [`ListBlobsIncludeItem::Metadata`](crate::models::ListBlobsIncludeItem::Metadata).
+    pub metadata: HashMap<String, String>,
+
+    /// The tags of the blob. Only listed with [`ListBlobsIncludeItem::Tags`](crate::models::ListBlobsIncludeItem::Tags).
+    pub tags: HashMap<String, String>,
+}
+
+/// A virtual directory returned when listing the blobs of a container by hierarchy: the common prefix of the names of several blobs,
+/// up to and including the delimiter.
+#[derive(Clone, Default, Deserialize, SafeDebug)]
+#[non_exhaustive]
+#[serde(from = "BlobPrefixXml")]
+pub struct BlobPrefix {
+    /// The prefix of the names of the blobs.
+    pub name: String,
+}
+
+/// A page of the blobs of a container.
+#[derive(Clone, Default, Deserialize, SafeDebug, azure_core::http::Model)]
+#[non_exhaustive]
+#[serde(from = "EnumerationResultsXml")]
+#[typespec(format = "xml")]
+pub struct ListBlobsResult {
+    /// The prefix the names of the blobs start with, if any.
+    pub prefix: Option<String>,
+
+    /// The marker this page starts from, if it is not the first page.
+    pub marker: Option<String>,
+
+    /// The maximum number of blobs in a page.
+    pub max_results: Option<i32>,
+
+    /// The marker of the next page, if any.
+    pub next_marker: Option<String>,
+
+    /// The blobs.
+    pub blob_items: Vec<BlobItem>,
+}
+
+/// A page of the blobs and virtual directories of a container.
+#[derive(Clone, Default, Deserialize, SafeDebug, azure_core::http::Model)]
+#[non_exhaustive]
+#[serde(from = "EnumerationResultsXml")]
+#[typespec(format = "xml")]
+pub struct ListBlobsHierarchyResult {
+    /// The prefix the names of the blobs start with, if any.
+    pub prefix: Option<String>,
+
+    /// The delimiter separating the virtual directories in the names of the blobs.
+    pub delimiter: Option<String>,
+
+    /// The marker this page starts from, if it is not the first page.
+    pub marker: Option<String>,
+
+    /// The maximum number of blobs and prefixes in a page.
+    pub max_results: Option<i32>,
+
+    /// The marker of the next page, if any.
+    pub next_marker: Option<String>,
+
+    /// The blobs directly under the prefix.
+    pub blob_items: Vec<BlobItem>,
+
+    /// The virtual directories directly under the prefix.
+    pub blob_prefixes: Vec<BlobPrefix>,
+}
+
+#[derive(Deserialize)]
+struct EnumerationResultsXml {
+    #[serde(rename = "Prefix")]
+    prefix: Option<String>,
+    #[serde(rename = "Delimiter")]
+    delimiter: Option<String>,
+    #[serde(rename = "Marker")]
+    marker: Option<String>,
+    #[serde(rename = "MaxResults")]
+    max_results: Option<i32>,
+    #[serde(rename = "NextMarker")]
+    next_marker: Option<String>,
+    #[serde(default, rename = "Blobs")]
+    blobs: BlobsXml,
+}
+
+#[derive(Default, Deserialize)]
+struct BlobsXml {
+    #[serde(default, rename = "$value")]
+    entries: Vec<BlobEntryXml>,
+}
+
+#[derive(Deserialize)]
+enum BlobEntryXml {
+    Blob(Box<BlobItem>),
+    BlobPrefix(BlobPrefix),
+}
+
+#[derive(Deserialize)]
+struct BlobXml {
+    #[serde(rename = "Name")]
+    name: BlobName,
+    #[serde(rename = "Deleted")]
+    deleted: Option<bool>,
+    #[serde(rename = "Snapshot")]
+    snapshot: Option<String>,
+    #[serde(rename = "VersionId")]
+    version_id: Option<String>,
+    #[serde(rename = "IsCurrentVersion")]
+    is_current_version: Option<bool>,
+    #[serde(rename = "HasVersionsOnly")]
+    has_versions_only: Option<bool>,
+    #[serde(default, rename = "Properties")]
+    properties: BlobPropertiesInternal,
+    #[serde(rename = "Metadata")]
+    metadata: Option<HashMap<String, String>>,
+    #[serde(rename = "Tags")]
+    tags: Option<BlobTags>,
+}
+
+#[derive(Deserialize)]
+struct BlobPrefixXml {
+    #[serde(rename = "Name")]
+    name: BlobName,
+}
+
+impl From<EnumerationResultsXml> for ListBlobsResult {
+    fn from(value: EnumerationResultsXml) -> Self {
+        let (blob_items, _) = value.blobs.split();
+        Self {
+            prefix: non_empty(value.prefix),
+            marker: non_empty(value.marker),
+            max_results: value.max_results,
+            next_marker: non_empty(value.next_marker),
+            blob_items,
+        }
+    }
+}
+
+impl From<EnumerationResultsXml> for ListBlobsHierarchyResult {
+    fn from(value: EnumerationResultsXml) -> Self {
+        let (blob_items, blob_prefixes) = value.blobs.split();
+        Self {
+            prefix: non_empty(value.prefix),
+            delimiter: non_empty(value.delimiter),
+            marker: non_empty(value.marker),
+            max_results: value.max_results,
+            next_marker: non_empty(value.next_marker),
+            blob_items,
+            blob_prefixes,
+        }
+    }
+}
+
+impl BlobsXml {
+    fn split(self) -> (Vec<BlobItem>, Vec<BlobPrefix>) {
+        let mut blob_items = Vec::new();
+        let mut blob_prefixes = Vec::new();
+        for entry in self.entries {
+            match entry {
+                BlobEntryXml::Blob(blob_item) => blob_items.push(*blob_item),
+                BlobEntryXml::BlobPrefix(blob_prefix) => blob_prefixes.push(blob_prefix),
+            }
+        }
+        (blob_items, blob_prefixes)
+    }
+}
+
+impl From<BlobXml> for BlobItem {
+    fn from(value: BlobXml) -> Self {
+        // The attributes of the Metadata element, such as `Encrypted`, are not metadata.
+        let metadata = value
+            .metadata
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|(name, _)| !name.starts_with('@'))
+            .collect();
+        let tags = value
+            .tags
+            .and_then(|tags| tags.blob_tag_set)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|tag| Some((tag.key?, tag.value.unwrap_or_default())))
+            .collect();
+        Self {
+            name: decode_name(value.name),
+            deleted: value.deleted.unwrap_or_default(),
+            snapshot: non_empty(value.snapshot),
+            version_id: non_empty(value.version_id),
+            is_current_version: value.is_current_version,
+            has_versions_only: value.has_versions_only,
+            properties: value.properties,
+            metadata,
+            tags,
+        }
+    }
+}
+
+impl From<BlobPrefixXml> for BlobPrefix {
+    fn from(value: BlobPrefixXml) -> Self {
+        Self {
+            name: decode_name(value.name),
+        }
+    }
+}
+
+/// Decodes the name of a blob, which the service percent-encodes when it contains characters that are not valid in XML.
+fn decode_name(name: BlobName) -> String {
+    let content = name.content.unwrap_or_default();
+    match name.encoded {
+        Some(true) => percent_decode(&content),
+        _ => content,
+    }
+}
+
+/// Empty elements, such as `<NextMarker />` on the last page, are read as empty strings.
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ListBlobsHierarchyResult, ListBlobsResult};
+    use crate::models::BlobType;
+    use azure_core::xml::read_xml_str;
+
+    #[test]
+    pub fn reads_flat_listing() {
+        let page: ListBlobsResult = read_xml_str(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://myaccount.blob.core.windows.net/" ContainerName="photos">
+  <Prefix>2025/</Prefix>
+  <MaxResults>2</MaxResults>
+  <Blobs>
+    <Blob>
+      <Name>2025/hello world.txt</Name>
+      <Snapshot>2025-03-01T12:00:00.0000000Z</Snapshot>
+      <Properties>
+        <Creation-Time>Sat, 01 Mar 2025 12:00:00 GMT</Creation-Time>
+        <Last-Modified>Sat, 01 Mar 2025 12:00:00 GMT</Last-Modified>
+        <Etag>0x8DD58B8E0AB0C47</Etag>
+        <Content-Length>11</Content-Length>
+        <Content-Type>text/plain</Content-Type>
+        <Content-MD5>XrY7u+Ae7tCTyyK7j1rNww==</Content-MD5>
+        <BlobType>BlockBlob</BlobType>
+        <AccessTier>Hot</AccessTier>
+        <AccessTierInferred>true</AccessTierInferred>
+        <LeaseStatus>unlocked</LeaseStatus>
+        <LeaseState>available</LeaseState>
+        <ServerEncrypted>true</ServerEncrypted>
+      </Properties>
+      <Metadata Encrypted="false">
+        <project>storage sdk</project>
+        <owner>blob</owner>
+      </Metadata>
+      <Tags>
+        <TagSet>
+          <Tag><Key>status</Key><Value>draft</Value></Tag>
+        </TagSet>
+      </Tags>
+    </Blob>
+    <Blob>
+      <Name Encoded="true">2025/%EF%BF%BEcontrol</Name>
+      <Deleted>true</Deleted>
+      <VersionId>2025-03-01T12:00:00.0000000Z</VersionId>
+      <IsCurrentVersion>true</IsCurrentVersion>
+      <Properties>
+        <Content-Length>0</Content-Length>
+        <BlobType>AppendBlob</BlobType>
+      </Properties>
+      <Metadata />
+    </Blob>
+  </Blobs>
+  <NextMarker>2!64!marker</NextMarker>
+</EnumerationResults>"#,
+        )
+        .unwrap();
+
+        assert_eq!(Some("2025/"), page.prefix.as_deref());
+        assert_eq!(None, page.marker);
+        assert_eq!(Some(2), page.max_results);
+        assert_eq!(Some("2!64!marker"), page.next_marker.as_deref());
+        assert_eq!(2, page.blob_items.len());
+
+        let blob = &page.blob_items[0];
+        assert_eq!("2025/hello world.txt", blob.name);
+        assert!(!blob.deleted);
+        assert_eq!(
+            Some("2025-03-01T12:00:00.0000000Z"),
+            blob.snapshot.as_deref()
+        );
+        assert_eq!(Some(11), blob.properties.content_length);
+        assert_eq!(Some(BlobType::BlockBlob), blob.properties.blob_type);
+        assert_eq!(2, blob.metadata.len());
+        assert_eq!(
+            Some("storage sdk"),
+            blob.metadata.get("project").map(String::as_str)
+        );
+        assert_eq!(Some("draft"), blob.tags.get("status").map(String::as_str));
+
+        let blob = &page.blob_items[1];
+        assert_eq!("2025/\u{fffe}control", blob.name);
+        assert!(blob.deleted);
+        assert_eq!(Some(true), blob.is_current_version);
+        assert!(blob.metadata.is_empty());
+        assert!(blob.tags.is_empty());
+    }
+
+    #[test]
+    pub fn reads_hierarchy_listing() {
+        let page: ListBlobsHierarchyResult = read_xml_str(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<EnumerationResults ServiceEndpoint="https://myaccount.blob.core.windows.net/" ContainerName="photos">
+  <Marker>2!64!marker</Marker>
+  <Delimiter>/</Delimiter>
+  <Blobs>
+    <BlobPrefix><Name>2024/</Name></BlobPrefix>
+    <Blob><Name>readme.md</Name><Properties /></Blob>
+    <BlobPrefix><Name>2025/</Name></BlobPrefix>
+  </Blobs>
+  <NextMarker />
+</EnumerationResults>"#,
+        )
+        .unwrap();
+
+        assert_eq!(None, page.prefix);
+        assert_eq!(Some("/"), page.delimiter.as_deref());
+        assert_eq!(Some("2!64!marker"), page.marker.as_deref());
+        assert_eq!(None, page.next_marker);
+        assert_eq!(
+            vec!["readme.md"],
+            page.blob_items
+                .iter()
+                .map(|blob| blob.name.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["2024/", "2025/"],
+            page.blob_prefixes
+                .iter()
+                .map(|prefix| prefix.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
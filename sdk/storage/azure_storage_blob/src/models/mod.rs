@@ -0,0 +1,37 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+mod list;
+mod page;
+
+pub use crate::generated::models::{
+    AccessTier, AppendBlobClientAppendBlockFromUrlResult,
+    AppendBlobClientAppendBlockFromUrlResultHeaders, AppendBlobClientAppendBlockResult,
+    AppendBlobClientAppendBlockResultHeaders, AppendBlobClientCreateResult,
+    AppendBlobClientCreateResultHeaders, AppendBlobClientSealResult,
+    AppendBlobClientSealResultHeaders, ArchiveStatus, BlobClientAbortCopyFromUrlResult,
+    BlobClientAbortCopyFromUrlResultHeaders, BlobClientAcquireLeaseResult,
+    BlobClientAcquireLeaseResultHeaders, BlobClientBreakLeaseResult,
+    BlobClientBreakLeaseResultHeaders, BlobClientChangeLeaseResult,
+    BlobClientChangeLeaseResultHeaders, BlobClientCopyFromUrlResult,
+    BlobClientCopyFromUrlResultHeaders, BlobClientDownloadResult, BlobClientDownloadResultHeaders,
+    BlobClientGetPropertiesResult, BlobClientGetPropertiesResultHeaders,
+    BlobClientReleaseLeaseResult, BlobClientReleaseLeaseResultHeaders, BlobClientRenewLeaseResult,
+    BlobClientRenewLeaseResultHeaders, BlobClientStartCopyFromUrlResult,
+    BlobClientStartCopyFromUrlResultHeaders, BlobContainerClientGetPropertiesResult,
+    BlobContainerClientGetPropertiesResultHeaders, BlobImmutabilityPolicyMode,
+    BlobPropertiesInternal, BlobType, BlockBlobClientCommitBlockListResult,
+    BlockBlobClientStageBlockResult, BlockBlobClientUploadResult, BlockList, BlockListType,
+    BlockLookupList, ContainerItem, ContainerProperties, CopyStatus, EncryptionAlgorithmType,
+    LeaseDuration, LeaseState, LeaseStatus, ListBlobsIncludeItem, ListContainersIncludeType,
+    ListContainersSegmentResponse, PageBlobClientClearPagesResult,
+    PageBlobClientClearPagesResultHeaders, PageBlobClientCopyIncrementalResult,
+    PageBlobClientCopyIncrementalResultHeaders, PageBlobClientCreateResult,
+    PageBlobClientCreateResultHeaders, PageBlobClientResizeResult,
+    PageBlobClientResizeResultHeaders, PageBlobClientUpdateSequenceNumberResult,
+    PageBlobClientUpdateSequenceNumberResultHeaders, PageBlobClientUploadPagesResult,
+    PageBlobClientUploadPagesResultHeaders, PublicAccessType, RehydratePriority,
+    SequenceNumberActionType, StorageServiceProperties, UserDelegationKey,
+};
+pub use list::{BlobItem, BlobPrefix, ListBlobsHierarchyResult, ListBlobsResult};
+pub use page::{PageRange, PageRangesResult};
@@ -0,0 +1,127 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! The pages returned when listing the page ranges of a page blob.
+//!
+//! The valid and cleared ranges of a page are interleaved in the `PageList` element of the response, so they are read through
+//! private types and exposed as separate lists of [`PageRange`] values.
+
+use azure_core::fmt::SafeDebug;
+use serde::Deserialize;
+
+/// A range of pages of a page blob.
+#[derive(Clone, Copy, Default, Deserialize, SafeDebug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct PageRange {
+    /// The offset of the first byte of the range.
+    #[serde(rename = "Start")]
+    pub start: u64,
+
+    /// The offset of the last byte of the range, included in the range.
+    #[serde(rename = "End")]
+    pub end: u64,
+}
+
+impl PageRange {
+    /// Gets the number of bytes of the range.
+    pub fn len(&self) -> u64 {
+        self.end + 1 - self.start
+    }
+
+    /// Returns `true` if the range has no bytes, which the service never returns.
+    pub fn is_empty(&self) -> bool {
+        self.end < self.start
+    }
+}
+
+/// A page of the page ranges of a page blob, or of the differences between two snapshots of a page blob.
+#[derive(Clone, Default, Deserialize, SafeDebug, azure_core::http::Model)]
+#[non_exhaustive]
+#[serde(from = "PageListXml")]
+#[typespec(format = "xml")]
+pub struct PageRangesResult {
+    /// The ranges holding data, or changed since the previous snapshot for a difference.
+    pub page_ranges: Vec<PageRange>,
+
+    /// The ranges cleared since the previous snapshot. Only returned for a difference.
+    pub clear_ranges: Vec<PageRange>,
+
+    /// The marker of the next page, if any.
+    pub next_marker: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct PageListXml {
+    #[serde(default, rename = "$value")]
+    entries: Vec<PageListEntryXml>,
+}
+
+#[derive(Deserialize)]
+enum PageListEntryXml {
+    PageRange(PageRange),
+    ClearRange(PageRange),
+    NextMarker(Option<String>),
+}
+
+impl From<PageListXml> for PageRangesResult {
+    fn from(value: PageListXml) -> Self {
+        let mut result = Self::default();
+        for entry in value.entries {
+            match entry {
+                PageListEntryXml::PageRange(range) => result.page_ranges.push(range),
+                PageListEntryXml::ClearRange(range) => result.clear_ranges.push(range),
+                PageListEntryXml::NextMarker(marker) => {
+                    result.next_marker = marker.filter(|marker| !marker.is_empty())
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PageRange, PageRangesResult};
+    use azure_core::xml::read_xml_str;
+
+    #[test]
+    pub fn reads_interleaved_ranges() {
+        let page: PageRangesResult = read_xml_str(
+            r#"<?xml version="1.0" encoding="utf-8"?>
+<PageList>
+  <PageRange><Start>0</Start><End>511</End></PageRange>
+  <ClearRange><Start>512</Start><End>1023</End></ClearRange>
+  <PageRange><Start>1024</Start><End>2047</End></PageRange>
+  <NextMarker>2!48!MDAwMDA0</NextMarker>
+</PageList>"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec![
+                PageRange { start: 0, end: 511 },
+                PageRange {
+                    start: 1024,
+                    end: 2047
+                },
+            ],
+            page.page_ranges
+        );
+        assert_eq!(
+            vec![PageRange {
+                start: 512,
+                end: 1023
+            }],
+            page.clear_ranges
+        );
+        assert_eq!(1024, page.page_ranges[1].len());
+        assert_eq!(Some("2!48!MDAwMDA0"), page.next_marker.as_deref());
+
+        let page: PageRangesResult = read_xml_str(
+            r#"<?xml version="1.0" encoding="utf-8"?><PageList><NextMarker /></PageList>"#,
+        )
+        .unwrap();
+        assert!(page.page_ranges.is_empty());
+        assert_eq!(None, page.next_marker);
+    }
+}
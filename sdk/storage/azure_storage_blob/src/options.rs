@@ -0,0 +1,609 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{
+    models::{
+        AccessTier, EncryptionAlgorithmType, ListBlobsIncludeItem, ListContainersIncludeType,
+        RehydratePriority,
+    },
+    MetadataCopyMode, TransferProgress, TransferValidation,
+};
+use azure_core::{fmt::SafeDebug, http::ClientMethodOptions};
+use std::{collections::HashMap, ops::Range, time::Duration};
+use time::OffsetDateTime;
+
+/// Options to be passed to `BlobContainerClient::list_blobs()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobContainerClientListBlobsOptions<'a> {
+    /// The datasets to include in the listing, such as the metadata, snapshots, versions, deleted blobs, tags or uncommitted blobs.
+    pub include: Option<Vec<ListBlobsIncludeItem>>,
+
+    /// Specifies the maximum number of blobs in each page. If the request does not specify maxresults, or specifies a value
+    /// greater than 5000, the server will return up to 5000 items.
+    pub maxresults: Option<i32>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// Filters the results to return only blobs whose name begins with the specified prefix.
+    pub prefix: Option<String>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+impl BlobContainerClientListBlobsOptions<'_> {
+    pub fn into_owned(self) -> BlobContainerClientListBlobsOptions<'static> {
+        BlobContainerClientListBlobsOptions {
+            include: self.include,
+            maxresults: self.maxresults,
+            method_options: ClientMethodOptions {
+                context: self.method_options.context.into_owned(),
+            },
+            prefix: self.prefix,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Options to be passed to `BlobClient::download_to()` and `BlobClient::download_to_file()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobClientDownloadToOptions<'a> {
+    /// The maximum number of ranges downloaded at the same time. Defaults to 8.
+    pub concurrency: Option<usize>,
+
+    /// A condition that must be met in order for the download to start. The ETag of the blob is then required for all the
+    /// ranges, whether or not this is set.
+    pub if_match: Option<String>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// The number of times a range is requested again after it failed, from the first byte not received yet. Defaults to 3.
+    pub max_range_retries: Option<u32>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// Reports the number of bytes downloaded so far, after each range completes.
+    pub progress: Option<TransferProgress>,
+
+    /// The size of the ranges the blob is split in. Defaults to 4 MiB, which is also the maximum when `validation` is set.
+    pub range_size: Option<u64>,
+
+    /// The snapshot parameter is an opaque DateTime value that, when present, specifies the blob snapshot to retrieve. For
+    /// more information on working with blob snapshots, see [Creating a Snapshot of a Blob.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/creating-a-snapshot-of-a-blob)
+    pub snapshot: Option<String>,
+
+    /// Validates each range against the checksum returned by the service.
+    pub validation: Option<TransferValidation>,
+
+    /// The version id parameter is an opaque DateTime value that, when present, specifies the version of the blob to operate
+    /// on. It's for service version 2019-10-10 and newer.
+    pub version_id: Option<String>,
+}
+
+/// Options to be passed to `BlobServiceClient::list_containers()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobServiceClientListContainersOptions<'a> {
+    /// The datasets to include in the listing, such as the metadata or deleted containers.
+    pub include: Option<Vec<ListContainersIncludeType>>,
+
+    /// Specifies the maximum number of containers in each page. If the request does not specify maxresults, or specifies a value
+    /// greater than 5000, the server will return up to 5000 items.
+    pub maxresults: Option<i32>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// Filters the results to return only containers whose name begins with the specified prefix.
+    pub prefix: Option<String>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+impl BlobServiceClientListContainersOptions<'_> {
+    pub fn into_owned(self) -> BlobServiceClientListContainersOptions<'static> {
+        BlobServiceClientListContainersOptions {
+            include: self.include,
+            maxresults: self.maxresults,
+            method_options: ClientMethodOptions {
+                context: self.method_options.context.into_owned(),
+            },
+            prefix: self.prefix,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Options to be passed to `BlockBlobClient::upload_stream()`, `BlockBlobClient::upload_reader()` and `BlockBlobClient::upload_file()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlockBlobClientUploadStreamOptions<'a> {
+    /// Optional. Sets the blob's cache control. If specified, this property is stored with the blob and returned with a read
+    /// request.
+    pub blob_cache_control: Option<String>,
+
+    /// Optional. Sets the blob's content disposition. If specified, this property is stored with the blob and returned with
+    /// a read request.
+    pub blob_content_disposition: Option<String>,
+
+    /// Optional. Sets the blob's content encoding. If specified, this property is stored with the blob and returned with a read
+    /// request.
+    pub blob_content_encoding: Option<String>,
+
+    /// Optional. Set the blob's content language. If specified, this property is stored with the blob and returned with a read
+    /// request.
+    pub blob_content_language: Option<String>,
+
+    /// Optional. Sets the blob's content type. If specified, this property is stored with the blob and returned with a read request.
+    pub blob_content_type: Option<String>,
+
+    /// The size of the blocks staged when the data is larger than `max_single_upload_size`. Defaults to 4 MiB.
+    pub block_size: Option<u64>,
+
+    /// The maximum number of blocks staged at the same time. Defaults to 8.
+    pub concurrency: Option<usize>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_match: Option<String>,
+
+    /// A condition that must be met in order for the request to be processed. Set to `*` to fail if the blob already exists.
+    pub if_none_match: Option<String>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// The size up to which the data is uploaded with a single request, rather than staged in blocks. Defaults to 32 MiB.
+    ///
+    /// Data read from an `AsyncRead` is buffered in memory up to this size.
+    pub max_single_upload_size: Option<u64>,
+
+    /// The metadata headers.
+    pub metadata: Option<HashMap<String, String>>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// Reports the number of bytes uploaded so far, after each request completes.
+    pub progress: Option<TransferProgress>,
+
+    /// The tags of the blob.
+    pub tags: Option<HashMap<String, String>>,
+
+    /// The tier to be set on the blob.
+    pub tier: Option<AccessTier>,
+
+    /// Sends the checksum of the content of each request, for the service to validate it. A stream is read once more to
+    /// compute its checksum when it is uploaded with a single request.
+    pub validation: Option<TransferValidation>,
+}
+
+/// Options to be passed to `PageBlobClient::get_page_ranges()` and `PageBlobClient::get_page_ranges_diff()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct PageBlobClientGetPageRangesOptions<'a> {
+    /// A condition that must be met in order for the request to be processed.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// Specifies the maximum number of page ranges in each page.
+    pub maxresults: Option<i32>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// Only lists the page ranges within these bytes, which must start and end at multiples of 512 bytes.
+    pub range: Option<Range<u64>>,
+
+    /// The snapshot parameter is an opaque DateTime value that, when present, specifies the blob snapshot to retrieve. For
+    /// more information on working with blob snapshots, see [Creating a Snapshot of a Blob.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/creating-a-snapshot-of-a-blob)
+    pub snapshot: Option<String>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+impl PageBlobClientGetPageRangesOptions<'_> {
+    pub fn into_owned(self) -> PageBlobClientGetPageRangesOptions<'static> {
+        PageBlobClientGetPageRangesOptions {
+            if_match: self.if_match,
+            if_modified_since: self.if_modified_since,
+            if_none_match: self.if_none_match,
+            if_tags: self.if_tags,
+            if_unmodified_since: self.if_unmodified_since,
+            lease_id: self.lease_id,
+            maxresults: self.maxresults,
+            method_options: ClientMethodOptions {
+                context: self.method_options.context.into_owned(),
+            },
+            range: self.range,
+            snapshot: self.snapshot,
+            timeout: self.timeout,
+        }
+    }
+}
+
+/// Options to be passed to `BlobLeaseClient::acquire()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobLeaseClientAcquireOptions<'a> {
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value. Only supported for blobs.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobLeaseClient::break_lease()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobLeaseClientBreakOptions<'a> {
+    /// The number of seconds the lease should continue before it is broken, between 0 and 60. It is only used if it is shorter
+    /// than the time remaining on the lease. A fixed-duration lease breaks after its remaining period by default, and an
+    /// infinite lease breaks immediately.
+    pub break_period: Option<i32>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value. Only supported for blobs.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobLeaseClient::change()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobLeaseClientChangeOptions<'a> {
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value. Only supported for blobs.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobLeaseClient::release()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobLeaseClientReleaseOptions<'a> {
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value. Only supported for blobs.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobLeaseClient::renew()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobLeaseClientRenewOptions<'a> {
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed. Only supported for blobs.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value. Only supported for blobs.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobClient::begin_copy_from_url()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobClientBeginCopyFromUrlOptions<'a> {
+    /// The authorization of the source, such as `Bearer <token>` for an Entra ID token, when it is not authorized with a SAS
+    /// token in its URL.
+    pub copy_source_authorization: Option<String>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// Whether the metadata of the source is copied, the default, or replaced.
+    pub metadata: Option<MetadataCopyMode>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The delay between two reads of the properties of the blob by the returned poller. Defaults to 5 seconds.
+    pub polling_interval: Option<Duration>,
+
+    /// The priority of the rehydration of the new blob, when copying an archived blob to an online tier.
+    pub rehydrate_priority: Option<RehydratePriority>,
+
+    /// The copy only proceeds if the ETag of the source matches this string.
+    pub source_if_match: Option<String>,
+
+    /// The copy only proceeds if the source has been modified since this time.
+    pub source_if_modified_since: Option<OffsetDateTime>,
+
+    /// The copy only proceeds if the ETag of the source does not match this string.
+    pub source_if_none_match: Option<String>,
+
+    /// The copy only proceeds if the source has not been modified since this time.
+    pub source_if_unmodified_since: Option<OffsetDateTime>,
+
+    /// The tags of the new blob. The tags of the source are not copied.
+    pub tags: Option<HashMap<String, String>>,
+
+    /// The tier of the new blob.
+    pub tier: Option<AccessTier>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `BlobClient::copy_from_url()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct BlobClientCopyFromUrlOptions<'a> {
+    /// The authorization of the source, such as `Bearer <token>` for an Entra ID token, when it is not authorized with a SAS
+    /// token in its URL.
+    pub copy_source_authorization: Option<String>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_match: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has been modified since the specified date-time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// A condition that must be met in order for the request to be processed.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value.
+    pub if_tags: Option<String>,
+
+    /// A date-time value. A request is made under the condition that the resource has not been modified since the specified
+    /// date-time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// Whether the metadata of the source is copied, the default, or replaced.
+    pub metadata: Option<MetadataCopyMode>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The copy only proceeds if the ETag of the source matches this string.
+    pub source_if_match: Option<String>,
+
+    /// The copy only proceeds if the source has been modified since this time.
+    pub source_if_modified_since: Option<OffsetDateTime>,
+
+    /// The copy only proceeds if the ETag of the source does not match this string.
+    pub source_if_none_match: Option<String>,
+
+    /// The copy only proceeds if the source has not been modified since this time.
+    pub source_if_unmodified_since: Option<OffsetDateTime>,
+
+    /// The tags of the new blob. The tags of the source are not copied.
+    pub tags: Option<HashMap<String, String>>,
+
+    /// The tier of the new blob.
+    pub tier: Option<AccessTier>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+}
+
+/// Options to be passed to `AppendBlobClient::append_block()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct AppendBlobClientAppendBlockOptions<'a> {
+    /// Optional conditional header, used only for the Append Block operation. A number indicating the byte offset to compare.
+    /// Append Block will succeed only if the append position is equal to this number. If it is not, the request will fail with
+    /// the AppendPositionConditionNotMet error (HTTP status code 412 - Precondition Failed).
+    pub append_position: Option<i64>,
+
+    /// An opaque, globally-unique, client-generated string identifier for the request.
+    pub client_request_id: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the algorithm to use for encryption. If not specified, the default is
+    /// AES256.
+    pub encryption_algorithm: Option<EncryptionAlgorithmType>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the encryption key to use to encrypt the data provided in the request.
+    /// If not specified, the request will be encrypted with the root account key.
+    pub encryption_key: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the SHA256 hash of the encryption key used to encrypt the data provided
+    /// in the request. This header is only used for encryption with a customer-provided key. If the request is authenticated
+    /// with a client token, this header should be specified using the SHA256 hash of the encryption key.
+    pub encryption_key_sha256: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the encryption scope to use to encrypt the data provided in the request.
+    /// If not specified, the request will be encrypted with the root account key.
+    pub encryption_scope: Option<String>,
+
+    /// The request should only proceed if an entity matches this string.
+    pub if_match: Option<String>,
+
+    /// The request should only proceed if the entity was modified after this time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// The request should only proceed if no entity matches this string.
+    pub if_none_match: Option<String>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value.
+    pub if_tags: Option<String>,
+
+    /// The request should only proceed if the entity was not modified after this time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// Optional conditional header. The max length in bytes permitted for the append blob. If the Append Block operation would
+    /// cause the blob to exceed that limit or if the blob size is already greater than the value specified in this header, the
+    /// request will fail with MaxBlobSizeConditionNotMet error (HTTP status code 412 - Precondition Failed).
+    pub max_size: Option<i64>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+
+    /// Sends the checksum of the block, for the service to validate it.
+    pub validation: Option<TransferValidation>,
+}
+
+/// Options to be passed to `PageBlobClient::upload_pages()`
+#[derive(Clone, Default, SafeDebug)]
+pub struct PageBlobClientUploadPagesOptions<'a> {
+    /// An opaque, globally-unique, client-generated string identifier for the request.
+    pub client_request_id: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the algorithm to use for encryption. If not specified, the default is
+    /// AES256.
+    pub encryption_algorithm: Option<EncryptionAlgorithmType>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the encryption key to use to encrypt the data provided in the request.
+    /// If not specified, the request will be encrypted with the root account key.
+    pub encryption_key: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the SHA256 hash of the encryption key used to encrypt the data provided
+    /// in the request. This header is only used for encryption with a customer-provided key. If the request is authenticated
+    /// with a client token, this header should be specified using the SHA256 hash of the encryption key.
+    pub encryption_key_sha256: Option<String>,
+
+    /// Optional. Version 2019-07-07 and later. Specifies the encryption scope to use to encrypt the data provided in the request.
+    /// If not specified, the request will be encrypted with the root account key.
+    pub encryption_scope: Option<String>,
+
+    /// The request should only proceed if an entity matches this string.
+    pub if_match: Option<String>,
+
+    /// The request should only proceed if the entity was modified after this time.
+    pub if_modified_since: Option<OffsetDateTime>,
+
+    /// The request should only proceed if no entity matches this string.
+    pub if_none_match: Option<String>,
+
+    /// Specify this header value to operate only on a blob if it has the specified sequence number.
+    pub if_sequence_number_equal_to: Option<i64>,
+
+    /// Specify this header value to operate only on a blob if it has a sequence number less than the specified.
+    pub if_sequence_number_less_than: Option<i64>,
+
+    /// Specify this header value to operate only on a blob if it has a sequence number less than or equal to the specified.
+    pub if_sequence_number_less_than_or_equal_to: Option<i64>,
+
+    /// Specify a SQL where clause on blob tags to operate only on blobs with a matching value.
+    pub if_tags: Option<String>,
+
+    /// The request should only proceed if the entity was not modified after this time.
+    pub if_unmodified_since: Option<OffsetDateTime>,
+
+    /// If specified, the operation only succeeds if the resource's lease is active and matches this ID.
+    pub lease_id: Option<String>,
+
+    /// Allows customization of the method call.
+    pub method_options: ClientMethodOptions<'a>,
+
+    /// The timeout parameter is expressed in seconds. For more information, see [Setting Timeouts for Blob Service Operations.](https://docs.microsoft.com/en-us/rest/api/storageservices/fileservices/setting-timeouts-for-blob-service-operations)
+    pub timeout: Option<i32>,
+
+    /// Sends the checksum of the pages, for the service to validate it.
+    pub validation: Option<TransferValidation>,
+}
@@ -3,4 +3,148 @@
 
 mod storage_headers_policy;
 
+use azure_core::{
+    error::ErrorKind,
+    http::{ClientOptions, Context, Method, Pager, PagerResult, Pipeline, Request, Response, Url},
+    xml, Result,
+};
+use azure_storage_common::StorageCredential;
+use serde::Deserialize;
+use std::sync::Arc;
+
 pub use storage_headers_policy::StorageHeadersPolicy;
+
+/// Parses the endpoint of a client, which must use http(s), the same way the generated clients do.
+pub(crate) fn parse_endpoint(endpoint: &str) -> Result<Url> {
+    let mut endpoint = Url::parse(endpoint)?;
+    if !endpoint.scheme().starts_with("http") {
+        return Err(azure_core::Error::message(
+            ErrorKind::Other,
+            format!("{endpoint} must use http(s)"),
+        ));
+    }
+    endpoint.set_query(None);
+    Ok(endpoint)
+}
+
+/// Builds the pipeline of a client authorizing its requests with the given credential.
+///
+/// The authorization policy is the last per-try policy, so that it signs the headers added by all the other policies.
+pub(crate) fn pipeline(
+    mut client_options: ClientOptions,
+    credential: StorageCredential,
+) -> Pipeline {
+    client_options
+        .per_call_policies
+        .push(Arc::new(StorageHeadersPolicy));
+    if let Some(authorization_policy) = credential.authorization_policy() {
+        client_options.per_try_policies.push(authorization_policy);
+    }
+    Pipeline::new(
+        option_env!("CARGO_PKG_NAME"),
+        option_env!("CARGO_PKG_VERSION"),
+        client_options,
+        Vec::default(),
+        Vec::default(),
+    )
+}
+
+/// The marker of the next page of a listing, empty or missing on the last page.
+#[derive(Deserialize)]
+struct NextMarker {
+    #[serde(rename = "NextMarker")]
+    next_marker: Option<String>,
+}
+
+/// Builds a GET request for an XML document, such as a page of a listing.
+pub(crate) fn xml_request(url: Url, version: &str) -> Request {
+    let mut request = Request::new(url, Method::Get);
+    request.insert_header("accept", "application/xml");
+    request.insert_header("x-ms-version", version.to_string());
+    request
+}
+
+/// Builds a pager listing resources, sending the first request again for each page with the `NextMarker` of the previous
+/// page as its `marker` parameter.
+pub(crate) fn marker_pager<T: Send + 'static>(
+    pipeline: Pipeline,
+    first_request: Request,
+    ctx: Context<'static>,
+) -> Pager<T> {
+    Pager::from_callback(move |marker: Option<String>| {
+        let mut request = first_request.clone();
+        if let Some(marker) = marker {
+            request
+                .url_mut()
+                .query_pairs_mut()
+                .append_pair("marker", &marker);
+        }
+        let pipeline = pipeline.clone();
+        let ctx = ctx.clone();
+        async move {
+            let rsp: Response<T> = pipeline.send(&ctx, &mut request).await?;
+            let (status, headers, body) = rsp.deconstruct();
+            let bytes = body.collect().await?;
+            let res: NextMarker = xml::read_xml(&bytes)?;
+            let rsp = Response::from_bytes(status, headers, bytes);
+            Ok(match res.next_marker.filter(|marker| !marker.is_empty()) {
+                Some(next_marker) => PagerResult::Continue {
+                    response: rsp,
+                    continuation: next_marker,
+                },
+                None => PagerResult::Complete { response: rsp },
+            })
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{marker_pager, xml_request};
+    use crate::{fake::FakeStorage, models::ListContainersSegmentResponse};
+    use azure_core::http::{headers::Headers, Context, Response, StatusCode, Url};
+    use azure_storage_common::StorageCredential;
+    use futures::TryStreamExt;
+
+    #[tokio::test]
+    pub async fn follows_next_markers() {
+        // Returns two pages of containers, the first ending with a marker.
+        let storage = FakeStorage::new(|request, _| {
+            let body = match request.url().query_pairs().any(|(name, _)| name == "marker") {
+                false => "<EnumerationResults><Containers><Container><Name>photos</Name><Properties><Last-Modified>Sat, 01 Mar 2025 12:00:00 GMT</Last-Modified></Properties><Metadata><project>sdk</project></Metadata></Container></Containers><NextMarker>/myaccount/videos</NextMarker></EnumerationResults>",
+                true => "<EnumerationResults><Marker>/myaccount/videos</Marker><Containers><Container><Name>videos</Name></Container></Containers><NextMarker /></EnumerationResults>",
+            };
+            Response::from_bytes(StatusCode::Ok, Headers::new(), body)
+        });
+        let pipeline = super::pipeline(storage.client_options(), StorageCredential::Anonymous);
+        let url = Url::parse("https://myaccount.blob.core.windows.net/?comp=list").unwrap();
+        let pages: Vec<Response<ListContainersSegmentResponse>> =
+            marker_pager(pipeline, xml_request(url, "2025-01-05"), Context::new())
+                .try_collect()
+                .await
+                .unwrap();
+
+        let mut names = Vec::new();
+        for page in pages {
+            let page = page.into_body().await.unwrap();
+            for container in page.container_items.unwrap_or_default() {
+                names.push(container.name.unwrap());
+                if let Some(metadata) = container.metadata {
+                    assert_eq!(Some("sdk"), metadata.get("project").map(String::as_str));
+                }
+            }
+        }
+        assert_eq!(vec!["photos", "videos"], names);
+        assert_eq!(
+            vec![
+                "https://myaccount.blob.core.windows.net/?comp=list",
+                "https://myaccount.blob.core.windows.net/?comp=list&marker=%2Fmyaccount%2Fvideos",
+            ],
+            storage
+                .requests()
+                .iter()
+                .map(|request| request.url().as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,564 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Service and user delegation shared access signatures (SAS) for containers and blobs.
+//!
+//! A service SAS is signed with the key of the account, and may refer to a stored access policy of the container.
+//! A user delegation SAS is signed with a [`UserDelegationKey`] obtained from [`BlobServiceClient::get_user_delegation_key()`](crate::BlobServiceClient::get_user_delegation_key)
+//! with Microsoft Entra ID credentials.
+//! See [`azure_storage_common::sas`] for the account SAS.
+
+use crate::models::UserDelegationKey;
+use azure_core::{
+    base64, credentials::Secret, date::OffsetDateTime, error::ErrorKind, hmac, Result,
+};
+use azure_storage_common::{
+    sas::{to_sas_time, SAS_VERSION},
+    SharedKeyCredential,
+};
+use std::fmt;
+
+pub use azure_storage_common::sas::{SasIpRange, SasProtocol, SasToken};
+
+/// The operations allowed with a service or user delegation SAS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlobSasPermissions {
+    pub read: bool,
+    pub add: bool,
+    pub create: bool,
+    pub write: bool,
+    pub delete: bool,
+    pub delete_version: bool,
+    pub permanent_delete: bool,
+    pub list: bool,
+    pub tags: bool,
+    pub filter_by_tags: bool,
+    pub set_immutability_policy: bool,
+}
+
+impl fmt::Display for BlobSasPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags = [
+            (self.read, 'r'),
+            (self.add, 'a'),
+            (self.create, 'c'),
+            (self.write, 'w'),
+            (self.delete, 'd'),
+            (self.delete_version, 'x'),
+            (self.permanent_delete, 'y'),
+            (self.list, 'l'),
+            (self.tags, 't'),
+            (self.filter_by_tags, 'f'),
+            (self.set_immutability_policy, 'i'),
+        ];
+        for (_, letter) in flags.iter().filter(|(set, _)| *set) {
+            write!(f, "{letter}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The resource accessible with a SAS.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Resource {
+    Container,
+    Blob(String),
+    Snapshot {
+        blob_name: String,
+        snapshot: String,
+    },
+    Version {
+        blob_name: String,
+        version_id: String,
+    },
+}
+
+/// Builds a service or user delegation SAS, granting access to a container, a blob, a blob snapshot or a blob version.
+///
+/// Unless a stored access policy defines them, the permissions and expiry must be set.
+///
+/// ```
+/// use azure_core::date::OffsetDateTime;
+/// use azure_storage_blob::{
+///     sas::{BlobSasBuilder, BlobSasPermissions, SasProtocol},
+///     SharedKeyCredential,
+/// };
+/// # fn main() -> azure_core::Result<()> {
+/// let credential = SharedKeyCredential::new("myaccount", "a2V5");
+/// let token = BlobSasBuilder::blob("photos", "cat.jpg")
+///     .with_permissions(BlobSasPermissions { read: true, ..Default::default() })
+///     .with_expiry(OffsetDateTime::now_utc() + std::time::Duration::from_secs(3600))
+///     .with_protocol(SasProtocol::Https)
+///     .sign(&credential)?;
+/// println!("https://myaccount.blob.core.windows.net/photos/cat.jpg?{token}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BlobSasBuilder {
+    container_name: String,
+    resource: Resource,
+    permissions: Option<BlobSasPermissions>,
+    start: Option<OffsetDateTime>,
+    expiry: Option<OffsetDateTime>,
+    identifier: Option<String>,
+    ip_range: Option<SasIpRange>,
+    protocol: Option<SasProtocol>,
+    cache_control: Option<String>,
+    content_disposition: Option<String>,
+    content_encoding: Option<String>,
+    content_language: Option<String>,
+    content_type: Option<String>,
+}
+
+impl BlobSasBuilder {
+    fn new(container_name: String, resource: Resource) -> Self {
+        Self {
+            container_name,
+            resource,
+            permissions: None,
+            start: None,
+            expiry: None,
+            identifier: None,
+            ip_range: None,
+            protocol: None,
+            cache_control: None,
+            content_disposition: None,
+            content_encoding: None,
+            content_language: None,
+            content_type: None,
+        }
+    }
+
+    /// Creates a builder for a SAS granting access to a container and its blobs.
+    pub fn container(container_name: impl Into<String>) -> Self {
+        Self::new(container_name.into(), Resource::Container)
+    }
+
+    /// Creates a builder for a SAS granting access to a blob.
+    pub fn blob(container_name: impl Into<String>, blob_name: impl Into<String>) -> Self {
+        Self::new(container_name.into(), Resource::Blob(blob_name.into()))
+    }
+
+    /// Creates a builder for a SAS granting access to a snapshot of a blob.
+    ///
+    /// The SAS must be appended to the URL of the snapshot, including its `snapshot` query parameter.
+    pub fn blob_snapshot(
+        container_name: impl Into<String>,
+        blob_name: impl Into<String>,
+        snapshot: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            container_name.into(),
+            Resource::Snapshot {
+                blob_name: blob_name.into(),
+                snapshot: snapshot.into(),
+            },
+        )
+    }
+
+    /// Creates a builder for a SAS granting access to a version of a blob.
+    ///
+    /// The SAS must be appended to the URL of the version, including its `versionid` query parameter.
+    pub fn blob_version(
+        container_name: impl Into<String>,
+        blob_name: impl Into<String>,
+        version_id: impl Into<String>,
+    ) -> Self {
+        Self::new(
+            container_name.into(),
+            Resource::Version {
+                blob_name: blob_name.into(),
+                version_id: version_id.into(),
+            },
+        )
+    }
+
+    /// Sets the operations allowed with the SAS.
+    pub fn with_permissions(mut self, permissions: BlobSasPermissions) -> Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// Sets the time from which the SAS is valid. By default, the SAS is valid immediately.
+    pub fn with_start(mut self, start: OffsetDateTime) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the time after which the SAS is no longer valid.
+    pub fn with_expiry(mut self, expiry: OffsetDateTime) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// Sets the identifier of the stored access policy of the container the SAS refers to.
+    ///
+    /// Stored access policies can only be used with a service SAS.
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Sets the IP addresses from which requests made with the SAS are allowed. By default, any address is allowed.
+    pub fn with_ip_range(mut self, ip_range: SasIpRange) -> Self {
+        self.ip_range = Some(ip_range);
+        self
+    }
+
+    /// Sets the protocols allowed for requests made with the SAS. By default, both HTTPS and HTTP are allowed.
+    pub fn with_protocol(mut self, protocol: SasProtocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Overrides the `Cache-Control` header of the responses to requests made with the SAS.
+    pub fn with_cache_control(mut self, cache_control: impl Into<String>) -> Self {
+        self.cache_control = Some(cache_control.into());
+        self
+    }
+
+    /// Overrides the `Content-Disposition` header of the responses to requests made with the SAS.
+    pub fn with_content_disposition(mut self, content_disposition: impl Into<String>) -> Self {
+        self.content_disposition = Some(content_disposition.into());
+        self
+    }
+
+    /// Overrides the `Content-Encoding` header of the responses to requests made with the SAS.
+    pub fn with_content_encoding(mut self, content_encoding: impl Into<String>) -> Self {
+        self.content_encoding = Some(content_encoding.into());
+        self
+    }
+
+    /// Overrides the `Content-Language` header of the responses to requests made with the SAS.
+    pub fn with_content_language(mut self, content_language: impl Into<String>) -> Self {
+        self.content_language = Some(content_language.into());
+        self
+    }
+
+    /// Overrides the `Content-Type` header of the responses to requests made with the SAS.
+    pub fn with_content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Builds the string signed by [`BlobSasBuilder::sign()`] for the given account.
+    pub fn string_to_sign(&self, account_name: &str) -> String {
+        [
+            self.permissions(),
+            self.start(),
+            self.expiry(),
+            self.canonicalized_resource(account_name),
+            self.identifier.clone().unwrap_or_default(),
+            self.ip_range(),
+            self.protocol(),
+            SAS_VERSION.to_string(),
+            self.signed_resource().to_string(),
+            self.snapshot_time().to_string(),
+            // The encryption scope.
+            String::new(),
+        ]
+        .into_iter()
+        .chain(self.response_headers().map(|(_, value)| value))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    /// Signs a service SAS with the key of the account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither the permissions and expiry, nor a stored access policy identifier, are set.
+    pub fn sign(&self, credential: &SharedKeyCredential) -> Result<SasToken> {
+        if self.identifier.is_none() {
+            self.check_permissions_and_expiry()?;
+        }
+        let signature = credential.sign(&self.string_to_sign(credential.account_name()))?;
+        Ok(self.token(
+            [("si", self.identifier.clone().unwrap_or_default())],
+            signature,
+        ))
+    }
+
+    /// Builds the string signed by [`BlobSasBuilder::sign_with_user_delegation_key()`] for the given account and key.
+    pub fn user_delegation_string_to_sign(
+        &self,
+        account_name: &str,
+        key: &UserDelegationKey,
+    ) -> String {
+        let key_field = |value: &Option<String>| value.clone().unwrap_or_default();
+        [
+            self.permissions(),
+            self.start(),
+            self.expiry(),
+            self.canonicalized_resource(account_name),
+            key_field(&key.signed_oid),
+            key_field(&key.signed_tid),
+            key_field(&key.signed_start),
+            key_field(&key.signed_expiry),
+            key_field(&key.signed_service),
+            key_field(&key.signed_version),
+            // The authorized and unauthorized user object IDs, and the correlation ID.
+            String::new(),
+            String::new(),
+            String::new(),
+            self.ip_range(),
+            self.protocol(),
+            SAS_VERSION.to_string(),
+            self.signed_resource().to_string(),
+            self.snapshot_time().to_string(),
+            // The encryption scope.
+            String::new(),
+        ]
+        .into_iter()
+        .chain(self.response_headers().map(|(_, value)| value))
+        .collect::<Vec<_>>()
+        .join("\n")
+    }
+
+    /// Signs a user delegation SAS with a key obtained from [`BlobServiceClient::get_user_delegation_key()`](crate::BlobServiceClient::get_user_delegation_key).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the permissions or expiry are not set, if a stored access policy identifier is set, or if the key is incomplete.
+    pub fn sign_with_user_delegation_key(
+        &self,
+        account_name: &str,
+        key: &UserDelegationKey,
+    ) -> Result<SasToken> {
+        if self.identifier.is_some() {
+            return Err(azure_core::Error::message(
+                ErrorKind::Credential,
+                "a user delegation SAS cannot refer to a stored access policy",
+            ));
+        }
+        self.check_permissions_and_expiry()?;
+        let value = key.value.as_ref().ok_or_else(|| {
+            azure_core::Error::message(
+                ErrorKind::Credential,
+                "the user delegation key has no value",
+            )
+        })?;
+        let signature = hmac::hmac_sha256(
+            &self.user_delegation_string_to_sign(account_name, key),
+            &Secret::new(base64::encode(value)),
+        )?;
+        let key_field = |value: &Option<String>| value.clone().unwrap_or_default();
+        Ok(self.token(
+            [
+                ("skoid", key_field(&key.signed_oid)),
+                ("sktid", key_field(&key.signed_tid)),
+                ("skt", key_field(&key.signed_start)),
+                ("ske", key_field(&key.signed_expiry)),
+                ("sks", key_field(&key.signed_service)),
+                ("skv", key_field(&key.signed_version)),
+            ],
+            signature,
+        ))
+    }
+
+    fn check_permissions_and_expiry(&self) -> Result<()> {
+        if self.permissions.is_none() || self.expiry.is_none() {
+            return Err(azure_core::Error::message(
+                ErrorKind::Credential,
+                "the permissions and expiry of the SAS must be set, unless it refers to a stored access policy",
+            ));
+        }
+        Ok(())
+    }
+
+    fn token<const N: usize>(
+        &self,
+        signed_key: [(&'static str, String); N],
+        signature: String,
+    ) -> SasToken {
+        [
+            ("sv", SAS_VERSION.to_string()),
+            ("sr", self.signed_resource().to_string()),
+            ("sp", self.permissions()),
+            ("st", self.start()),
+            ("se", self.expiry()),
+        ]
+        .into_iter()
+        .chain(signed_key)
+        .chain([("sip", self.ip_range()), ("spr", self.protocol())])
+        .chain(self.response_headers())
+        .chain([("sig", signature)])
+        .collect()
+    }
+
+    fn permissions(&self) -> String {
+        self.permissions.map(|p| p.to_string()).unwrap_or_default()
+    }
+
+    fn start(&self) -> String {
+        self.start.as_ref().map(to_sas_time).unwrap_or_default()
+    }
+
+    fn expiry(&self) -> String {
+        self.expiry.as_ref().map(to_sas_time).unwrap_or_default()
+    }
+
+    fn ip_range(&self) -> String {
+        self.ip_range.map(|r| r.to_string()).unwrap_or_default()
+    }
+
+    fn protocol(&self) -> String {
+        self.protocol.map(|p| p.to_string()).unwrap_or_default()
+    }
+
+    fn canonicalized_resource(&self, account_name: &str) -> String {
+        match &self.resource {
+            Resource::Container => format!("/blob/{}/{}", account_name, self.container_name),
+            Resource::Blob(blob_name)
+            | Resource::Snapshot { blob_name, .. }
+            | Resource::Version { blob_name, .. } => {
+                format!(
+                    "/blob/{}/{}/{}",
+                    account_name, self.container_name, blob_name
+                )
+            }
+        }
+    }
+
+    fn signed_resource(&self) -> &'static str {
+        match self.resource {
+            Resource::Container => "c",
+            Resource::Blob(_) => "b",
+            Resource::Snapshot { .. } => "bs",
+            Resource::Version { .. } => "bv",
+        }
+    }
+
+    fn snapshot_time(&self) -> &str {
+        match &self.resource {
+            Resource::Snapshot { snapshot, .. } => snapshot,
+            Resource::Version { version_id, .. } => version_id,
+            _ => "",
+        }
+    }
+
+    /// The response header overrides, in the order of the string to sign.
+    fn response_headers(&self) -> impl Iterator<Item = (&'static str, String)> {
+        [
+            ("rscc", &self.cache_control),
+            ("rscd", &self.content_disposition),
+            ("rsce", &self.content_encoding),
+            ("rscl", &self.content_language),
+            ("rsct", &self.content_type),
+        ]
+        .map(|(name, value)| (name, value.clone().unwrap_or_default()))
+        .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BlobSasBuilder, BlobSasPermissions, SasIpRange, SasProtocol};
+    use crate::models::UserDelegationKey;
+    use azure_core::date::parse_rfc3339;
+    use azure_storage_common::SharedKeyCredential;
+
+    const ACCOUNT_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn read_write() -> BlobSasPermissions {
+        BlobSasPermissions {
+            read: true,
+            write: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    pub fn signs_service_sas() {
+        let credential = SharedKeyCredential::new("myaccount", ACCOUNT_KEY);
+        let builder = BlobSasBuilder::blob("photos", "2025/cat.jpg")
+            .with_permissions(read_write())
+            .with_start(parse_rfc3339("2025-03-01T12:00:00Z").unwrap())
+            .with_expiry(parse_rfc3339("2025-03-02T12:00:00Z").unwrap())
+            .with_ip_range(SasIpRange::single("168.1.5.65".parse().unwrap()))
+            .with_protocol(SasProtocol::HttpsAndHttp)
+            .with_content_disposition("attachment; filename=cat.jpg")
+            .with_content_type("image/jpeg");
+        assert_eq!(
+            "rw\n2025-03-01T12:00:00Z\n2025-03-02T12:00:00Z\n/blob/myaccount/photos/2025/cat.jpg\n\n168.1.5.65\nhttps,http\n2025-01-05\nb\n\n\n\nattachment; filename=cat.jpg\n\n\nimage/jpeg",
+            builder.string_to_sign("myaccount")
+        );
+        assert_eq!(
+            "sv=2025-01-05&sr=b&sp=rw&st=2025-03-01T12%3A00%3A00Z&se=2025-03-02T12%3A00%3A00Z&sip=168.1.5.65&spr=https%2Chttp&rscd=attachment%3B%20filename%3Dcat.jpg&rsct=image%2Fjpeg&sig=zYxDJhANYs7FxKvJctVfZ6qU9NsfNFarbAXC8xM21JQ%3D",
+            builder.sign(&credential).unwrap().to_string()
+        );
+
+        // The permissions and expiry are defined by the stored access policy.
+        let builder = BlobSasBuilder::container("photos").with_identifier("read-only");
+        assert_eq!(
+            "\n\n\n/blob/myaccount/photos\nread-only\n\n\n2025-01-05\nc\n\n\n\n\n\n\n",
+            builder.string_to_sign("myaccount")
+        );
+        assert_eq!(
+            "sv=2025-01-05&sr=c&si=read-only&sig=q%2Fv6tcDZwRw5JnylnkUI4pmV493oSd%2BQ9KvAj7d2Zv8%3D",
+            builder.sign(&credential).unwrap().to_string()
+        );
+        assert!(BlobSasBuilder::container("photos")
+            .sign(&credential)
+            .is_err());
+    }
+
+    #[test]
+    pub fn signs_snapshot_and_version_sas() {
+        let expiry = parse_rfc3339("2025-03-02T12:00:00Z").unwrap();
+        let snapshot =
+            BlobSasBuilder::blob_snapshot("photos", "cat.jpg", "2025-03-01T10:00:00.1234567Z")
+                .with_permissions(read_write())
+                .with_expiry(expiry);
+        assert!(snapshot
+            .string_to_sign("myaccount")
+            .contains("\nbs\n2025-03-01T10:00:00.1234567Z\n"));
+        let version =
+            BlobSasBuilder::blob_version("photos", "cat.jpg", "2025-03-01T10:00:00.7654321Z")
+                .with_permissions(read_write())
+                .with_expiry(expiry);
+        assert!(version
+            .string_to_sign("myaccount")
+            .contains("\nbv\n2025-03-01T10:00:00.7654321Z\n"));
+    }
+
+    #[test]
+    pub fn signs_user_delegation_sas() {
+        let key = UserDelegationKey {
+            signed_oid: Some("f81d4fae-7dec-11d0-a765-00a0c91e6bf6".into()),
+            signed_tid: Some("72f988bf-86f1-41af-91ab-2d7cd011db47".into()),
+            signed_start: Some("2025-03-01T00:00:00Z".into()),
+            signed_expiry: Some("2025-03-08T00:00:00Z".into()),
+            signed_service: Some("b".into()),
+            signed_version: Some("2025-01-05".into()),
+            value: Some(b"0123456789abcdef0123456789abcdef".to_vec()),
+        };
+
+        let builder = BlobSasBuilder::container("photos")
+            .with_permissions(BlobSasPermissions {
+                read: true,
+                list: true,
+                ..Default::default()
+            })
+            .with_expiry(parse_rfc3339("2025-03-02T12:00:00Z").unwrap());
+        assert_eq!(
+            "rl\n\n2025-03-02T12:00:00Z\n/blob/myaccount/photos\nf81d4fae-7dec-11d0-a765-00a0c91e6bf6\n72f988bf-86f1-41af-91ab-2d7cd011db47\n2025-03-01T00:00:00Z\n2025-03-08T00:00:00Z\nb\n2025-01-05\n\n\n\n\n\n2025-01-05\nc\n\n\n\n\n\n\n",
+            builder.user_delegation_string_to_sign("myaccount", &key)
+        );
+        assert_eq!(
+            "sv=2025-01-05&sr=c&sp=rl&se=2025-03-02T12%3A00%3A00Z&skoid=f81d4fae-7dec-11d0-a765-00a0c91e6bf6&sktid=72f988bf-86f1-41af-91ab-2d7cd011db47&skt=2025-03-01T00%3A00%3A00Z&ske=2025-03-08T00%3A00%3A00Z&sks=b&skv=2025-01-05&sig=C73cyVuPTgs%2BuqbqN3n8yDUW7MJamBTDwHnJkCeqchE%3D",
+            builder
+                .sign_with_user_delegation_key("myaccount", &key)
+                .unwrap()
+                .to_string()
+        );
+
+        assert!(builder
+            .clone()
+            .with_identifier("read-only")
+            .sign_with_user_delegation_key("myaccount", &key)
+            .is_err());
+    }
+}
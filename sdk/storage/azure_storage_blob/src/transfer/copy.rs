@@ -0,0 +1,487 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{
+    generated::clients::BlobClient as GeneratedBlobClient,
+    models::{
+        BlobClientAbortCopyFromUrlResult, BlobClientCopyFromUrlResult,
+        BlobClientGetPropertiesResultHeaders, BlobClientStartCopyFromUrlResult,
+        BlobClientStartCopyFromUrlResultHeaders, CopyStatus,
+    },
+    transfer::tags_string,
+    BlobClientAbortCopyFromUrlOptions, BlobClientBeginCopyFromUrlOptions,
+    BlobClientCopyFromUrlOptions, BlobClientGetPropertiesOptions,
+};
+use azure_core::{
+    date,
+    error::ErrorKind,
+    http::{poller::PollerStatus, ClientMethodOptions, Context, Method, Request, Response},
+    sleep::sleep,
+    Error, Result,
+};
+use std::{collections::HashMap, time::Duration};
+
+/// The default delay between two reads of the properties of a blob by a [`BlobCopyPoller`].
+const DEFAULT_POLLING_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How a copy sets the metadata of the new blob.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum MetadataCopyMode {
+    /// Copies the metadata of the source.
+    #[default]
+    Copy,
+
+    /// Sets these name-value pairs as the metadata of the new blob, instead of the metadata of the source. The service copies
+    /// the metadata of the source when no pair is given.
+    Replace(HashMap<String, String>),
+}
+
+/// Tracks a copy to a blob, which the service runs in the background.
+///
+/// Copies within a storage account usually complete as soon as they start, while copies from other accounts or from any URL
+/// take time proportional to the size of the source. The status of the copy is read from the properties of the destination
+/// blob, in its `x-ms-copy-status` and `x-ms-copy-progress` headers.
+///
+/// Returned by [`BlobClient::begin_copy_from_url()`](crate::BlobClient::begin_copy_from_url).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+/// # let blob_client: azure_storage_blob::BlobClient = panic!("this is a non-running example");
+/// let poller = blob_client
+///     .begin_copy_from_url("https://other.blob.core.windows.net/container/blob?sv=...".to_string(), None)
+///     .await?;
+/// println!("Started copy {}", poller.copy_id());
+/// poller.wait().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct BlobCopyPoller {
+    client: GeneratedBlobClient,
+    context: Context<'static>,
+    copy_id: String,
+    copy_status: CopyStatus,
+    copy_progress: Option<String>,
+    status_description: Option<String>,
+    polling_interval: Duration,
+}
+
+impl BlobCopyPoller {
+    /// Gets the ID of the copy, used to abort it.
+    pub fn copy_id(&self) -> &str {
+        &self.copy_id
+    }
+
+    /// Gets the status of the copy, as of the last time the properties of the blob were read.
+    pub fn status(&self) -> PollerStatus {
+        match &self.copy_status {
+            CopyStatus::Pending => PollerStatus::InProgress,
+            CopyStatus::Success => PollerStatus::Succeeded,
+            CopyStatus::Failed => PollerStatus::Failed,
+            CopyStatus::Aborted => PollerStatus::Canceled,
+            CopyStatus::UnknownValue(status) => PollerStatus::Other(status.clone()),
+        }
+    }
+
+    /// Gets the number of bytes copied so far and the size of the source, as of the last time the properties of the blob
+    /// were read, if the service returned them.
+    pub fn copy_progress(&self) -> Option<(u64, u64)> {
+        let (copied, total) = self.copy_progress.as_deref()?.split_once('/')?;
+        Some((copied.parse().ok()?, total.parse().ok()?))
+    }
+
+    /// Gets the description of the failure of the copy, if the copy failed.
+    pub fn status_description(&self) -> Option<&str> {
+        self.status_description.as_deref()
+    }
+
+    /// Reads the properties of the blob again if the copy is still in progress, and returns the updated status.
+    pub async fn poll(&mut self) -> Result<PollerStatus> {
+        if self.copy_status == CopyStatus::Pending {
+            let options = BlobClientGetPropertiesOptions {
+                method_options: ClientMethodOptions {
+                    context: self.context.clone(),
+                },
+                ..Default::default()
+            };
+            let response = self.client.get_properties(Some(options)).await?;
+            let copy_id = response.copy_id()?;
+            if copy_id.as_deref() != Some(&self.copy_id) {
+                return Err(Error::message(
+                    ErrorKind::Other,
+                    format!(
+                        "the copy {} to the blob was replaced by another operation",
+                        self.copy_id
+                    ),
+                ));
+            }
+            self.copy_status = response.copy_status()?.unwrap_or(CopyStatus::Pending);
+            self.copy_progress = response.copy_progress()?;
+            self.status_description = response.copy_status_description()?;
+        }
+        Ok(self.status())
+    }
+
+    /// Waits for the copy to complete, reading the properties of the blob at the polling interval, and returns an error if
+    /// the copy failed or was aborted.
+    pub async fn wait(mut self) -> Result<()> {
+        while self.copy_status == CopyStatus::Pending {
+            sleep(self.polling_interval).await;
+            self.poll().await?;
+        }
+        match self.status() {
+            PollerStatus::Succeeded => Ok(()),
+            PollerStatus::Canceled => Err(Error::message(
+                ErrorKind::Other,
+                format!("the copy {} was aborted", self.copy_id),
+            )),
+            _ => Err(Error::message(
+                ErrorKind::Other,
+                format!(
+                    "the copy {} did not succeed: {}",
+                    self.copy_id,
+                    self.status_description
+                        .as_deref()
+                        .unwrap_or("no description")
+                ),
+            )),
+        }
+    }
+
+    /// Aborts the copy, leaving the destination blob empty, with its metadata.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - Optional configuration for the request.
+    pub async fn abort(
+        &mut self,
+        options: Option<BlobClientAbortCopyFromUrlOptions<'_>>,
+    ) -> Result<Response<BlobClientAbortCopyFromUrlResult>> {
+        let response = abort_copy(&self.client, &self.copy_id, options).await?;
+        self.copy_status = CopyStatus::Aborted;
+        Ok(response)
+    }
+}
+
+// The copy requests are built by hand rather than through the generated `start_copy_from_url`, `copy_from_url` and
+// `abort_copy_from_url`, which the service rejects: the generated copies add a `comp=copy` query parameter, which Copy
+// Blob doesn't take, and the synchronous copy sends `sync` as a query parameter instead of the `x-ms-requires-sync`
+// header; the generated abort adds an empty `copyid` parameter and never sends `x-ms-copy-action: abort`. Replace them
+// with the generated methods once the generator is fixed.
+
+/// Starts a copy from `source_url` to the blob of `client`, returning a poller tracking it.
+pub(crate) async fn begin_copy(
+    client: GeneratedBlobClient,
+    source_url: String,
+    options: Option<BlobClientBeginCopyFromUrlOptions<'_>>,
+) -> Result<BlobCopyPoller> {
+    let options = options.unwrap_or_default();
+    let ctx = Context::with_context(&options.method_options.context);
+    let mut request = copy_request(&client, source_url, &options)?;
+    if let Some(rehydrate_priority) = &options.rehydrate_priority {
+        request.insert_header("x-ms-rehydrate-priority", rehydrate_priority.to_string());
+    }
+    let response: Response<BlobClientStartCopyFromUrlResult> =
+        client.pipeline.send(&ctx, &mut request).await?;
+    let copy_id = response.copy_id()?.ok_or_else(|| {
+        Error::message(
+            ErrorKind::DataConversion,
+            "the service did not return the ID of the copy",
+        )
+    })?;
+    Ok(BlobCopyPoller {
+        copy_status: response.copy_status()?.unwrap_or(CopyStatus::Pending),
+        client,
+        context: options.method_options.context.into_owned(),
+        copy_id,
+        copy_progress: None,
+        status_description: None,
+        polling_interval: options.polling_interval.unwrap_or(DEFAULT_POLLING_INTERVAL),
+    })
+}
+
+/// Copies `source_url` to the blob of `client`, returning once the service has copied it.
+pub(crate) async fn copy_from_url(
+    client: &GeneratedBlobClient,
+    source_url: String,
+    options: Option<BlobClientCopyFromUrlOptions<'_>>,
+) -> Result<Response<BlobClientCopyFromUrlResult>> {
+    let options = options.unwrap_or_default();
+    let options = BlobClientBeginCopyFromUrlOptions {
+        copy_source_authorization: options.copy_source_authorization,
+        if_match: options.if_match,
+        if_modified_since: options.if_modified_since,
+        if_none_match: options.if_none_match,
+        if_tags: options.if_tags,
+        if_unmodified_since: options.if_unmodified_since,
+        lease_id: options.lease_id,
+        metadata: options.metadata,
+        method_options: options.method_options,
+        source_if_match: options.source_if_match,
+        source_if_modified_since: options.source_if_modified_since,
+        source_if_none_match: options.source_if_none_match,
+        source_if_unmodified_since: options.source_if_unmodified_since,
+        tags: options.tags,
+        tier: options.tier,
+        timeout: options.timeout,
+        ..Default::default()
+    };
+    let ctx = Context::with_context(&options.method_options.context);
+    let mut request = copy_request(client, source_url, &options)?;
+    request.insert_header("x-ms-requires-sync", "true");
+    client.pipeline.send(&ctx, &mut request).await
+}
+
+/// Aborts the copy `copy_id` to the blob of `client`.
+pub(crate) async fn abort_copy(
+    client: &GeneratedBlobClient,
+    copy_id: &str,
+    options: Option<BlobClientAbortCopyFromUrlOptions<'_>>,
+) -> Result<Response<BlobClientAbortCopyFromUrlResult>> {
+    let options = options.unwrap_or_default();
+    let ctx = Context::with_context(&options.method_options.context);
+    let mut url = blob_url(client)?;
+    url.query_pairs_mut()
+        .append_pair("comp", "copy")
+        .append_pair("copyid", copy_id);
+    if let Some(timeout) = options.timeout {
+        url.query_pairs_mut()
+            .append_pair("timeout", &timeout.to_string());
+    }
+    let mut request = Request::new(url, Method::Put);
+    request.insert_header("x-ms-copy-action", "abort");
+    if let Some(client_request_id) = options.client_request_id {
+        request.insert_header("x-ms-client-request-id", client_request_id);
+    }
+    if let Some(lease_id) = options.lease_id {
+        request.insert_header("x-ms-lease-id", lease_id);
+    }
+    request.insert_header("x-ms-version", client.version.clone());
+    client.pipeline.send(&ctx, &mut request).await
+}
+
+/// Gets the URL of the blob of `client`.
+fn blob_url(client: &GeneratedBlobClient) -> Result<azure_core::http::Url> {
+    Ok(client
+        .endpoint
+        .join(&format!("{}/{}", client.container_name, client.blob_name))?)
+}
+
+/// Builds the request shared by the asynchronous and synchronous copies, which only differ by a few headers.
+fn copy_request(
+    client: &GeneratedBlobClient,
+    source_url: String,
+    options: &BlobClientBeginCopyFromUrlOptions<'_>,
+) -> Result<Request> {
+    let mut url = blob_url(client)?;
+    if let Some(timeout) = options.timeout {
+        url.query_pairs_mut()
+            .append_pair("timeout", &timeout.to_string());
+    }
+    let mut request = Request::new(url, Method::Put);
+    request.insert_header("x-ms-copy-source", source_url);
+    if let Some(copy_source_authorization) = &options.copy_source_authorization {
+        request.insert_header(
+            "x-ms-copy-source-authorization",
+            copy_source_authorization.clone(),
+        );
+    }
+    if let Some(if_match) = &options.if_match {
+        request.insert_header("if-match", if_match.clone());
+    }
+    if let Some(if_modified_since) = &options.if_modified_since {
+        request.insert_header("if-modified-since", date::to_rfc7231(if_modified_since));
+    }
+    if let Some(if_none_match) = &options.if_none_match {
+        request.insert_header("if-none-match", if_none_match.clone());
+    }
+    if let Some(if_unmodified_since) = &options.if_unmodified_since {
+        request.insert_header("if-unmodified-since", date::to_rfc7231(if_unmodified_since));
+    }
+    if let Some(if_tags) = &options.if_tags {
+        request.insert_header("x-ms-if-tags", if_tags.clone());
+    }
+    if let Some(lease_id) = &options.lease_id {
+        request.insert_header("x-ms-lease-id", lease_id.clone());
+    }
+    if let Some(MetadataCopyMode::Replace(metadata)) = &options.metadata {
+        for (name, value) in metadata {
+            request.insert_header(format!("x-ms-meta-{name}"), value.clone());
+        }
+    }
+    if let Some(source_if_match) = &options.source_if_match {
+        request.insert_header("x-ms-source-if-match", source_if_match.clone());
+    }
+    if let Some(source_if_modified_since) = &options.source_if_modified_since {
+        request.insert_header(
+            "x-ms-source-if-modified-since",
+            date::to_rfc7231(source_if_modified_since),
+        );
+    }
+    if let Some(source_if_none_match) = &options.source_if_none_match {
+        request.insert_header("x-ms-source-if-none-match", source_if_none_match.clone());
+    }
+    if let Some(source_if_unmodified_since) = &options.source_if_unmodified_since {
+        request.insert_header(
+            "x-ms-source-if-unmodified-since",
+            date::to_rfc7231(source_if_unmodified_since),
+        );
+    }
+    if let Some(tags) = &options.tags {
+        request.insert_header("x-ms-tags", tags_string(tags));
+    }
+    if let Some(tier) = &options.tier {
+        request.insert_header("x-ms-access-tier", tier.to_string());
+    }
+    request.insert_header("x-ms-version", client.version.clone());
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fake::{header, FakeStorage},
+        models::AccessTier,
+        BlobClient, BlobClientBeginCopyFromUrlOptions, BlobClientCopyFromUrlOptions,
+        MetadataCopyMode,
+    };
+    use azure_core::http::{headers::Headers, poller::PollerStatus, Method, Response, StatusCode};
+    use std::{collections::HashMap, time::Duration};
+
+    const SOURCE_URL: &str =
+        "https://other.blob.core.windows.net/images/source.png?sv=2025-01-05&sig=abc";
+
+    /// Starts a copy which completes after two reads of the properties of the destination blob.
+    fn fake_copy() -> FakeStorage {
+        FakeStorage::new(|request, requests| {
+            let reads = requests
+                .iter()
+                .filter(|request| *request.method() == Method::Head)
+                .count();
+            let mut headers = Headers::new();
+            headers.insert("x-ms-copy-id", "copy-1");
+            let status = match request.method() {
+                Method::Head => {
+                    let (status, progress) = match reads {
+                        1 => ("pending", "512/1024"),
+                        _ => ("success", "1024/1024"),
+                    };
+                    headers.insert("x-ms-copy-status", status);
+                    headers.insert("x-ms-copy-progress", progress);
+                    StatusCode::Ok
+                }
+                _ if request
+                    .url()
+                    .query()
+                    .is_some_and(|query| query.contains("copyid")) =>
+                {
+                    StatusCode::NoContent
+                }
+                _ if header(request, "x-ms-requires-sync").is_some() => {
+                    headers.insert("x-ms-copy-status", "success");
+                    StatusCode::Accepted
+                }
+                _ => {
+                    headers.insert("x-ms-copy-status", "pending");
+                    StatusCode::Accepted
+                }
+            };
+            Response::from_bytes(status, headers, "")
+        })
+    }
+
+    fn blob_client(storage: &FakeStorage) -> BlobClient {
+        storage.blob_client("images", "copy.png")
+    }
+
+    #[tokio::test]
+    pub async fn polls_copy_until_success() {
+        let storage = fake_copy();
+        let options = BlobClientBeginCopyFromUrlOptions {
+            copy_source_authorization: Some("Bearer token".to_string()),
+            metadata: Some(MetadataCopyMode::Replace(HashMap::from([(
+                "origin".to_string(),
+                "other".to_string(),
+            )]))),
+            polling_interval: Some(Duration::from_millis(1)),
+            tags: Some(HashMap::from([("kind".to_string(), "image".to_string())])),
+            tier: Some(AccessTier::Cool),
+            ..Default::default()
+        };
+        let mut poller = blob_client(&storage)
+            .begin_copy_from_url(SOURCE_URL.to_string(), Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!("copy-1", poller.copy_id());
+        assert!(matches!(poller.status(), PollerStatus::InProgress));
+        assert!(matches!(
+            poller.poll().await.unwrap(),
+            PollerStatus::InProgress
+        ));
+        assert_eq!(Some((512, 1024)), poller.copy_progress());
+        poller.wait().await.unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(3, requests.len());
+        let start = &requests[0];
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net/images/copy.png",
+            start.url().as_str()
+        );
+        for (name, value) in [
+            ("x-ms-copy-source", SOURCE_URL),
+            ("x-ms-copy-source-authorization", "Bearer token"),
+            ("x-ms-meta-origin", "other"),
+            ("x-ms-tags", "kind=image"),
+            ("x-ms-access-tier", "Cool"),
+        ] {
+            assert_eq!(Some(value), header(start, name).as_deref());
+        }
+        assert_eq!(None, header(start, "x-ms-requires-sync"));
+    }
+
+    #[tokio::test]
+    pub async fn aborts_copy() {
+        let storage = fake_copy();
+        let mut poller = blob_client(&storage)
+            .begin_copy_from_url(SOURCE_URL.to_string(), None)
+            .await
+            .unwrap();
+        poller.abort(None).await.unwrap();
+
+        assert!(matches!(poller.status(), PollerStatus::Canceled));
+        assert!(poller.wait().await.is_err());
+        let requests = storage.requests();
+        let abort = &requests[1];
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net/images/copy.png?comp=copy&copyid=copy-1",
+            abort.url().as_str()
+        );
+        assert_eq!(Some("abort"), header(abort, "x-ms-copy-action").as_deref());
+    }
+
+    #[tokio::test]
+    pub async fn copies_synchronously() {
+        let storage = fake_copy();
+        let options = BlobClientCopyFromUrlOptions {
+            metadata: Some(MetadataCopyMode::Copy),
+            ..Default::default()
+        };
+        blob_client(&storage)
+            .copy_from_url(SOURCE_URL.to_string(), Some(options))
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(
+            Some("true"),
+            header(&requests[0], "x-ms-requires-sync").as_deref()
+        );
+        assert!(!requests[0]
+            .headers()
+            .iter()
+            .any(|(name, _)| name.as_str().starts_with("x-ms-meta-")));
+    }
+}
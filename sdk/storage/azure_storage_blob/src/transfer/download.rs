@@ -0,0 +1,500 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Downloads of blobs split in ranges, fetched concurrently and written in order.
+
+use crate::{
+    generated::{
+        clients::BlobClient as GeneratedBlobClient,
+        models::{BlobClientDownloadOptions, BlobClientDownloadResultHeaders},
+    },
+    transfer::Checksum,
+    BlobClientDownloadToOptions, TransferValidation,
+};
+use azure_core::{
+    error::ErrorKind,
+    http::{ClientMethodOptions, StatusCode},
+    Bytes, Result,
+};
+use futures::{io::AsyncWrite, stream, AsyncWriteExt, Future, Stream, StreamExt, TryStreamExt};
+
+const DEFAULT_RANGE_SIZE: u64 = 4 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 8;
+const DEFAULT_MAX_RANGE_RETRIES: u32 = 3;
+
+/// The maximum size of a range for which the service returns a checksum.
+const MAX_VALIDATED_RANGE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// The sizes of a download, validated from its options.
+struct Settings {
+    range_size: u64,
+    concurrency: usize,
+    max_range_retries: u32,
+}
+
+impl Settings {
+    fn new(options: &BlobClientDownloadToOptions<'_>) -> Result<Self> {
+        let range_size = options.range_size.unwrap_or(DEFAULT_RANGE_SIZE);
+        let max_range_size = match options.validation {
+            Some(_) => MAX_VALIDATED_RANGE_SIZE,
+            None => u64::from(u32::MAX),
+        };
+        if range_size == 0 || range_size > max_range_size {
+            return Err(azure_core::Error::message(
+                ErrorKind::Other,
+                format!("the range size must be between 1 and {max_range_size} bytes"),
+            ));
+        }
+        Ok(Self {
+            range_size,
+            concurrency: options.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1),
+            max_range_retries: options
+                .max_range_retries
+                .unwrap_or(DEFAULT_MAX_RANGE_RETRIES),
+        })
+    }
+}
+
+/// A range of a blob, along with the length and ETag of the whole blob.
+struct BlobRange {
+    data: Bytes,
+    blob_length: u64,
+    etag: Option<String>,
+}
+
+/// Downloads the blob to a writer, writing the ranges in order as they complete.
+pub(crate) async fn download_to<W>(
+    client: &GeneratedBlobClient,
+    mut writer: W,
+    options: BlobClientDownloadToOptions<'_>,
+) -> Result<u64>
+where
+    W: AsyncWrite + Unpin + Send,
+{
+    let settings = Settings::new(&options)?;
+    let Some(first) = first_range(client, &settings, &options).await? else {
+        return Ok(0);
+    };
+    writer.write_all(&first.data).await?;
+    let mut downloaded = first.data.len() as u64;
+    report(&options, downloaded);
+
+    let mut ranges = ranges(client, &first, &settings, &options).buffered(settings.concurrency);
+    while let Some((_, data)) = ranges.try_next().await? {
+        writer.write_all(&data).await?;
+        downloaded += data.len() as u64;
+        report(&options, downloaded);
+    }
+    writer.flush().await?;
+    Ok(downloaded)
+}
+
+/// Downloads the blob to a file, writing each range at its offset as soon as it completes.
+#[cfg(feature = "tokio")]
+pub(crate) async fn download_to_file(
+    client: &GeneratedBlobClient,
+    mut file: tokio::fs::File,
+    options: BlobClientDownloadToOptions<'_>,
+) -> Result<u64> {
+    use std::io::SeekFrom;
+    use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+    let settings = Settings::new(&options)?;
+    let Some(first) = first_range(client, &settings, &options).await? else {
+        file.set_len(0).await?;
+        return Ok(0);
+    };
+    file.set_len(first.blob_length).await?;
+    file.write_all(&first.data).await?;
+    let mut downloaded = first.data.len() as u64;
+    report(&options, downloaded);
+
+    let mut ranges =
+        ranges(client, &first, &settings, &options).buffer_unordered(settings.concurrency);
+    while let Some((offset, data)) = ranges.try_next().await? {
+        file.seek(SeekFrom::Start(offset)).await?;
+        file.write_all(&data).await?;
+        downloaded += data.len() as u64;
+        report(&options, downloaded);
+    }
+    file.flush().await?;
+    Ok(downloaded)
+}
+
+/// Downloads the first range, which tells the length and ETag of the blob, or returns `None` if the blob is empty.
+async fn first_range(
+    client: &GeneratedBlobClient,
+    settings: &Settings,
+    options: &BlobClientDownloadToOptions<'_>,
+) -> Result<Option<BlobRange>> {
+    let if_match = options.if_match.clone();
+    match fetch_range(client, 0, settings.range_size, if_match, settings, options).await {
+        Ok(range) => Ok(Some(range)),
+        // The service refuses any range of an empty blob.
+        Err(err)
+            if matches!(
+                err.kind(),
+                ErrorKind::HttpResponse {
+                    status: StatusCode::RequestedRangeNotSatisfiable,
+                    ..
+                }
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Returns the downloads of the ranges after the first one, with their offsets.
+fn ranges<'a>(
+    client: &'a GeneratedBlobClient,
+    first: &BlobRange,
+    settings: &'a Settings,
+    options: &'a BlobClientDownloadToOptions<'_>,
+) -> impl Stream<Item = impl Future<Output = Result<(u64, Bytes)>> + 'a> + 'a {
+    let blob_length = first.blob_length;
+    // Pinning the ETag of the first range fails the download if the blob is changed before it completes.
+    let etag = first.etag.clone().or_else(|| options.if_match.clone());
+    let offsets = (first.data.len() as u64..blob_length).step_by(settings.range_size as usize);
+    stream::iter(offsets).map(move |offset| {
+        let if_match = etag.clone();
+        async move {
+            let length = settings.range_size.min(blob_length - offset);
+            let range = fetch_range(client, offset, length, if_match, settings, options).await?;
+            Ok((offset, range.data))
+        }
+    })
+}
+
+/// Downloads `length` bytes from `offset`, or fewer at the end of the blob.
+///
+/// The retry policy of the client retries the requests that fail, but not the responses whose content fails to be received
+/// or validated. Those are requested again from the first byte not received yet, up to `max_range_retries` times.
+async fn fetch_range(
+    client: &GeneratedBlobClient,
+    offset: u64,
+    length: u64,
+    mut if_match: Option<String>,
+    settings: &Settings,
+    options: &BlobClientDownloadToOptions<'_>,
+) -> Result<BlobRange> {
+    let mut data = Vec::new();
+    let mut end = offset + length;
+    let mut blob_length = 0;
+    let mut retries = 0;
+    loop {
+        let received = data.len();
+        let start = offset + received as u64;
+        let result = async {
+            let download_options = BlobClientDownloadOptions {
+                if_match: if_match.clone(),
+                lease_id: options.lease_id.clone(),
+                method_options: ClientMethodOptions {
+                    context: options.method_options.context.clone(),
+                },
+                range: Some(format!("bytes={start}-{}", end - 1)),
+                range_get_content_crc64: (options.validation == Some(TransferValidation::Crc64))
+                    .then_some(true),
+                range_get_content_md5: (options.validation == Some(TransferValidation::Md5))
+                    .then_some(true),
+                snapshot: options.snapshot.clone(),
+                version_id: options.version_id.clone(),
+                ..Default::default()
+            };
+            let response = client.download(Some(download_options)).await?;
+
+            (end, blob_length) = parse_content_range(response.content_range()?.as_deref())?;
+            if if_match.is_none() {
+                if_match = response.etag()?;
+            }
+            let expected = match options.validation {
+                Some(TransferValidation::Md5) => response.content_md5()?,
+                Some(TransferValidation::Crc64) => response.content_crc64()?,
+                None => None,
+            };
+
+            let mut checksum = options.validation.map(Checksum::new);
+            let mut body = response.into_raw_body();
+            while let Some(chunk) = body.try_next().await? {
+                if let Some(checksum) = &mut checksum {
+                    checksum.update(&chunk);
+                }
+                data.extend_from_slice(&chunk);
+            }
+            if offset + (data.len() as u64) < end {
+                return Err(azure_core::Error::message(
+                    ErrorKind::Io,
+                    format!(
+                        "the response ended before the end of the range bytes={start}-{}",
+                        end - 1
+                    ),
+                ));
+            }
+            if let Some(checksum) = checksum {
+                let content = format!("the range bytes={start}-{}", end - 1);
+                checksum.verify(expected.as_deref(), &content)?;
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) => {
+                return Ok(BlobRange {
+                    data: data.into(),
+                    blob_length,
+                    etag: if_match,
+                });
+            }
+            // The blob changed, or the request was refused, so that requesting the range again would fail too.
+            Err(err) if matches!(err.kind(), ErrorKind::HttpResponse { .. }) => return Err(err),
+            Err(err) if retries >= settings.max_range_retries => return Err(err),
+            Err(_) => {
+                // The content received before a failure can only be kept when it does not need to be validated
+                // as a whole.
+                if options.validation.is_some() {
+                    data.truncate(received);
+                }
+                retries += 1;
+            }
+        }
+    }
+}
+
+/// Parses a `Content-Range` header such as `bytes 0-1023/4096` into the exclusive end of the range and the length of
+/// the blob.
+fn parse_content_range(content_range: Option<&str>) -> Result<(u64, u64)> {
+    let parse = |content_range: &str| {
+        let (range, length) = content_range.strip_prefix("bytes ")?.split_once('/')?;
+        let (_, end) = range.split_once('-')?;
+        Some((end.parse::<u64>().ok()? + 1, length.parse().ok()?))
+    };
+    content_range.and_then(parse).ok_or_else(|| {
+        azure_core::Error::message(
+            ErrorKind::DataConversion,
+            format!("invalid Content-Range header: {content_range:?}"),
+        )
+    })
+}
+
+fn report(options: &BlobClientDownloadToOptions<'_>, downloaded: u64) {
+    if let Some(progress) = &options.progress {
+        progress.report(downloaded);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fake::{header, FakeStorage},
+        BlobClient, BlobClientDownloadToOptions, TransferProgress, TransferValidation,
+    };
+    use azure_core::{
+        base64,
+        http::{headers::Headers, Request, Response, StatusCode},
+        Bytes,
+    };
+    use azure_storage_common::{crc64, StorageError, StorageErrorKind};
+    use md5::{Digest, Md5};
+    use std::sync::{Arc, Mutex};
+
+    const ETAG: &str = "\"0x8DD58B8E0AB0C47\"";
+
+    /// Serves the ranges of `content`, failing a response of the ranges starting at the offsets in `corrupted` and
+    /// `truncated`, once for each time an offset is given.
+    fn fake_blob(content: &'static [u8], corrupted: Vec<u64>, truncated: Vec<u64>) -> FakeStorage {
+        let content = Bytes::from_static(content);
+        let corrupted = Mutex::new(corrupted);
+        let truncated = Mutex::new(truncated);
+        FakeStorage::new(move |request, _| {
+            if header(request, "if-match").is_some_and(|if_match| if_match != ETAG) {
+                return Response::from_bytes(StatusCode::PreconditionFailed, Headers::new(), "");
+            }
+            let range = header(request, "x-ms-range").unwrap();
+            let (start, end) = range
+                .strip_prefix("bytes=")
+                .unwrap()
+                .split_once('-')
+                .unwrap();
+            let start: u64 = start.parse().unwrap();
+            let length = content.len() as u64;
+            if start >= length {
+                return Response::from_bytes(
+                    StatusCode::RequestedRangeNotSatisfiable,
+                    Headers::new(),
+                    "",
+                );
+            }
+            let end = end.parse::<u64>().unwrap().min(length - 1);
+            let data = content.slice(start as usize..=end as usize);
+
+            let mut headers = Headers::new();
+            headers.insert("etag", ETAG);
+            headers.insert("content-range", format!("bytes {start}-{end}/{length}"));
+            if header(request, "x-ms-range-get-content-md5").as_deref() == Some("true") {
+                headers.insert("content-md5", base64::encode(Md5::digest(&data)));
+            }
+            if header(request, "x-ms-range-get-content-crc64").as_deref() == Some("true") {
+                headers.insert(
+                    "x-ms-content-crc64",
+                    base64::encode(crc64::checksum(&data).to_le_bytes()),
+                );
+            }
+            let mut body = data.to_vec();
+            if take(&corrupted, start) {
+                body[0] ^= 0xff;
+            }
+            if take(&truncated, start) {
+                body.truncate(1);
+            }
+            Response::from_bytes(StatusCode::PartialContent, headers, body)
+        })
+    }
+
+    fn take(offsets: &Mutex<Vec<u64>>, offset: u64) -> bool {
+        let mut offsets = offsets.lock().unwrap();
+        let index = offsets.iter().position(|other| *other == offset);
+        index.map(|index| offsets.remove(index)).is_some()
+    }
+
+    fn client(storage: &FakeStorage) -> BlobClient {
+        storage.blob_client("container", "blob")
+    }
+
+    fn ranges(storage: &FakeStorage) -> Vec<Option<String>> {
+        storage
+            .requests()
+            .iter()
+            .map(|request| header(request, "x-ms-range"))
+            .collect()
+    }
+
+    #[tokio::test]
+    pub async fn downloads_validated_ranges_in_order() {
+        let storage = fake_blob(b"0123456789", vec![3], Vec::new());
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let options = BlobClientDownloadToOptions {
+            range_size: Some(3),
+            concurrency: Some(3),
+            validation: Some(TransferValidation::Md5),
+            progress: Some(TransferProgress::new({
+                let reported = reported.clone();
+                move |downloaded| reported.lock().unwrap().push(downloaded)
+            })),
+            ..Default::default()
+        };
+        let mut content = Vec::new();
+        let downloaded = client(&storage)
+            .download_to(&mut content, Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(10, downloaded);
+        assert_eq!(b"0123456789".to_vec(), content);
+        assert_eq!(vec![3, 6, 9, 10], *reported.lock().unwrap());
+
+        let requests = storage.requests();
+        assert_eq!(5, requests.len());
+        assert_eq!(None, header(&requests[0], "if-match"));
+        for request in &requests[1..] {
+            assert_eq!(Some(ETAG), header(request, "if-match").as_deref());
+            assert_eq!(
+                Some("true"),
+                header(request, "x-ms-range-get-content-md5").as_deref()
+            );
+        }
+        drop(requests);
+        // The corrupted range is requested again as a whole.
+        let mut ranges = ranges(&storage);
+        ranges.sort();
+        assert_eq!(
+            vec![
+                Some("bytes=0-2".to_string()),
+                Some("bytes=3-5".to_string()),
+                Some("bytes=3-5".to_string()),
+                Some("bytes=6-8".to_string()),
+                Some("bytes=9-9".to_string()),
+            ],
+            ranges
+        );
+    }
+
+    #[tokio::test]
+    pub async fn resumes_truncated_ranges() {
+        let storage = fake_blob(b"0123456789", Vec::new(), vec![0]);
+        let options = BlobClientDownloadToOptions {
+            range_size: Some(8),
+            ..Default::default()
+        };
+        let mut content = Vec::new();
+        client(&storage)
+            .download_to(&mut content, Some(options))
+            .await
+            .unwrap();
+
+        assert_eq!(b"0123456789".to_vec(), content);
+        assert_eq!(
+            vec![
+                Some("bytes=0-7".to_string()),
+                Some("bytes=1-7".to_string()),
+                Some("bytes=8-9".to_string()),
+            ],
+            ranges(&storage)
+        );
+    }
+
+    #[tokio::test]
+    pub async fn downloads_empty_blobs() {
+        let storage = fake_blob(b"", Vec::new(), Vec::new());
+        let mut content = Vec::new();
+        let downloaded = client(&storage)
+            .download_to(&mut content, None)
+            .await
+            .unwrap();
+
+        assert_eq!(0, downloaded);
+        assert!(content.is_empty());
+        assert_eq!(1, storage.requests().len());
+    }
+
+    #[tokio::test]
+    pub async fn fails_when_the_blob_changes() {
+        let storage = fake_blob(b"0123456789", Vec::new(), Vec::new());
+        let options = BlobClientDownloadToOptions {
+            if_match: Some("\"0x8DD58B8E0AB0C48\"".to_string()),
+            ..Default::default()
+        };
+        let error = client(&storage)
+            .download_to(Vec::new(), Some(options))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            error.kind(),
+            azure_core::error::ErrorKind::HttpResponse {
+                status: StatusCode::PreconditionFailed,
+                ..
+            }
+        ));
+        assert_eq!(1, storage.requests().len());
+    }
+
+    #[tokio::test]
+    pub async fn fails_when_ranges_keep_mismatching() {
+        let storage = fake_blob(b"0123456789", vec![0; 4], Vec::new());
+        let options = BlobClientDownloadToOptions {
+            validation: Some(TransferValidation::Crc64),
+            ..Default::default()
+        };
+        let error = client(&storage)
+            .download_to(Vec::new(), Some(options))
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            Some(StorageErrorKind::Crc64Mismatch),
+            StorageError::from_error(&error).map(|error| error.kind())
+        );
+        assert_eq!(4, storage.requests().len());
+    }
+}
@@ -0,0 +1,147 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Transfers of large blobs, split in blocks or ranges sent concurrently, and server-side copies of blobs.
+
+pub(crate) mod copy;
+pub(crate) mod download;
+pub(crate) mod upload;
+
+use azure_core::{base64, http::Body, Result};
+use azure_storage_common::{crc64::Crc64, StorageError, StorageErrorKind};
+use md5::{Digest, Md5};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    sync::Arc,
+};
+
+pub use copy::{BlobCopyPoller, MetadataCopyMode};
+
+/// A callback reporting the number of bytes transferred so far.
+///
+/// Blocks and ranges complete out of order, so the reported count only tells how much of the data was transferred,
+/// not up to which offset.
+#[derive(Clone)]
+pub struct TransferProgress(Arc<dyn Fn(u64) + Send + Sync>);
+
+impl TransferProgress {
+    /// Creates a new `TransferProgress` calling `callback` with the number of bytes transferred so far.
+    pub fn new(callback: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    pub(crate) fn report(&self, transferred: u64) {
+        (self.0)(transferred)
+    }
+}
+
+impl fmt::Debug for TransferProgress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransferProgress")
+    }
+}
+
+/// A checksum validating the integrity of each request of a transfer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferValidation {
+    /// The MD5 hash of the content, sent in the `Content-MD5` header.
+    Md5,
+
+    /// The Azure Storage CRC64 of the content, sent in the `x-ms-content-crc64` header.
+    Crc64,
+}
+
+/// A checksum of the content of a request or response, computed as the content is sent or received.
+pub(crate) enum Checksum {
+    Md5(Md5),
+    Crc64(Crc64),
+}
+
+impl Checksum {
+    pub(crate) fn new(validation: TransferValidation) -> Self {
+        match validation {
+            TransferValidation::Md5 => Self::Md5(Md5::new()),
+            TransferValidation::Crc64 => Self::Crc64(Crc64::new()),
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(md5) => md5.update(data),
+            Self::Crc64(crc64) => crc64.update(data),
+        }
+    }
+
+    /// Returns the checksum as encoded, before base64, in the `Content-MD5` or `x-ms-content-crc64` header.
+    fn finish(self) -> Vec<u8> {
+        match self {
+            Self::Md5(md5) => md5.finalize().to_vec(),
+            Self::Crc64(crc64) => crc64.to_bytes().to_vec(),
+        }
+    }
+
+    /// Returns the `Content-MD5` and `x-ms-content-crc64` headers of a request, one of which is set, for the service to
+    /// validate the content it receives.
+    pub(crate) fn into_headers(self) -> (Option<String>, Option<String>) {
+        match self {
+            Self::Md5(_) => (Some(base64::encode(self.finish())), None),
+            Self::Crc64(_) => (None, Some(base64::encode(self.finish()))),
+        }
+    }
+
+    /// Fails with a [`StorageError`] if the checksum differs from the `expected` one, returned by the service in the
+    /// `Content-MD5` or `x-ms-content-crc64` header of `content`.
+    pub(crate) fn verify(self, expected: Option<&str>, content: &str) -> Result<()> {
+        let (kind, name) = match self {
+            Self::Md5(_) => (StorageErrorKind::Md5Mismatch, "MD5"),
+            Self::Crc64(_) => (StorageErrorKind::Crc64Mismatch, "CRC64"),
+        };
+        let expected = expected.map(base64::decode).transpose()?;
+        if expected.as_deref() != Some(self.finish().as_slice()) {
+            return Err(StorageError::new(
+                kind,
+                format!("the {name} of {content} does not match the one returned by the service"),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Returns the `Content-MD5` and `x-ms-content-crc64` headers of `data`, when it is validated.
+pub(crate) fn checksum_headers(
+    validation: Option<TransferValidation>,
+    data: &[u8],
+) -> (Option<String>, Option<String>) {
+    let Some(validation) = validation else {
+        return (None, None);
+    };
+    let mut checksum = Checksum::new(validation);
+    checksum.update(data);
+    checksum.into_headers()
+}
+
+/// Returns the `Content-MD5` and `x-ms-content-crc64` headers of the body of a request, when it is validated. A stream is read
+/// to compute its checksum, then reset to be sent.
+pub(crate) async fn body_checksum_headers(
+    validation: Option<TransferValidation>,
+    body: &mut Body,
+) -> Result<(Option<String>, Option<String>)> {
+    match (validation, body) {
+        (None, _) => Ok((None, None)),
+        (validation, Body::Bytes(bytes)) => Ok(checksum_headers(validation, bytes)),
+        #[cfg(not(target_arch = "wasm32"))]
+        (Some(validation), Body::SeekableStream(stream)) => {
+            upload::stream_checksum(stream, validation).await
+        }
+    }
+}
+
+/// Encodes tags as a query string, as expected by the `x-ms-tags` header.
+pub(crate) fn tags_string(tags: &HashMap<String, String>) -> String {
+    let tags: BTreeMap<_, _> = tags.iter().collect();
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(tags)
+        .finish()
+}
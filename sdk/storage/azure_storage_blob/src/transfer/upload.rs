@@ -0,0 +1,460 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Uploads of block blobs, with a single request for small data, or by staging blocks concurrently and committing them.
+
+use crate::{
+    generated::{
+        clients::BlockBlobClient as GeneratedBlockBlobClient,
+        models::{
+            BlockBlobClientCommitBlockListOptions, BlockBlobClientStageBlockOptions,
+            BlockBlobClientUploadOptions, BlockBlobClientUploadResult, BlockLookupList,
+        },
+    },
+    transfer::{checksum_headers, tags_string, Checksum},
+    BlockBlobClientUploadStreamOptions, TransferValidation,
+};
+use azure_core::{
+    error::ErrorKind,
+    http::{Body, ClientMethodOptions, RequestContent, Response},
+    stream::SeekableStream,
+    Bytes, Result,
+};
+use futures::{io::AsyncRead, stream, AsyncReadExt, Stream, StreamExt, TryStreamExt};
+use uuid::Uuid;
+
+const DEFAULT_BLOCK_SIZE: u64 = 4 * 1024 * 1024;
+const DEFAULT_MAX_SINGLE_UPLOAD_SIZE: u64 = 32 * 1024 * 1024;
+const DEFAULT_CONCURRENCY: usize = 8;
+
+/// The maximum size of a block, since version 2019-12-12.
+const MAX_BLOCK_SIZE: u64 = 4000 * 1024 * 1024;
+
+/// The maximum number of blocks of a blob.
+const MAX_BLOCKS: usize = 50_000;
+
+/// The sizes of an upload, validated from its options.
+struct Settings {
+    block_size: usize,
+    max_single_upload_size: u64,
+    concurrency: usize,
+}
+
+impl Settings {
+    fn new(options: &BlockBlobClientUploadStreamOptions<'_>) -> Result<Self> {
+        let block_size = options.block_size.unwrap_or(DEFAULT_BLOCK_SIZE);
+        if block_size == 0 || block_size > MAX_BLOCK_SIZE {
+            return Err(azure_core::Error::message(
+                ErrorKind::Other,
+                format!("the block size must be between 1 and {MAX_BLOCK_SIZE} bytes"),
+            ));
+        }
+        Ok(Self {
+            block_size: block_size as usize,
+            max_single_upload_size: options
+                .max_single_upload_size
+                .unwrap_or(DEFAULT_MAX_SINGLE_UPLOAD_SIZE),
+            concurrency: options.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1),
+        })
+    }
+}
+
+/// Uploads a stream of known length, sending it as is when it fits in a single request.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn upload_stream(
+    client: &GeneratedBlockBlobClient,
+    mut stream: Box<dyn SeekableStream>,
+    options: BlockBlobClientUploadStreamOptions<'_>,
+) -> Result<Response<BlockBlobClientUploadResult>> {
+    let settings = Settings::new(&options)?;
+    let length = stream.len() as u64;
+    if length <= settings.max_single_upload_size {
+        let checksums = match options.validation {
+            Some(validation) => stream_checksum(&mut stream, validation).await?,
+            None => (None, None),
+        };
+        // The retry policy resets the stream before sending it again.
+        let body: RequestContent<Bytes> = Body::from(stream).into();
+        return upload_once(client, body, length, checksums, &options).await;
+    }
+    let chunks = chunks(stream, settings.block_size);
+    upload_blocks(client, chunks, &settings, &options).await
+}
+
+/// Uploads data of unknown length, buffering up to the maximum size of a single upload to find out whether it fits.
+pub(crate) async fn upload_reader<R>(
+    client: &GeneratedBlockBlobClient,
+    mut reader: R,
+    options: BlockBlobClientUploadStreamOptions<'_>,
+) -> Result<Response<BlockBlobClientUploadResult>>
+where
+    R: AsyncRead + Unpin + Send,
+{
+    let settings = Settings::new(&options)?;
+    let mut buffered = Vec::new();
+    let mut length = 0;
+    while length <= settings.max_single_upload_size {
+        let chunk = read_chunk(&mut reader, settings.block_size).await?;
+        if chunk.is_empty() {
+            let body = match buffered.len() {
+                1 => buffered.remove(0),
+                _ => Bytes::from(buffered.concat()),
+            };
+            let checksums = checksum_headers(options.validation, &body);
+            return upload_once(client, body.try_into()?, length, checksums, &options).await;
+        }
+        length += chunk.len() as u64;
+        buffered.push(chunk);
+    }
+
+    // The buffered chunks are the first blocks.
+    let chunks =
+        stream::iter(buffered.into_iter().map(Ok)).chain(chunks(reader, settings.block_size));
+    upload_blocks(client, chunks, &settings, &options).await
+}
+
+/// Computes the checksum of a stream by reading it in chunks, then resets it to be sent.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) async fn stream_checksum(
+    stream: &mut Box<dyn SeekableStream>,
+    validation: TransferValidation,
+) -> Result<(Option<String>, Option<String>)> {
+    let mut checksum = Checksum::new(validation);
+    let mut buffer = vec![0; DEFAULT_BLOCK_SIZE as usize];
+    loop {
+        match stream.read(&mut buffer).await? {
+            0 => break,
+            read => checksum.update(&buffer[..read]),
+        }
+    }
+    stream.reset().await?;
+    Ok(checksum.into_headers())
+}
+
+/// Reads the data in chunks of `block_size` bytes, the last one being shorter.
+fn chunks<R>(reader: R, block_size: usize) -> impl Stream<Item = Result<Bytes>> + Send
+where
+    R: AsyncRead + Unpin + Send,
+{
+    stream::try_unfold(reader, move |mut reader| async move {
+        let chunk = read_chunk(&mut reader, block_size).await?;
+        Ok((!chunk.is_empty()).then_some((chunk, reader)))
+    })
+}
+
+/// Reads up to `size` bytes, fewer only at the end of the data.
+async fn read_chunk<R>(reader: &mut R, size: usize) -> Result<Bytes>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buffer = vec![0; size];
+    let mut length = 0;
+    while length < size {
+        match reader.read(&mut buffer[length..]).await? {
+            0 => break,
+            read => length += read,
+        }
+    }
+    buffer.truncate(length);
+    Ok(buffer.into())
+}
+
+async fn upload_once(
+    client: &GeneratedBlockBlobClient,
+    body: RequestContent<Bytes>,
+    length: u64,
+    (transactional_content_md5, transactional_content_crc64): (Option<String>, Option<String>),
+    options: &BlockBlobClientUploadStreamOptions<'_>,
+) -> Result<Response<BlockBlobClientUploadResult>> {
+    let upload_options = BlockBlobClientUploadOptions {
+        blob_cache_control: options.blob_cache_control.clone(),
+        blob_content_disposition: options.blob_content_disposition.clone(),
+        blob_content_encoding: options.blob_content_encoding.clone(),
+        blob_content_language: options.blob_content_language.clone(),
+        blob_content_type: options.blob_content_type.clone(),
+        blob_tags_string: options.tags.as_ref().map(tags_string),
+        if_match: options.if_match.clone(),
+        if_none_match: options.if_none_match.clone(),
+        lease_id: options.lease_id.clone(),
+        metadata: options.metadata.clone(),
+        method_options: ClientMethodOptions {
+            context: options.method_options.context.clone(),
+        },
+        tier: options.tier.clone(),
+        transactional_content_crc64,
+        transactional_content_md5,
+        ..Default::default()
+    };
+    let response = client.upload(body, length, Some(upload_options)).await?;
+    if let Some(progress) = &options.progress {
+        progress.report(length);
+    }
+    Ok(response)
+}
+
+/// Stages the chunks as blocks, at most `concurrency` at a time, then commits them in order.
+async fn upload_blocks(
+    client: &GeneratedBlockBlobClient,
+    chunks: impl Stream<Item = Result<Bytes>> + Send,
+    settings: &Settings,
+    options: &BlockBlobClientUploadStreamOptions<'_>,
+) -> Result<Response<BlockBlobClientUploadResult>> {
+    // The IDs of the blocks of an upload share a random prefix, so that concurrent uploads to the same blob do not mix their blocks.
+    let upload_id = Uuid::new_v4();
+    let (count, _) = chunks
+        .enumerate()
+        .map(|(index, chunk)| async move {
+            if index >= MAX_BLOCKS {
+                return Err(azure_core::Error::message(
+                    ErrorKind::Other,
+                    format!(
+                        "the data is larger than {MAX_BLOCKS} blocks of {} bytes",
+                        settings.block_size
+                    ),
+                ));
+            }
+            let chunk = chunk?;
+            let length = chunk.len() as u64;
+            let (transactional_content_md5, transactional_content_crc64) =
+                checksum_headers(options.validation, &chunk);
+            // Each block is retried on its own by the retry policy of the client.
+            let stage_options = BlockBlobClientStageBlockOptions {
+                lease_id: options.lease_id.clone(),
+                method_options: ClientMethodOptions {
+                    context: options.method_options.context.clone(),
+                },
+                transactional_content_crc64,
+                transactional_content_md5,
+                ..Default::default()
+            };
+            client
+                .stage_block(
+                    block_id(&upload_id, index).as_bytes(),
+                    length,
+                    chunk.try_into()?,
+                    Some(stage_options),
+                )
+                .await?;
+            Ok(length)
+        })
+        .buffer_unordered(settings.concurrency)
+        .try_fold((0, 0), |(count, uploaded), length| async move {
+            let uploaded = uploaded + length;
+            if let Some(progress) = &options.progress {
+                progress.report(uploaded);
+            }
+            Ok((count + 1, uploaded))
+        })
+        .await?;
+
+    let blocks = BlockLookupList {
+        latest: Some(
+            (0..count)
+                .map(|index| block_id(&upload_id, index).into_bytes())
+                .collect(),
+        ),
+        ..Default::default()
+    };
+    let commit_options = BlockBlobClientCommitBlockListOptions {
+        blob_cache_control: options.blob_cache_control.clone(),
+        blob_content_disposition: options.blob_content_disposition.clone(),
+        blob_content_encoding: options.blob_content_encoding.clone(),
+        blob_content_language: options.blob_content_language.clone(),
+        blob_content_type: options.blob_content_type.clone(),
+        blob_tags_string: options.tags.as_ref().map(tags_string),
+        if_match: options.if_match.clone(),
+        if_none_match: options.if_none_match.clone(),
+        lease_id: options.lease_id.clone(),
+        metadata: options.metadata.clone(),
+        method_options: ClientMethodOptions {
+            context: options.method_options.context.clone(),
+        },
+        tier: options.tier.clone(),
+        ..Default::default()
+    };
+    let response = client
+        .commit_block_list(blocks.try_into()?, Some(commit_options))
+        .await?;
+
+    // Both Put Blob and Put Block List return the properties of the new blob in the same headers.
+    let (status, headers, body) = response.deconstruct();
+    Ok(Response::from_bytes(status, headers, body.collect().await?))
+}
+
+/// Builds the ID of a block, which must have the same length for all the blocks of a blob.
+fn block_id(upload_id: &Uuid, index: usize) -> String {
+    format!("{upload_id}-{index:08}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        fake::{header, query, FakeStorage},
+        models::{AccessTier, BlockLookupList},
+        BlockBlobClientUploadStreamOptions, TransferProgress, TransferValidation,
+    };
+    use azure_core::{
+        base64,
+        http::{headers::Headers, Body, Request, Response, StatusCode},
+        stream::BytesStream,
+        xml, Bytes,
+    };
+    use azure_storage_common::crc64;
+    use futures::io::Cursor;
+    use md5::{Digest, Md5};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+
+    /// Records the requests sent to the blob, and accepts all of them.
+    fn fake_blob() -> FakeStorage {
+        FakeStorage::new(|_, _| {
+            let mut headers = Headers::new();
+            headers.insert("etag", "\"0x8DD58B8E0AB0C47\"");
+            Response::from_bytes(StatusCode::Created, headers, "")
+        })
+    }
+
+    fn body(request: &Request) -> Bytes {
+        match request.body() {
+            Body::Bytes(bytes) => bytes.clone(),
+            _ => panic!("the body of the request is a stream"),
+        }
+    }
+
+    fn progress() -> (TransferProgress, Arc<Mutex<Vec<u64>>>) {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let progress = TransferProgress::new({
+            let reported = reported.clone();
+            move |transferred| reported.lock().unwrap().push(transferred)
+        });
+        (progress, reported)
+    }
+
+    #[tokio::test]
+    pub async fn stages_and_commits_blocks() {
+        let storage = fake_blob();
+        let (progress, reported) = progress();
+        let options = BlockBlobClientUploadStreamOptions {
+            block_size: Some(4),
+            max_single_upload_size: Some(6),
+            concurrency: Some(2),
+            metadata: Some(HashMap::from([("project".to_string(), "sdk".to_string())])),
+            tags: Some(HashMap::from([
+                ("status".to_string(), "draft".to_string()),
+                ("owner name".to_string(), "blob".to_string()),
+            ])),
+            tier: Some(AccessTier::Cool),
+            progress: Some(progress),
+            ..Default::default()
+        };
+        storage
+            .block_blob_client("container", "blob")
+            .upload_reader(Cursor::new(b"0123456789".to_vec()), Some(options))
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(4, requests.len());
+        let mut blocks = HashMap::new();
+        for request in &requests[..3] {
+            assert_eq!(Some("block"), query(request, "comp").as_deref());
+            let block_id = base64::decode(query(request, "blockid").unwrap()).unwrap();
+            blocks.insert(block_id, body(request));
+        }
+
+        let commit = &requests[3];
+        assert_eq!(Some("blocklist"), query(commit, "comp").as_deref());
+        assert_eq!(Some("sdk"), header(commit, "x-ms-meta-project").as_deref());
+        assert_eq!(
+            Some("owner+name=blob&status=draft"),
+            header(commit, "x-ms-tags").as_deref()
+        );
+        assert_eq!(Some("Cool"), header(commit, "x-ms-access-tier").as_deref());
+        let committed: BlockLookupList = xml::read_xml(&body(commit)).unwrap();
+        let data: Vec<u8> = committed
+            .latest
+            .unwrap()
+            .iter()
+            .flat_map(|block_id| blocks[block_id].to_vec())
+            .collect();
+        assert_eq!(b"0123456789".to_vec(), data);
+        assert_eq!(Some(&10), reported.lock().unwrap().last());
+    }
+
+    #[tokio::test]
+    pub async fn uploads_small_data_at_once() {
+        let storage = fake_blob();
+        let client = storage.block_blob_client("container", "blob");
+        let (progress, reported) = progress();
+        let options = BlockBlobClientUploadStreamOptions {
+            max_single_upload_size: Some(5),
+            progress: Some(progress),
+            ..Default::default()
+        };
+        let stream = BytesStream::new(Bytes::from_static(b"hello"));
+        client
+            .upload_stream(Box::new(stream), Some(options.clone()))
+            .await
+            .unwrap();
+        client
+            .upload_reader(Cursor::new(b"hello".to_vec()), Some(options))
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(2, requests.len());
+        for request in requests.iter() {
+            assert_eq!(None, query(request, "comp"));
+            assert_eq!(
+                Some("BlockBlob"),
+                header(request, "x-ms-blob-type").as_deref()
+            );
+            assert_eq!(5, request.body().len());
+        }
+        assert_eq!(b"hello", &body(&requests[1])[..]);
+        assert_eq!(vec![5, 5], *reported.lock().unwrap());
+    }
+
+    #[tokio::test]
+    pub async fn sends_checksums() {
+        let storage = fake_blob();
+        let client = storage.block_blob_client("container", "blob");
+        let stream = BytesStream::new(Bytes::from_static(b"hello"));
+        let options = BlockBlobClientUploadStreamOptions {
+            validation: Some(TransferValidation::Md5),
+            ..Default::default()
+        };
+        client
+            .upload_stream(Box::new(stream), Some(options))
+            .await
+            .unwrap();
+        let options = BlockBlobClientUploadStreamOptions {
+            block_size: Some(4),
+            max_single_upload_size: Some(4),
+            validation: Some(TransferValidation::Crc64),
+            ..Default::default()
+        };
+        client
+            .upload_reader(Cursor::new(b"0123456789".to_vec()), Some(options))
+            .await
+            .unwrap();
+
+        let requests = storage.requests();
+        assert_eq!(5, requests.len());
+        assert_eq!(
+            Some(base64::encode(Md5::digest(b"hello"))),
+            header(&requests[0], "content-md5")
+        );
+        // The stream is sent from its start after its checksum is computed.
+        assert_eq!(5, requests[0].body().len());
+        for request in &requests[1..4] {
+            let crc64 = crc64::checksum(&body(request)).to_le_bytes();
+            assert_eq!(
+                Some(base64::encode(crc64)),
+                header(request, "x-ms-content-crc64")
+            );
+        }
+    }
+}
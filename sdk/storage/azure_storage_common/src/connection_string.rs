@@ -0,0 +1,314 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{sas::SasToken, SharedKeyCredential, StorageCredential};
+use azure_core::{error::ErrorKind, http::Url, Result};
+use std::{collections::HashMap, str::FromStr};
+
+/// The name of the account of the Azurite emulator.
+pub const DEVELOPMENT_ACCOUNT_NAME: &str = "devstoreaccount1";
+
+/// The well-known key of the account of the Azurite emulator.
+pub const DEVELOPMENT_ACCOUNT_KEY: &str =
+    "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+/// The endpoints and credential of a Storage account, parsed from a connection string.
+///
+/// The connection strings of Storage accounts are shown in the Azure portal, for example:
+///
+/// ```
+/// use azure_storage_common::{ConnectionString, StorageCredential};
+///
+/// # fn main() -> azure_core::Result<()> {
+/// let connection_string: ConnectionString = "DefaultEndpointsProtocol=https;AccountName=myaccount;AccountKey=a2V5;EndpointSuffix=core.windows.net".parse()?;
+/// assert_eq!(Some("https://myaccount.blob.core.windows.net/"), connection_string.blob_endpoint().map(|url| url.as_str()));
+/// assert!(matches!(connection_string.credential(), StorageCredential::SharedKey(_)));
+///
+/// // The Azurite emulator.
+/// let connection_string: ConnectionString = "UseDevelopmentStorage=true".parse()?;
+/// assert_eq!(Some("http://127.0.0.1:10000/devstoreaccount1/"), connection_string.blob_endpoint().map(|url| url.as_str()));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The credential is a [`StorageCredential::SharedKey`] when the connection string contains an `AccountKey`,
+/// a [`StorageCredential::Sas`] when it contains a `SharedAccessSignature`, and [`StorageCredential::Anonymous`] otherwise.
+#[derive(Clone, Debug)]
+pub struct ConnectionString {
+    account_name: Option<String>,
+    credential: StorageCredential,
+    blob_endpoint: Option<Url>,
+    queue_endpoint: Option<Url>,
+    table_endpoint: Option<Url>,
+    file_endpoint: Option<Url>,
+}
+
+impl ConnectionString {
+    /// Gets the name of the account, if the connection string contains it.
+    pub fn account_name(&self) -> Option<&str> {
+        self.account_name.as_deref()
+    }
+
+    /// Gets the credential to authorize requests with.
+    pub fn credential(&self) -> &StorageCredential {
+        &self.credential
+    }
+
+    /// Gets the endpoint of the Blob service.
+    pub fn blob_endpoint(&self) -> Option<&Url> {
+        self.blob_endpoint.as_ref()
+    }
+
+    /// Gets the endpoint of the Queue service.
+    pub fn queue_endpoint(&self) -> Option<&Url> {
+        self.queue_endpoint.as_ref()
+    }
+
+    /// Gets the endpoint of the Table service.
+    pub fn table_endpoint(&self) -> Option<&Url> {
+        self.table_endpoint.as_ref()
+    }
+
+    /// Gets the endpoint of the File service. The Azurite emulator has none.
+    pub fn file_endpoint(&self) -> Option<&Url> {
+        self.file_endpoint.as_ref()
+    }
+
+    fn development_storage(settings: &HashMap<String, &str>) -> Result<Self> {
+        let proxy = match settings.get("developmentstorageproxyuri") {
+            Some(proxy) => Url::parse(proxy)?,
+            None => Url::parse("http://127.0.0.1")?,
+        };
+        let endpoint = |port: u16| -> Result<Option<Url>> {
+            let mut url = proxy.clone();
+            url.set_port(Some(port)).map_err(|_| {
+                azure_core::Error::message(
+                    ErrorKind::DataConversion,
+                    "the development storage proxy URI cannot have a port",
+                )
+            })?;
+            url.set_path(&format!("/{DEVELOPMENT_ACCOUNT_NAME}/"));
+            Ok(Some(url))
+        };
+        Ok(Self {
+            account_name: Some(DEVELOPMENT_ACCOUNT_NAME.to_string()),
+            credential: StorageCredential::SharedKey(SharedKeyCredential::new(
+                DEVELOPMENT_ACCOUNT_NAME,
+                DEVELOPMENT_ACCOUNT_KEY,
+            )),
+            blob_endpoint: endpoint(10000)?,
+            queue_endpoint: endpoint(10001)?,
+            table_endpoint: endpoint(10002)?,
+            file_endpoint: None,
+        })
+    }
+}
+
+impl FromStr for ConnectionString {
+    type Err = azure_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        // Setting names are case-insensitive, and values may contain `=`, as in base64 keys.
+        let mut settings = HashMap::new();
+        for setting in s.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+            let (name, value) = setting.split_once('=').ok_or_else(|| {
+                azure_core::Error::message(
+                    ErrorKind::DataConversion,
+                    format!("the connection string setting '{setting}' has no value"),
+                )
+            })?;
+            settings.insert(name.trim().to_lowercase(), value.trim());
+        }
+
+        if settings
+            .get("usedevelopmentstorage")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+        {
+            return Self::development_storage(&settings);
+        }
+
+        let account_name = settings.get("accountname").map(|name| name.to_string());
+        let credential = match (
+            settings.get("accountkey"),
+            settings.get("sharedaccesssignature"),
+        ) {
+            (Some(_), Some(_)) => {
+                return Err(azure_core::Error::message(
+                    ErrorKind::DataConversion,
+                    "the connection string cannot contain both an AccountKey and a SharedAccessSignature",
+                ))
+            }
+            (Some(key), None) => {
+                let account_name = account_name.clone().ok_or_else(|| {
+                    azure_core::Error::message(
+                        ErrorKind::DataConversion,
+                        "the connection string contains an AccountKey but no AccountName",
+                    )
+                })?;
+                StorageCredential::SharedKey(SharedKeyCredential::new(
+                    account_name,
+                    key.to_string(),
+                ))
+            }
+            (None, Some(sas)) => StorageCredential::Sas(sas.parse::<SasToken>()?),
+            (None, None) => StorageCredential::Anonymous,
+        };
+
+        let protocol = settings
+            .get("defaultendpointsprotocol")
+            .copied()
+            .unwrap_or("https");
+        let suffix = settings
+            .get("endpointsuffix")
+            .copied()
+            .unwrap_or("core.windows.net");
+        let endpoint = |setting: &str, service: &str| -> Result<Option<Url>> {
+            let url = match (settings.get(setting), &account_name) {
+                (Some(endpoint), _) => Url::parse(endpoint)?,
+                (None, Some(account_name)) => {
+                    Url::parse(&format!("{protocol}://{account_name}.{service}.{suffix}"))?
+                }
+                (None, None) => return Ok(None),
+            };
+            Ok(Some(with_trailing_slash(url)))
+        };
+        let connection_string = Self {
+            blob_endpoint: endpoint("blobendpoint", "blob")?,
+            queue_endpoint: endpoint("queueendpoint", "queue")?,
+            table_endpoint: endpoint("tableendpoint", "table")?,
+            file_endpoint: endpoint("fileendpoint", "file")?,
+            account_name,
+            credential,
+        };
+        if connection_string.blob_endpoint.is_none()
+            && connection_string.queue_endpoint.is_none()
+            && connection_string.table_endpoint.is_none()
+            && connection_string.file_endpoint.is_none()
+        {
+            return Err(azure_core::Error::message(
+                ErrorKind::DataConversion,
+                "the connection string contains neither an AccountName nor a service endpoint",
+            ));
+        }
+        Ok(connection_string)
+    }
+}
+
+/// Ensures the path of an endpoint ends with `/`, so that joining a resource path to it keeps the whole path, as with the Azurite emulator.
+fn with_trailing_slash(mut url: Url) -> Url {
+    if !url.path().ends_with('/') {
+        let path = format!("{}/", url.path());
+        url.set_path(&path);
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConnectionString;
+    use crate::StorageCredential;
+
+    fn endpoints(connection_string: &ConnectionString) -> [Option<&str>; 4] {
+        [
+            connection_string.blob_endpoint(),
+            connection_string.queue_endpoint(),
+            connection_string.table_endpoint(),
+            connection_string.file_endpoint(),
+        ]
+        .map(|url| url.map(|url| url.as_str()))
+    }
+
+    #[test]
+    pub fn parses_account_connection_strings() {
+        let connection_string: ConnectionString =
+            "DefaultEndpointsProtocol=http;AccountName=myaccount;AccountKey=a2V5==;EndpointSuffix=core.chinacloudapi.cn;"
+                .parse()
+                .unwrap();
+        assert_eq!(Some("myaccount"), connection_string.account_name());
+        assert_eq!(
+            [
+                Some("http://myaccount.blob.core.chinacloudapi.cn/"),
+                Some("http://myaccount.queue.core.chinacloudapi.cn/"),
+                Some("http://myaccount.table.core.chinacloudapi.cn/"),
+                Some("http://myaccount.file.core.chinacloudapi.cn/"),
+            ],
+            endpoints(&connection_string)
+        );
+        assert!(matches!(
+            connection_string.credential(),
+            StorageCredential::SharedKey(credential) if credential.account_name() == "myaccount"
+        ));
+
+        let connection_string: ConnectionString =
+            "BlobEndpoint=https://myaccount.blob.core.windows.net;SharedAccessSignature=sv=2025-01-05&ss=b&srt=co&sp=rl&se=2025-03-02T12%3A00%3A00Z&sig=abc%3D"
+                .parse()
+                .unwrap();
+        assert_eq!(None, connection_string.account_name());
+        assert_eq!(
+            [
+                Some("https://myaccount.blob.core.windows.net/"),
+                None,
+                None,
+                None
+            ],
+            endpoints(&connection_string)
+        );
+        assert!(matches!(
+            connection_string.credential(),
+            StorageCredential::Sas(token) if token.get("sig") == Some("abc=")
+        ));
+    }
+
+    #[test]
+    pub fn parses_development_storage_connection_strings() {
+        let connection_string: ConnectionString = "UseDevelopmentStorage=true".parse().unwrap();
+        assert_eq!(Some("devstoreaccount1"), connection_string.account_name());
+        assert_eq!(
+            [
+                Some("http://127.0.0.1:10000/devstoreaccount1/"),
+                Some("http://127.0.0.1:10001/devstoreaccount1/"),
+                Some("http://127.0.0.1:10002/devstoreaccount1/"),
+                None,
+            ],
+            endpoints(&connection_string)
+        );
+        assert!(matches!(
+            connection_string.credential(),
+            StorageCredential::SharedKey(_)
+        ));
+
+        let connection_string: ConnectionString =
+            "UseDevelopmentStorage=true;DevelopmentStorageProxyUri=http://azurite"
+                .parse()
+                .unwrap();
+        assert_eq!(
+            Some("http://azurite:10000/devstoreaccount1/"),
+            endpoints(&connection_string)[0]
+        );
+
+        // Azurite endpoints given explicitly keep the account in their path.
+        let connection_string: ConnectionString = "DefaultEndpointsProtocol=http;AccountName=devstoreaccount1;AccountKey=a2V5;BlobEndpoint=http://127.0.0.1:10000/devstoreaccount1"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            Some("http://127.0.0.1:10000/devstoreaccount1/"),
+            endpoints(&connection_string)[0]
+        );
+    }
+
+    #[test]
+    pub fn rejects_invalid_connection_strings() {
+        for connection_string in [
+            "",
+            "AccountKey=a2V5",
+            "AccountName=myaccount;AccountKey",
+            "AccountName=myaccount;AccountKey=a2V5;SharedAccessSignature=sig=abc",
+            "BlobEndpoint=not a url",
+        ] {
+            assert!(
+                connection_string.parse::<ConnectionString>().is_err(),
+                "{connection_string}"
+            );
+        }
+    }
+}
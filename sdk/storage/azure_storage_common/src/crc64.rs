@@ -0,0 +1,102 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! The CRC64 used by Azure Storage to validate the integrity of the data sent with a request, or returned in a response.
+
+/// The reversed polynomial used by Azure Storage, the one of CRC-64/NVME.
+const POLYNOMIAL: u64 = 0x9A6C_9329_AC4B_C9B5;
+
+const TABLE: [u64; 256] = table();
+
+const fn table() -> [u64; 256] {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// Computes the Azure Storage CRC64 of data received in several parts.
+///
+/// # Examples
+///
+/// ```
+/// use azure_storage_common::crc64::{self, Crc64};
+///
+/// let mut crc = Crc64::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), crc64::checksum(b"123456789"));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Crc64(u64);
+
+impl Crc64 {
+    /// Creates a new `Crc64` over no data.
+    pub fn new() -> Self {
+        Self(!0)
+    }
+
+    /// Adds `data` to the checksum.
+    pub fn update(&mut self, data: &[u8]) {
+        self.0 = data.iter().fold(self.0, |crc, byte| {
+            TABLE[((crc ^ u64::from(*byte)) & 0xff) as usize] ^ (crc >> 8)
+        });
+    }
+
+    /// Returns the checksum of the data added so far.
+    pub fn finish(&self) -> u64 {
+        !self.0
+    }
+
+    /// Returns the checksum of the data added so far as sent in the `x-ms-content-crc64` header, in little endian order.
+    pub fn to_bytes(&self) -> [u8; 8] {
+        self.finish().to_le_bytes()
+    }
+}
+
+impl Default for Crc64 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Computes the Azure Storage CRC64 of `data`.
+pub fn checksum(data: &[u8]) -> u64 {
+    let mut crc = Crc64::new();
+    crc.update(data);
+    crc.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn matches_check_value() {
+        assert_eq!(checksum(b""), 0);
+        assert_eq!(checksum(b"123456789"), 0xAE8B_1486_0A79_9888);
+    }
+
+    #[test]
+    pub fn encodes_little_endian() {
+        let mut crc = Crc64::new();
+        crc.update(b"123456789");
+        assert_eq!(
+            crc.to_bytes(),
+            [0x88, 0x98, 0x79, 0x0A, 0x86, 0x14, 0x8B, 0xAE]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use crate::{sas::SasToken, SharedKeyAuthorizationPolicy, SharedKeyCredential};
+use async_trait::async_trait;
+use azure_core::{
+    credentials::TokenCredential,
+    http::{
+        policies::{BearerTokenCredentialPolicy, Policy, PolicyResult},
+        Context, Request,
+    },
+};
+use std::sync::Arc;
+
+/// The scope of the Entra ID tokens used to authenticate with the Storage services.
+const STORAGE_SCOPE: &str = "https://storage.azure.com/.default";
+
+/// The credential used by a Storage client to authorize its requests.
+#[derive(Clone, Debug)]
+pub enum StorageCredential {
+    /// Requests are authorized with Entra ID tokens.
+    Token(Arc<dyn TokenCredential>),
+
+    /// Requests are signed with the key of the account.
+    SharedKey(SharedKeyCredential),
+
+    /// Requests are authorized with a shared access signature, appended to their URL.
+    Sas(SasToken),
+
+    /// Requests are not authorized, to access public containers and blobs.
+    Anonymous,
+}
+
+impl StorageCredential {
+    /// Gets the policy authorizing the requests with this credential, if any.
+    ///
+    /// The policy must be a per-try policy, added after any policy changing the request.
+    pub fn authorization_policy(&self) -> Option<Arc<dyn Policy>> {
+        match self {
+            StorageCredential::Token(credential) => Some(Arc::new(
+                BearerTokenCredentialPolicy::new(credential.clone(), [STORAGE_SCOPE]),
+            )),
+            StorageCredential::SharedKey(credential) => Some(Arc::new(
+                SharedKeyAuthorizationPolicy::new(credential.clone()),
+            )),
+            StorageCredential::Sas(token) => Some(Arc::new(SasAuthorizationPolicy {
+                token: token.clone(),
+            })),
+            StorageCredential::Anonymous => None,
+        }
+    }
+}
+
+impl From<Arc<dyn TokenCredential>> for StorageCredential {
+    fn from(credential: Arc<dyn TokenCredential>) -> Self {
+        StorageCredential::Token(credential)
+    }
+}
+
+impl<T: TokenCredential + 'static> From<Arc<T>> for StorageCredential {
+    fn from(credential: Arc<T>) -> Self {
+        StorageCredential::Token(credential)
+    }
+}
+
+impl From<SharedKeyCredential> for StorageCredential {
+    fn from(credential: SharedKeyCredential) -> Self {
+        StorageCredential::SharedKey(credential)
+    }
+}
+
+impl From<SasToken> for StorageCredential {
+    fn from(token: SasToken) -> Self {
+        StorageCredential::Sas(token)
+    }
+}
+
+/// Appends a SAS token to the query of the requests.
+#[derive(Debug)]
+struct SasAuthorizationPolicy {
+    token: SasToken,
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for SasAuthorizationPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        assert!(
+            !next.is_empty(),
+            "Authorization policies cannot be the last policy of a pipeline"
+        );
+
+        // Retries send the same request, to which the token was already appended.
+        if !request.url().query_pairs().any(|(name, _)| name == "sig") {
+            *request.url_mut() = self.token.append_to(request.url());
+        }
+
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageCredential;
+    use crate::sas::SasToken;
+    use async_trait::async_trait;
+    use azure_core::http::{
+        policies::{Policy, PolicyResult},
+        Context, Method, Request, Url,
+    };
+    use std::{
+        slice,
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Debug, Default)]
+    struct CaptureUrl(Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl Policy for CaptureUrl {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            self.0.lock().unwrap().push(request.url().to_string());
+            Err(azure_core::Error::message(
+                azure_core::error::ErrorKind::Other,
+                "the request was captured",
+            ))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn appends_sas_token_once() {
+        let token: SasToken = "sv=2025-01-05&sr=c&sp=rl&sig=abc%2B%3D".parse().unwrap();
+        let policy = StorageCredential::from(token)
+            .authorization_policy()
+            .unwrap();
+        let capture = Arc::new(CaptureUrl::default());
+        let next: Arc<dyn Policy> = capture.clone();
+        let url =
+            Url::parse("https://myaccount.blob.core.windows.net/photos?restype=container").unwrap();
+        let mut request = Request::new(url, Method::Get);
+        for _ in 0..2 {
+            let _ = policy
+                .send(&Context::default(), &mut request, slice::from_ref(&next))
+                .await;
+        }
+
+        let expected = "https://myaccount.blob.core.windows.net/photos?restype=container&sv=2025-01-05&sr=c&sp=rl&sig=abc%2B%3D";
+        assert_eq!(vec![expected, expected], *capture.0.lock().unwrap());
+        assert!(StorageCredential::Anonymous
+            .authorization_policy()
+            .is_none());
+    }
+}
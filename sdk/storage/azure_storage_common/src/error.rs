@@ -0,0 +1,119 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+use azure_core::error::{ErrorKind, HttpError};
+use std::fmt;
+
+/// The kind of a [`StorageError`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StorageErrorKind {
+    /// The content of a request or response does not match its `Content-MD5`.
+    Md5Mismatch,
+
+    /// The content of a request or response does not match its `x-ms-content-crc64`.
+    Crc64Mismatch,
+}
+
+impl StorageErrorKind {
+    /// Returns the error code returned by the service for this kind of error.
+    fn error_code(&self) -> &'static str {
+        match self {
+            Self::Md5Mismatch => "Md5Mismatch",
+            Self::Crc64Mismatch => "Crc64Mismatch",
+        }
+    }
+}
+
+/// Describes an integrity error of the content sent to or received from the Storage service.
+///
+/// The methods of the clients return [`azure_core::Error`]s. Use [`StorageError::from_error()`] to tell whether an error is a checksum
+/// mismatch, whether the content was validated by the client or by the service:
+///
+/// ```
+/// use azure_storage_common::{StorageError, StorageErrorKind};
+///
+/// let error: azure_core::Error = StorageError::new(StorageErrorKind::Crc64Mismatch, "the CRC64 does not match").into();
+/// assert_eq!(
+///     Some(StorageErrorKind::Crc64Mismatch),
+///     StorageError::from_error(&error).map(|error| error.kind())
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageError {
+    kind: StorageErrorKind,
+    message: String,
+}
+
+impl StorageError {
+    /// Creates a new `StorageError`.
+    pub fn new(kind: StorageErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Gets the Storage details of `error`, if `error` (or one of its sources) is a `StorageError`, or an HTTP error whose code
+    /// is the one of a [`StorageErrorKind`].
+    pub fn from_error(error: &azure_core::Error) -> Option<Self> {
+        let mut source = error.get_ref()? as &dyn std::error::Error;
+        loop {
+            if let Some(e) = source.downcast_ref::<Self>() {
+                return Some(e.clone());
+            }
+            if let Some(e) = source.downcast_ref::<HttpError>() {
+                let kind = [
+                    StorageErrorKind::Md5Mismatch,
+                    StorageErrorKind::Crc64Mismatch,
+                ]
+                .into_iter()
+                .find(|kind| e.error_code() == Some(kind.error_code()))?;
+                return Some(Self::new(kind, e.to_string()));
+            }
+            source = source.source()?;
+        }
+    }
+
+    /// Gets the kind of the error.
+    pub fn kind(&self) -> StorageErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind.error_code(), self.message)
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<StorageError> for azure_core::Error {
+    fn from(error: StorageError) -> Self {
+        azure_core::Error::new(ErrorKind::DataConversion, error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use azure_core::http::{headers::Headers, Response, StatusCode};
+
+    #[tokio::test]
+    pub async fn reads_service_error_codes() {
+        let mut headers = Headers::new();
+        headers.insert("x-ms-error-code", "Md5Mismatch");
+        let response = Response::<()>::from_bytes(StatusCode::BadRequest, headers, "");
+        let error = azure_core::Error::new(
+            ErrorKind::HttpResponse {
+                status: StatusCode::BadRequest,
+                error_code: Some("Md5Mismatch".to_string()),
+            },
+            HttpError::new(response).await,
+        );
+
+        let storage_error = StorageError::from_error(&error).unwrap();
+        assert_eq!(StorageErrorKind::Md5Mismatch, storage_error.kind());
+    }
+}
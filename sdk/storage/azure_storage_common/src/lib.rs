@@ -1,2 +1,16 @@
 // Copyright (c) Microsoft Corporation. All rights reserved.
 // Licensed under the MIT License.
+
+#![doc = include_str!("../README.md")]
+
+mod connection_string;
+pub mod crc64;
+mod credential;
+mod error;
+pub mod sas;
+mod shared_key;
+
+pub use connection_string::{ConnectionString, DEVELOPMENT_ACCOUNT_KEY, DEVELOPMENT_ACCOUNT_NAME};
+pub use credential::StorageCredential;
+pub use error::{StorageError, StorageErrorKind};
+pub use shared_key::{SharedKeyAuthorizationPolicy, SharedKeyCredential};
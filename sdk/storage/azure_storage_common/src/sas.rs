@@ -0,0 +1,474 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Shared access signatures (SAS), as defined in the [official documentation](https://learn.microsoft.com/rest/api/storageservices/delegate-access-with-shared-access-signature).
+//!
+//! This module defines the account SAS, and the types shared with the service and user delegation SAS of each Storage service.
+
+use crate::SharedKeyCredential;
+use azure_core::{
+    date::{self, OffsetDateTime},
+    error::ErrorKind,
+    http::Url,
+    Result,
+};
+use std::{fmt, net::IpAddr, str::FromStr};
+use time::UtcOffset;
+
+/// The version of the Storage services the SAS tokens are signed for.
+pub const SAS_VERSION: &str = "2025-01-05";
+
+/// Formats a date as expected by the `st` and `se` parameters of SAS tokens: in UTC, without fractional seconds.
+pub fn to_sas_time(date: &OffsetDateTime) -> String {
+    let date = date.to_offset(UtcOffset::UTC);
+    // Zero is always a valid nanosecond.
+    date::to_rfc3339(&date.replace_nanosecond(0).unwrap_or(date))
+}
+
+/// A signed SAS token: the query parameters to add to the URL of a resource to access it.
+///
+/// The [`Display`](fmt::Display) implementation writes the token as an encoded query string, without the leading `?`.
+/// The [`Debug`] implementation will not print the signature.
+#[derive(Clone, PartialEq, Eq)]
+pub struct SasToken {
+    parameters: Vec<(String, String)>,
+}
+
+impl SasToken {
+    /// Gets the value of a query parameter of the token, such as `se` for its expiry.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Iterates over the query parameters of the token.
+    pub fn parameters(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Returns the URL of a resource, with the token appended to its query.
+    pub fn append_to(&self, url: &Url) -> Url {
+        let mut url = url.clone();
+        let query = match url.query() {
+            Some(query) if !query.is_empty() => format!("{query}&{self}"),
+            _ => self.to_string(),
+        };
+        url.set_query(Some(&query));
+        url
+    }
+}
+
+/// Creates a token from its query parameters, skipping those with an empty value.
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for SasToken {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        Self {
+            parameters: iter
+                .into_iter()
+                .map(|(name, value)| (name.into(), value.into()))
+                .filter(|(_, value)| !value.is_empty())
+                .collect(),
+        }
+    }
+}
+
+/// Parses a token from a query string, with or without the leading `?`.
+impl FromStr for SasToken {
+    type Err = azure_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let token: Self = url::form_urlencoded::parse(s.trim_start_matches('?').as_bytes())
+            .map(|(name, value)| (name.into_owned(), value.into_owned()))
+            .collect();
+        if token.get("sig").is_none() {
+            return Err(azure_core::Error::message(
+                ErrorKind::Credential,
+                "the SAS token has no signature",
+            ));
+        }
+        Ok(token)
+    }
+}
+
+impl fmt::Display for SasToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let encode = |s: &str| {
+            url::form_urlencoded::byte_serialize(s.as_bytes())
+                .collect::<String>()
+                .replace('+', "%20")
+        };
+        for (i, (name, value)) in self.parameters.iter().enumerate() {
+            if i > 0 {
+                f.write_str("&")?;
+            }
+            write!(f, "{}={}", encode(name), encode(value))?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for SasToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in &self.parameters {
+            if name == "sig" {
+                map.entry(name, &"<REDACTED>");
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+/// The protocols allowed for requests made with a SAS token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SasProtocol {
+    /// Only HTTPS requests are allowed.
+    Https,
+
+    /// Both HTTPS and HTTP requests are allowed.
+    HttpsAndHttp,
+}
+
+impl fmt::Display for SasProtocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SasProtocol::Https => "https",
+            SasProtocol::HttpsAndHttp => "https,http",
+        })
+    }
+}
+
+/// The IP address, or inclusive range of IP addresses, from which requests made with a SAS token are allowed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SasIpRange {
+    start: IpAddr,
+    end: Option<IpAddr>,
+}
+
+impl SasIpRange {
+    /// Allows requests from a range of IP addresses, `start` and `end` included.
+    pub fn new(start: IpAddr, end: IpAddr) -> Self {
+        Self {
+            start,
+            end: Some(end),
+        }
+    }
+
+    /// Allows requests from a single IP address.
+    pub fn single(address: IpAddr) -> Self {
+        Self {
+            start: address,
+            end: None,
+        }
+    }
+}
+
+impl fmt::Display for SasIpRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.end {
+            Some(end) => write!(f, "{}-{}", self.start, end),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+/// The services accessible with an account SAS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccountSasServices {
+    pub blob: bool,
+    pub queue: bool,
+    pub table: bool,
+    pub file: bool,
+}
+
+impl fmt::Display for AccountSasServices {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_flags(
+            f,
+            &[
+                (self.blob, 'b'),
+                (self.queue, 'q'),
+                (self.table, 't'),
+                (self.file, 'f'),
+            ],
+        )
+    }
+}
+
+/// The types of resources accessible with an account SAS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccountSasResourceTypes {
+    /// Service-level APIs, such as listing the containers.
+    pub service: bool,
+
+    /// Container-level APIs, such as creating a container or listing its blobs.
+    pub container: bool,
+
+    /// Object-level APIs, such as uploading or downloading a blob.
+    pub object: bool,
+}
+
+impl fmt::Display for AccountSasResourceTypes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_flags(
+            f,
+            &[
+                (self.service, 's'),
+                (self.container, 'c'),
+                (self.object, 'o'),
+            ],
+        )
+    }
+}
+
+/// The operations allowed with an account SAS.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AccountSasPermissions {
+    pub read: bool,
+    pub write: bool,
+    pub delete: bool,
+    pub delete_version: bool,
+    pub permanent_delete: bool,
+    pub list: bool,
+    pub add: bool,
+    pub create: bool,
+    pub update: bool,
+    pub process: bool,
+    pub tags: bool,
+    pub filter_by_tags: bool,
+    pub set_immutability_policy: bool,
+}
+
+impl fmt::Display for AccountSasPermissions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_flags(
+            f,
+            &[
+                (self.read, 'r'),
+                (self.write, 'w'),
+                (self.delete, 'd'),
+                (self.delete_version, 'x'),
+                (self.permanent_delete, 'y'),
+                (self.list, 'l'),
+                (self.add, 'a'),
+                (self.create, 'c'),
+                (self.update, 'u'),
+                (self.process, 'p'),
+                (self.tags, 't'),
+                (self.filter_by_tags, 'f'),
+                (self.set_immutability_policy, 'i'),
+            ],
+        )
+    }
+}
+
+/// Writes the letters of the flags that are set, in the order required by the service.
+pub(crate) fn write_flags(f: &mut fmt::Formatter<'_>, flags: &[(bool, char)]) -> fmt::Result {
+    for (_, letter) in flags.iter().filter(|(set, _)| *set) {
+        write!(f, "{letter}")?;
+    }
+    Ok(())
+}
+
+/// Builds an account SAS, granting access to the resources of one or more services of a Storage account.
+///
+/// ```
+/// use azure_core::date::OffsetDateTime;
+/// use azure_storage_common::{
+///     sas::{AccountSasBuilder, AccountSasPermissions, AccountSasResourceTypes, AccountSasServices},
+///     SharedKeyCredential,
+/// };
+/// # fn main() -> azure_core::Result<()> {
+/// let credential = SharedKeyCredential::new("myaccount", "a2V5");
+/// let token = AccountSasBuilder::new(
+///     AccountSasServices { blob: true, ..Default::default() },
+///     AccountSasResourceTypes { container: true, object: true, ..Default::default() },
+///     AccountSasPermissions { read: true, list: true, ..Default::default() },
+///     OffsetDateTime::now_utc() + std::time::Duration::from_secs(3600),
+/// )
+/// .sign(&credential)?;
+/// println!("https://myaccount.blob.core.windows.net/?{token}");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct AccountSasBuilder {
+    services: AccountSasServices,
+    resource_types: AccountSasResourceTypes,
+    permissions: AccountSasPermissions,
+    start: Option<OffsetDateTime>,
+    expiry: OffsetDateTime,
+    ip_range: Option<SasIpRange>,
+    protocol: Option<SasProtocol>,
+}
+
+impl AccountSasBuilder {
+    /// Creates a new `AccountSasBuilder`.
+    ///
+    /// # Arguments
+    ///
+    /// * `services` - The services accessible with the SAS.
+    /// * `resource_types` - The types of resources accessible with the SAS.
+    /// * `permissions` - The operations allowed with the SAS.
+    /// * `expiry` - The time after which the SAS is no longer valid.
+    pub fn new(
+        services: AccountSasServices,
+        resource_types: AccountSasResourceTypes,
+        permissions: AccountSasPermissions,
+        expiry: OffsetDateTime,
+    ) -> Self {
+        Self {
+            services,
+            resource_types,
+            permissions,
+            start: None,
+            expiry,
+            ip_range: None,
+            protocol: None,
+        }
+    }
+
+    /// Sets the time from which the SAS is valid. By default, the SAS is valid immediately.
+    pub fn with_start(mut self, start: OffsetDateTime) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Sets the IP addresses from which requests made with the SAS are allowed. By default, any address is allowed.
+    pub fn with_ip_range(mut self, ip_range: SasIpRange) -> Self {
+        self.ip_range = Some(ip_range);
+        self
+    }
+
+    /// Sets the protocols allowed for requests made with the SAS. By default, both HTTPS and HTTP are allowed.
+    pub fn with_protocol(mut self, protocol: SasProtocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+
+    /// Builds the string signed by [`AccountSasBuilder::sign()`] for the given account.
+    pub fn string_to_sign(&self, account_name: &str) -> String {
+        format!(
+            "{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n{}\n",
+            account_name,
+            self.permissions,
+            self.services,
+            self.resource_types,
+            self.start.as_ref().map(to_sas_time).unwrap_or_default(),
+            to_sas_time(&self.expiry),
+            display_or_empty(self.ip_range),
+            display_or_empty(self.protocol),
+            SAS_VERSION,
+            // The encryption scope.
+            "",
+        )
+    }
+
+    /// Signs the SAS with the key of the account.
+    pub fn sign(&self, credential: &SharedKeyCredential) -> Result<SasToken> {
+        let signature = credential.sign(&self.string_to_sign(credential.account_name()))?;
+        Ok(SasToken::from_iter([
+            ("sv", SAS_VERSION.to_string()),
+            ("ss", self.services.to_string()),
+            ("srt", self.resource_types.to_string()),
+            ("sp", self.permissions.to_string()),
+            (
+                "st",
+                self.start.as_ref().map(to_sas_time).unwrap_or_default(),
+            ),
+            ("se", to_sas_time(&self.expiry)),
+            ("sip", display_or_empty(self.ip_range)),
+            ("spr", display_or_empty(self.protocol)),
+            ("sig", signature),
+        ]))
+    }
+}
+
+/// Formats an optional value, or returns an empty string.
+pub(crate) fn display_or_empty<T: fmt::Display>(value: Option<T>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AccountSasBuilder, AccountSasPermissions, AccountSasResourceTypes, AccountSasServices,
+        SasIpRange, SasProtocol, SasToken,
+    };
+    use crate::SharedKeyCredential;
+    use azure_core::{date::parse_rfc3339, http::Url};
+
+    const ACCOUNT_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn account_sas() -> AccountSasBuilder {
+        AccountSasBuilder::new(
+            AccountSasServices {
+                blob: true,
+                file: true,
+                ..Default::default()
+            },
+            AccountSasResourceTypes {
+                container: true,
+                object: true,
+                ..Default::default()
+            },
+            AccountSasPermissions {
+                read: true,
+                write: true,
+                list: true,
+                create: true,
+                ..Default::default()
+            },
+            parse_rfc3339("2025-03-02T12:00:00.5+01:00").unwrap(),
+        )
+        .with_start(parse_rfc3339("2025-03-01T12:00:00Z").unwrap())
+        .with_ip_range(SasIpRange::new(
+            "168.1.5.60".parse().unwrap(),
+            "168.1.5.70".parse().unwrap(),
+        ))
+        .with_protocol(SasProtocol::Https)
+    }
+
+    #[test]
+    pub fn signs_account_sas() {
+        let credential = SharedKeyCredential::new("myaccount", ACCOUNT_KEY);
+        assert_eq!(
+            "myaccount\nrwlc\nbf\nco\n2025-03-01T12:00:00Z\n2025-03-02T11:00:00Z\n168.1.5.60-168.1.5.70\nhttps\n2025-01-05\n\n",
+            account_sas().string_to_sign("myaccount")
+        );
+        let token = account_sas().sign(&credential).unwrap();
+        assert_eq!(
+            "sv=2025-01-05&ss=bf&srt=co&sp=rwlc&st=2025-03-01T12%3A00%3A00Z&se=2025-03-02T11%3A00%3A00Z&sip=168.1.5.60-168.1.5.70&spr=https&sig=p6Iz%2B7VnoofipuAXHnKjUt7GZ5vneTYmQ1AQF1GOfvY%3D",
+            token.to_string()
+        );
+        assert_eq!(
+            Some("p6Iz+7VnoofipuAXHnKjUt7GZ5vneTYmQ1AQF1GOfvY="),
+            token.get("sig")
+        );
+    }
+
+    #[test]
+    pub fn parses_and_appends_tokens() {
+        let token: SasToken =
+            "?sv=2025-01-05&sr=b&sp=r&rsct=text%2Fplain%3B%20charset%3Dutf-8&sig=abc%2B%2F%3D"
+                .parse()
+                .unwrap();
+        assert_eq!(Some("text/plain; charset=utf-8"), token.get("rsct"));
+        assert_eq!(Some("abc+/="), token.get("sig"));
+        assert!(!format!("{:?}", token).contains("abc"));
+
+        let url = Url::parse("https://myaccount.blob.core.windows.net/c/b?snapshot=1").unwrap();
+        assert_eq!(
+            "https://myaccount.blob.core.windows.net/c/b?snapshot=1&sv=2025-01-05&sr=b&sp=r&rsct=text%2Fplain%3B%20charset%3Dutf-8&sig=abc%2B%2F%3D",
+            token.append_to(&url).as_str()
+        );
+
+        assert!("sv=2025-01-05&sp=r".parse::<SasToken>().is_err());
+    }
+}
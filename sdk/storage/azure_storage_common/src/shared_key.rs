@@ -0,0 +1,304 @@
+// Copyright (c) Microsoft Corporation. All rights reserved.
+// Licensed under the MIT License.
+
+//! Shared Key authorization, as defined in the [official documentation](https://learn.microsoft.com/rest/api/storageservices/authorize-with-shared-key).
+
+use async_trait::async_trait;
+use azure_core::{
+    credentials::Secret,
+    date::{self, OffsetDateTime},
+    hmac::hmac_sha256,
+    http::{
+        headers::{
+            HeaderName, HeaderValue, AUTHORIZATION, CONTENT_ENCODING, CONTENT_LANGUAGE,
+            CONTENT_LENGTH, CONTENT_MD5, CONTENT_TYPE, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH,
+            IF_UNMODIFIED_SINCE, MS_DATE, RANGE,
+        },
+        policies::{Policy, PolicyResult},
+        Context, Request,
+    },
+    Result,
+};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The name and key of a Storage account, used to sign requests with Shared Key authorization.
+///
+/// The [`Debug`] implementation will not print the account key.
+#[derive(Clone, Debug)]
+pub struct SharedKeyCredential {
+    account_name: String,
+    account_key: Secret,
+}
+
+impl SharedKeyCredential {
+    /// Creates a new `SharedKeyCredential`.
+    ///
+    /// # Arguments
+    ///
+    /// * `account_name` - The name of the Storage account.
+    /// * `account_key` - One of the base64 encoded access keys of the Storage account.
+    pub fn new(account_name: impl Into<String>, account_key: impl Into<Secret>) -> Self {
+        Self {
+            account_name: account_name.into(),
+            account_key: account_key.into(),
+        }
+    }
+
+    /// Gets the name of the Storage account.
+    pub fn account_name(&self) -> &str {
+        &self.account_name
+    }
+
+    /// Signs `string_to_sign` with the account key, returning the base64 encoded HMAC-SHA256 signature.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account key is not valid base64.
+    pub fn sign(&self, string_to_sign: &str) -> Result<String> {
+        hmac_sha256(string_to_sign, &self.account_key)
+    }
+}
+
+/// Authorizes requests with the `SharedKey` scheme of the Storage services.
+///
+/// The policy sets the `x-ms-date` header, then signs the method, the standard headers, the `x-ms-*` headers and the resource of the request.
+/// It must run after any policy adding headers to the request, as changing a signed header invalidates the signature.
+#[derive(Clone, Debug)]
+pub struct SharedKeyAuthorizationPolicy {
+    credential: SharedKeyCredential,
+}
+
+impl SharedKeyAuthorizationPolicy {
+    /// Creates a new `SharedKeyAuthorizationPolicy` signing requests with the given credential.
+    pub fn new(credential: SharedKeyCredential) -> Self {
+        Self { credential }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl Policy for SharedKeyAuthorizationPolicy {
+    async fn send(
+        &self,
+        ctx: &Context,
+        request: &mut Request,
+        next: &[Arc<dyn Policy>],
+    ) -> PolicyResult {
+        assert!(
+            !next.is_empty(),
+            "Authorization policies cannot be the last policy of a pipeline"
+        );
+
+        // Each retry is signed again with a new date, as the service rejects requests older than 15 minutes.
+        request.insert_header(
+            MS_DATE,
+            HeaderValue::from(date::to_rfc7231(&OffsetDateTime::now_utc())),
+        );
+        let signature = self
+            .credential
+            .sign(&string_to_sign(&self.credential.account_name, request))?;
+        request.insert_header(
+            AUTHORIZATION,
+            HeaderValue::from(format!(
+                "SharedKey {}:{}",
+                self.credential.account_name, signature
+            )),
+        );
+
+        next[0].send(ctx, request, &next[1..]).await
+    }
+}
+
+/// Builds the string to sign of a request, for the Blob, Queue and File services version 2009-09-19 and later.
+fn string_to_sign(account_name: &str, request: &Request) -> String {
+    let headers = request.headers();
+    let header = |name: &HeaderName| headers.get_optional_str(name).unwrap_or_default();
+
+    // Since version 2015-02-21, the Content-Length must be empty when zero.
+    let content_length = match headers.get_optional_str(&CONTENT_LENGTH) {
+        Some(length) => length.to_string(),
+        None => request.body().len().to_string(),
+    };
+    let content_length = if content_length == "0" {
+        ""
+    } else {
+        content_length.as_str()
+    };
+
+    let mut result = [
+        request.method().as_ref(),
+        header(&CONTENT_ENCODING),
+        header(&CONTENT_LANGUAGE),
+        content_length,
+        header(&CONTENT_MD5),
+        header(&CONTENT_TYPE),
+        // The Date is empty, because x-ms-date is always set.
+        "",
+        header(&IF_MODIFIED_SINCE),
+        header(&IF_MATCH),
+        header(&IF_NONE_MATCH),
+        header(&IF_UNMODIFIED_SINCE),
+        header(&RANGE),
+    ]
+    .join("\n");
+    result.push('\n');
+    canonicalize_headers(request, &mut result);
+    canonicalize_resource(account_name, request, &mut result);
+    result
+}
+
+/// Appends the `x-ms-*` headers, sorted by name, each followed by a new line.
+fn canonicalize_headers(request: &Request, result: &mut String) {
+    let mut headers: Vec<_> = request
+        .headers()
+        .iter()
+        .filter(|(name, _)| name.as_str().starts_with("x-ms-"))
+        .collect();
+    headers.sort_by(|(a, _), (b, _)| a.as_str().cmp(b.as_str()));
+    for (name, value) in headers {
+        result.push_str(name.as_str());
+        result.push(':');
+        result.push_str(value.as_str().trim());
+        result.push('\n');
+    }
+}
+
+/// Appends the account and the encoded path of the request, followed by the query parameters sorted by name.
+///
+/// Values of parameters given more than once are sorted and separated by commas.
+fn canonicalize_resource(account_name: &str, request: &Request, result: &mut String) {
+    result.push('/');
+    result.push_str(account_name);
+    result.push_str(request.url().path());
+
+    let mut parameters: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, value) in request.url().query_pairs() {
+        parameters
+            .entry(name.to_lowercase())
+            .or_default()
+            .push(value.into_owned());
+    }
+    for (name, mut values) in parameters {
+        values.sort();
+        result.push('\n');
+        result.push_str(&name);
+        result.push(':');
+        result.push_str(&values.join(","));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{string_to_sign, SharedKeyAuthorizationPolicy, SharedKeyCredential};
+    use async_trait::async_trait;
+    use azure_core::{
+        http::{
+            headers::{AUTHORIZATION, CONTENT_TYPE, MS_DATE},
+            policies::{Policy, PolicyResult},
+            Context, Method, Request, Url,
+        },
+        Bytes,
+    };
+    use std::sync::{Arc, Mutex};
+
+    // The well-known key of the Azurite emulator.
+    const ACCOUNT_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn put_block_request() -> Request {
+        let url = Url::parse("http://127.0.0.1:10000/devstoreaccount1/container/dir/hello%20world.txt?comp=block&Timeout=30&blockid=ZZZZ&blockid=AAAA").unwrap();
+        let mut request = Request::new(url, Method::Put);
+        request.insert_header(CONTENT_TYPE, "text/plain; charset=UTF-8");
+        request.insert_header(MS_DATE, "Sat, 01 Mar 2025 12:00:00 GMT");
+        request.insert_header("x-ms-version", "2025-01-05");
+        request.insert_header("x-ms-blob-type", "BlockBlob");
+        request.insert_header("x-ms-client-request-id", "req-1");
+        request.insert_header("x-ms-meta-project", "  storage sdk ");
+        request.insert_header("user-agent", "not signed");
+        request.set_body(Bytes::from_static(b"hello world"));
+        request
+    }
+
+    #[test]
+    pub fn builds_string_to_sign() {
+        assert_eq!(
+            "PUT\n\n\n11\n\ntext/plain; charset=UTF-8\n\n\n\n\n\n\n\
+            x-ms-blob-type:BlockBlob\n\
+            x-ms-client-request-id:req-1\n\
+            x-ms-date:Sat, 01 Mar 2025 12:00:00 GMT\n\
+            x-ms-meta-project:storage sdk\n\
+            x-ms-version:2025-01-05\n\
+            /devstoreaccount1/devstoreaccount1/container/dir/hello%20world.txt\n\
+            blockid:AAAA,ZZZZ\n\
+            comp:block\n\
+            timeout:30",
+            string_to_sign("devstoreaccount1", &put_block_request())
+        );
+
+        let url = Url::parse("https://myaccount.blob.core.windows.net/").unwrap();
+        let mut request = Request::new(url, Method::Get);
+        request.insert_header(MS_DATE, "Sat, 01 Mar 2025 12:00:00 GMT");
+        request.insert_header("content-length", "0");
+        assert_eq!(
+            "GET\n\n\n\n\n\n\n\n\n\n\n\nx-ms-date:Sat, 01 Mar 2025 12:00:00 GMT\n/myaccount/",
+            string_to_sign("myaccount", &request)
+        );
+    }
+
+    #[test]
+    pub fn signs_with_account_key() {
+        let credential = SharedKeyCredential::new("devstoreaccount1", ACCOUNT_KEY);
+        let string_to_sign = string_to_sign("devstoreaccount1", &put_block_request());
+        assert_eq!(
+            "a7MINDC+P/nem4L7fNnLYKoZs4H52ZqztA48xOvFOas=",
+            credential.sign(&string_to_sign).unwrap()
+        );
+
+        let credential = SharedKeyCredential::new("devstoreaccount1", "not base64!");
+        assert!(credential.sign(&string_to_sign).is_err());
+    }
+
+    #[derive(Debug, Default)]
+    struct CaptureHeaders(Mutex<Option<Request>>);
+
+    #[async_trait]
+    impl Policy for CaptureHeaders {
+        async fn send(
+            &self,
+            _ctx: &Context,
+            request: &mut Request,
+            _next: &[Arc<dyn Policy>],
+        ) -> PolicyResult {
+            *self.0.lock().unwrap() = Some(request.clone());
+            Err(azure_core::Error::message(
+                azure_core::error::ErrorKind::Other,
+                "the request was captured",
+            ))
+        }
+    }
+
+    #[tokio::test]
+    pub async fn authorizes_requests() {
+        let credential = SharedKeyCredential::new("devstoreaccount1", ACCOUNT_KEY);
+        let policy = SharedKeyAuthorizationPolicy::new(credential.clone());
+        let capture = Arc::new(CaptureHeaders::default());
+        let next: Arc<dyn Policy> = capture.clone();
+        let mut request = put_block_request();
+        let _ = policy
+            .send(&Context::default(), &mut request, &[next])
+            .await;
+
+        let request = capture.0.lock().unwrap().take().unwrap();
+        assert_ne!(
+            "Sat, 01 Mar 2025 12:00:00 GMT",
+            request.headers().get_str(&MS_DATE).unwrap()
+        );
+        let expected = format!(
+            "SharedKey devstoreaccount1:{}",
+            credential
+                .sign(&string_to_sign("devstoreaccount1", &request))
+                .unwrap()
+        );
+        assert_eq!(expected, request.headers().get_str(&AUTHORIZATION).unwrap());
+    }
+}